@@ -394,6 +394,7 @@ mod tests {
             state: PropertyState::Ok,
             perm: libindigo::types::PropertyPerm::ReadWrite,
             property_type: PropertyType::Text,
+            switch_rule: None,
             items: std::collections::HashMap::new(),
             timeout: None,
             timestamp: None,
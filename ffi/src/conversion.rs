@@ -13,7 +13,8 @@ use libindigo::error::{IndigoError, Result};
 use libindigo::types::{BlobTransferMode, Property};
 #[cfg(feature = "sys-available")]
 use libindigo::types::{
-    LightState, PropertyItem, PropertyPerm, PropertyState, PropertyType, PropertyValue, SwitchState,
+    LightState, PropertyItem, PropertyPerm, PropertyState, PropertyType, PropertyValue, SwitchRule,
+    SwitchState,
 };
 #[cfg(feature = "sys-available")]
 use std::collections::HashMap;
@@ -78,6 +79,12 @@ pub unsafe fn property_from_c(c_property: *const indigo_property) -> Result<Prop
     // Convert property type and items
     let (property_type, items) = items_from_c(prop)?;
 
+    // Switch rule only applies to switch properties
+    let switch_rule = match property_type {
+        PropertyType::Switch => rule_from_c(prop.rule),
+        _ => None,
+    };
+
     // Extract optional fields
     let timeout = if prop.timeout > 0.0 {
         Some(prop.timeout)
@@ -105,6 +112,7 @@ pub unsafe fn property_from_c(c_property: *const indigo_property) -> Result<Prop
         state,
         perm,
         property_type,
+        switch_rule,
         items,
         timeout,
         timestamp,
@@ -187,6 +195,19 @@ fn state_from_c(state: indigo_property_state) -> Result<PropertyState> {
     }
 }
 
+/// Convert a C switch rule to Rust SwitchRule.
+///
+/// Returns `None` for rules that are not recognized.
+#[cfg(feature = "sys-available")]
+fn rule_from_c(rule: indigo_rule) -> Option<SwitchRule> {
+    match rule.0 {
+        1 => Some(SwitchRule::OneOfMany),
+        2 => Some(SwitchRule::AtMostOne),
+        3 => Some(SwitchRule::AnyOfMany),
+        _ => None,
+    }
+}
+
 /// Convert Rust PropertyState to C property state.
 #[cfg(feature = "sys-available")]
 pub fn state_to_c(state: PropertyState) -> indigo_property_state {
@@ -337,7 +358,16 @@ unsafe fn items_from_c(
 
                 items.insert(
                     name.clone(),
-                    PropertyItem::new(name, label, PropertyValue::Blob { data, format, size }),
+                    PropertyItem::new(
+                        name,
+                        label,
+                        PropertyValue::Blob {
+                            data,
+                            format,
+                            size,
+                            compressed_size: None,
+                        },
+                    ),
                 );
             }
             Ok((PropertyType::Blob, items))
@@ -183,7 +183,7 @@ impl FactoryComponent for RelmPropertyItem {
                         set_label: Some(&self.item.name),
                     }
                 }
-                PropertyValue::Blob{data: _, format, size} => {
+                PropertyValue::Blob{format, size, ..} => {
                     gtk::Box {
                         set_orientation: gtk::Orientation::Horizontal,
                         set_spacing: 10,
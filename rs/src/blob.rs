@@ -0,0 +1,1071 @@
+//! Streaming reception of BLOBs
+//!
+//! Images of modern cameras are sent as BLOBs of 100 MB and more. Instead of
+//! buffering a whole `setBLOBVector` before parsing it, the transport decodes
+//! the base64 content of each `oneBLOB` element as it arrives and removes it
+//! from the read buffer, so that the buffer only holds the markup of the
+//! message.
+//!
+//! The decoded data is collected in memory and delivered in
+//! [`OneBLOB::data`](crate::protocol::OneBLOB::data), unless a
+//! [`BlobSinkFactory`] provides a sink for the BLOB, such as a file. Data
+//! written to a sink is never held in memory as a whole, so memory use is
+//! bounded regardless of the size of the BLOB.
+//!
+//! Streaming applies to the XML protocol, which sends the content of BLOBs
+//! inline. The JSON protocol sends URLs of BLOBs instead, which the
+//! transport fetches over HTTP from the INDIGO server, into the same memory
+//! buffers or sinks.
+//!
+//! BLOBs in `.z` formats, such as `.fits.z`, are sent compressed to save
+//! bandwidth. They are decompressed as they arrive and delivered without the
+//! suffix, with the size as transferred in
+//! [`OneBLOB::compressed_size`](crate::protocol::OneBLOB::compressed_size).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo_rs::blob::{BlobHeader, BlobSink};
+//! use std::sync::Arc;
+//!
+//! // Write images straight to files
+//! strategy
+//!     .set_blob_sink_factory(Some(Arc::new(|header: &BlobHeader| {
+//!         let file = std::fs::File::create(format!("{}{}", header.name, header.format)).ok()?;
+//!         Some(Box::new(tokio::fs::File::from_std(file)) as BlobSink)
+//!     })))
+//!     .await;
+//! ```
+
+use crate::protocol::{Base64Decoder, OneBLOB, ProtocolMessage};
+use flate2::write::{GzDecoder, GzEncoder, ZlibDecoder};
+use flate2::Compression;
+use libindigo::error::{IndigoError, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// Largest buffer reserved in advance for a BLOB collected in memory, as the
+/// announced size is not trusted.
+const MAX_PREALLOCATION: usize = 4 * 1024 * 1024; // 4 MB
+
+/// A destination for the decoded content of a BLOB.
+pub type BlobSink = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Chooses a sink for each received BLOB.
+///
+/// Returning `None` collects the BLOB in memory.
+pub type BlobSinkFactory = Arc<dyn Fn(&BlobHeader) -> Option<BlobSink> + Send + Sync>;
+
+/// Description of a BLOB whose content is about to be received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobHeader {
+    /// Device name.
+    pub device: String,
+    /// Property name.
+    pub property: String,
+    /// Element name.
+    pub name: String,
+    /// BLOB format/extension.
+    pub format: String,
+    /// BLOB size in bytes, as announced by the sender.
+    pub size: usize,
+}
+
+/// Where the content of a BLOB goes.
+enum Output {
+    /// Collected in memory.
+    Memory(Vec<u8>),
+    /// Written to a sink.
+    Sink(BlobSink),
+}
+
+impl Output {
+    fn new(header: &BlobHeader, sinks: Option<&BlobSinkFactory>) -> Self {
+        match sinks.and_then(|factory| factory(header)) {
+            Some(sink) => Output::Sink(sink),
+            None => Output::Memory(Vec::with_capacity(header.size.min(MAX_PREALLOCATION))),
+        }
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<()> {
+        match self {
+            Output::Memory(buffer) => {
+                buffer.extend_from_slice(data);
+                Ok(())
+            }
+            Output::Sink(sink) => sink.write_all(data).await.map_err(sink_error),
+        }
+    }
+
+    /// Finishes the BLOB, returning the data collected in memory.
+    async fn finish(self) -> Result<Option<Vec<u8>>> {
+        match self {
+            Output::Memory(data) => Ok(Some(data)),
+            Output::Sink(mut sink) => {
+                sink.shutdown().await.map_err(sink_error)?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Receives the content of a BLOB, decompressing it if it was sent in a
+/// `.z` format.
+struct Target {
+    output: Output,
+    inflater: Option<Inflater>,
+    /// Buffer for the decompressed piece written to a sink.
+    inflated: Vec<u8>,
+    /// Size of the data before decompression.
+    transferred: usize,
+    /// Size of the decompressed data.
+    size: usize,
+}
+
+impl Target {
+    fn new(header: &BlobHeader, sinks: Option<&BlobSinkFactory>) -> Self {
+        // Sinks see the format of the data they get
+        let (output, inflater) = match header.format.strip_suffix(COMPRESSED_SUFFIX) {
+            Some(format) => {
+                let header = BlobHeader {
+                    format: format.to_string(),
+                    ..header.clone()
+                };
+                (Output::new(&header, sinks), Some(Inflater::new()))
+            }
+            None => (Output::new(header, sinks), None),
+        };
+        Target {
+            output,
+            inflater,
+            inflated: Vec::new(),
+            transferred: 0,
+            size: 0,
+        }
+    }
+
+    /// Gets the memory buffer of a BLOB received as is.
+    fn memory(&mut self) -> Option<&mut Vec<u8>> {
+        match (&mut self.output, &self.inflater) {
+            (Output::Memory(data), None) => Some(data),
+            _ => None,
+        }
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.transferred += data.len();
+        let Some(inflater) = &mut self.inflater else {
+            return self.output.write(data).await;
+        };
+        self.inflated.clear();
+        inflater.inflate(data, &mut self.inflated)?;
+        self.size += self.inflated.len();
+        self.output.write(&self.inflated).await
+    }
+
+    /// Finishes the BLOB, returning the data collected in memory.
+    async fn finish(mut self) -> Result<Received> {
+        let compressed_size = match self.inflater.take() {
+            Some(inflater) => {
+                self.inflated.clear();
+                inflater.finish(&mut self.inflated)?;
+                self.size += self.inflated.len();
+                self.output.write(&self.inflated).await?;
+                Some(self.transferred)
+            }
+            None => None,
+        };
+        Ok(Received {
+            data: self.output.finish().await?,
+            size: self.size,
+            compressed_size,
+        })
+    }
+}
+
+/// A BLOB received by the transport.
+struct Received {
+    /// Data collected in memory, `None` if written to a sink.
+    data: Option<Vec<u8>>,
+    /// Size of the decompressed data, for a compressed BLOB.
+    size: usize,
+    /// Size of the data as transferred, for a compressed BLOB.
+    compressed_size: Option<usize>,
+}
+
+impl Received {
+    /// Puts the BLOB into its element, describing the decompressed data.
+    fn deliver(self, element: &mut OneBLOB) {
+        element.data = self.data;
+        if self.compressed_size.is_some() {
+            if let Some(format) = element.format.strip_suffix(COMPRESSED_SUFFIX) {
+                element.format = format.to_string();
+            }
+            element.size = self.size;
+            element.compressed_size = self.compressed_size;
+        }
+    }
+}
+
+fn sink_error(e: std::io::Error) -> IndigoError {
+    IndigoError::BlobError(format!("Failed to write BLOB: {}", e))
+}
+
+/// A BLOB whose base64 content is being received.
+struct Content {
+    decoder: Base64Decoder,
+    target: Target,
+    /// Buffer for the decoded piece written to a sink.
+    decoded: Vec<u8>,
+    /// First failure, after which the rest of the content is skipped.
+    error: Option<IndigoError>,
+}
+
+impl Content {
+    fn new(target: Target) -> Self {
+        Content {
+            decoder: Base64Decoder::new(),
+            target,
+            decoded: Vec::new(),
+            error: None,
+        }
+    }
+
+    async fn write(&mut self, encoded: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = match self.target.memory() {
+            // Decode straight into memory
+            Some(data) => self.decoder.decode(encoded, data),
+            None => {
+                self.decoded.clear();
+                match self.decoder.decode(encoded, &mut self.decoded) {
+                    Ok(()) => self.target.write(&self.decoded).await,
+                    Err(e) => Err(e),
+                }
+            }
+        };
+        self.error = result.err();
+    }
+
+    async fn finish(self) -> Result<Received> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        self.decoder.finish()?;
+        self.target.finish().await
+    }
+}
+
+/// Progress of scanning the message at the start of the read buffer.
+enum Scan {
+    /// Waiting for the first tag of the message.
+    Start,
+    /// Inside a BLOB vector of a device and property.
+    Vector(String, String),
+    /// Nothing left to stream in the message.
+    Done,
+}
+
+/// Streams the content of BLOBs out of a read buffer.
+///
+/// The buffer must start with the message being received: [`take`] is
+/// called when the message is removed from the buffer.
+///
+/// [`take`]: BlobStreamer::take
+pub(crate) struct BlobStreamer {
+    sinks: Option<BlobSinkFactory>,
+    scan: Scan,
+    /// Length of the scanned start of the buffer.
+    scanned: usize,
+    /// BLOB whose content starts at the end of the scanned part.
+    content: Option<Content>,
+    /// BLOBs of the message received so far.
+    received: VecDeque<Result<Received>>,
+}
+
+impl BlobStreamer {
+    pub(crate) fn new(sinks: Option<BlobSinkFactory>) -> Self {
+        BlobStreamer {
+            sinks,
+            scan: Scan::Start,
+            scanned: 0,
+            content: None,
+            received: VecDeque::new(),
+        }
+    }
+
+    /// Decodes the BLOB content in the buffer and removes it.
+    pub(crate) async fn stream(&mut self, buffer: &mut Vec<u8>) {
+        loop {
+            if let Some(content) = &mut self.content {
+                let end = buffer[self.scanned..].iter().position(|&b| b == b'<');
+                let stop = end.map_or(buffer.len(), |end| self.scanned + end);
+                content.write(&buffer[self.scanned..stop]).await;
+                buffer.drain(self.scanned..stop);
+                if end.is_none() {
+                    return;
+                }
+                if let Some(content) = self.content.take() {
+                    self.received.push_back(content.finish().await);
+                }
+            }
+
+            let rest = &buffer[self.scanned..];
+            let start = match self.scan {
+                Scan::Done => return,
+                Scan::Start => match rest.iter().position(|b| !b.is_ascii_whitespace()) {
+                    Some(start) if rest[start] == b'<' => start,
+                    Some(_) => {
+                        self.scan = Scan::Done;
+                        return;
+                    }
+                    None => return,
+                },
+                Scan::Vector(..) => match rest.iter().position(|&b| b == b'<') {
+                    Some(start) => start,
+                    None => return,
+                },
+            };
+            let Some(end) = find_tag_end(&rest[start..]) else {
+                return;
+            };
+            let tag = &rest[start..=start + end];
+            self.scanned += start + end + 1;
+            self.scan_tag(tag);
+        }
+    }
+
+    /// Follows a tag of the message, starting the content of BLOBs.
+    fn scan_tag(&mut self, tag: &[u8]) {
+        // The tag is read on its own, closing tags without opening ones
+        let mut reader = Reader::from_reader(tag);
+        reader.config_mut().allow_unmatched_ends = true;
+        let event = reader.read_event();
+        match (&self.scan, event) {
+            (Scan::Start, Ok(Event::Start(e)))
+                if matches!(e.name().as_ref(), b"setBLOBVector" | b"newBLOBVector") =>
+            {
+                match (attribute(&e, "device"), attribute(&e, "name")) {
+                    (Some(device), Some(name)) => self.scan = Scan::Vector(device, name),
+                    _ => self.scan = Scan::Done,
+                }
+            }
+            (Scan::Vector(device, property), Ok(Event::Start(e)))
+                if e.name().as_ref() == b"oneBLOB" =>
+            {
+                let header = BlobHeader {
+                    device: device.clone(),
+                    property: property.clone(),
+                    name: attribute(&e, "name").unwrap_or_default(),
+                    format: attribute(&e, "format").unwrap_or_default(),
+                    size: attribute(&e, "size")
+                        .and_then(|size| size.parse().ok())
+                        .unwrap_or(0),
+                };
+                self.content = Some(Content::new(Target::new(&header, self.sinks.as_ref())));
+            }
+            (Scan::Vector(..), Ok(Event::End(e)))
+                if matches!(e.name().as_ref(), b"setBLOBVector" | b"newBLOBVector") =>
+            {
+                self.scan = Scan::Done;
+            }
+            (Scan::Vector(..), _) => {}
+            _ => self.scan = Scan::Done,
+        }
+    }
+
+    /// Takes the BLOBs of the message removed from the buffer, and starts
+    /// scanning the next message.
+    pub(crate) fn take(&mut self) -> ReceivedBlobs {
+        self.scan = Scan::Start;
+        self.scanned = 0;
+        self.content = None;
+        ReceivedBlobs(std::mem::take(&mut self.received))
+    }
+}
+
+impl std::fmt::Debug for BlobStreamer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlobStreamer")
+            .field("scanned", &self.scanned)
+            .field("received", &self.received.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// BLOBs streamed out of a message.
+pub(crate) struct ReceivedBlobs(VecDeque<Result<Received>>);
+
+impl ReceivedBlobs {
+    /// Puts the decoded data into the BLOB elements of a message.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first failure to decode or write a BLOB of the message.
+    pub(crate) fn attach(mut self, message: &mut ProtocolMessage) -> Result<()> {
+        let elements = match message {
+            ProtocolMessage::SetBLOBVector(v) => &mut v.elements,
+            ProtocolMessage::NewBLOBVector(v) => &mut v.elements,
+            _ => return Ok(()),
+        };
+        for element in elements {
+            match self.0.pop_front() {
+                Some(blob) => blob?.deliver(element),
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Compressed BLOBs
+// ============================================================================
+
+/// Format suffix of BLOBs sent compressed, as in `.fits.z`.
+pub const COMPRESSED_SUFFIX: &str = ".z";
+
+/// First byte of gzip data, which zlib data never starts with.
+const GZIP_MAGIC: u8 = 0x1f;
+
+/// Compresses the data of a BLOB to be sent in a `.z` format.
+///
+/// INDIGO compresses BLOBs with gzip.
+///
+/// # Errors
+///
+/// Returns a `BlobError` if compression fails.
+pub fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .and_then(|()| encoder.finish())
+        .map_err(|e| IndigoError::BlobError(format!("Failed to compress BLOB: {}", e)))
+}
+
+/// Decompresses the data of a BLOB received in a `.z` format.
+///
+/// Both the gzip data of INDIGO and the zlib data of INDI are accepted.
+///
+/// # Errors
+///
+/// Returns a `BlobError` if the data is not valid compressed data.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut inflater = Inflater::new();
+    let mut output = Vec::new();
+    inflater.inflate(data, &mut output)?;
+    inflater.finish(&mut output)?;
+    Ok(output)
+}
+
+/// Decompresses a BLOB piece by piece.
+enum Inflater {
+    /// Waiting for the first byte, which tells gzip from zlib.
+    Start,
+    Gzip(GzDecoder<Vec<u8>>),
+    Zlib(ZlibDecoder<Vec<u8>>),
+}
+
+impl Inflater {
+    fn new() -> Self {
+        Inflater::Start
+    }
+
+    /// Decompresses a piece of data, appending the result to `output`.
+    fn inflate(&mut self, input: &[u8], output: &mut Vec<u8>) -> Result<()> {
+        if let (Inflater::Start, Some(&first)) = (&*self, input.first()) {
+            *self = if first == GZIP_MAGIC {
+                Inflater::Gzip(GzDecoder::new(Vec::new()))
+            } else {
+                Inflater::Zlib(ZlibDecoder::new(Vec::new()))
+            };
+        }
+        let inflated = match self {
+            Inflater::Start => return Ok(()),
+            Inflater::Gzip(decoder) => decoder.write_all(input).map(|()| decoder.get_mut()),
+            Inflater::Zlib(decoder) => decoder.write_all(input).map(|()| decoder.get_mut()),
+        }
+        .map_err(inflate_error)?;
+        output.append(inflated);
+        Ok(())
+    }
+
+    /// Decompresses the rest of the data, appending it to `output`.
+    fn finish(self, output: &mut Vec<u8>) -> Result<()> {
+        let mut inflated = match self {
+            Inflater::Start => return Err(inflate_error("no data")),
+            Inflater::Gzip(decoder) => decoder.finish(),
+            Inflater::Zlib(decoder) => decoder.finish(),
+        }
+        .map_err(inflate_error)?;
+        output.append(&mut inflated);
+        Ok(())
+    }
+}
+
+fn inflate_error(e: impl std::fmt::Display) -> IndigoError {
+    IndigoError::BlobError(format!("Failed to decompress BLOB: {}", e))
+}
+
+/// Compresses the BLOBs of a `newBLOBVector`, adding the `.z` suffix to
+/// their formats.
+///
+/// BLOBs already in a `.z` format are sent as they are. The size stays that
+/// of the uncompressed data.
+///
+/// # Errors
+///
+/// Returns a `BlobError` if a BLOB can't be compressed.
+pub(crate) fn compress_blobs(message: &mut ProtocolMessage) -> Result<()> {
+    let ProtocolMessage::NewBLOBVector(vector) = message else {
+        return Ok(());
+    };
+    for element in &mut vector.elements {
+        if element.format.ends_with(COMPRESSED_SUFFIX) {
+            continue;
+        }
+        let data = element.take_data()?;
+        element.data = Some(compress(&data)?);
+        element.value.clear();
+        element.format.push_str(COMPRESSED_SUFFIX);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// BLOBs Sent by URL
+// ============================================================================
+
+/// Largest HTTP response head accepted when fetching a BLOB.
+const MAX_HTTP_HEAD: usize = 64 * 1024;
+
+/// Gets the URL of a BLOB sent by reference instead of inline.
+///
+/// INDIGO servers send URLs such as `/blob/0x10381d798.fits`, relative to
+/// the server, or absolute `http://` URLs. Paths always end with the format
+/// extension, and base64 data never contains a dot, so the two can't be
+/// confused although both may start with a slash.
+pub(crate) fn blob_url(value: &str) -> Option<&str> {
+    let value = value.trim();
+    let is_url = value.starts_with("http://")
+        || value.starts_with("https://")
+        || (value.starts_with('/') && value.contains('.'));
+    is_url.then_some(value)
+}
+
+/// Splits a BLOB URL into the address of the HTTP server and the path.
+fn resolve_url(url: &str, server: Option<SocketAddr>) -> Result<(String, String)> {
+    if url.starts_with("https://") {
+        return Err(IndigoError::NotSupported(format!(
+            "Fetching BLOBs over HTTPS: {}",
+            url
+        )));
+    }
+    match url.strip_prefix("http://") {
+        Some(rest) => {
+            let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            let host = if host.contains(':') {
+                host.to_string()
+            } else {
+                format!("{}:80", host)
+            };
+            let path = if path.is_empty() { "/" } else { path };
+            Ok((host, path.to_string()))
+        }
+        None => {
+            let server = server.ok_or_else(|| {
+                IndigoError::InvalidState(format!("No server to fetch BLOB {} from", url))
+            })?;
+            Ok((server.to_string(), url.to_string()))
+        }
+    }
+}
+
+impl BlobStreamer {
+    /// Fetches the BLOBs of a `setBLOBVector` that were sent by URL.
+    ///
+    /// Relative URLs refer to the INDIGO server, which also speaks HTTP on
+    /// its port. The data goes to the same targets as streamed BLOBs, with
+    /// `value` left empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BlobError` if a BLOB can't be fetched.
+    pub(crate) async fn fetch(
+        &mut self,
+        message: &mut ProtocolMessage,
+        server: Option<SocketAddr>,
+        read_timeout: Duration,
+    ) -> Result<()> {
+        let ProtocolMessage::SetBLOBVector(vector) = message else {
+            return Ok(());
+        };
+        for element in &mut vector.elements {
+            if element.data.is_some() {
+                continue;
+            }
+            let Some(url) = blob_url(&element.value) else {
+                continue;
+            };
+            let (host, path) = resolve_url(url, server)?;
+            let header = BlobHeader {
+                device: vector.attrs.device.clone(),
+                property: vector.attrs.name.clone(),
+                name: element.name.clone(),
+                format: element.format.clone(),
+                size: element.size,
+            };
+            let mut target = Target::new(&header, self.sinks.as_ref());
+            http_get(&host, &path, &mut target, read_timeout).await?;
+            target.finish().await?.deliver(element);
+            element.value.clear();
+        }
+        Ok(())
+    }
+}
+
+/// Fetches a resource with an HTTP/1.1 GET request, writing the body to a
+/// target as it arrives.
+async fn http_get(
+    host: &str,
+    path: &str,
+    target: &mut Target,
+    read_timeout: Duration,
+) -> Result<()> {
+    let failed = |e: &dyn std::fmt::Display| {
+        IndigoError::BlobError(format!(
+            "Failed to fetch BLOB http://{}{}: {}",
+            host, path, e
+        ))
+    };
+    let mut stream = timeout(read_timeout, TcpStream::connect(host))
+        .await
+        .map_err(|e| failed(&e))?
+        .map_err(|e| failed(&e))?;
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, host
+    );
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| failed(&e))?;
+
+    let mut buffer = Vec::new();
+    // Read the status line and headers
+    let head_end = loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if buffer.len() > MAX_HTTP_HEAD {
+            return Err(failed(&"response head too large"));
+        }
+        let count = read_more(&mut stream, &mut buffer, read_timeout)
+            .await
+            .map_err(|e| failed(&e))?;
+        if count == 0 {
+            return Err(failed(&"connection closed"));
+        }
+    };
+    let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let status = lines.next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(failed(&status));
+    }
+    let mut length = None;
+    for (name, value) in lines.filter_map(|line| line.split_once(':')) {
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => {
+                length = Some(value.parse::<usize>().map_err(|e| failed(&e))?);
+            }
+            "transfer-encoding" if !value.eq_ignore_ascii_case("identity") => {
+                return Err(failed(&format!("unsupported transfer encoding {}", value)));
+            }
+            _ => {}
+        }
+    }
+
+    // Read the body, delimited by its length or the end of the connection
+    let mut remaining = length.unwrap_or(usize::MAX);
+    buffer.drain(..head_end);
+    loop {
+        let count = buffer.len().min(remaining);
+        target.write(&buffer[..count]).await?;
+        remaining -= count;
+        buffer.clear();
+        if remaining == 0 {
+            break;
+        }
+        let count = read_more(&mut stream, &mut buffer, read_timeout)
+            .await
+            .map_err(|e| failed(&e))?;
+        if count == 0 {
+            break;
+        }
+    }
+    if length.is_some() && remaining > 0 {
+        return Err(failed(&"connection closed before end of data"));
+    }
+    Ok(())
+}
+
+/// Reads more data from a stream into a buffer, returning the number of
+/// bytes read.
+async fn read_more(
+    stream: &mut TcpStream,
+    buffer: &mut Vec<u8>,
+    read_timeout: Duration,
+) -> std::io::Result<usize> {
+    buffer.reserve(64 * 1024);
+    timeout(read_timeout, stream.read_buf(buffer))
+        .await
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "read timed out"))?
+}
+
+/// Finds the end of a tag starting at the beginning of the data, skipping
+/// attribute values.
+fn find_tag_end(data: &[u8]) -> Option<usize> {
+    let mut quote = None;
+    for (i, &byte) in data.iter().enumerate().skip(1) {
+        match (quote, byte) {
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (Some(q), _) if byte == q => quote = None,
+            (None, b'>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Gets the value of an attribute of a tag.
+fn attribute(tag: &BytesStart, name: &str) -> Option<String> {
+    let attribute = tag.try_get_attribute(name).ok()??;
+    attribute
+        .unescape_value()
+        .ok()
+        .map(|value| value.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{
+        encode_blob, OneBLOB, ProtocolParser, SetBLOBVector, SetVectorAttributes,
+    };
+
+    /// Streams a message fed in pieces, parsing it once complete.
+    async fn stream_in_pieces(
+        streamer: &mut BlobStreamer,
+        message: &[u8],
+        piece: usize,
+    ) -> (usize, Result<ProtocolMessage>) {
+        let mut buffer = Vec::new();
+        let mut largest = 0;
+        for chunk in message.chunks(piece) {
+            buffer.extend_from_slice(chunk);
+            largest = largest.max(buffer.len());
+            streamer.stream(&mut buffer).await;
+        }
+        let blobs = streamer.take();
+        let mut parsed = ProtocolParser::parse_message(&buffer).unwrap();
+        let result = blobs.attach(&mut parsed).map(|()| parsed);
+        (largest, result)
+    }
+
+    fn blob_message(blobs: &[&[u8]]) -> Vec<u8> {
+        blob_message_in(".fits", blobs)
+    }
+
+    fn blob_message_in(format: &str, blobs: &[&[u8]]) -> Vec<u8> {
+        let mut xml =
+            String::from("<setBLOBVector device=\"CCD\" name=\"CCD_IMAGE\" state=\"Ok\">\n");
+        for (i, blob) in blobs.iter().enumerate() {
+            let encoded = encode_blob(blob);
+            let lines: Vec<&str> = encoded
+                .as_bytes()
+                .chunks(76)
+                .map(|line| std::str::from_utf8(line).unwrap())
+                .collect();
+            xml.push_str(&format!(
+                "<oneBLOB name=\"IMAGE{}\" format=\"{}\" size=\"{}\">\n{}\n</oneBLOB>\n",
+                i,
+                format,
+                blob.len(),
+                lines.join("\n")
+            ));
+        }
+        xml.push_str("</setBLOBVector>");
+        xml.into_bytes()
+    }
+
+    #[tokio::test]
+    async fn test_stream_into_memory() {
+        let image: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let message = blob_message(&[&image, b"second"]);
+
+        for piece in [1, 3, 4096] {
+            let mut streamer = BlobStreamer::new(None);
+            let (largest, result) = stream_in_pieces(&mut streamer, &message, piece).await;
+            let ProtocolMessage::SetBLOBVector(v) = result.unwrap() else {
+                panic!("Expected setBLOBVector");
+            };
+            assert_eq!(v.elements.len(), 2);
+            assert_eq!(v.elements[0].value.trim(), "");
+            assert_eq!(v.elements[0].data.as_deref(), Some(&image[..]));
+            assert_eq!(v.elements[1].data.as_deref(), Some(&b"second"[..]));
+            // Only the markup is buffered
+            assert!(largest < 300 + piece, "{} bytes buffered", largest);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_into_sink() {
+        let image: Vec<u8> = (0..10_000u32).map(|i| (i % 13) as u8).collect();
+        let (writer, mut reader) = tokio::io::duplex(64 * 1024);
+        let writer = std::sync::Mutex::new(Some(writer));
+        let factory: BlobSinkFactory = Arc::new(move |header: &BlobHeader| {
+            assert_eq!(header.device, "CCD");
+            assert_eq!(header.property, "CCD_IMAGE");
+            assert_eq!(header.format, ".fits");
+            let writer = writer.lock().unwrap().take()?;
+            Some(Box::new(writer) as BlobSink)
+        });
+
+        let mut streamer = BlobStreamer::new(Some(factory));
+        let message = blob_message(&[&image, b"second"]);
+        let (_, result) = stream_in_pieces(&mut streamer, &message, 1000).await;
+        let ProtocolMessage::SetBLOBVector(v) = result.unwrap() else {
+            panic!("Expected setBLOBVector");
+        };
+        // The first BLOB went to the sink, the second into memory
+        assert_eq!(v.elements[0].data, None);
+        assert_eq!(v.elements[1].data.as_deref(), Some(&b"second"[..]));
+
+        let mut written = Vec::new();
+        tokio::io::AsyncReadExt::read_to_end(&mut reader, &mut written)
+            .await
+            .unwrap();
+        assert_eq!(written, image);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_content_fails_message() {
+        let mut streamer = BlobStreamer::new(None);
+        let message = b"<setBLOBVector device=\"CCD\" name=\"CCD_IMAGE\">\
+            <oneBLOB name=\"IMAGE\" format=\".fits\" size=\"3\">A*==</oneBLOB></setBLOBVector>";
+        let (_, result) = stream_in_pieces(&mut streamer, message, 10).await;
+        assert!(matches!(result, Err(IndigoError::BlobError(_))));
+
+        // The next message is streamed again
+        let message = blob_message(&[b"next"]);
+        let (_, result) = stream_in_pieces(&mut streamer, &message, 10).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_stream_stops_at_end_of_message() {
+        let first = blob_message(&[b"first"]);
+        let second = blob_message(&[b"second"]);
+        let mut buffer = [first, second.clone()].concat();
+
+        let mut streamer = BlobStreamer::new(None);
+        streamer.stream(&mut buffer).await;
+        assert!(buffer.ends_with(&second));
+        let end = buffer.len() - second.len();
+        assert_eq!(streamer.take().0.len(), 1);
+
+        buffer.drain(..end);
+        streamer.stream(&mut buffer).await;
+        assert_eq!(streamer.take().0.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_other_messages_untouched() {
+        let messages: [&[u8]; 3] = [
+            b"<defTextVector device=\"D\" name=\"N\" state=\"Idle\" perm=\"ro\">\
+              <defText name=\"T\">a &lt; b</defText></defTextVector>",
+            b"<setBLOBVector device=\"D\" name=\"N\"/>",
+            b"{\"setTextVector\":{\"device\":\"<oneBLOB>\",\"name\":\"N\",\"items\":[]}}",
+        ];
+        for message in messages {
+            let mut streamer = BlobStreamer::new(None);
+            let mut buffer = message.to_vec();
+            streamer.stream(&mut buffer).await;
+            assert_eq!(buffer, message);
+            assert!(streamer.take().0.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_compressed_blobs() {
+        let image: Vec<u8> = (0..200_000u32).map(|i| (i / 100 % 7) as u8).collect();
+        let gzip = compress(&image).unwrap();
+        let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), Compression::fast());
+        zlib.write_all(&image).unwrap();
+        let zlib = zlib.finish().unwrap();
+        assert!(gzip.len() < image.len() / 10);
+
+        let message = blob_message_in(".fits.z", &[&gzip, &zlib]);
+        let mut streamer = BlobStreamer::new(None);
+        let (_, result) = stream_in_pieces(&mut streamer, &message, 100).await;
+        let ProtocolMessage::SetBLOBVector(v) = result.unwrap() else {
+            panic!("Expected setBLOBVector");
+        };
+        for (element, compressed) in v.elements.iter().zip([&gzip, &zlib]) {
+            assert_eq!(element.format, ".fits");
+            assert_eq!(element.size, image.len());
+            assert_eq!(element.compressed_size, Some(compressed.len()));
+            assert!(element.data.as_deref() == Some(&image[..]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_compressed_blob_into_sink() {
+        let image = b"SIMPLE  =                    T".repeat(1000);
+        let (writer, mut reader) = tokio::io::duplex(64 * 1024);
+        let writer = std::sync::Mutex::new(Some(writer));
+        let factory: BlobSinkFactory = Arc::new(move |header: &BlobHeader| {
+            assert_eq!(header.format, ".fits");
+            let writer = writer.lock().unwrap().take()?;
+            Some(Box::new(writer) as BlobSink)
+        });
+
+        let mut streamer = BlobStreamer::new(Some(factory));
+        let message = blob_message_in(".fits.z", &[&compress(&image).unwrap()]);
+        let (_, result) = stream_in_pieces(&mut streamer, &message, 1000).await;
+        let ProtocolMessage::SetBLOBVector(v) = result.unwrap() else {
+            panic!("Expected setBLOBVector");
+        };
+        assert_eq!(v.elements[0].data, None);
+        assert_eq!(v.elements[0].size, image.len());
+
+        let mut written = Vec::new();
+        tokio::io::AsyncReadExt::read_to_end(&mut reader, &mut written)
+            .await
+            .unwrap();
+        assert_eq!(written, image);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_compressed_data_fails_message() {
+        assert!(decompress(b"not compressed").is_err());
+        assert!(decompress(b"").is_err());
+
+        let mut streamer = BlobStreamer::new(None);
+        let message = blob_message_in(".fits.z", &[b"not compressed"]);
+        let (_, result) = stream_in_pieces(&mut streamer, &message, 10).await;
+        assert!(matches!(result, Err(IndigoError::BlobError(_))));
+    }
+
+    #[test]
+    fn test_compress_new_blobs() {
+        let mut message = ProtocolMessage::NewBLOBVector(crate::protocol::NewBLOBVector {
+            attrs: crate::protocol::NewVectorAttributes {
+                device: "CCD".to_string(),
+                name: "CCD_IMAGE".to_string(),
+                timestamp: None,
+            },
+            elements: vec![OneBLOB {
+                name: "IMAGE".to_string(),
+                size: 5,
+                format: ".fits".to_string(),
+                value: encode_blob(b"image"),
+                data: None,
+                compressed_size: None,
+            }],
+        });
+        compress_blobs(&mut message).unwrap();
+        let ProtocolMessage::NewBLOBVector(v) = message else {
+            panic!("Expected newBLOBVector");
+        };
+        assert_eq!(v.elements[0].format, ".fits.z");
+        assert_eq!(v.elements[0].size, 5);
+        let data = v.elements[0].data.as_deref().unwrap();
+        assert_eq!(decompress(data).unwrap(), b"image");
+    }
+
+    #[test]
+    fn test_blob_url() {
+        assert_eq!(
+            blob_url("/blob/0x10381d798.fits"),
+            Some("/blob/0x10381d798.fits")
+        );
+        assert_eq!(
+            blob_url(" http://host/a.fits\n"),
+            Some("http://host/a.fits")
+        );
+        // Base64 of a JPEG starts with a slash
+        assert_eq!(blob_url("/9j/4AAQSkZJRgABAQ=="), None);
+        assert_eq!(blob_url(""), None);
+
+        let server = "10.0.0.2:7624".parse().ok();
+        assert_eq!(
+            resolve_url("/blob/1.fits", server).unwrap(),
+            ("10.0.0.2:7624".to_string(), "/blob/1.fits".to_string())
+        );
+        assert_eq!(
+            resolve_url("http://imager.local/blob/1.fits", server).unwrap(),
+            ("imager.local:80".to_string(), "/blob/1.fits".to_string())
+        );
+        assert!(resolve_url("https://imager.local/1.fits", server).is_err());
+        assert!(resolve_url("/blob/1.fits", None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_body_until_connection_closes() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 1024];
+            let _ = stream.read(&mut request).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.0 200 OK\r\nContent-Type: image/fits\r\n\r\nSIMPLE")
+                .await
+                .unwrap();
+            stream.write_all(b" = T").await.unwrap();
+        });
+
+        let vector = SetBLOBVector {
+            attrs: SetVectorAttributes {
+                device: "CCD".to_string(),
+                name: "CCD_IMAGE".to_string(),
+                state: None,
+                timeout: None,
+                timestamp: None,
+                message: None,
+            },
+            elements: vec![OneBLOB {
+                name: "IMAGE".to_string(),
+                size: 10,
+                format: ".fits".to_string(),
+                value: "/blob/0x1.fits".to_string(),
+                data: None,
+                compressed_size: None,
+            }],
+        };
+        let mut message = ProtocolMessage::SetBLOBVector(vector);
+        let mut streamer = BlobStreamer::new(None);
+        streamer
+            .fetch(&mut message, Some(addr), Duration::from_secs(5))
+            .await
+            .unwrap();
+        let ProtocolMessage::SetBLOBVector(v) = message else {
+            panic!("Expected setBLOBVector");
+        };
+        assert_eq!(v.elements[0].value, "");
+        assert_eq!(v.elements[0].data.as_deref(), Some(&b"SIMPLE = T"[..]));
+    }
+}
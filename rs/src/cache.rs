@@ -0,0 +1,445 @@
+//! Client-side Property Cache
+//!
+//! This module maintains a queryable model of everything the server has
+//! defined, kept up to date from `defXXXVector`, `setXXXVector` and
+//! `delProperty` messages.
+//!
+//! # Overview
+//!
+//! The cache is organized as devices containing properties, which in turn
+//! contain items:
+//!
+//! - **Definitions** replace the cached property with the full definition
+//! - **Updates** are merged into the previously defined property, keeping
+//!   metadata (labels, groups, number ranges) that set messages do not carry
+//! - **Deletions** remove a single property, or the whole device when no
+//!   property name is given
+//!
+//! Device metadata ([`DeviceInfo`]) is derived from the standard `INFO`
+//! property whenever it is defined or updated, and the devices connected
+//! according to their `CONNECTION` property are tracked in
+//! [`ConnectedDevices`].
+
+use libindigo::name;
+use libindigo::types::{Device, DeviceInfo, Property, PropertyValue, SwitchState};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Names of the connected devices, shared so that they can be read without
+/// locking the cache.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectedDevices(Arc<Mutex<HashSet<String>>>);
+
+impl ConnectedDevices {
+    /// Returns `true` if the device reports being connected.
+    pub fn contains(&self, device: &str) -> bool {
+        self.names().contains(device)
+    }
+
+    /// Records the connection state reported by a `CONNECTION` property.
+    fn track(&self, property: &Property) {
+        if property.name != name::CONNECTION_PROPERTY {
+            return;
+        }
+        let connected = property
+            .items
+            .get(name::CONNECTION_CONNECTED_ITEM)
+            .is_some_and(|item| {
+                item.value
+                    == PropertyValue::Switch {
+                        state: SwitchState::On,
+                    }
+            });
+        let mut names = self.names();
+        if connected {
+            names.insert(property.device.clone());
+        } else {
+            names.remove(&property.device);
+        }
+    }
+
+    fn names(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Cache of devices and properties reported by an INDIGO server.
+#[derive(Debug, Default)]
+pub struct PropertyCache {
+    /// Known devices indexed by name.
+    devices: HashMap<String, Device>,
+    /// Devices whose `CONNECTION` property reports them connected.
+    connected: ConnectedDevices,
+}
+
+impl PropertyCache {
+    /// Creates a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a property definition, replacing any previous definition.
+    pub fn define(&mut self, property: Property) {
+        let device = self
+            .devices
+            .entry(property.device.clone())
+            .or_insert_with(|| Device::new(property.device.clone()));
+
+        if property.name == name::INFO_PROPERTY {
+            update_device_info(&mut device.info, &property);
+        }
+        self.connected.track(&property);
+        device.properties.insert(property.name.clone(), property);
+    }
+
+    /// Merges a property update into the cached definition.
+    ///
+    /// Item values, state, timeout, timestamp and message are taken from the
+    /// update; everything else is preserved from the definition. Updates for
+    /// properties that have not been defined are stored as-is.
+    ///
+    /// Returns the merged property.
+    pub fn update(&mut self, property: Property) -> Property {
+        let device = self
+            .devices
+            .entry(property.device.clone())
+            .or_insert_with(|| Device::new(property.device.clone()));
+
+        let merged = match device.properties.get_mut(&property.name) {
+            Some(cached) => {
+                merge_update(cached, property);
+                cached.clone()
+            }
+            None => {
+                device
+                    .properties
+                    .insert(property.name.clone(), property.clone());
+                property
+            }
+        };
+
+        if merged.name == name::INFO_PROPERTY {
+            update_device_info(&mut device.info, &merged);
+        }
+        self.connected.track(&merged);
+        merged
+    }
+
+    /// Removes a property, or the whole device if `name` is `None`.
+    pub fn delete(&mut self, device: &str, name: Option<&str>) {
+        if name.is_none_or(|name| name == name::CONNECTION_PROPERTY) {
+            self.connected.names().remove(device);
+        }
+        match name {
+            Some(name) => {
+                if let Some(cached) = self.devices.get_mut(device) {
+                    cached.properties.remove(name);
+                }
+            }
+            None => {
+                self.devices.remove(device);
+            }
+        }
+    }
+
+    /// Gets a device by name.
+    pub fn device(&self, name: &str) -> Option<&Device> {
+        self.devices.get(name)
+    }
+
+    /// Gets a property by device and property name.
+    pub fn property(&self, device: &str, name: &str) -> Option<&Property> {
+        self.devices
+            .get(device)
+            .and_then(|d| d.properties.get(name))
+    }
+
+    /// Returns all known devices.
+    pub fn devices(&self) -> impl Iterator<Item = &Device> {
+        self.devices.values()
+    }
+
+    /// Gets the shared names of the connected devices.
+    pub fn connected(&self) -> ConnectedDevices {
+        self.connected.clone()
+    }
+
+    /// Removes all cached devices and properties.
+    pub fn clear(&mut self) {
+        self.devices.clear();
+        self.connected.names().clear();
+    }
+}
+
+/// Merges the values carried by a set message into a cached property.
+fn merge_update(cached: &mut Property, update: Property) {
+    cached.state = update.state;
+    cached.timeout = update.timeout.or(cached.timeout);
+    cached.timestamp = update.timestamp;
+    cached.message = update.message;
+
+    for (item_name, item) in update.items {
+        match cached.items.get_mut(&item_name) {
+            Some(existing) => merge_value(&mut existing.value, item.value),
+            None => {
+                cached.items.insert(item_name, item);
+            }
+        }
+    }
+}
+
+/// Merges an updated item value, keeping number limits from the definition.
+fn merge_value(existing: &mut PropertyValue, update: PropertyValue) {
+    match (existing, update) {
+        (
+            PropertyValue::Number { value, .. },
+            PropertyValue::Number {
+                value: new_value, ..
+            },
+        ) => *value = new_value,
+        (existing, update) => *existing = update,
+    }
+}
+
+/// Refreshes device metadata from the standard `INFO` property.
+fn update_device_info(info: &mut DeviceInfo, property: &Property) {
+    let text = |item: &str| match property.items.get(item).map(|i| &i.value) {
+        Some(PropertyValue::Text(value)) if !value.is_empty() => Some(value.clone()),
+        _ => None,
+    };
+
+    if let Some(interface) =
+        text(name::INFO_DEVICE_INTERFACE_ITEM).and_then(|s| parse_interface(&s))
+    {
+        info.interface = interface;
+        info.driver_interface = Some(interface);
+    }
+    if let Some(version) = text(name::INFO_DEVICE_VERSION_ITEM) {
+        info.version = Some(version);
+    }
+    if let Some(driver) = text(name::INFO_DEVICE_DRIVER_ITEM) {
+        info.driver_name = Some(driver);
+    }
+    if let Some(version) = text("DRIVER_VERSION") {
+        info.driver_version = Some(version);
+    }
+}
+
+/// Parses a `DEVICE_INTERFACE` value, which servers send in decimal or hex.
+fn parse_interface(value: &str) -> Option<u32> {
+    let value = value.trim();
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libindigo::types::{PropertyItem, PropertyState, PropertyType};
+
+    fn number_property(value: f64, min: f64, max: f64) -> Property {
+        Property::builder()
+            .device("CCD Simulator")
+            .name("CCD_EXPOSURE")
+            .group("Camera")
+            .label("Exposure")
+            .property_type(PropertyType::Number)
+            .item(PropertyItem::new(
+                "EXPOSURE",
+                "Duration",
+                PropertyValue::Number {
+                    value,
+                    min,
+                    max,
+                    step: 0.01,
+                    format: "%.2f".to_string(),
+                },
+            ))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_define_and_lookup() {
+        let mut cache = PropertyCache::new();
+        cache.define(number_property(1.0, 0.0, 3600.0));
+
+        assert!(cache.device("CCD Simulator").is_some());
+        assert!(cache.property("CCD Simulator", "CCD_EXPOSURE").is_some());
+        assert!(cache.property("CCD Simulator", "CCD_TEMPERATURE").is_none());
+        assert_eq!(cache.devices().count(), 1);
+        assert_eq!(
+            cache.device("CCD Simulator").unwrap().groups(),
+            vec!["Camera".to_string()]
+        );
+    }
+
+    fn connection(connected: bool) -> Property {
+        let state = |on| {
+            if on {
+                SwitchState::On
+            } else {
+                SwitchState::Off
+            }
+        };
+        Property::builder()
+            .device("CCD Simulator")
+            .name(name::CONNECTION_PROPERTY)
+            .property_type(PropertyType::Switch)
+            .item(PropertyItem::new(
+                name::CONNECTION_CONNECTED_ITEM,
+                "Connected",
+                PropertyValue::switch(state(connected)),
+            ))
+            .item(PropertyItem::new(
+                name::CONNECTION_DISCONNECTED_ITEM,
+                "Disconnected",
+                PropertyValue::switch(state(!connected)),
+            ))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_tracks_connected_devices() {
+        let mut cache = PropertyCache::new();
+        let connected = cache.connected();
+        cache.define(connection(false));
+        assert!(!connected.contains("CCD Simulator"));
+
+        cache.update(connection(true));
+        assert!(connected.contains("CCD Simulator"));
+        cache.update(connection(false));
+        assert!(!connected.contains("CCD Simulator"));
+
+        cache.update(connection(true));
+        cache.delete("CCD Simulator", None);
+        assert!(!connected.contains("CCD Simulator"));
+        cache.define(connection(true));
+        cache.clear();
+        assert!(!connected.contains("CCD Simulator"));
+    }
+
+    #[test]
+    fn test_update_keeps_number_limits() {
+        let mut cache = PropertyCache::new();
+        cache.define(number_property(1.0, 0.0, 3600.0));
+
+        let mut update = number_property(5.0, f64::MIN, f64::MAX);
+        update.group = String::new();
+        update.label = String::new();
+        update.state = PropertyState::Busy;
+
+        let merged = cache.update(update);
+        assert_eq!(merged.state, PropertyState::Busy);
+        assert_eq!(merged.group, "Camera");
+        assert_eq!(merged.label, "Exposure");
+        assert_eq!(
+            merged.items["EXPOSURE"].value,
+            PropertyValue::Number {
+                value: 5.0,
+                min: 0.0,
+                max: 3600.0,
+                step: 0.01,
+                format: "%.2f".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_update_keeps_switch_rule_and_perm() {
+        use libindigo::types::{PropertyPerm, SwitchRule, SwitchState};
+
+        let switch =
+            |state| PropertyItem::new("CONNECTED", "Connected", PropertyValue::Switch { state });
+        let mut cache = PropertyCache::new();
+        cache.define(
+            Property::builder()
+                .device("CCD Simulator")
+                .name("CONNECTION")
+                .property_type(PropertyType::Switch)
+                .perm(PropertyPerm::ReadWrite)
+                .switch_rule(SwitchRule::OneOfMany)
+                .item(switch(SwitchState::Off))
+                .build()
+                .unwrap(),
+        );
+
+        let mut update = Property::builder()
+            .device("CCD Simulator")
+            .name("CONNECTION")
+            .property_type(PropertyType::Switch)
+            .perm(PropertyPerm::ReadOnly)
+            .item(switch(SwitchState::On))
+            .build()
+            .unwrap();
+        update.items.get_mut("CONNECTED").unwrap().label = String::new();
+
+        let merged = cache.update(update);
+        assert_eq!(merged.switch_rule, Some(SwitchRule::OneOfMany));
+        assert_eq!(merged.perm, PropertyPerm::ReadWrite);
+        assert_eq!(merged.items["CONNECTED"].label, "Connected");
+        assert_eq!(
+            merged.items["CONNECTED"].value,
+            PropertyValue::Switch {
+                state: SwitchState::On
+            }
+        );
+    }
+
+    #[test]
+    fn test_delete_property_and_device() {
+        let mut cache = PropertyCache::new();
+        cache.define(number_property(1.0, 0.0, 3600.0));
+
+        cache.delete("CCD Simulator", Some("CCD_EXPOSURE"));
+        assert!(cache.property("CCD Simulator", "CCD_EXPOSURE").is_none());
+        assert!(cache.device("CCD Simulator").is_some());
+
+        cache.delete("CCD Simulator", None);
+        assert!(cache.device("CCD Simulator").is_none());
+    }
+
+    #[test]
+    fn test_info_property_populates_device_info() {
+        let mut cache = PropertyCache::new();
+        cache.define(
+            Property::builder()
+                .device("CCD Simulator")
+                .name(name::INFO_PROPERTY)
+                .property_type(PropertyType::Text)
+                .item(PropertyItem::new(
+                    name::INFO_DEVICE_INTERFACE_ITEM,
+                    "Interface",
+                    PropertyValue::text("1"),
+                ))
+                .item(PropertyItem::new(
+                    name::INFO_DEVICE_DRIVER_ITEM,
+                    "Driver",
+                    PropertyValue::text("indigo_ccd_simulator"),
+                ))
+                .build()
+                .unwrap(),
+        );
+
+        let info = &cache.device("CCD Simulator").unwrap().info;
+        assert_eq!(info.interface, 1);
+        assert_eq!(info.driver_name.as_deref(), Some("indigo_ccd_simulator"));
+    }
+
+    #[test]
+    fn test_parse_interface() {
+        assert_eq!(parse_interface("9"), Some(9));
+        assert_eq!(parse_interface("0x10"), Some(16));
+        assert_eq!(parse_interface("bogus"), None);
+    }
+}
@@ -36,12 +36,19 @@
 
 use async_trait::async_trait;
 use libindigo::client::strategy::ClientStrategy;
+use libindigo::client::{ConnectionEvent, PropertyEvent, ReconnectPolicy};
+use libindigo::device::traits::DeviceHandle;
+use libindigo::device::DeviceInterface;
 use libindigo::error::{IndigoError, Result};
 use libindigo::types::property::PropertyItem;
-use libindigo::types::value::{LightState, PropertyValue, SwitchState as DomainSwitchState};
-use libindigo::types::{BlobTransferMode, Property, PropertyPerm, PropertyState, PropertyType};
+use libindigo::types::value::{
+    LightState, PropertyValue, SwitchRule, SwitchState as DomainSwitchState,
+};
+use libindigo::types::{
+    BlobTransferMode, Device, Property, PropertyPerm, PropertyState, PropertyType,
+};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex, PoisonError};
 use tokio::sync::{mpsc, Mutex};
 use tokio::task::JoinHandle;
 
@@ -50,13 +57,16 @@ use crate::monitoring::ServerMonitor;
 #[cfg(feature = "monitoring")]
 use libindigo::client::monitoring::{ClientEvent, MonitoringConfig, MonitoringEvent};
 
+use crate::blob::{self, BlobSinkFactory};
+use crate::cache::{ConnectedDevices, PropertyCache};
 use crate::protocol::{
-    decode_blob, encode_blob, BLOBEnable, EnableBLOB, GetProperties, NewBLOBVector,
-    NewNumberVector, NewSwitchVector, NewTextVector, NewVectorAttributes, OneBLOB, OneNumber,
-    OneSwitch, OneText, ProtocolMessage, SwitchState as ProtocolSwitchState,
+    BLOBEnable, EnableBLOB, GetProperties, NewBLOBVector, NewNumberVector, NewSwitchVector,
+    NewTextVector, NewVectorAttributes, OneBLOB, OneNumber, OneSwitch, OneText, ProtocolMessage,
+    SwitchRule as ProtocolSwitchRule, SwitchState as ProtocolSwitchState,
 };
 use crate::protocol_negotiation::{ProtocolNegotiator, ProtocolType};
-use crate::transport::{Transport, WriteTransport};
+use crate::recording::SessionRecorder;
+use crate::transport::{ReadTransport, Transport, WriteTransport};
 
 /// Rust client strategy implementation.
 ///
@@ -71,6 +81,10 @@ use crate::transport::{Transport, WriteTransport};
 pub struct RsClientStrategy {
     /// Shared internal state.
     state: Arc<Mutex<ClientState>>,
+    /// Reconnect policy, shared with the state and set without awaiting it.
+    reconnect_policy: Arc<StdMutex<Option<ReconnectPolicy>>>,
+    /// Connected devices, shared with the property cache.
+    connected: ConnectedDevices,
 }
 
 /// Internal client state.
@@ -84,14 +98,32 @@ struct ClientState {
     property_rx: Option<mpsc::UnboundedReceiver<Property>>,
     /// List of property subscribers for multi-subscriber pattern.
     property_subscribers: Vec<mpsc::UnboundedSender<Property>>,
+    /// List of property event subscribers.
+    event_subscribers: Vec<mpsc::UnboundedSender<PropertyEvent>>,
+    /// Cache of devices and properties defined by the server.
+    cache: PropertyCache,
     /// Background task handle for receiving messages.
     background_task: Option<JoinHandle<()>>,
     /// Connection state flag.
     connected: bool,
+    /// URL of the server, kept for reconnecting.
+    url: Option<String>,
+    /// Reconnect policy, if automatic reconnection is enabled.
+    reconnect_policy: Arc<StdMutex<Option<ReconnectPolicy>>>,
+    /// BLOB modes requested with `enable_blob`, restored after reconnecting.
+    blob_modes: Vec<EnableBLOB>,
+    /// List of connection event subscribers.
+    connection_subscribers: Vec<mpsc::UnboundedSender<ConnectionEvent>>,
     /// Negotiated protocol type.
     protocol: ProtocolType,
     /// Protocol negotiator for establishing protocol.
     negotiator: ProtocolNegotiator,
+    /// Recorder of the session, applied to every connection.
+    recorder: Option<SessionRecorder>,
+    /// Sinks for the content of received BLOBs, applied to every connection.
+    blob_sinks: Option<BlobSinkFactory>,
+    /// Whether BLOBs sent to the server are compressed.
+    compress_blobs: bool,
     /// Monitoring configuration (when monitoring feature is enabled).
     #[cfg(feature = "monitoring")]
     monitoring_config: Option<MonitoringConfig>,
@@ -103,6 +135,133 @@ struct ClientState {
     monitoring_subscribers: Vec<mpsc::UnboundedSender<ClientEvent>>,
 }
 
+impl ClientState {
+    /// Returns `true` while the receiver task is running, including while it
+    /// is reconnecting.
+    fn session_active(&self) -> bool {
+        self.background_task
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Notifies connection event subscribers, removing disconnected ones.
+    fn emit_connection_event(&mut self, event: ConnectionEvent) {
+        self.connection_subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    /// Records a BLOB mode so it can be restored after reconnecting.
+    ///
+    /// A later request for the same device and property replaces the earlier one.
+    fn record_blob_mode(&mut self, enable: EnableBLOB) {
+        self.blob_modes
+            .retain(|m| m.device != enable.device || m.name != enable.name);
+        self.blob_modes.push(enable);
+    }
+    /// Applies a message received from the server.
+    ///
+    /// Keeps the property cache up to date, then notifies property and
+    /// event subscribers. Disconnected subscribers are removed.
+    fn dispatch_message(&mut self, msg: ProtocolMessage) {
+        let event = match msg {
+            ProtocolMessage::DelProperty(del) => {
+                self.cache.delete(&del.device, del.name.as_deref());
+                PropertyEvent::Deleted {
+                    device: del.device,
+                    name: del.name,
+                    message: del.message,
+                    timestamp: del.timestamp,
+                }
+            }
+            ProtocolMessage::Message(m) => match m.message {
+                Some(message) => PropertyEvent::Message {
+                    device: m.device,
+                    message,
+                    timestamp: m.timestamp,
+                },
+                None => {
+                    tracing::trace!("Ignoring message without text");
+                    return;
+                }
+            },
+            msg => {
+                let is_definition = matches!(
+                    msg,
+                    ProtocolMessage::DefTextVector(_)
+                        | ProtocolMessage::DefNumberVector(_)
+                        | ProtocolMessage::DefSwitchVector(_)
+                        | ProtocolMessage::DefLightVector(_)
+                        | ProtocolMessage::DefBLOBVector(_)
+                );
+
+                // Set messages may omit the state, in which case it is unchanged
+                let keeps_state = match &msg {
+                    ProtocolMessage::SetTextVector(v) => v.attrs.state.is_none(),
+                    ProtocolMessage::SetNumberVector(v) => v.attrs.state.is_none(),
+                    ProtocolMessage::SetSwitchVector(v) => v.attrs.state.is_none(),
+                    ProtocolMessage::SetLightVector(v) => v.attrs.state.is_none(),
+                    ProtocolMessage::SetBLOBVector(v) => v.attrs.state.is_none(),
+                    _ => false,
+                };
+
+                // Convert protocol message to property
+                let Some(mut property) = RsClientStrategy::convert_to_property(msg) else {
+                    tracing::trace!("Message did not convert to property (control message)");
+                    return;
+                };
+                tracing::debug!(
+                    "Converted to property: device={}, name={}, items={}",
+                    property.device,
+                    property.name,
+                    property.items.len()
+                );
+
+                // Keep the property cache up to date. Updates are merged into
+                // the definition, so subscribers always see complete metadata.
+                let property = if is_definition {
+                    self.cache.define(property.clone());
+                    property
+                } else {
+                    if keeps_state {
+                        if let Some(cached) = self.cache.property(&property.device, &property.name)
+                        {
+                            property.state = cached.state;
+                        }
+                    }
+                    self.cache.update(property)
+                };
+
+                // Send to legacy channel (backward compatibility)
+                if let Some(ref tx) = self.property_tx {
+                    let _ = tx.send(property.clone());
+                }
+
+                // Broadcast to all property subscribers
+                let subscriber_count = self.property_subscribers.len();
+                self.property_subscribers
+                    .retain(|subscriber| subscriber.send(property.clone()).is_ok());
+
+                let retained_count = self.property_subscribers.len();
+                if retained_count < subscriber_count {
+                    tracing::debug!(
+                        "Removed {} disconnected subscribers",
+                        subscriber_count - retained_count
+                    );
+                }
+
+                if is_definition {
+                    PropertyEvent::Defined(property)
+                } else {
+                    PropertyEvent::Updated(property)
+                }
+            }
+        };
+
+        self.event_subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
 impl RsClientStrategy {
     /// Creates a new Rust client strategy.
     ///
@@ -130,16 +289,28 @@ impl RsClientStrategy {
     /// let strategy = RsClientStrategy::with_protocol_negotiator(negotiator);
     /// ```
     pub fn with_protocol_negotiator(negotiator: ProtocolNegotiator) -> Self {
+        let reconnect_policy = Arc::new(StdMutex::new(None));
+        let cache = PropertyCache::new();
+        let connected = cache.connected();
         RsClientStrategy {
             state: Arc::new(Mutex::new(ClientState {
                 write_transport: None,
                 property_tx: None,
                 property_rx: None,
                 property_subscribers: Vec::new(),
+                event_subscribers: Vec::new(),
+                cache,
                 background_task: None,
                 connected: false,
+                url: None,
+                reconnect_policy: Arc::clone(&reconnect_policy),
+                blob_modes: Vec::new(),
+                connection_subscribers: Vec::new(),
                 protocol: ProtocolType::default(),
                 negotiator,
+                recorder: None,
+                blob_sinks: None,
+                compress_blobs: false,
                 #[cfg(feature = "monitoring")]
                 monitoring_config: None,
                 #[cfg(feature = "monitoring")]
@@ -147,9 +318,31 @@ impl RsClientStrategy {
                 #[cfg(feature = "monitoring")]
                 monitoring_subscribers: Vec::new(),
             })),
+            reconnect_policy,
+            connected,
         }
     }
 
+    /// Creates another handle to the same client.
+    ///
+    /// The handle shares the connection, property cache and subscribers, and
+    /// is used by remote device implementations to talk to their device.
+    pub(crate) fn shared(&self) -> Self {
+        RsClientStrategy {
+            state: Arc::clone(&self.state),
+            reconnect_policy: Arc::clone(&self.reconnect_policy),
+            connected: self.connected.clone(),
+        }
+    }
+
+    /// Returns `true` if the `CONNECTION` property of a device last reported
+    /// it connected.
+    ///
+    /// Unlike the other accessors, this doesn't wait for the client state.
+    pub fn is_device_connected(&self, device: &str) -> bool {
+        self.connected.contains(device)
+    }
+
     /// Sets the protocol preference for this client.
     ///
     /// This should be called before connecting to the server.
@@ -174,6 +367,53 @@ impl RsClientStrategy {
         state.negotiator = ProtocolNegotiator::new(protocol, fallback_enabled);
     }
 
+    /// Records the messages of the session.
+    ///
+    /// Recording starts with the next connection and continues across
+    /// reconnects. Pass `None` to stop recording from the next connection.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let strategy = RsClientStrategy::new();
+    /// strategy.set_recorder(Some(SessionRecorder::create("session.jsonl")?)).await;
+    /// ```
+    pub async fn set_recorder(&self, recorder: Option<SessionRecorder>) {
+        let mut state = self.state.lock().await;
+        state.recorder = recorder;
+    }
+
+    /// Chooses sinks for the content of received BLOBs.
+    ///
+    /// The content of BLOBs is decoded as it arrives, into memory unless the
+    /// factory provides a sink. Applies from the next connection. See
+    /// [`crate::blob`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// strategy
+    ///     .set_blob_sink_factory(Some(Arc::new(|header: &BlobHeader| {
+    ///         let file = std::fs::File::create(format!("{}{}", header.name, header.format)).ok()?;
+    ///         Some(Box::new(tokio::fs::File::from_std(file)) as BlobSink)
+    ///     })))
+    ///     .await;
+    /// ```
+    pub async fn set_blob_sink_factory(&self, sinks: Option<BlobSinkFactory>) {
+        let mut state = self.state.lock().await;
+        state.blob_sinks = sinks;
+    }
+
+    /// Compresses the BLOBs sent to the server.
+    ///
+    /// BLOBs are sent in `.z` formats, such as `.fits.z`, which INDIGO and
+    /// INDI servers decompress. Compressed BLOBs received from the server
+    /// are always decompressed, see [`crate::blob`].
+    pub async fn set_blob_compression(&self, enabled: bool) {
+        let mut state = self.state.lock().await;
+        state.compress_blobs = enabled;
+    }
+
     /// Gets the currently negotiated protocol type.
     ///
     /// Returns the protocol that was successfully negotiated with the server.
@@ -238,6 +478,40 @@ impl RsClientStrategy {
         rx
     }
 
+    /// Gets a snapshot of a device and its properties from the property cache.
+    ///
+    /// The cache is kept up to date from property definitions, updates and
+    /// deletions received from the server, so no round-trip is made.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(device) = strategy.device("CCD Simulator").await {
+    ///     for group in device.groups() {
+    ///         println!("{}: {} properties", group, device.properties_in_group(&group).count());
+    ///     }
+    /// }
+    /// ```
+    pub async fn device(&self, name: &str) -> Option<Device> {
+        let state = self.state.lock().await;
+        state.cache.device(name).cloned()
+    }
+
+    /// Gets a snapshot of a property from the property cache.
+    ///
+    /// Returns `None` if the server has not defined the property, or has
+    /// since deleted it.
+    pub async fn property(&self, device: &str, name: &str) -> Option<Property> {
+        let state = self.state.lock().await;
+        state.cache.property(device, name).cloned()
+    }
+
+    /// Gets a snapshot of all devices currently known from the property cache.
+    pub async fn devices(&self) -> Vec<Device> {
+        let state = self.state.lock().await;
+        state.cache.devices().cloned().collect()
+    }
+
     /// Starts the background task for receiving messages from the server.
     ///
     /// This task continuously reads messages from the transport, converts them
@@ -269,56 +543,25 @@ impl RsClientStrategy {
                         message_count += 1;
                         tracing::debug!("Received message #{}: {:?}", message_count, msg);
 
-                        // Convert protocol message to property
-                        match Self::convert_to_property(msg) {
-                            Some(property) => {
-                                tracing::debug!(
-                                    "Converted to property: device={}, name={}, items={}",
-                                    property.device,
-                                    property.name,
-                                    property.items.len()
-                                );
-
-                                // Broadcast property update to all subscribers
-                                let mut state = task_state.lock().await;
-
-                                // Send to legacy channel (backward compatibility)
-                                if let Some(ref tx) = state.property_tx {
-                                    let _ = tx.send(property.clone());
-                                }
-
-                                // Broadcast to all subscribers
-                                // Remove disconnected subscribers while iterating
-                                let subscriber_count = state.property_subscribers.len();
-                                state
-                                    .property_subscribers
-                                    .retain(|subscriber| subscriber.send(property.clone()).is_ok());
-
-                                let retained_count = state.property_subscribers.len();
-                                if retained_count < subscriber_count {
-                                    tracing::debug!(
-                                        "Removed {} disconnected subscribers",
-                                        subscriber_count - retained_count
-                                    );
-                                }
-                            }
-                            None => {
-                                tracing::trace!(
-                                    "Message did not convert to property (control message)"
-                                );
-                            }
-                        }
+                        let mut state = task_state.lock().await;
+                        state.dispatch_message(msg);
                     }
                     Err(e) => {
                         tracing::warn!("Error receiving message: {}", e);
 
                         // Check if this is a connection error
                         if matches!(e, IndigoError::ConnectionError(_)) {
-                            // Connection closed, exit task
-                            tracing::info!("Connection closed, exiting receiver task");
-                            let mut state = task_state.lock().await;
-                            state.connected = false;
-                            break;
+                            // Connection closed, reconnect if enabled or exit task
+                            match Self::reconnect(&task_state, e.to_string()).await {
+                                Some(transport) => {
+                                    read_transport = transport;
+                                    continue;
+                                }
+                                None => {
+                                    tracing::info!("Connection closed, exiting receiver task");
+                                    break;
+                                }
+                            }
                         }
                         // For other errors, continue trying
                         tracing::debug!("Non-fatal error, continuing to receive");
@@ -341,6 +584,116 @@ impl RsClientStrategy {
         Ok(())
     }
 
+    /// Handles a lost connection, reconnecting if a reconnect policy is set.
+    ///
+    /// Returns the read half of the new connection, or `None` if reconnection
+    /// is disabled or all attempts failed.
+    async fn reconnect(state: &Arc<Mutex<ClientState>>, reason: String) -> Option<ReadTransport> {
+        let (url, policy) = {
+            let mut state = state.lock().await;
+            state.connected = false;
+            state.write_transport = None;
+            state.emit_connection_event(ConnectionEvent::Disconnected { reason });
+            let policy = state
+                .reconnect_policy
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone();
+            match (state.url.clone(), policy) {
+                (Some(url), Some(policy)) => (url, policy),
+                _ => return None,
+            }
+        };
+
+        let mut attempts = 0;
+        while policy.allows_attempt(attempts) {
+            attempts += 1;
+            let delay = policy.delay_for_attempt(attempts);
+            state
+                .lock()
+                .await
+                .emit_connection_event(ConnectionEvent::Reconnecting {
+                    attempt: attempts,
+                    delay,
+                });
+            tracing::info!(
+                "Reconnecting to {} (attempt {}) in {:?}",
+                url,
+                attempts,
+                delay
+            );
+            tokio::time::sleep(delay).await;
+
+            match Self::reestablish(&url, state).await {
+                Ok(read_transport) => {
+                    tracing::info!("Reconnected to {} after {} attempts", url, attempts);
+                    state
+                        .lock()
+                        .await
+                        .emit_connection_event(ConnectionEvent::Reconnected);
+                    return Some(read_transport);
+                }
+                Err(e) => tracing::warn!("Reconnect attempt {} failed: {}", attempts, e),
+            }
+        }
+
+        tracing::warn!(
+            "Giving up reconnecting to {} after {} attempts",
+            url,
+            attempts
+        );
+        state
+            .lock()
+            .await
+            .emit_connection_event(ConnectionEvent::ReconnectFailed { attempts });
+        None
+    }
+
+    /// Re-establishes the connection to the server.
+    ///
+    /// Renegotiates the protocol, re-enumerates all properties and restores
+    /// previously requested BLOB modes before the new connection is used.
+    async fn reestablish(url: &str, state: &Arc<Mutex<ClientState>>) -> Result<ReadTransport> {
+        let (negotiator, blob_modes, recorder, blob_sinks) = {
+            let state = state.lock().await;
+            (
+                state.negotiator.clone(),
+                state.blob_modes.clone(),
+                state.recorder.clone(),
+                state.blob_sinks.clone(),
+            )
+        };
+
+        let mut transport = Transport::connect(url).await?;
+        transport.set_recorder(recorder);
+        transport.set_blob_sink_factory(blob_sinks);
+        let protocol = negotiator.negotiate(&mut transport).await?;
+        transport.set_protocol(protocol);
+        let (read_transport, mut write_transport) = transport.split()?;
+
+        write_transport
+            .send_message(&ProtocolMessage::GetProperties(GetProperties {
+                version: Some("1.7".to_string()),
+                device: None,
+                name: None,
+            }))
+            .await?;
+        for enable in blob_modes {
+            write_transport
+                .send_message(&ProtocolMessage::EnableBLOB(enable))
+                .await?;
+        }
+
+        // Definitions are re-sent by the server, so start from an empty cache
+        let mut state = state.lock().await;
+        state.write_transport = Some(write_transport);
+        state.protocol = protocol;
+        state.cache.clear();
+        state.connected = true;
+
+        Ok(read_transport)
+    }
+
     /// Converts a protocol message to a domain `Property` type.
     ///
     /// Returns `None` for messages that don't represent properties
@@ -368,6 +721,7 @@ impl RsClientStrategy {
                     state: v.attrs.state,
                     perm: v.perm,
                     property_type: PropertyType::Text,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -400,6 +754,7 @@ impl RsClientStrategy {
                     state: v.attrs.state,
                     perm: v.perm,
                     property_type: PropertyType::Number,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -430,6 +785,11 @@ impl RsClientStrategy {
                     state: v.attrs.state,
                     perm: v.perm,
                     property_type: PropertyType::Switch,
+                    switch_rule: Some(match v.rule {
+                        ProtocolSwitchRule::OneOfMany => SwitchRule::OneOfMany,
+                        ProtocolSwitchRule::AtMostOne => SwitchRule::AtMostOne,
+                        ProtocolSwitchRule::AnyOfMany => SwitchRule::AnyOfMany,
+                    }),
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -462,6 +822,7 @@ impl RsClientStrategy {
                     state: v.attrs.state,
                     perm: PropertyPerm::ReadOnly, // Lights are always read-only
                     property_type: PropertyType::Light,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -480,6 +841,7 @@ impl RsClientStrategy {
                                 data: Vec::new(),
                                 format: String::new(),
                                 size: 0,
+                                compressed_size: None,
                             },
                         },
                     );
@@ -492,6 +854,7 @@ impl RsClientStrategy {
                     state: v.attrs.state,
                     perm: v.perm,
                     property_type: PropertyType::Blob,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -520,6 +883,7 @@ impl RsClientStrategy {
                     state: v.attrs.state.unwrap_or(PropertyState::Idle),
                     perm: PropertyPerm::ReadWrite,
                     property_type: PropertyType::Text,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -552,6 +916,7 @@ impl RsClientStrategy {
                     state: v.attrs.state.unwrap_or(PropertyState::Idle),
                     perm: PropertyPerm::ReadWrite,
                     property_type: PropertyType::Number,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -582,6 +947,7 @@ impl RsClientStrategy {
                     state: v.attrs.state.unwrap_or(PropertyState::Idle),
                     perm: PropertyPerm::ReadWrite,
                     property_type: PropertyType::Switch,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -614,6 +980,7 @@ impl RsClientStrategy {
                     state: v.attrs.state.unwrap_or(PropertyState::Idle),
                     perm: PropertyPerm::ReadOnly,
                     property_type: PropertyType::Light,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -622,9 +989,9 @@ impl RsClientStrategy {
             }
             ProtocolMessage::SetBLOBVector(v) => {
                 let mut items = HashMap::new();
-                for elem in v.elements {
-                    // Decode base64 BLOB data
-                    let data = match decode_blob(&elem.value) {
+                for mut elem in v.elements {
+                    // Take the data decoded by the transport or decode it
+                    let data = match elem.take_data() {
                         Ok(decoded) => decoded,
                         Err(e) => {
                             tracing::warn!(
@@ -645,6 +1012,7 @@ impl RsClientStrategy {
                                 data,
                                 format: elem.format,
                                 size: elem.size,
+                                compressed_size: elem.compressed_size,
                             },
                         },
                     );
@@ -657,6 +1025,7 @@ impl RsClientStrategy {
                     state: v.attrs.state.unwrap_or(PropertyState::Idle),
                     perm: PropertyPerm::ReadWrite,
                     property_type: PropertyType::Blob,
+                    switch_rule: None,
                     items,
                     timeout: v.attrs.timeout,
                     timestamp: v.attrs.timestamp,
@@ -743,14 +1112,18 @@ impl RsClientStrategy {
                 // Convert BLOB property to newBLOBVector message
                 let mut elements = Vec::new();
                 for (name, item) in &prop.items {
-                    if let PropertyValue::Blob { data, format, size } = &item.value {
-                        // Encode binary data as base64
-                        let encoded = encode_blob(data);
+                    if let PropertyValue::Blob {
+                        data, format, size, ..
+                    } = &item.value
+                    {
+                        // Encoded as base64 when serialized
                         elements.push(OneBLOB {
                             name: name.clone(),
                             size: *size,
                             format: format.clone(),
-                            value: encoded,
+                            value: String::new(),
+                            data: Some(data.clone()),
+                            compressed_size: None,
                         });
                     }
                 }
@@ -895,12 +1268,14 @@ impl ClientStrategy for RsClientStrategy {
     async fn connect(&mut self, url: &str) -> Result<()> {
         let state = self.state.lock().await;
 
-        if state.connected {
+        if state.connected || state.session_active() {
             return Err(IndigoError::InvalidState("Already connected".to_string()));
         }
 
         // Create and connect transport
         let mut transport = Transport::connect(url).await?;
+        transport.set_recorder(state.recorder.clone());
+        transport.set_blob_sink_factory(state.blob_sinks.clone());
 
         // Negotiate protocol with server
         let negotiator = state.negotiator.clone();
@@ -924,7 +1299,11 @@ impl ClientStrategy for RsClientStrategy {
         let mut state = self.state.lock().await;
         state.write_transport = Some(write_transport);
         state.protocol = protocol;
+        state.cache.clear();
+        state.blob_modes.clear();
+        state.url = Some(url.to_string());
         state.connected = true;
+        state.emit_connection_event(ConnectionEvent::Connected);
 
         // Drop the lock before starting the receiver task
         drop(state);
@@ -947,6 +1326,7 @@ impl ClientStrategy for RsClientStrategy {
     /// Disconnects from the INDIGO server.
     ///
     /// Stops the background receiver task and closes the TCP connection.
+    /// Any reconnection in progress is cancelled.
     ///
     /// # Errors
     ///
@@ -954,7 +1334,7 @@ impl ClientStrategy for RsClientStrategy {
     async fn disconnect(&mut self) -> Result<()> {
         let mut state = self.state.lock().await;
 
-        if !state.connected {
+        if !state.connected && !state.session_active() {
             return Err(IndigoError::InvalidState("Not connected".to_string()));
         }
 
@@ -978,7 +1358,13 @@ impl ClientStrategy for RsClientStrategy {
         state.property_tx = None;
         state.property_rx = None;
         state.property_subscribers.clear();
+        state.event_subscribers.clear();
+        state.cache.clear();
+        state.url = None;
         state.connected = false;
+        state.emit_connection_event(ConnectionEvent::Disconnected {
+            reason: "Disconnected by client".to_string(),
+        });
 
         tracing::info!("Disconnected from server");
 
@@ -1065,7 +1451,10 @@ impl ClientStrategy for RsClientStrategy {
         }
 
         // Convert property to protocol message
-        let msg = Self::convert_from_property(property)?;
+        let mut msg = Self::convert_from_property(property)?;
+        if state.compress_blobs {
+            blob::compress_blobs(&mut msg)?;
+        }
 
         // Send via write transport
         if let Some(ref mut write_transport) = state.write_transport {
@@ -1140,6 +1529,54 @@ impl ClientStrategy for RsClientStrategy {
             ));
         }
 
+        // Remember the mode so it can be restored after reconnecting
+        if let ProtocolMessage::EnableBLOB(enable) = msg {
+            state.record_blob_mode(enable);
+        }
+
+        Ok(())
+    }
+
+    /// Subscribes to property events.
+    ///
+    /// Events are emitted for every property definition, update and deletion,
+    /// and for every message received from the server. Subscribers are dropped
+    /// on disconnect.
+    async fn subscribe_events(&self) -> Result<mpsc::UnboundedReceiver<PropertyEvent>> {
+        let mut state = self.state.lock().await;
+        let (tx, rx) = mpsc::unbounded_channel();
+        state.event_subscribers.push(tx);
+        Ok(rx)
+    }
+
+    /// Subscribes to connection state events.
+    ///
+    /// Subscribers are kept across disconnects, so they can be created before
+    /// connecting and observe the whole session.
+    async fn subscribe_connection_events(
+        &self,
+    ) -> Result<mpsc::UnboundedReceiver<ConnectionEvent>> {
+        let mut state = self.state.lock().await;
+        let (tx, rx) = mpsc::unbounded_channel();
+        state.connection_subscribers.push(tx);
+        Ok(rx)
+    }
+
+    async fn open_device(&self, device: &str, interface: DeviceInterface) -> Result<DeviceHandle> {
+        let mut handles = self.open_handles(Some(device), interface).await?;
+        Ok(handles.remove(0))
+    }
+
+    async fn open_devices(&self, interface: DeviceInterface) -> Result<Vec<DeviceHandle>> {
+        self.open_handles(None, interface).await
+    }
+
+    fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) -> Result<()> {
+        // Used when the connection is lost
+        *self
+            .reconnect_policy
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(policy);
         Ok(())
     }
 
@@ -1275,6 +1712,10 @@ mod tests {
         assert_eq!(property.device, "CCD Simulator");
         assert_eq!(property.name, "CONNECTION");
         assert_eq!(property.property_type, PropertyType::Switch);
+        assert_eq!(
+            property.switch_rule,
+            Some(libindigo::types::SwitchRule::OneOfMany)
+        );
         assert_eq!(property.items.len(), 2);
     }
 
@@ -1298,6 +1739,7 @@ mod tests {
             state: PropertyState::Idle,
             perm: PropertyPerm::ReadWrite,
             property_type: PropertyType::Text,
+            switch_rule: None,
             items,
             timeout: None,
             timestamp: None,
@@ -1344,6 +1786,7 @@ mod tests {
             state: PropertyState::Idle,
             perm: PropertyPerm::ReadWrite,
             property_type: PropertyType::Number,
+            switch_rule: None,
             items,
             timeout: None,
             timestamp: None,
@@ -1386,6 +1829,7 @@ mod tests {
             state: PropertyState::Idle,
             perm: PropertyPerm::ReadWrite,
             property_type: PropertyType::Switch,
+            switch_rule: None,
             items,
             timeout: None,
             timestamp: None,
@@ -1428,6 +1872,7 @@ mod tests {
             state: PropertyState::Idle,
             perm: PropertyPerm::ReadOnly,
             property_type: PropertyType::Light,
+            switch_rule: None,
             items,
             timeout: None,
             timestamp: None,
@@ -1451,6 +1896,238 @@ mod tests {
         assert!(state.property_tx.is_none());
     }
 
+    #[tokio::test]
+    async fn test_dispatch_emits_typed_events() {
+        use crate::protocol::{
+            DefText, DefTextVector, DelProperty, Message, SetTextVector, SetVectorAttributes,
+            VectorAttributes,
+        };
+
+        let strategy = RsClientStrategy::new();
+        let mut events = strategy.subscribe_events().await.unwrap();
+        let mut state = strategy.state.lock().await;
+
+        state.dispatch_message(ProtocolMessage::DefTextVector(DefTextVector {
+            attrs: VectorAttributes {
+                device: "CCD Simulator".to_string(),
+                name: "FILE_NAME".to_string(),
+                label: "File name".to_string(),
+                group: "Image".to_string(),
+                state: PropertyState::Idle,
+                timeout: None,
+                timestamp: None,
+                message: None,
+            },
+            perm: PropertyPerm::ReadWrite,
+            elements: vec![DefText {
+                name: "PATH".to_string(),
+                label: "Path".to_string(),
+                value: "/tmp".to_string(),
+            }],
+        }));
+        state.dispatch_message(ProtocolMessage::SetTextVector(SetTextVector {
+            attrs: SetVectorAttributes {
+                device: "CCD Simulator".to_string(),
+                name: "FILE_NAME".to_string(),
+                state: Some(PropertyState::Ok),
+                timeout: None,
+                timestamp: None,
+                message: None,
+            },
+            elements: vec![OneText {
+                name: "PATH".to_string(),
+                value: "/home".to_string(),
+            }],
+        }));
+        state.dispatch_message(ProtocolMessage::Message(Message {
+            device: Some("CCD Simulator".to_string()),
+            timestamp: None,
+            message: Some("Hello".to_string()),
+        }));
+        state.dispatch_message(ProtocolMessage::DelProperty(DelProperty {
+            device: "CCD Simulator".to_string(),
+            name: Some("FILE_NAME".to_string()),
+            timestamp: None,
+            message: None,
+        }));
+
+        assert!(
+            matches!(events.try_recv().unwrap(), PropertyEvent::Defined(p) if p.name == "FILE_NAME")
+        );
+        match events.try_recv().unwrap() {
+            PropertyEvent::Updated(p) => {
+                assert_eq!(p.state, PropertyState::Ok);
+                assert_eq!(p.label, "File name");
+                assert_eq!(
+                    p.items["PATH"].value,
+                    PropertyValue::Text("/home".to_string())
+                );
+            }
+            other => panic!("Expected Updated event, got {:?}", other),
+        }
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            PropertyEvent::Message { message, .. } if message == "Hello"
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            PropertyEvent::Deleted { device, name: Some(name), .. }
+                if device == "CCD Simulator" && name == "FILE_NAME"
+        ));
+        assert!(state.cache.property("CCD Simulator", "FILE_NAME").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_merges_number_update_into_definition() {
+        use crate::protocol::{
+            DefNumber, DefNumberVector, SetNumberVector, SetVectorAttributes, VectorAttributes,
+        };
+
+        let strategy = RsClientStrategy::new();
+        let mut properties = strategy.subscribe_properties().await;
+        let mut state = strategy.state.lock().await;
+
+        state.dispatch_message(ProtocolMessage::DefNumberVector(DefNumberVector {
+            attrs: VectorAttributes {
+                device: "CCD Simulator".to_string(),
+                name: "CCD_EXPOSURE".to_string(),
+                label: "Exposure".to_string(),
+                group: "Camera".to_string(),
+                state: PropertyState::Busy,
+                timeout: Some(60.0),
+                timestamp: None,
+                message: None,
+            },
+            perm: PropertyPerm::ReadWrite,
+            elements: vec![DefNumber {
+                name: "EXPOSURE".to_string(),
+                label: "Duration".to_string(),
+                format: "%5.2f".to_string(),
+                min: 0.0,
+                max: 3600.0,
+                step: 1.0,
+                value: 10.0,
+            }],
+        }));
+        state.dispatch_message(ProtocolMessage::SetNumberVector(SetNumberVector {
+            attrs: SetVectorAttributes {
+                device: "CCD Simulator".to_string(),
+                name: "CCD_EXPOSURE".to_string(),
+                state: None,
+                timeout: None,
+                timestamp: None,
+                message: None,
+            },
+            elements: vec![OneNumber {
+                name: "EXPOSURE".to_string(),
+                value: 5.0,
+            }],
+        }));
+
+        let _definition = properties.try_recv().unwrap();
+        let update = properties.try_recv().unwrap();
+        assert_eq!(update.state, PropertyState::Busy);
+        assert_eq!(update.perm, PropertyPerm::ReadWrite);
+        assert_eq!(update.timeout, Some(60.0));
+        assert_eq!(update.items["EXPOSURE"].label, "Duration");
+        assert_eq!(
+            update.items["EXPOSURE"].value,
+            PropertyValue::Number {
+                value: 5.0,
+                min: 0.0,
+                max: 3600.0,
+                step: 1.0,
+                format: "%5.2f".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_and_restores_blob_modes() {
+        use std::time::Duration;
+        use tokio::io::AsyncReadExt;
+        use tokio::net::{TcpListener, TcpStream};
+
+        async fn read_until(stream: &mut TcpStream, needle: &str) -> String {
+            let mut received = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !String::from_utf8_lossy(&received).contains(needle) {
+                let n = stream.read(&mut buf).await.unwrap();
+                assert!(n > 0, "client closed the connection");
+                received.extend_from_slice(&buf[..n]);
+            }
+            String::from_utf8_lossy(&received).into_owned()
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            // Drop the first connection once the client has enabled BLOBs
+            let (mut first, _) = listener.accept().await.unwrap();
+            read_until(&mut first, "enableBLOB").await;
+            drop(first);
+
+            // The second connection must re-enumerate and restore BLOB modes
+            let (mut second, _) = listener.accept().await.unwrap();
+            let received = read_until(&mut second, "enableBLOB").await;
+            (received, second)
+        });
+
+        let mut strategy = RsClientStrategy::new();
+        strategy
+            .set_reconnect_policy(
+                ReconnectPolicy::default()
+                    .with_initial_delay(Duration::from_millis(10))
+                    .with_jitter(0.0)
+                    .with_max_attempts(5),
+            )
+            .unwrap();
+        let mut events = strategy.subscribe_connection_events().await.unwrap();
+
+        strategy.connect(&addr.to_string()).await.unwrap();
+        strategy
+            .enable_blob("CCD Simulator", None, BlobTransferMode::Also)
+            .await
+            .unwrap();
+
+        async fn next(
+            events: &mut mpsc::UnboundedReceiver<ConnectionEvent>,
+        ) -> Option<ConnectionEvent> {
+            tokio::time::timeout(Duration::from_secs(5), events.recv())
+                .await
+                .unwrap()
+        }
+        assert_eq!(next(&mut events).await, Some(ConnectionEvent::Connected));
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ConnectionEvent::Disconnected { .. })
+        ));
+        assert!(matches!(
+            next(&mut events).await,
+            Some(ConnectionEvent::Reconnecting { attempt: 1, .. })
+        ));
+        assert_eq!(next(&mut events).await, Some(ConnectionEvent::Reconnected));
+
+        let (received, _connection) = server.await.unwrap();
+        assert!(received.contains("getProperties"));
+        assert!(received.contains("CCD Simulator"));
+        assert!(strategy.state.lock().await.connected);
+
+        strategy.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_policy_is_not_dropped() {
+        let mut strategy = RsClientStrategy::new();
+        // The receiver task may hold the state while the policy is set
+        let state = strategy.state.clone();
+        let guard = state.lock().await;
+        strategy
+            .set_reconnect_policy(ReconnectPolicy::default())
+            .unwrap();
+        assert!(guard.reconnect_policy.lock().unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn test_connect_requires_url() {
         let mut strategy = RsClientStrategy::new();
@@ -23,6 +23,8 @@
 //! - **Protocol Layer**: XML and JSON parsing/serialization
 //! - **Transport Layer**: TCP connection management
 //! - **Client Layer**: High-level client implementation
+//! - **Server Layer** (`device` feature): Hosts device drivers over TCP
+//! - **Mock Server** (`test-server` feature): Simulated INDIGO server for tests
 //!
 //! # Quick Start
 //!
@@ -73,14 +75,17 @@
 //! # Feature Flags
 //!
 //! - `client` (default): Enable client functionality
-//! - `device`: Enable device driver support (future)
+//! - `device`: Enable the `IndigoServer` hosting device drivers written in Rust
 //! - `discovery`: Enable mDNS server discovery (pure Rust, no FFI)
+//! - `test-server`: Enable the `mock_server` module for testing clients
+//! - `image`: Enable the `image` module decoding camera images in FITS, XISF and raw formats
 
 // Re-export core API from libindigo
 pub use libindigo::{
     // Client types
     client::{
-        AvailabilityStatus, Client, ClientBuilder, ClientEvent, MonitoringConfig, MonitoringEvent,
+        AvailabilityStatus, Client, ClientBuilder, ClientEvent, ConnectionEvent, MonitoringConfig,
+        MonitoringEvent, PropertyEvent, PropertyOutcome, ReconnectPolicy,
     },
     // Error handling
     error::{IndigoError, Result},
@@ -94,6 +99,10 @@ pub use libindigo::{
 // Re-export the name module (INDIGO constants)
 pub use libindigo::name;
 
+// Re-export image decoding from core when feature is enabled
+#[cfg(feature = "image")]
+pub use libindigo::image;
+
 // Re-export discovery types from core when feature is enabled
 #[cfg(feature = "discovery")]
 pub use libindigo::discovery::{
@@ -102,12 +111,20 @@ pub use libindigo::discovery::{
 };
 
 // Internal modules
+pub mod blob;
+mod cache;
 mod client;
 pub mod protocol;
 pub mod protocol_json;
 pub mod protocol_negotiation;
+pub mod recording;
+pub mod remote;
 mod transport;
 
+// Optional device server hosting Rust drivers
+#[cfg(feature = "device")]
+pub mod server;
+
 // Optional discovery module (pure Rust mDNS)
 #[cfg(feature = "discovery")]
 pub mod discovery;
@@ -120,9 +137,23 @@ pub use discovery::{announce_service, AnnouncementHandle, ServerDiscovery, Serve
 #[cfg(feature = "monitoring")]
 pub mod monitoring;
 
+// Optional mock server for testing clients
+#[cfg(feature = "test-server")]
+pub mod mock_server;
+
+// Export the device server
+#[cfg(feature = "device")]
+pub use server::IndigoServer;
+
 // Export the RS strategy implementation
 pub use client::RsClientStrategy;
 
+// Export remote device implementations
+pub use remote::{
+    RemoteCamera, RemoteDevice, RemoteFilterWheel, RemoteFocuser, RemoteGuider, RemoteInterface,
+    RemoteMount,
+};
+
 // Export protocol negotiation types for advanced users
 pub use protocol_negotiation::{ProtocolNegotiator, ProtocolType};
 
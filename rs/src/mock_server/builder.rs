@@ -1,6 +1,7 @@
 //! Fluent builder API for the mock INDIGO server.
 
 use super::device::MockDevice;
+use super::fault::Faults;
 use super::server::{MockIndigoServer, ServerConfig};
 use libindigo::error::Result;
 use std::time::Duration;
@@ -38,6 +39,25 @@ impl MockServerBuilder {
         self
     }
 
+    /// Keep properties busy for a while after change requests, like real hardware
+    pub fn with_busy_delay(mut self, delay: Duration) -> Self {
+        self.config.busy_delay = Some(delay);
+        self
+    }
+
+    /// Inject faults into every connection
+    pub fn with_faults(mut self, faults: Faults) -> Self {
+        self.config.faults = faults;
+        self
+    }
+
+    /// Inject faults into one connection, numbered from 1 in the order
+    /// clients connect, instead of the faults of every connection
+    pub fn with_connection_faults(mut self, connection: usize, faults: Faults) -> Self {
+        self.config.connection_faults.insert(connection, faults);
+        self
+    }
+
     /// Add a mock device
     pub fn with_device(mut self, device: MockDevice) -> Self {
         self.devices.push(device);
@@ -54,6 +74,21 @@ impl MockServerBuilder {
         self.with_device(super::presets::mount_simulator())
     }
 
+    /// Add a preset device (Focuser simulator)
+    pub fn with_focuser_simulator(self) -> Self {
+        self.with_device(super::presets::focuser_simulator())
+    }
+
+    /// Add a preset device (Filter wheel simulator)
+    pub fn with_wheel_simulator(self) -> Self {
+        self.with_device(super::presets::wheel_simulator())
+    }
+
+    /// Add a preset device (Guider simulator)
+    pub fn with_guider_simulator(self) -> Self {
+        self.with_device(super::presets::guider_simulator())
+    }
+
     /// Enable verbose logging
     pub fn verbose(mut self) -> Self {
         self.config.verbose = true;
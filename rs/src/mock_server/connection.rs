@@ -0,0 +1,203 @@
+//! Per-client connection handler for the mock INDIGO server.
+
+use super::fault::Faults;
+use super::handler::MessageHandler;
+use super::server::ServerState;
+use super::subscription::ClientSubscription;
+use crate::protocol::{ProtocolMessage, ProtocolSerializer};
+use crate::protocol_json::JsonProtocolSerializer;
+use crate::protocol_negotiation::ProtocolType;
+use crate::transport::{Transport, WriteTransport};
+use libindigo::error::{IndigoError, Result};
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc};
+
+/// Represents a single client connection
+pub struct Connection {
+    /// Unique connection ID
+    id: usize,
+
+    /// TCP stream
+    stream: TcpStream,
+
+    /// Shared server state
+    state: Arc<ServerState>,
+
+    /// Faults injected into this connection
+    faults: Faults,
+
+    /// Shutdown signal receiver
+    shutdown_rx: broadcast::Receiver<()>,
+
+    /// Property update receiver
+    update_rx: mpsc::UnboundedReceiver<ProtocolMessage>,
+
+    /// Property update sender (for subscription)
+    update_tx: mpsc::UnboundedSender<ProtocolMessage>,
+}
+
+impl Connection {
+    /// Create a new connection handler
+    pub fn new(
+        id: usize,
+        stream: TcpStream,
+        state: Arc<ServerState>,
+        faults: Faults,
+        shutdown_rx: broadcast::Receiver<()>,
+    ) -> Self {
+        let (update_tx, update_rx) = mpsc::unbounded_channel();
+
+        Self {
+            id,
+            stream,
+            state,
+            faults,
+            shutdown_rx,
+            update_rx,
+            update_tx,
+        }
+    }
+
+    /// Main connection handling loop
+    pub async fn handle(mut self) -> Result<()> {
+        // Send each write on its own when splitting messages
+        if self.faults.split_writes.is_some() {
+            let _ = self.stream.set_nodelay(true);
+        }
+
+        // Split stream for reading and writing; the protocol is detected
+        // from the messages the client sends
+        let (mut reader, mut writer) = Transport::from_stream(self.stream).split()?;
+
+        // Create message handler
+        let mut handler = MessageHandler::new(self.state.clone(), &self.faults);
+
+        // Subscribe this connection
+        {
+            let mut subscriptions = self.state.subscriptions.write().await;
+            subscriptions.subscribe(ClientSubscription {
+                connection_id: self.id,
+                device_filter: None,
+                property_filter: None,
+                sender: self.update_tx.clone(),
+            });
+        }
+
+        let mut sent = 0;
+
+        'connection: loop {
+            tokio::select! {
+                // Read messages from client
+                received = reader.receive_message() => {
+                    match received {
+                        Ok(message) => {
+                            // Answer in the protocol the client speaks
+                            writer.set_protocol(reader.protocol());
+
+                            // Update stats
+                            {
+                                let mut stats = self.state.stats.write().await;
+                                stats.messages_received += 1;
+                            }
+
+                            // Handle message
+                            match handler.handle(message).await {
+                                Ok(responses) => {
+                                    // Send responses
+                                    for response in responses {
+                                        sent += 1;
+                                        if let Err(e) = Self::send_message(&mut writer, &response, &self.state, &self.faults, sent).await {
+                                            tracing::warn!("Connection {} send error: {}", self.id, e);
+                                            break;
+                                        }
+                                        if self.faults.disconnect_after == Some(sent) {
+                                            break 'connection;
+                                        }
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Connection {} handler error: {}", self.id, e);
+                                }
+                            }
+                        }
+                        // EOF - client disconnected
+                        Err(IndigoError::ConnectionError(_)) => break,
+                        // Idle clients are normal
+                        Err(IndigoError::Timeout(_)) => {}
+                        Err(e) => {
+                            tracing::warn!("Connection {} parse error: {}", self.id, e);
+                        }
+                    }
+                }
+
+                // Send property updates to client
+                Some(update) = self.update_rx.recv() => {
+                    sent += 1;
+                    if let Err(e) = Self::send_message(&mut writer, &update, &self.state, &self.faults, sent).await {
+                        tracing::warn!("Connection {} update send error: {}", self.id, e);
+                        break;
+                    }
+                    if self.faults.disconnect_after == Some(sent) {
+                        break;
+                    }
+                }
+
+                // Handle shutdown
+                _ = self.shutdown_rx.recv() => {
+                    break;
+                }
+            }
+        }
+
+        // Unsubscribe on disconnect
+        {
+            let mut subscriptions = self.state.subscriptions.write().await;
+            subscriptions.unsubscribe(self.id);
+        }
+
+        Ok(())
+    }
+
+    /// Send a protocol message to the client, injecting the faults for its
+    /// message number
+    async fn send_message(
+        writer: &mut WriteTransport,
+        message: &ProtocolMessage,
+        state: &Arc<ServerState>,
+        faults: &Faults,
+        number: usize,
+    ) -> Result<()> {
+        let mut text = match writer.protocol() {
+            ProtocolType::Json => JsonProtocolSerializer::serialize(message),
+            ProtocolType::Xml => ProtocolSerializer::serialize(message)
+                .map(|xml| String::from_utf8_lossy(&xml).into_owned()),
+        }
+        .map_err(|e| IndigoError::ProtocolError(format!("Serialization error: {}", e)))?;
+        if let Some(malformation) = faults.malformed.get(&number) {
+            text = malformation.apply(message, &text)?;
+        }
+        text.push('\n');
+
+        if let Some(delay) = faults.response_delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        let chunk_size = faults.split_writes.unwrap_or(text.len()).max(1);
+        for chunk in text.as_bytes().chunks(chunk_size) {
+            writer.write_raw(chunk).await?;
+
+            if faults.split_writes.is_some() {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        // Update stats
+        {
+            let mut stats = state.stats.write().await;
+            stats.messages_sent += 1;
+        }
+
+        Ok(())
+    }
+}
@@ -131,7 +131,7 @@ mod tests {
     use crate::mock_server::property::{
         NumberValue, PropertyItem, PropertyType, PropertyTypeMetadata, PropertyValue,
     };
-    use libindigo_rs::protocol::{PropertyPerm, PropertyState};
+    use crate::protocol::{PropertyPerm, PropertyState};
 
     #[test]
     fn test_device_registry() {
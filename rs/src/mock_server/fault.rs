@@ -0,0 +1,87 @@
+//! Fault injection for the mock INDIGO server.
+
+use crate::protocol::ProtocolMessage;
+use crate::protocol_json::JsonProtocolSerializer;
+use libindigo::error::Result;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Misbehaviour of a connection to the mock server, for testing how clients
+/// cope with unreliable servers.
+///
+/// Messages are numbered from 1 in the order they are sent on the connection.
+///
+/// # Example
+///
+/// ```
+/// use libindigo_rs::mock_server::{Faults, Malformation};
+/// use std::collections::HashMap;
+///
+/// let faults = Faults {
+///     disconnect_after: Some(10),
+///     malformed: HashMap::from([(3, Malformation::Truncated)]),
+///     ..Faults::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Faults {
+    /// Close the connection after sending this many messages
+    pub disconnect_after: Option<usize>,
+
+    /// Corrupt messages, by message number
+    pub malformed: HashMap<usize, Malformation>,
+
+    /// Wait before sending each message
+    pub response_delay: Option<Duration>,
+
+    /// Write messages in chunks of at most this many bytes
+    pub split_writes: Option<usize>,
+
+    /// Answer change requests with the Alert state, leaving values unchanged
+    pub alert_changes: bool,
+
+    /// Answer change requests of these properties with the Alert state, as
+    /// `alert_changes` does for all properties
+    pub alert_properties: HashSet<String>,
+
+    /// Republish a property in its current state before answering a change
+    /// request, as an update sent before the server saw the request, such as
+    /// coordinates republished by a tracking mount
+    pub stale_updates: bool,
+}
+
+/// Corruption of a message sent by the mock server
+///
+/// Truncated messages are sent in the protocol of the client. The invalid
+/// JSON and XML are sent as they are to clients of either protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Malformation {
+    /// Send only the first half of the message
+    Truncated,
+    /// Send the message as JSON with a syntax error, keeping its braces
+    /// balanced
+    InvalidJson,
+    /// Send an XML element with mismatched tags instead of the message
+    InvalidXml,
+}
+
+impl Malformation {
+    /// Corrupt a message, serialized in the protocol of the client as `text`
+    pub(super) fn apply(self, message: &ProtocolMessage, text: &str) -> Result<String> {
+        Ok(match self {
+            Malformation::Truncated => {
+                let mut end = text.len() / 2;
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                text[..end].to_string()
+            }
+            Malformation::InvalidJson => {
+                JsonProtocolSerializer::serialize(message)?.replacen(':', "=", 1)
+            }
+            Malformation::InvalidXml => {
+                "<defTextVector device=\"Mock\" name=\"BROKEN\"></defNumberVector>".to_string()
+            }
+        })
+    }
+}
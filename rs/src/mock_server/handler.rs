@@ -1,36 +1,42 @@
 //! Message handler for INDIGO protocol messages.
 
-use super::device::DeviceRegistry;
+use super::fault::Faults;
 use super::property::{MockProperty, PropertyType, PropertyUpdate, PropertyValue};
 use super::server::ServerState;
-use super::subscription::ClientSubscription;
-use libindigo::error::{IndigoError, Result};
-use libindigo_rs::protocol::{
+use crate::protocol::{
     DefBLOB, DefBLOBVector, DefLight, DefLightVector, DefNumber, DefNumberVector, DefSwitch,
     DefSwitchVector, DefText, DefTextVector, EnableBLOB, GetProperties, NewNumberVector,
-    NewSwitchVector, NewTextVector, NewVectorAttributes, OneBLOB, OneLight, OneNumber, OneSwitch,
-    OneText, PropertyPerm, PropertyState, ProtocolMessage, SetBLOBVector, SetLightVector,
-    SetNumberVector, SetSwitchVector, SetTextVector, SetVectorAttributes, SwitchRule, SwitchState,
-    VectorAttributes,
+    NewSwitchVector, NewTextVector, OneBLOB, OneLight, OneNumber, OneSwitch, OneText,
+    PropertyState, ProtocolMessage, SetBLOBVector, SetLightVector, SetNumberVector,
+    SetSwitchVector, SetTextVector, SetVectorAttributes, SwitchRule, SwitchState, VectorAttributes,
 };
+use libindigo::error::{IndigoError, Result};
+use std::collections::HashSet;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
 /// Handles INDIGO protocol messages
 pub struct MessageHandler {
-    /// Connection ID
-    connection_id: usize,
-
     /// Shared server state
     state: Arc<ServerState>,
+
+    /// Answer change requests with the Alert state
+    alert_changes: bool,
+
+    /// Answer change requests of these properties with the Alert state
+    alert_properties: HashSet<String>,
+
+    /// Republish properties before answering change requests
+    stale_updates: bool,
 }
 
 impl MessageHandler {
-    /// Create a new message handler
-    pub fn new(connection_id: usize, state: Arc<ServerState>) -> Self {
+    /// Create a new message handler for a connection with faults
+    pub fn new(state: Arc<ServerState>, faults: &Faults) -> Self {
         Self {
-            connection_id,
             state,
+            alert_changes: faults.alert_changes,
+            alert_properties: faults.alert_properties.clone(),
+            stale_updates: faults.stale_updates,
         }
     }
 
@@ -57,7 +63,7 @@ impl MessageHandler {
         // List properties based on filters
         let properties = devices.list_properties(msg.device.as_deref());
 
-        for (device_name, property) in properties {
+        for (_, property) in properties {
             // Apply property filter
             if let Some(ref name_filter) = msg.name {
                 if &property.name != name_filter {
@@ -76,8 +82,6 @@ impl MessageHandler {
 
     /// Handle newTextVector request
     async fn handle_new_text_vector(&mut self, msg: NewTextVector) -> Result<Vec<ProtocolMessage>> {
-        let mut devices = self.state.devices.write().await;
-
         // Build property update
         let items: Vec<(String, PropertyValue)> = msg
             .elements
@@ -85,34 +89,8 @@ impl MessageHandler {
             .map(|e| (e.name.clone(), PropertyValue::Text(e.value.clone())))
             .collect();
 
-        let update = PropertyUpdate {
-            state: Some(PropertyState::Ok),
-            items,
-            message: None,
-        };
-
-        // Apply update
-        devices.update_property(&msg.attrs.device, &msg.attrs.name, update)?;
-
-        // Get updated property
-        let property = devices
-            .get_property(&msg.attrs.device, &msg.attrs.name)
-            .ok_or_else(|| {
-                IndigoError::ProtocolError(format!(
-                    "Property '{}.{}' not found",
-                    msg.attrs.device, msg.attrs.name
-                ))
-            })?
-            .clone();
-
-        drop(devices);
-
-        // Notify subscribers
-        let set_msg = property_to_set_message(&property)?;
-        let subscriptions = self.state.subscriptions.read().await;
-        subscriptions.notify_property_update(&property.device, &property.name, set_msg.clone());
-
-        Ok(vec![set_msg])
+        self.apply_request(&msg.attrs.device, &msg.attrs.name, items)
+            .await
     }
 
     /// Handle newNumberVector request
@@ -120,7 +98,7 @@ impl MessageHandler {
         &mut self,
         msg: NewNumberVector,
     ) -> Result<Vec<ProtocolMessage>> {
-        let mut devices = self.state.devices.write().await;
+        let devices = self.state.devices.read().await;
 
         // Get the property to extract format/min/max/step
         let property = devices
@@ -167,34 +145,9 @@ impl MessageHandler {
             })
             .collect();
 
-        let update = PropertyUpdate {
-            state: Some(PropertyState::Ok),
-            items,
-            message: None,
-        };
-
-        // Apply update
-        devices.update_property(&msg.attrs.device, &msg.attrs.name, update)?;
-
-        // Get updated property
-        let property = devices
-            .get_property(&msg.attrs.device, &msg.attrs.name)
-            .ok_or_else(|| {
-                IndigoError::ProtocolError(format!(
-                    "Property '{}.{}' not found",
-                    msg.attrs.device, msg.attrs.name
-                ))
-            })?
-            .clone();
-
         drop(devices);
-
-        // Notify subscribers
-        let set_msg = property_to_set_message(&property)?;
-        let subscriptions = self.state.subscriptions.read().await;
-        subscriptions.notify_property_update(&property.device, &property.name, set_msg.clone());
-
-        Ok(vec![set_msg])
+        self.apply_request(&msg.attrs.device, &msg.attrs.name, items)
+            .await
     }
 
     /// Handle newSwitchVector request
@@ -202,8 +155,6 @@ impl MessageHandler {
         &mut self,
         msg: NewSwitchVector,
     ) -> Result<Vec<ProtocolMessage>> {
-        let mut devices = self.state.devices.write().await;
-
         // Build property update
         let items: Vec<(String, PropertyValue)> = msg
             .elements
@@ -211,34 +162,92 @@ impl MessageHandler {
             .map(|e| (e.name.clone(), PropertyValue::Switch(e.value.to_bool())))
             .collect();
 
+        self.apply_request(&msg.attrs.device, &msg.attrs.name, items)
+            .await
+    }
+
+    /// Apply the items of a change request and notify subscribers.
+    ///
+    /// With a busy delay configured, the property is first reported as busy
+    /// and completes with `Ok` once the delay has passed.
+    async fn apply_request(
+        &mut self,
+        device: &str,
+        name: &str,
+        items: Vec<(String, PropertyValue)>,
+    ) -> Result<Vec<ProtocolMessage>> {
+        if self.stale_updates {
+            let update = PropertyUpdate {
+                state: None,
+                items: vec![],
+                message: None,
+            };
+            Self::update_and_notify(&self.state, device, name, update).await?;
+        }
+
+        if self.alert_changes || self.alert_properties.contains(name) {
+            let update = PropertyUpdate {
+                state: Some(PropertyState::Alert),
+                items: vec![],
+                message: Some("Simulated failure".to_string()),
+            };
+            Self::update_and_notify(&self.state, device, name, update).await?;
+            return Ok(vec![]);
+        }
+
+        let state = match self.state.busy_delay {
+            Some(_) => PropertyState::Busy,
+            None => PropertyState::Ok,
+        };
         let update = PropertyUpdate {
-            state: Some(PropertyState::Ok),
+            state: Some(state),
             items,
             message: None,
         };
+        Self::update_and_notify(&self.state, device, name, update).await?;
+
+        if let Some(delay) = self.state.busy_delay {
+            let state = self.state.clone();
+            let (device, name) = (device.to_string(), name.to_string());
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let update = PropertyUpdate {
+                    state: Some(PropertyState::Ok),
+                    items: vec![],
+                    message: None,
+                };
+                if let Err(e) = Self::update_and_notify(&state, &device, &name, update).await {
+                    tracing::warn!("Failed to complete {}.{}: {}", device, name, e);
+                }
+            });
+        }
 
-        // Apply update
-        devices.update_property(&msg.attrs.device, &msg.attrs.name, update)?;
-
-        // Get updated property
-        let property = devices
-            .get_property(&msg.attrs.device, &msg.attrs.name)
-            .ok_or_else(|| {
-                IndigoError::ProtocolError(format!(
-                    "Property '{}.{}' not found",
-                    msg.attrs.device, msg.attrs.name
-                ))
-            })?
-            .clone();
+        // The requesting connection is notified as a subscriber
+        Ok(vec![])
+    }
 
-        drop(devices);
+    /// Apply an update to a property and notify subscribers
+    async fn update_and_notify(
+        state: &ServerState,
+        device: &str,
+        name: &str,
+        update: PropertyUpdate,
+    ) -> Result<()> {
+        let property = {
+            let mut devices = state.devices.write().await;
+            devices.update_property(device, name, update)?;
+            devices
+                .get_property(device, name)
+                .ok_or_else(|| {
+                    IndigoError::ProtocolError(format!("Property '{}.{}' not found", device, name))
+                })?
+                .clone()
+        };
 
-        // Notify subscribers
         let set_msg = property_to_set_message(&property)?;
-        let subscriptions = self.state.subscriptions.read().await;
-        subscriptions.notify_property_update(&property.device, &property.name, set_msg.clone());
-
-        Ok(vec![set_msg])
+        let subscriptions = state.subscriptions.read().await;
+        subscriptions.notify_property_update(&property.device, &property.name, set_msg);
+        Ok(())
     }
 
     /// Handle enableBLOB request
@@ -453,6 +462,8 @@ pub fn property_to_set_message(property: &MockProperty) -> Result<ProtocolMessag
                         PropertyValue::Blob(blob) => blob.url.clone(),
                         _ => String::new(),
                     },
+                    data: None,
+                    compressed_size: None,
                 })
                 .collect(),
         })),
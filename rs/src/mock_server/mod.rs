@@ -1,12 +1,15 @@
 //! Pure Rust mock INDIGO server for testing.
 //!
 //! This module provides a mock INDIGO server implementation that supports
-//! the JSON protocol (version 512) without any FFI dependencies.
+//! the JSON protocol (version 512) without any FFI dependencies. It is
+//! available with the `test-server` feature, so that applications can test
+//! against simulated devices without a running `indigo_server`.
 //!
 //! # Example
 //!
-//! ```ignore
-//! use tests::mock_server::MockServerBuilder;
+//! ```no_run
+//! # async fn example() -> libindigo::error::Result<()> {
+//! use libindigo_rs::mock_server::MockServerBuilder;
 //!
 //! let server = MockServerBuilder::new()
 //!     .with_ccd_simulator()
@@ -17,23 +20,29 @@
 //! // Use addr for testing...
 //!
 //! server.shutdown().await?;
+//! # Ok(())
+//! # }
 //! ```
 
 mod builder;
 mod connection;
 mod device;
+mod fault;
 mod handler;
 pub mod presets;
 mod property;
+mod replay;
 mod server;
 mod subscription;
 
 // Public API exports
 pub use builder::MockServerBuilder;
 pub use device::{DeviceMetadata, DeviceRegistry, MockDevice};
+pub use fault::{Faults, Malformation};
 pub use property::{
     BlobValue, MockProperty, NumberValue, PropertyItem, PropertyType, PropertyTypeMetadata,
     PropertyUpdate, PropertyValue,
 };
+pub use replay::ReplayServer;
 pub use server::{MockIndigoServer, ServerConfig, ServerState, ServerStats};
 pub use subscription::{ClientSubscription, SubscriptionManager};
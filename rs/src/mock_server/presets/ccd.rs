@@ -0,0 +1,237 @@
+//! CCD camera simulator preset device.
+
+use super::{number, property, switch, switches, text};
+use crate::mock_server::device::{DeviceMetadata, MockDevice};
+use crate::mock_server::property::*;
+use crate::protocol::{PropertyPerm, PropertyState, SwitchRule};
+use std::collections::HashMap;
+
+const DEVICE: &str = "CCD Simulator";
+
+/// Creates a mock CCD camera simulator device
+pub fn ccd_simulator() -> MockDevice {
+    let mut device = MockDevice {
+        name: DEVICE.to_string(),
+        interface: 0x01, // CCD interface
+        properties: HashMap::new(),
+        metadata: DeviceMetadata {
+            version: Some("1.0".to_string()),
+            driver_name: Some("indigo_ccd_simulator".to_string()),
+            driver_version: Some("2.0.300".to_string()),
+            driver_interface: Some(0x01),
+        },
+    };
+
+    let properties = [
+        // INFO property (read-only)
+        property(
+            DEVICE,
+            "INFO",
+            "Main",
+            "Info",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Text,
+            vec![
+                text("DEVICE_DRIVER", "Driver", "indigo_ccd_simulator"),
+                text("DEVICE_VERSION", "Version", "2.0.300"),
+                text("DEVICE_INTERFACE", "Interface", "1"),
+                text("DEVICE_MODEL", "Model", "CCD Simulator"),
+            ],
+        ),
+        // CONNECTION property
+        switches(
+            DEVICE,
+            "CONNECTION",
+            "Main",
+            "Connection",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("CONNECTED", "Connected", false),
+                switch("DISCONNECTED", "Disconnected", true),
+            ],
+        ),
+        // CCD_INFO property (read-only)
+        property(
+            DEVICE,
+            "CCD_INFO",
+            "Main",
+            "CCD Info",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Number,
+            vec![
+                number("WIDTH", "Width", 1280.0, 0.0, 10000.0, 1.0),
+                number("HEIGHT", "Height", 1024.0, 0.0, 10000.0, 1.0),
+                number("PIXEL_SIZE", "Pixel size", 5.2, 0.0, 100.0, 0.1),
+                number(
+                    "MAX_HORIZONTAL_BIN",
+                    "Max horizontal binning",
+                    4.0,
+                    0.0,
+                    16.0,
+                    1.0,
+                ),
+                number(
+                    "MAX_VERTICAL_BIN",
+                    "Max vertical binning",
+                    4.0,
+                    0.0,
+                    16.0,
+                    1.0,
+                ),
+                number("BITS_PER_PIXEL", "Bits per pixel", 16.0, 8.0, 32.0, 8.0),
+            ],
+        ),
+        // CCD_EXPOSURE property
+        property(
+            DEVICE,
+            "CCD_EXPOSURE",
+            "Camera",
+            "Exposure",
+            PropertyState::Idle,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number(
+                "EXPOSURE",
+                "Exposure time",
+                1.0,
+                0.001,
+                3600.0,
+                0.001,
+            )],
+        ),
+        // CCD_ABORT_EXPOSURE property
+        switches(
+            DEVICE,
+            "CCD_ABORT_EXPOSURE",
+            "Camera",
+            "Abort exposure",
+            SwitchRule::AnyOfMany,
+            vec![switch("ABORT_EXPOSURE", "Abort", false)],
+        ),
+        // CCD_FRAME_TYPE property
+        switches(
+            DEVICE,
+            "CCD_FRAME_TYPE",
+            "Camera",
+            "Frame type",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("LIGHT", "Light", true),
+                switch("BIAS", "Bias", false),
+                switch("DARK", "Dark", false),
+                switch("FLAT", "Flat", false),
+            ],
+        ),
+        // CCD_BIN property
+        property(
+            DEVICE,
+            "CCD_BIN",
+            "Camera",
+            "Binning",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![
+                number("HORIZONTAL", "Horizontal binning", 1.0, 1.0, 4.0, 1.0),
+                number("VERTICAL", "Vertical binning", 1.0, 1.0, 4.0, 1.0),
+            ],
+        ),
+        // CCD_FRAME property
+        property(
+            DEVICE,
+            "CCD_FRAME",
+            "Camera",
+            "Frame",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![
+                number("LEFT", "Left", 0.0, 0.0, 1280.0, 1.0),
+                number("TOP", "Top", 0.0, 0.0, 1024.0, 1.0),
+                number("WIDTH", "Width", 1280.0, 0.0, 1280.0, 1.0),
+                number("HEIGHT", "Height", 1024.0, 0.0, 1024.0, 1.0),
+                number("BITS_PER_PIXEL", "Bits per pixel", 16.0, 8.0, 16.0, 8.0),
+            ],
+        ),
+        // CCD_TEMPERATURE property
+        property(
+            DEVICE,
+            "CCD_TEMPERATURE",
+            "Cooler",
+            "Temperature",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number("TEMPERATURE", "Temperature", 20.0, -50.0, 50.0, 0.1)],
+        ),
+        // CCD_COOLER property
+        switches(
+            DEVICE,
+            "CCD_COOLER",
+            "Cooler",
+            "Cooler",
+            SwitchRule::OneOfMany,
+            vec![switch("ON", "On", false), switch("OFF", "Off", true)],
+        ),
+        // CCD_COOLER_POWER property (read-only)
+        property(
+            DEVICE,
+            "CCD_COOLER_POWER",
+            "Cooler",
+            "Cooler power",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Number,
+            vec![number("POWER", "Power", 0.0, 0.0, 100.0, 1.0)],
+        ),
+        // CCD_GAIN property
+        property(
+            DEVICE,
+            "CCD_GAIN",
+            "Camera",
+            "Gain",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number("GAIN", "Gain", 100.0, 0.0, 500.0, 1.0)],
+        ),
+        // CCD_OFFSET property
+        property(
+            DEVICE,
+            "CCD_OFFSET",
+            "Camera",
+            "Offset",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number("OFFSET", "Offset", 10.0, 0.0, 255.0, 1.0)],
+        ),
+        // CCD_IMAGE property (read-only BLOB)
+        property(
+            DEVICE,
+            "CCD_IMAGE",
+            "Image",
+            "Image",
+            PropertyState::Idle,
+            PropertyPerm::ReadOnly,
+            PropertyType::Blob,
+            vec![PropertyItem {
+                name: "IMAGE".to_string(),
+                label: "Image".to_string(),
+                value: PropertyValue::Blob(BlobValue {
+                    url: String::new(),
+                    format: ".fits".to_string(),
+                    size: 0,
+                }),
+            }],
+        ),
+    ];
+
+    for property in properties {
+        device.properties.insert(property.name.clone(), property);
+    }
+
+    device
+}
@@ -0,0 +1,167 @@
+//! Focuser simulator preset device.
+
+use super::{number, property, switch, switches, text};
+use crate::mock_server::device::{DeviceMetadata, MockDevice};
+use crate::mock_server::property::*;
+use crate::protocol::{PropertyPerm, PropertyState, SwitchRule};
+use std::collections::HashMap;
+
+const DEVICE: &str = "Focuser Simulator";
+
+/// Creates a mock focuser simulator device
+pub fn focuser_simulator() -> MockDevice {
+    let mut device = MockDevice {
+        name: DEVICE.to_string(),
+        interface: 0x04, // Focuser interface
+        properties: HashMap::new(),
+        metadata: DeviceMetadata {
+            version: Some("1.0".to_string()),
+            driver_name: Some("indigo_focuser_simulator".to_string()),
+            driver_version: Some("2.0.300".to_string()),
+            driver_interface: Some(0x04),
+        },
+    };
+
+    let properties = [
+        // INFO property (read-only)
+        property(
+            DEVICE,
+            "INFO",
+            "Main",
+            "Info",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Text,
+            vec![
+                text("DEVICE_DRIVER", "Driver", "indigo_focuser_simulator"),
+                text("DEVICE_VERSION", "Version", "2.0.300"),
+                text("DEVICE_INTERFACE", "Interface", "4"),
+                text("DEVICE_MODEL", "Model", "Focuser Simulator"),
+            ],
+        ),
+        // CONNECTION property
+        switches(
+            DEVICE,
+            "CONNECTION",
+            "Main",
+            "Connection",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("CONNECTED", "Connected", false),
+                switch("DISCONNECTED", "Disconnected", true),
+            ],
+        ),
+        // FOCUSER_POSITION property
+        property(
+            DEVICE,
+            "FOCUSER_POSITION",
+            "Focuser",
+            "Position",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number("POSITION", "Position", 5000.0, 0.0, 65535.0, 1.0)],
+        ),
+        // FOCUSER_STEPS property
+        property(
+            DEVICE,
+            "FOCUSER_STEPS",
+            "Focuser",
+            "Steps",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number("STEPS", "Steps", 0.0, 0.0, 65535.0, 1.0)],
+        ),
+        // FOCUSER_DIRECTION property
+        switches(
+            DEVICE,
+            "FOCUSER_DIRECTION",
+            "Focuser",
+            "Direction",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("MOVE_INWARD", "Move inward", true),
+                switch("MOVE_OUTWARD", "Move outward", false),
+            ],
+        ),
+        // FOCUSER_ABORT_MOTION property
+        switches(
+            DEVICE,
+            "FOCUSER_ABORT_MOTION",
+            "Focuser",
+            "Abort motion",
+            SwitchRule::AnyOfMany,
+            vec![switch("ABORT_MOTION", "Abort", false)],
+        ),
+        // FOCUSER_TEMPERATURE property (read-only)
+        property(
+            DEVICE,
+            "FOCUSER_TEMPERATURE",
+            "Focuser",
+            "Temperature",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Number,
+            vec![number("TEMPERATURE", "Temperature", 12.5, -50.0, 50.0, 0.1)],
+        ),
+        // FOCUSER_COMPENSATION property
+        property(
+            DEVICE,
+            "FOCUSER_COMPENSATION",
+            "Focuser",
+            "Temperature compensation",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number(
+                "COMPENSATION",
+                "Steps per degree",
+                0.0,
+                -10000.0,
+                10000.0,
+                1.0,
+            )],
+        ),
+        // FOCUSER_MODE property
+        switches(
+            DEVICE,
+            "FOCUSER_MODE",
+            "Focuser",
+            "Mode",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("MANUAL", "Manual", true),
+                switch("AUTOMATIC", "Automatic", false),
+            ],
+        ),
+        // FOCUSER_BACKLASH property
+        property(
+            DEVICE,
+            "FOCUSER_BACKLASH",
+            "Focuser",
+            "Backlash",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number("BACKLASH", "Backlash", 0.0, 0.0, 1000.0, 1.0)],
+        ),
+        // FOCUSER_SPEED property
+        property(
+            DEVICE,
+            "FOCUSER_SPEED",
+            "Focuser",
+            "Speed",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number("SPEED", "Speed", 1.0, 1.0, 5.0, 1.0)],
+        ),
+    ];
+
+    for property in properties {
+        device.properties.insert(property.name.clone(), property);
+    }
+
+    device
+}
@@ -0,0 +1,103 @@
+//! Guider simulator preset device.
+
+use super::{number, property, switch, switches, text};
+use crate::mock_server::device::{DeviceMetadata, MockDevice};
+use crate::mock_server::property::*;
+use crate::protocol::{PropertyPerm, PropertyState, SwitchRule};
+use std::collections::HashMap;
+
+const DEVICE: &str = "Guider Simulator";
+
+/// Creates a mock guider simulator device
+pub fn guider_simulator() -> MockDevice {
+    let mut device = MockDevice {
+        name: DEVICE.to_string(),
+        interface: 0x10, // Guider interface
+        properties: HashMap::new(),
+        metadata: DeviceMetadata {
+            version: Some("1.0".to_string()),
+            driver_name: Some("indigo_guider_simulator".to_string()),
+            driver_version: Some("2.0.300".to_string()),
+            driver_interface: Some(0x10),
+        },
+    };
+
+    let properties = [
+        // INFO property (read-only)
+        property(
+            DEVICE,
+            "INFO",
+            "Main",
+            "Info",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Text,
+            vec![
+                text("DEVICE_DRIVER", "Driver", "indigo_guider_simulator"),
+                text("DEVICE_VERSION", "Version", "2.0.300"),
+                text("DEVICE_INTERFACE", "Interface", "16"),
+                text("DEVICE_MODEL", "Model", "Guider Simulator"),
+            ],
+        ),
+        // CONNECTION property
+        switches(
+            DEVICE,
+            "CONNECTION",
+            "Main",
+            "Connection",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("CONNECTED", "Connected", false),
+                switch("DISCONNECTED", "Disconnected", true),
+            ],
+        ),
+        // GUIDER_GUIDE_DEC property
+        property(
+            DEVICE,
+            "GUIDER_GUIDE_DEC",
+            "Guider",
+            "Guide DEC",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![
+                number("NORTH", "North (ms)", 0.0, 0.0, 10000.0, 1.0),
+                number("SOUTH", "South (ms)", 0.0, 0.0, 10000.0, 1.0),
+            ],
+        ),
+        // GUIDER_GUIDE_RA property
+        property(
+            DEVICE,
+            "GUIDER_GUIDE_RA",
+            "Guider",
+            "Guide RA",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![
+                number("EAST", "East (ms)", 0.0, 0.0, 10000.0, 1.0),
+                number("WEST", "West (ms)", 0.0, 0.0, 10000.0, 1.0),
+            ],
+        ),
+        // GUIDER_RATE property
+        property(
+            DEVICE,
+            "GUIDER_RATE",
+            "Guider",
+            "Guide rate",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![
+                number("RATE", "RA (% of sidereal)", 50.0, 10.0, 90.0, 1.0),
+                number("DEC_RATE", "DEC (% of sidereal)", 50.0, 10.0, 90.0, 1.0),
+            ],
+        ),
+    ];
+
+    for property in properties {
+        device.properties.insert(property.name.clone(), property);
+    }
+
+    device
+}
@@ -0,0 +1,110 @@
+//! Preset device configurations for common INDIGO devices.
+
+mod ccd;
+mod focuser;
+mod guider;
+mod mount;
+mod wheel;
+
+pub use ccd::ccd_simulator;
+pub use focuser::focuser_simulator;
+pub use guider::guider_simulator;
+pub use mount::mount_simulator;
+pub use wheel::wheel_simulator;
+
+use crate::mock_server::property::*;
+use crate::protocol::{PropertyPerm, PropertyState, SwitchRule};
+
+/// Creates a property with the given items.
+#[allow(clippy::too_many_arguments)]
+fn property(
+    device: &str,
+    name: &str,
+    group: &str,
+    label: &str,
+    state: PropertyState,
+    perm: PropertyPerm,
+    property_type: PropertyType,
+    items: Vec<PropertyItem>,
+) -> MockProperty {
+    let type_metadata = match property_type {
+        PropertyType::Text => PropertyTypeMetadata::Text,
+        PropertyType::Number => PropertyTypeMetadata::Number,
+        PropertyType::Switch => PropertyTypeMetadata::Switch {
+            rule: SwitchRule::OneOfMany,
+        },
+        PropertyType::Light => PropertyTypeMetadata::Light,
+        PropertyType::Blob => PropertyTypeMetadata::Blob,
+    };
+    MockProperty {
+        device: device.to_string(),
+        name: name.to_string(),
+        group: group.to_string(),
+        label: label.to_string(),
+        state,
+        perm,
+        property_type,
+        items,
+        timeout: None,
+        timestamp: None,
+        message: None,
+        type_metadata,
+    }
+}
+
+/// Creates a read-write switch property with the given rule.
+fn switches(
+    device: &str,
+    name: &str,
+    group: &str,
+    label: &str,
+    rule: SwitchRule,
+    items: Vec<PropertyItem>,
+) -> MockProperty {
+    MockProperty {
+        type_metadata: PropertyTypeMetadata::Switch { rule },
+        ..property(
+            device,
+            name,
+            group,
+            label,
+            PropertyState::Idle,
+            PropertyPerm::ReadWrite,
+            PropertyType::Switch,
+            items,
+        )
+    }
+}
+
+/// Creates a text item.
+fn text(name: &str, label: &str, value: &str) -> PropertyItem {
+    PropertyItem {
+        name: name.to_string(),
+        label: label.to_string(),
+        value: PropertyValue::Text(value.to_string()),
+    }
+}
+
+/// Creates a switch item.
+fn switch(name: &str, label: &str, on: bool) -> PropertyItem {
+    PropertyItem {
+        name: name.to_string(),
+        label: label.to_string(),
+        value: PropertyValue::Switch(on),
+    }
+}
+
+/// Creates a number item.
+fn number(name: &str, label: &str, value: f64, min: f64, max: f64, step: f64) -> PropertyItem {
+    PropertyItem {
+        name: name.to_string(),
+        label: label.to_string(),
+        value: PropertyValue::Number(NumberValue {
+            value,
+            format: if step < 1.0 { "%.2f" } else { "%.0f" }.to_string(),
+            min,
+            max,
+            step,
+        }),
+    }
+}
@@ -0,0 +1,219 @@
+//! Telescope mount simulator preset device.
+
+use super::{number, property, switch, switches, text};
+use crate::mock_server::device::{DeviceMetadata, MockDevice};
+use crate::mock_server::property::*;
+use crate::protocol::{PropertyPerm, PropertyState, SwitchRule};
+use std::collections::HashMap;
+
+const DEVICE: &str = "Mount Simulator";
+
+/// Creates a mock telescope mount simulator device
+pub fn mount_simulator() -> MockDevice {
+    let mut device = MockDevice {
+        name: DEVICE.to_string(),
+        interface: 0x08, // Mount interface
+        properties: HashMap::new(),
+        metadata: DeviceMetadata {
+            version: Some("1.0".to_string()),
+            driver_name: Some("indigo_mount_simulator".to_string()),
+            driver_version: Some("2.0.300".to_string()),
+            driver_interface: Some(0x08),
+        },
+    };
+
+    let properties = [
+        // INFO property (read-only)
+        property(
+            DEVICE,
+            "INFO",
+            "Main",
+            "Info",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Text,
+            vec![
+                text("DEVICE_DRIVER", "Driver", "indigo_mount_simulator"),
+                text("DEVICE_VERSION", "Version", "2.0.300"),
+                text("DEVICE_INTERFACE", "Interface", "8"),
+                text("DEVICE_MODEL", "Model", "Mount Simulator"),
+            ],
+        ),
+        // CONNECTION property
+        switches(
+            DEVICE,
+            "CONNECTION",
+            "Main",
+            "Connection",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("CONNECTED", "Connected", false),
+                switch("DISCONNECTED", "Disconnected", true),
+            ],
+        ),
+        // MOUNT_EQUATORIAL_COORDINATES property
+        property(
+            DEVICE,
+            "MOUNT_EQUATORIAL_COORDINATES",
+            "Main",
+            "Equatorial Coordinates",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![
+                number("RA", "Right Ascension", 0.0, 0.0, 24.0, 0.0001),
+                number("DEC", "Declination", 0.0, -90.0, 90.0, 0.0001),
+            ],
+        ),
+        // MOUNT_HORIZONTAL_COORDINATES property
+        property(
+            DEVICE,
+            "MOUNT_HORIZONTAL_COORDINATES",
+            "Main",
+            "Horizontal Coordinates",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![
+                number("ALT", "Altitude", 45.0, -90.0, 90.0, 0.0001),
+                number("AZ", "Azimuth", 180.0, 0.0, 360.0, 0.0001),
+            ],
+        ),
+        // MOUNT_ON_COORDINATES_SET property
+        switches(
+            DEVICE,
+            "MOUNT_ON_COORDINATES_SET",
+            "Main",
+            "On coordinates set",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("TRACK", "Slew and track", true),
+                switch("SYNC", "Sync", false),
+                switch("SLEW", "Slew", false),
+            ],
+        ),
+        // MOUNT_ABORT_MOTION property
+        switches(
+            DEVICE,
+            "MOUNT_ABORT_MOTION",
+            "Main",
+            "Abort motion",
+            SwitchRule::AnyOfMany,
+            vec![switch("ABORT_MOTION", "Abort", false)],
+        ),
+        // MOUNT_TRACKING property
+        switches(
+            DEVICE,
+            "MOUNT_TRACKING",
+            "Main",
+            "Tracking",
+            SwitchRule::OneOfMany,
+            vec![switch("ON", "On", false), switch("OFF", "Off", true)],
+        ),
+        // MOUNT_TRACK_RATE property
+        switches(
+            DEVICE,
+            "MOUNT_TRACK_RATE",
+            "Main",
+            "Track rate",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("SIDEREAL", "Sidereal", true),
+                switch("SOLAR", "Solar", false),
+                switch("LUNAR", "Lunar", false),
+                switch("CUSTOM", "Custom", false),
+            ],
+        ),
+        // MOUNT_SLEW_RATE property
+        switches(
+            DEVICE,
+            "MOUNT_SLEW_RATE",
+            "Main",
+            "Slew rate",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("GUIDE", "Guide", false),
+                switch("CENTERING", "Centering", true),
+                switch("FIND", "Find", false),
+                switch("MAX", "Max", false),
+            ],
+        ),
+        // MOUNT_MOTION_DEC property
+        switches(
+            DEVICE,
+            "MOUNT_MOTION_DEC",
+            "Main",
+            "Move N/S",
+            SwitchRule::AtMostOne,
+            vec![
+                switch("NORTH", "North", false),
+                switch("SOUTH", "South", false),
+            ],
+        ),
+        // MOUNT_MOTION_RA property
+        switches(
+            DEVICE,
+            "MOUNT_MOTION_RA",
+            "Main",
+            "Move W/E",
+            SwitchRule::AtMostOne,
+            vec![switch("WEST", "West", false), switch("EAST", "East", false)],
+        ),
+        // MOUNT_PARK property
+        switches(
+            DEVICE,
+            "MOUNT_PARK",
+            "Main",
+            "Park",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("PARKED", "Parked", true),
+                switch("UNPARKED", "Unparked", false),
+            ],
+        ),
+        // MOUNT_PARK_SET property
+        switches(
+            DEVICE,
+            "MOUNT_PARK_SET",
+            "Main",
+            "Set park position",
+            SwitchRule::AtMostOne,
+            vec![
+                switch("DEFAULT", "Default", false),
+                switch("CURRENT", "Current", false),
+            ],
+        ),
+        // MOUNT_LST_TIME property (read-only)
+        property(
+            DEVICE,
+            "MOUNT_LST_TIME",
+            "Site",
+            "Local sidereal time",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Number,
+            vec![number("TIME", "LST", 12.5, 0.0, 24.0, 0.0001)],
+        ),
+        // GEOGRAPHIC_COORDINATES property
+        property(
+            DEVICE,
+            "GEOGRAPHIC_COORDINATES",
+            "Site",
+            "Location",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![
+                number("LATITUDE", "Latitude", 51.5, -90.0, 90.0, 0.0001),
+                number("LONGITUDE", "Longitude", 0.0, -180.0, 360.0, 0.0001),
+                number("ELEVATION", "Elevation", 0.0, -400.0, 8000.0, 1.0),
+            ],
+        ),
+    ];
+
+    for property in properties {
+        device.properties.insert(property.name.clone(), property);
+    }
+
+    device
+}
@@ -0,0 +1,119 @@
+//! Filter wheel simulator preset device.
+
+use super::{number, property, switch, switches, text};
+use crate::mock_server::device::{DeviceMetadata, MockDevice};
+use crate::mock_server::property::*;
+use crate::protocol::{PropertyPerm, PropertyState, SwitchRule};
+use std::collections::HashMap;
+
+const DEVICE: &str = "Wheel Simulator";
+
+/// Filter names of the simulated wheel
+const FILTERS: [&str; 5] = ["Luminance", "Red", "Green", "Blue", "H-Alpha"];
+
+/// Creates a mock filter wheel simulator device
+pub fn wheel_simulator() -> MockDevice {
+    let mut device = MockDevice {
+        name: DEVICE.to_string(),
+        interface: 0x02, // Filter wheel interface
+        properties: HashMap::new(),
+        metadata: DeviceMetadata {
+            version: Some("1.0".to_string()),
+            driver_name: Some("indigo_wheel_simulator".to_string()),
+            driver_version: Some("2.0.300".to_string()),
+            driver_interface: Some(0x02),
+        },
+    };
+
+    let slots = FILTERS.len() as f64;
+    let properties = [
+        // INFO property (read-only)
+        property(
+            DEVICE,
+            "INFO",
+            "Main",
+            "Info",
+            PropertyState::Ok,
+            PropertyPerm::ReadOnly,
+            PropertyType::Text,
+            vec![
+                text("DEVICE_DRIVER", "Driver", "indigo_wheel_simulator"),
+                text("DEVICE_VERSION", "Version", "2.0.300"),
+                text("DEVICE_INTERFACE", "Interface", "2"),
+                text("DEVICE_MODEL", "Model", "Wheel Simulator"),
+            ],
+        ),
+        // CONNECTION property
+        switches(
+            DEVICE,
+            "CONNECTION",
+            "Main",
+            "Connection",
+            SwitchRule::OneOfMany,
+            vec![
+                switch("CONNECTED", "Connected", false),
+                switch("DISCONNECTED", "Disconnected", true),
+            ],
+        ),
+        // WHEEL_SLOT property
+        property(
+            DEVICE,
+            "WHEEL_SLOT",
+            "Filter Wheel",
+            "Slot",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            vec![number("SLOT", "Slot", 1.0, 1.0, slots, 1.0)],
+        ),
+        // WHEEL_SLOT_NAME property
+        property(
+            DEVICE,
+            "WHEEL_SLOT_NAME",
+            "Filter Wheel",
+            "Slot names",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Text,
+            FILTERS
+                .iter()
+                .enumerate()
+                .map(|(i, filter)| {
+                    text(
+                        &format!("SLOT_NAME_{}", i + 1),
+                        &format!("Slot #{}", i + 1),
+                        filter,
+                    )
+                })
+                .collect(),
+        ),
+        // WHEEL_SLOT_OFFSET property
+        property(
+            DEVICE,
+            "WHEEL_SLOT_OFFSET",
+            "Filter Wheel",
+            "Slot offsets",
+            PropertyState::Ok,
+            PropertyPerm::ReadWrite,
+            PropertyType::Number,
+            (1..=FILTERS.len())
+                .map(|slot| {
+                    number(
+                        &format!("SLOT_OFFSET_{}", slot),
+                        &format!("Slot #{}", slot),
+                        0.0,
+                        -1000.0,
+                        1000.0,
+                        1.0,
+                    )
+                })
+                .collect(),
+        ),
+    ];
+
+    for property in properties {
+        device.properties.insert(property.name.clone(), property);
+    }
+
+    device
+}
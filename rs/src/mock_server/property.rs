@@ -1,8 +1,7 @@
 //! Property state management for the mock INDIGO server.
 
+use crate::protocol::{PropertyPerm, PropertyState, SwitchRule};
 use libindigo::error::{IndigoError, Result};
-use libindigo_rs::protocol::{PropertyPerm, PropertyState, SwitchRule};
-use std::collections::HashMap;
 
 /// Type of property
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
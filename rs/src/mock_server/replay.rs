@@ -0,0 +1,203 @@
+//! Replay of recorded sessions.
+
+use crate::protocol::ProtocolMessage;
+use crate::recording::Recording;
+use crate::transport::{ReadTransport, Transport};
+use libindigo::error::{IndigoError, Result};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Server that feeds a recorded session back to clients.
+///
+/// Every client that connects is sent the messages the recorded client
+/// received, in the protocol the client speaks. The replay starts when the
+/// client sends its first message, as a real server answers the initial
+/// `getProperties`, and keeps the recorded time between messages divided by
+/// the speed. Messages sent by clients don't affect the replay, but are
+/// collected for assertions.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example() -> libindigo::error::Result<()> {
+/// use libindigo_rs::mock_server::ReplayServer;
+/// use libindigo_rs::recording::Recording;
+///
+/// // Replay ten times faster than recorded
+/// let recording = Recording::load("session.jsonl")?;
+/// let server = ReplayServer::start(recording, 10.0).await?;
+///
+/// // Connect a client to server.addr()...
+///
+/// server.shutdown().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplayServer {
+    /// TCP listener address
+    addr: SocketAddr,
+
+    /// Messages sent by clients
+    client_messages: Arc<Mutex<Vec<ProtocolMessage>>>,
+
+    /// Shutdown signal sender
+    shutdown_tx: broadcast::Sender<()>,
+
+    /// Server task handle
+    task_handle: Option<JoinHandle<()>>,
+}
+
+impl ReplayServer {
+    /// Start replaying a recording on a random local port
+    ///
+    /// A speed of 1 keeps the recorded timing, higher speeds accelerate it
+    /// and an infinite speed sends the messages without pauses.
+    pub async fn start(recording: Recording, speed: f64) -> Result<Self> {
+        if speed.is_nan() || speed <= 0.0 {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Replay speed {} is not positive",
+                speed
+            )));
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| IndigoError::ConnectionError(format!("Failed to bind: {}", e)))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| IndigoError::ConnectionError(format!("Failed to get address: {}", e)))?;
+
+        let client_messages = Arc::new(Mutex::new(Vec::new()));
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(16);
+        let task_handle = tokio::spawn(Self::run_server(
+            listener,
+            Arc::new(recording),
+            speed,
+            client_messages.clone(),
+            shutdown_rx,
+        ));
+
+        Ok(Self {
+            addr,
+            client_messages,
+            shutdown_tx,
+            task_handle: Some(task_handle),
+        })
+    }
+
+    /// Get the server's listening address
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Get the messages sent by clients, in the order they arrived
+    pub async fn client_messages(&self) -> Vec<ProtocolMessage> {
+        self.client_messages.lock().await.clone()
+    }
+
+    /// Shutdown the server, closing all connections
+    pub async fn shutdown(mut self) -> Result<()> {
+        let _ = self.shutdown_tx.send(());
+        if let Some(handle) = self.task_handle.take() {
+            handle
+                .await
+                .map_err(|e| IndigoError::ConnectionError(format!("Task join error: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Internal server loop
+    async fn run_server(
+        listener: TcpListener,
+        recording: Arc<Recording>,
+        speed: f64,
+        client_messages: Arc<Mutex<Vec<ProtocolMessage>>>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) {
+        loop {
+            tokio::select! {
+                result = listener.accept() => {
+                    match result {
+                        Ok((stream, _addr)) => {
+                            tokio::spawn(Self::replay(
+                                stream,
+                                recording.clone(),
+                                speed,
+                                client_messages.clone(),
+                                shutdown_rx.resubscribe(),
+                            ));
+                        }
+                        Err(e) => {
+                            tracing::warn!("Accept error: {}", e);
+                        }
+                    }
+                }
+                _ = shutdown_rx.recv() => {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Replays the recording to one client
+    async fn replay(
+        stream: TcpStream,
+        recording: Arc<Recording>,
+        speed: f64,
+        client_messages: Arc<Mutex<Vec<ProtocolMessage>>>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) {
+        let Ok((mut reader, mut writer)) = Transport::from_stream(stream).split() else {
+            return;
+        };
+
+        // Wait for the client to start the session
+        let first = tokio::select! {
+            message = reader.receive_message() => message,
+            _ = shutdown_rx.recv() => return,
+        };
+        let Ok(first) = first else {
+            return;
+        };
+        client_messages.lock().await.push(first);
+        writer.set_protocol(reader.protocol());
+
+        // Times are relative to the first message of the recorded client
+        let start = recording
+            .sent()
+            .next()
+            .map_or(Duration::ZERO, |message| message.time);
+        let started = Instant::now();
+        let send = async {
+            for message in recording.received() {
+                let due = message.time.saturating_sub(start).div_f64(speed);
+                tokio::time::sleep_until(started + due).await;
+                if let Err(e) = writer.send_message(&message.message).await {
+                    tracing::warn!("Replay send error: {}", e);
+                    break;
+                }
+            }
+        };
+
+        tokio::select! {
+            _ = async { tokio::join!(send, Self::collect(&mut reader, &client_messages)) } => {}
+            _ = shutdown_rx.recv() => {}
+        }
+    }
+
+    /// Collects the messages sent by a client until it disconnects
+    async fn collect(reader: &mut ReadTransport, messages: &Mutex<Vec<ProtocolMessage>>) {
+        loop {
+            match reader.receive_message().await {
+                Ok(message) => messages.lock().await.push(message),
+                Err(IndigoError::ConnectionError(_)) => break,
+                Err(_) => continue,
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 //! Main mock INDIGO server implementation.
 
 use super::device::DeviceRegistry;
+use super::fault::Faults;
 use super::property::{MockProperty, PropertyUpdate};
 use super::subscription::SubscriptionManager;
 use libindigo::error::{IndigoError, Result};
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -48,8 +50,27 @@ pub struct ServerConfig {
     /// Property update interval for streaming (None = no auto-updates)
     pub update_interval: Option<Duration>,
 
+    /// Time properties stay busy after a change request (None = complete immediately)
+    pub busy_delay: Option<Duration>,
+
     /// Enable verbose logging
     pub verbose: bool,
+
+    /// Faults injected into every connection
+    pub faults: Faults,
+
+    /// Faults injected into single connections, numbered from 1 in the order
+    /// clients connect
+    pub connection_faults: HashMap<usize, Faults>,
+}
+
+impl ServerConfig {
+    /// Get the faults injected into a connection
+    pub fn faults_for(&self, connection: usize) -> &Faults {
+        self.connection_faults
+            .get(&connection)
+            .unwrap_or(&self.faults)
+    }
 }
 
 impl Default for ServerConfig {
@@ -59,7 +80,10 @@ impl Default for ServerConfig {
             protocol_version: 512,
             max_connections: 10,
             update_interval: None,
+            busy_delay: None,
             verbose: false,
+            faults: Faults::default(),
+            connection_faults: HashMap::new(),
         }
     }
 }
@@ -77,6 +101,9 @@ pub struct ServerState {
 
     /// Server statistics
     pub stats: RwLock<ServerStats>,
+
+    /// Time properties stay busy after a change request
+    pub busy_delay: Option<Duration>,
 }
 
 /// Server statistics for testing/debugging
@@ -98,10 +125,17 @@ impl ServerState {
             subscriptions: RwLock::new(SubscriptionManager::new()),
             connection_count: AtomicUsize::new(0),
             stats: RwLock::new(ServerStats::default()),
+            busy_delay: None,
         }
     }
 }
 
+impl Default for ServerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MockIndigoServer {
     /// Create a new mock server with configuration and devices
     pub async fn new(
@@ -117,7 +151,10 @@ impl MockIndigoServer {
             .map_err(|e| IndigoError::ConnectionError(format!("Failed to get address: {}", e)))?;
 
         // Create shared state
-        let state = Arc::new(ServerState::new());
+        let state = Arc::new(ServerState {
+            busy_delay: config.busy_delay,
+            ..ServerState::new()
+        });
 
         // Add devices to registry
         {
@@ -151,6 +188,11 @@ impl MockIndigoServer {
         self.addr
     }
 
+    /// Get the server configuration
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
     /// Get server statistics
     pub async fn stats(&self) -> ServerStats {
         self.state.stats.read().await.clone()
@@ -252,6 +294,7 @@ impl MockIndigoServer {
                                 connection_id,
                                 stream,
                                 state.clone(),
+                                config.faults_for(connection_id).clone(),
                                 shutdown_rx.resubscribe(),
                             );
 
@@ -259,7 +302,7 @@ impl MockIndigoServer {
                             let state_clone = state.clone();
                             tokio::spawn(async move {
                                 if let Err(e) = conn.handle().await {
-                                    eprintln!("Connection {} error: {}", connection_id, e);
+                                    tracing::warn!("Connection {} error: {}", connection_id, e);
                                 }
                                 // Decrement active connections
                                 let mut stats = state_clone.stats.write().await;
@@ -267,7 +310,7 @@ impl MockIndigoServer {
                             });
                         }
                         Err(e) => {
-                            eprintln!("Accept error: {}", e);
+                            tracing::warn!("Accept error: {}", e);
                         }
                     }
                 }
@@ -293,7 +336,7 @@ impl MockIndigoServer {
                 _ = interval_timer.tick() => {
                     // Update simulated properties
                     if let Err(e) = Self::update_simulated_properties(&state).await {
-                        eprintln!("Property update error: {}", e);
+                        tracing::warn!("Property update error: {}", e);
                     }
                 }
                 _ = shutdown_rx.recv() => {
@@ -315,7 +358,7 @@ impl MockIndigoServer {
             if let Some(property) = device.properties.get_mut("CCD_TEMPERATURE") {
                 // Simulate temperature change
                 for item in &mut property.items {
-                    if item.name == "CCD_TEMPERATURE_VALUE" {
+                    if item.name == "TEMPERATURE" {
                         if let super::property::PropertyValue::Number(ref mut num) = item.value {
                             let mut rng = rand::thread_rng();
                             num.value += (rng.gen::<f64>() - 0.5) * 0.1;
@@ -323,7 +366,7 @@ impl MockIndigoServer {
                         }
                     }
                 }
-                property.state = libindigo_rs::protocol::PropertyState::Ok;
+                property.state = crate::protocol::PropertyState::Ok;
 
                 // Notify subscribers
                 if let Ok(message) = super::handler::property_to_set_message(property) {
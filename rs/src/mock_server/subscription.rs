@@ -1,6 +1,6 @@
 //! Property subscription management for the mock INDIGO server.
 
-use libindigo_rs::protocol::ProtocolMessage;
+use crate::protocol::ProtocolMessage;
 use std::collections::HashMap;
 use tokio::sync::mpsc;
 
@@ -92,7 +92,7 @@ impl Default for SubscriptionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use libindigo_rs::protocol::{GetProperties, ProtocolMessage};
+    use crate::protocol::{GetProperties, ProtocolMessage};
 
     #[test]
     fn test_subscription_manager() {
@@ -83,6 +83,82 @@ pub fn encode_blob(data: &[u8]) -> String {
     BASE64_STANDARD.encode(data)
 }
 
+/// Incremental decoder of base64-encoded BLOB data.
+///
+/// Decodes data in pieces of any length as it arrives, so that large BLOBs
+/// never have to be held in encoded form. Whitespace is skipped, as by
+/// [`decode_blob`].
+///
+/// # Example
+///
+/// ```
+/// use libindigo_rs::protocol::Base64Decoder;
+///
+/// let mut decoder = Base64Decoder::new();
+/// let mut data = Vec::new();
+/// decoder.decode(b"SGVsbG8s", &mut data).unwrap();
+/// decoder.decode(b"IHdvcmxk\nIQ==", &mut data).unwrap();
+/// decoder.finish().unwrap();
+/// assert_eq!(data, b"Hello, world!");
+/// ```
+#[derive(Debug, Default)]
+pub struct Base64Decoder {
+    /// Characters not decoded yet, less than a group of four between calls.
+    pending: Vec<u8>,
+    /// Whether the padded last group was decoded.
+    padded: bool,
+}
+
+impl Base64Decoder {
+    /// Creates a decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes a piece of data, appending the decoded bytes to `output`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BlobError` if the data is invalid.
+    pub fn decode(&mut self, input: &[u8], output: &mut Vec<u8>) -> Result<()> {
+        use base64::prelude::*;
+
+        self.pending
+            .extend(input.iter().filter(|b| !b.is_ascii_whitespace()));
+        let complete = self.pending.len() / 4 * 4;
+        if complete == 0 {
+            return Ok(());
+        }
+        if self.padded {
+            return Err(IndigoError::BlobError(
+                "Failed to decode base64 BLOB: data after padding".to_string(),
+            ));
+        }
+
+        self.padded = self.pending[complete - 1] == b'=';
+        BASE64_STANDARD
+            .decode_vec(&self.pending[..complete], output)
+            .map_err(|e| IndigoError::BlobError(format!("Failed to decode base64 BLOB: {}", e)))?;
+        self.pending.drain(..complete);
+        Ok(())
+    }
+
+    /// Finishes decoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BlobError` if the data ended in the middle of a group.
+    pub fn finish(self) -> Result<()> {
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(IndigoError::BlobError(
+                "Failed to decode base64 BLOB: truncated data".to_string(),
+            ))
+        }
+    }
+}
+
 // ============================================================================
 // Protocol Enums
 // ============================================================================
@@ -318,6 +394,36 @@ pub struct OneBLOB {
     pub format: String,
     /// Base64-encoded BLOB data.
     pub value: String,
+    /// Data received by the transport, with `value` left empty.
+    ///
+    /// Set by the transport, which streams the content of received BLOBs
+    /// instead of buffering it and fetches BLOBs sent by URL. `None` when
+    /// the BLOB was written to a sink.
+    pub data: Option<Vec<u8>>,
+    /// Size in bytes as transferred, for a BLOB received compressed.
+    ///
+    /// Set by the transport, which decompresses BLOBs in `.z` formats,
+    /// removing the suffix from `format` and setting `size` to the size of
+    /// the decompressed data.
+    pub compressed_size: Option<usize>,
+}
+
+impl OneBLOB {
+    /// Gets the base64-encoded data, encoding decoded data if necessary.
+    pub fn encoded_value(&self) -> std::borrow::Cow<'_, str> {
+        match &self.data {
+            Some(data) => std::borrow::Cow::Owned(encode_blob(data)),
+            None => std::borrow::Cow::Borrowed(&self.value),
+        }
+    }
+
+    /// Takes the decoded data, decoding `value` if necessary.
+    pub fn take_data(&mut self) -> Result<Vec<u8>> {
+        match self.data.take() {
+            Some(data) => Ok(data),
+            None => decode_blob(&self.value),
+        }
+    }
 }
 
 // ============================================================================
@@ -1173,6 +1279,8 @@ impl ProtocolParser {
                         size,
                         format,
                         value,
+                        data: None,
+                        compressed_size: None,
                     });
                 }
                 Ok(Event::End(e)) if e.name().as_ref() == b"setBLOBVector" => break,
@@ -1315,6 +1423,8 @@ impl ProtocolParser {
                         size,
                         format,
                         value,
+                        data: None,
+                        compressed_size: None,
                     });
                 }
                 Ok(Event::End(e)) if e.name().as_ref() == b"newBLOBVector" => break,
@@ -1810,7 +1920,7 @@ impl ProtocolSerializer {
                 .write_event(Event::Start(item_elem))
                 .map_err(|e| IndigoError::ProtocolError(format!("Write error: {}", e)))?;
             writer
-                .write_event(Event::Text(BytesText::new(&item.value)))
+                .write_event(Event::Text(BytesText::new(&item.encoded_value())))
                 .map_err(|e| IndigoError::ProtocolError(format!("Write error: {}", e)))?;
             writer
                 .write_event(Event::End(BytesEnd::new("oneBLOB")))
@@ -1948,7 +2058,7 @@ impl ProtocolSerializer {
                 .write_event(Event::Start(item_elem))
                 .map_err(|e| IndigoError::ProtocolError(format!("Write error: {}", e)))?;
             writer
-                .write_event(Event::Text(BytesText::new(&item.value)))
+                .write_event(Event::Text(BytesText::new(&item.encoded_value())))
                 .map_err(|e| IndigoError::ProtocolError(format!("Write error: {}", e)))?;
             writer
                 .write_event(Event::End(BytesEnd::new("oneBLOB")))
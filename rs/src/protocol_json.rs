@@ -548,6 +548,8 @@ impl JsonProtocolParser {
                 size,
                 format: Self::get_opt_string(item_obj, "format").unwrap_or_default(),
                 value, // Can be URL path or base64 data
+                data: None,
+                compressed_size: None,
             });
         }
 
@@ -677,6 +679,8 @@ impl JsonProtocolParser {
                 size: 0,
                 format: Self::get_string(item_obj, "format")?,
                 value: String::new(), // Not used in newBLOBVector
+                data: None,
+                compressed_size: None,
             });
         }
 
@@ -1052,7 +1056,7 @@ impl JsonProtocolSerializer {
                 item.insert("format".to_string(), json!(e.format));
                 item.insert("size".to_string(), json!(e.size));
                 // Include base64-encoded data or URL
-                item.insert("value".to_string(), json!(e.value));
+                item.insert("value".to_string(), json!(e.encoded_value()));
                 Value::Object(item)
             })
             .collect();
@@ -1135,7 +1139,7 @@ impl JsonProtocolSerializer {
                 item.insert("format".to_string(), json!(e.format));
                 item.insert("size".to_string(), json!(e.size));
                 // Include base64-encoded data
-                item.insert("value".to_string(), json!(e.value));
+                item.insert("value".to_string(), json!(e.encoded_value()));
                 Value::Object(item)
             })
             .collect();
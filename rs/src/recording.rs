@@ -0,0 +1,261 @@
+//! Recording of INDIGO sessions
+//!
+//! A [`SessionRecorder`] taps the transport of a client and writes every
+//! message sent and received, with the time since recording started, to a
+//! file. Recordings reproduce problems offline and turn real server traffic
+//! into regression tests: with the `test-server` feature, the
+//! `mock_server::ReplayServer` feeds a recorded session back to a client.
+//!
+//! # Format
+//!
+//! A recording is a JSON Lines file with one message per line. Messages are
+//! stored in the INDIGO JSON protocol, whichever protocol was used on the
+//! wire:
+//!
+//! ```text
+//! {"time":0.0,"direction":"sent","message":{"getProperties":{"version":512}}}
+//! {"time":0.0021,"direction":"received","message":{"defTextVector":{...}}}
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo_rs::recording::{Recording, SessionRecorder};
+//!
+//! let recorder = SessionRecorder::create("session.jsonl")?;
+//! let mut strategy = RsClientStrategy::new();
+//! strategy.set_recorder(Some(recorder.clone())).await;
+//! strategy.connect("localhost:7624").await?;
+//! // ...
+//! recorder.flush().await?;
+//!
+//! let recording = Recording::load("session.jsonl")?;
+//! ```
+
+use crate::protocol::ProtocolMessage;
+use crate::protocol_json::{JsonProtocolParser, JsonProtocolSerializer};
+use libindigo::error::{IndigoError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Direction of a recorded message, seen from the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Sent by the client to the server
+    Sent,
+    /// Received by the client from the server
+    Received,
+}
+
+/// A message of a recorded session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedMessage {
+    /// Time since recording started
+    pub time: Duration,
+    /// Whether the message was sent or received
+    pub direction: Direction,
+    /// The message
+    pub message: ProtocolMessage,
+}
+
+/// A line of a recording file.
+#[derive(Deserialize)]
+struct Line {
+    time: f64,
+    direction: Direction,
+    message: serde_json::Value,
+}
+
+/// Writes the messages of a session to a file.
+///
+/// Messages are written by a background thread, so recording never blocks
+/// the transport on the file. Cloning a recorder gives another handle to the
+/// same recording, so both halves of a transport record into one file. The
+/// thread finishes the file when the last handle is dropped.
+#[derive(Clone)]
+pub struct SessionRecorder {
+    inner: Arc<Mutex<RecorderInner>>,
+}
+
+struct RecorderInner {
+    commands: mpsc::Sender<Command>,
+    started: Instant,
+}
+
+/// A request to the writer thread of a recorder.
+enum Command {
+    Write(String),
+    Flush(oneshot::Sender<Result<()>>),
+}
+
+impl SessionRecorder {
+    /// Create a recorder writing to a new file, replacing an existing one
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Create a recorder writing to any writer
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        std::thread::spawn(move || write_lines(writer, receiver));
+        Self {
+            inner: Arc::new(Mutex::new(RecorderInner {
+                commands,
+                started: Instant::now(),
+            })),
+        }
+    }
+
+    /// Record a message
+    ///
+    /// The writer is flushed whenever it has caught up with the session, so
+    /// the recording is complete up to the last message even if the
+    /// application crashes.
+    pub fn record(&self, direction: Direction, message: &ProtocolMessage) -> Result<()> {
+        let json = JsonProtocolSerializer::serialize(message)?;
+        let direction = match direction {
+            Direction::Sent => "sent",
+            Direction::Received => "received",
+        };
+        // The lock keeps the times in the order of the lines
+        let inner = self.lock()?;
+        let line = format!(
+            r#"{{"time":{},"direction":"{}","message":{}}}"#,
+            inner.started.elapsed().as_secs_f64(),
+            direction,
+            json
+        );
+        inner.commands.send(Command::Write(line)).map_err(stopped)
+    }
+
+    /// Wait until all recorded messages are written and flushed
+    pub async fn flush(&self) -> Result<()> {
+        let (done, flushed) = oneshot::channel();
+        self.lock()?
+            .commands
+            .send(Command::Flush(done))
+            .map_err(stopped)?;
+        flushed.await.map_err(stopped)?
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, RecorderInner>> {
+        self.inner
+            .lock()
+            .map_err(|_| IndigoError::InvalidState("Recorder lock poisoned".to_string()))
+    }
+}
+
+fn stopped(_: impl std::error::Error) -> IndigoError {
+    IndigoError::InvalidState("Recorder stopped after a write error".to_string())
+}
+
+/// Writes the lines of a recording until every recorder handle is dropped.
+fn write_lines(mut writer: impl Write, commands: mpsc::Receiver<Command>) {
+    let mut next = commands.recv().ok();
+    while let Some(command) = next {
+        let result = match command {
+            Command::Write(line) => writeln!(writer, "{}", line),
+            Command::Flush(done) => {
+                let _ = done.send(writer.flush().map_err(IndigoError::from));
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to write recording: {}", e);
+            return;
+        }
+        next = match commands.try_recv() {
+            Ok(command) => Some(command),
+            Err(mpsc::TryRecvError::Disconnected) => None,
+            // Flush once the writer has caught up
+            Err(mpsc::TryRecvError::Empty) => match writer.flush() {
+                Ok(()) => commands.recv().ok(),
+                Err(e) => {
+                    tracing::warn!("Failed to write recording: {}", e);
+                    return;
+                }
+            },
+        };
+    }
+    if let Err(e) = writer.flush() {
+        tracing::warn!("Failed to write recording: {}", e);
+    }
+}
+
+impl std::fmt::Debug for SessionRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionRecorder").finish_non_exhaustive()
+    }
+}
+
+/// Records a message on a transport, logging failures so that they don't
+/// interrupt the session.
+pub(crate) fn record(
+    recorder: &Option<SessionRecorder>,
+    direction: Direction,
+    message: &ProtocolMessage,
+) {
+    if let Some(recorder) = recorder {
+        if let Err(e) = recorder.record(direction, message) {
+            tracing::warn!("Failed to record message: {}", e);
+        }
+    }
+}
+
+/// A recorded session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    /// Messages in the order they were recorded
+    pub messages: Vec<RecordedMessage>,
+}
+
+impl Recording {
+    /// Load a recording from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a recording, skipping empty lines
+    pub fn from_reader(reader: impl BufRead) -> Result<Self> {
+        let mut messages = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = |e: &dyn std::fmt::Display| {
+                IndigoError::ParseError(format!("Line {}: {}", number + 1, e))
+            };
+            let line: Line = serde_json::from_str(&line).map_err(|e| invalid(&e))?;
+            let message = JsonProtocolParser::parse_message(&line.message.to_string())
+                .map_err(|e| invalid(&e))?;
+            let time = Duration::try_from_secs_f64(line.time).map_err(|e| invalid(&e))?;
+            messages.push(RecordedMessage {
+                time,
+                direction: line.direction,
+                message,
+            });
+        }
+        Ok(Self { messages })
+    }
+
+    /// Get the messages received by the client
+    pub fn received(&self) -> impl Iterator<Item = &RecordedMessage> {
+        self.messages
+            .iter()
+            .filter(|m| m.direction == Direction::Received)
+    }
+
+    /// Get the messages sent by the client
+    pub fn sent(&self) -> impl Iterator<Item = &RecordedMessage> {
+        self.messages
+            .iter()
+            .filter(|m| m.direction == Direction::Sent)
+    }
+}
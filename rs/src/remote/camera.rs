@@ -0,0 +1,356 @@
+//! Camera implementation for remote CCD devices.
+
+use super::{impl_remote_device, selected_item, RemoteDevice};
+use crate::RsClientStrategy;
+use libindigo::client::outcome::DEFAULT_CHANGE_TIMEOUT;
+use libindigo::client::{ClientStrategy, PropertyEvent};
+use libindigo::device::traits::{BinningMode, Camera, CcdInfo, ExposureState, FrameType};
+use libindigo::device::DeviceInterface;
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
+use libindigo::types::{BlobTransferMode, Property, PropertyState, PropertyValue};
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+/// A camera on a remote INDIGO server.
+///
+/// Implements [`Camera`] using the standard `CCD_*` properties. Images are
+/// received as `CCD_IMAGE` BLOBs, which are enabled on the first exposure.
+pub struct RemoteCamera {
+    device: RemoteDevice,
+    /// Whether BLOB transfer has been enabled for `CCD_IMAGE`.
+    blob_enabled: bool,
+    /// Events for the running exposure and its duration.
+    pending: Mutex<Option<(mpsc::UnboundedReceiver<PropertyEvent>, Duration)>>,
+}
+
+impl RemoteCamera {
+    /// Creates a camera for a device defined on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device is not defined.
+    pub async fn new(client: &RsClientStrategy, device: &str) -> Result<Self> {
+        Ok(RemoteDevice::new(client, device).await?.into())
+    }
+
+    /// Gets the underlying remote device.
+    pub fn device(&self) -> &RemoteDevice {
+        &self.device
+    }
+
+    /// Gets the underlying remote device for direct property access.
+    pub fn device_mut(&mut self) -> &mut RemoteDevice {
+        &mut self.device
+    }
+
+    /// Returns the image from `CCD_IMAGE` if the property holds a completed BLOB.
+    fn image_from(property: &Property) -> Option<Vec<u8>> {
+        if property.state != PropertyState::Ok {
+            return None;
+        }
+        match property.items.get(name::CCD_IMAGE_ITEM).map(|i| &i.value) {
+            Some(PropertyValue::Blob { data, .. }) if !data.is_empty() => Some(data.clone()),
+            _ => None,
+        }
+    }
+
+    /// Waits for the image of the current exposure.
+    async fn wait_for_image(
+        &self,
+        events: &mut mpsc::UnboundedReceiver<PropertyEvent>,
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        let device = self.device.name();
+        let wait = async {
+            while let Some(event) = events.recv().await {
+                let PropertyEvent::Updated(property) = event else {
+                    continue;
+                };
+                if property.device != device {
+                    continue;
+                }
+                match property.name.as_str() {
+                    name::CCD_IMAGE_PROPERTY => {
+                        if let Some(image) = Self::image_from(&property) {
+                            return Ok(image);
+                        }
+                        if property.state == PropertyState::Alert {
+                            return Err(IndigoError::BlobError(format!(
+                                "{} failed to deliver the image",
+                                device
+                            )));
+                        }
+                    }
+                    name::CCD_EXPOSURE_PROPERTY if property.state == PropertyState::Alert => {
+                        return Err(IndigoError::InvalidState(format!(
+                            "{} exposure failed: {}",
+                            device,
+                            property.message.as_deref().unwrap_or("no message")
+                        )));
+                    }
+                    _ => {}
+                }
+            }
+            Err(IndigoError::ConnectionError(
+                "Event stream closed while waiting for the image".to_string(),
+            ))
+        };
+
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            IndigoError::Timeout(format!("No image from {} within {:?}", device, timeout))
+        })?
+    }
+}
+
+impl From<RemoteDevice> for RemoteCamera {
+    fn from(device: RemoteDevice) -> Self {
+        RemoteCamera {
+            device,
+            blob_enabled: false,
+            pending: Mutex::new(None),
+        }
+    }
+}
+
+impl_remote_device!(RemoteCamera, dyn Camera, DeviceInterface::Ccd);
+
+#[async_trait::async_trait]
+impl Camera for RemoteCamera {
+    async fn ccd_info(&self) -> Result<CcdInfo> {
+        let info = self.device.property(name::CCD_INFO_PROPERTY).await?;
+        let number = |item| super::number_item(&info, item);
+        Ok(CcdInfo {
+            width: number(name::CCD_INFO_WIDTH_ITEM)? as u32,
+            height: number(name::CCD_INFO_HEIGHT_ITEM)? as u32,
+            pixel_size: number(name::CCD_INFO_PIXEL_SIZE_ITEM)?,
+            max_bin_x: number(name::CCD_INFO_MAX_HORIZONTAL_BIN_ITEM)? as u32,
+            max_bin_y: number(name::CCD_INFO_MAX_VERTICAL_BIN_ITEM)? as u32,
+            bits_per_pixel: number(name::CCD_INFO_BITS_PER_PIXEL_ITEM)? as u32,
+        })
+    }
+
+    async fn start_exposure(&mut self, duration: f64) -> Result<()> {
+        if !self.blob_enabled {
+            let device = self.device.name().to_string();
+            self.device
+                .client_mut()
+                .enable_blob(
+                    &device,
+                    Some(name::CCD_IMAGE_PROPERTY),
+                    BlobTransferMode::Also,
+                )
+                .await?;
+            self.blob_enabled = true;
+        }
+
+        // Subscribe before starting so the image can't be missed
+        let events = self.device.client().subscribe_events().await?;
+        self.device
+            .send_numbers(
+                name::CCD_EXPOSURE_PROPERTY,
+                &[(name::CCD_EXPOSURE_ITEM, duration)],
+            )
+            .await?;
+        *self.pending.lock().await = Some((events, Duration::from_secs_f64(duration.max(0.0))));
+        Ok(())
+    }
+
+    async fn abort_exposure(&mut self) -> Result<()> {
+        self.pending.lock().await.take();
+        self.device
+            .change_switches(
+                name::CCD_ABORT_EXPOSURE_PROPERTY,
+                &[(name::CCD_ABORT_EXPOSURE_ITEM, true)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn exposure_state(&self) -> Result<ExposureState> {
+        let exposure = self.device.property(name::CCD_EXPOSURE_PROPERTY).await?;
+        Ok(match exposure.state {
+            PropertyState::Idle => ExposureState::Idle,
+            PropertyState::Ok => ExposureState::Complete,
+            PropertyState::Busy => {
+                ExposureState::Exposing(super::number_item(&exposure, name::CCD_EXPOSURE_ITEM)?)
+            }
+            PropertyState::Alert => {
+                let aborted = self
+                    .device
+                    .find_property(name::CCD_ABORT_EXPOSURE_PROPERTY)
+                    .await
+                    .is_some_and(|abort| abort.state == PropertyState::Ok);
+                if aborted {
+                    ExposureState::Aborted
+                } else {
+                    ExposureState::Error
+                }
+            }
+        })
+    }
+
+    async fn download_image(&self) -> Result<Vec<u8>> {
+        if let Some((mut events, duration)) = self.pending.lock().await.take() {
+            return self
+                .wait_for_image(&mut events, duration + DEFAULT_CHANGE_TIMEOUT)
+                .await;
+        }
+
+        // No exposure started by this handle: use the last image, or wait for one
+        let mut events = self.device.client().subscribe_events().await?;
+        if let Some(image) = self
+            .device
+            .find_property(name::CCD_IMAGE_PROPERTY)
+            .await
+            .and_then(|p| Self::image_from(&p))
+        {
+            return Ok(image);
+        }
+        self.wait_for_image(&mut events, DEFAULT_CHANGE_TIMEOUT)
+            .await
+    }
+
+    async fn set_frame_type(&mut self, frame_type: FrameType) -> Result<()> {
+        let item = match frame_type {
+            FrameType::Light => name::CCD_FRAME_TYPE_LIGHT_ITEM,
+            FrameType::Bias => name::CCD_FRAME_TYPE_BIAS_ITEM,
+            FrameType::Dark => name::CCD_FRAME_TYPE_DARK_ITEM,
+            FrameType::Flat => name::CCD_FRAME_TYPE_FLAT_ITEM,
+        };
+        self.device
+            .change_switches(name::CCD_FRAME_TYPE_PROPERTY, &[(item, true)])
+            .await?;
+        Ok(())
+    }
+
+    async fn frame_type(&self) -> Result<FrameType> {
+        let property = self.device.property(name::CCD_FRAME_TYPE_PROPERTY).await?;
+        match selected_item(&property) {
+            Some(name::CCD_FRAME_TYPE_LIGHT_ITEM) => Ok(FrameType::Light),
+            Some(name::CCD_FRAME_TYPE_BIAS_ITEM) => Ok(FrameType::Bias),
+            Some(name::CCD_FRAME_TYPE_DARK_ITEM) => Ok(FrameType::Dark),
+            Some(name::CCD_FRAME_TYPE_FLAT_ITEM) => Ok(FrameType::Flat),
+            other => Err(IndigoError::InvalidState(format!(
+                "Unsupported frame type: {}",
+                other.unwrap_or("none")
+            ))),
+        }
+    }
+
+    async fn set_binning(&mut self, binning: BinningMode) -> Result<()> {
+        self.device
+            .change_numbers(
+                name::CCD_BIN_PROPERTY,
+                &[
+                    (name::CCD_BIN_HORIZONTAL_ITEM, binning.x as f64),
+                    (name::CCD_BIN_VERTICAL_ITEM, binning.y as f64),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn binning(&self) -> Result<BinningMode> {
+        let bin = self.device.property(name::CCD_BIN_PROPERTY).await?;
+        Ok(BinningMode::new(
+            super::number_item(&bin, name::CCD_BIN_HORIZONTAL_ITEM)? as u32,
+            super::number_item(&bin, name::CCD_BIN_VERTICAL_ITEM)? as u32,
+        ))
+    }
+
+    async fn set_frame(&mut self, x: u32, y: u32, width: u32, height: u32) -> Result<()> {
+        self.device
+            .change_numbers(
+                name::CCD_FRAME_PROPERTY,
+                &[
+                    (name::CCD_FRAME_LEFT_ITEM, x as f64),
+                    (name::CCD_FRAME_TOP_ITEM, y as f64),
+                    (name::CCD_FRAME_WIDTH_ITEM, width as f64),
+                    (name::CCD_FRAME_HEIGHT_ITEM, height as f64),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn frame(&self) -> Result<(u32, u32, u32, u32)> {
+        let frame = self.device.property(name::CCD_FRAME_PROPERTY).await?;
+        let number = |item| super::number_item(&frame, item).map(|v| v as u32);
+        Ok((
+            number(name::CCD_FRAME_LEFT_ITEM)?,
+            number(name::CCD_FRAME_TOP_ITEM)?,
+            number(name::CCD_FRAME_WIDTH_ITEM)?,
+            number(name::CCD_FRAME_HEIGHT_ITEM)?,
+        ))
+    }
+
+    async fn set_temperature(&mut self, target: f64) -> Result<()> {
+        // The property stays busy until the target is reached, so don't wait
+        self.device
+            .send_numbers(
+                name::CCD_TEMPERATURE_PROPERTY,
+                &[(name::CCD_TEMPERATURE_ITEM, target)],
+            )
+            .await
+    }
+
+    async fn temperature(&self) -> Result<f64> {
+        self.device
+            .number(name::CCD_TEMPERATURE_PROPERTY, name::CCD_TEMPERATURE_ITEM)
+            .await
+    }
+
+    async fn set_cooler(&mut self, enabled: bool) -> Result<()> {
+        let item = if enabled {
+            name::CCD_COOLER_ON_ITEM
+        } else {
+            name::CCD_COOLER_OFF_ITEM
+        };
+        self.device
+            .change_switches(name::CCD_COOLER_PROPERTY, &[(item, true)])
+            .await?;
+        Ok(())
+    }
+
+    async fn cooler_enabled(&self) -> Result<bool> {
+        self.device
+            .switch(name::CCD_COOLER_PROPERTY, name::CCD_COOLER_ON_ITEM)
+            .await
+    }
+
+    async fn cooler_power(&self) -> Result<f64> {
+        self.device
+            .number(name::CCD_COOLER_POWER_PROPERTY, name::CCD_COOLER_POWER_ITEM)
+            .await
+    }
+
+    async fn set_gain(&mut self, gain: f64) -> Result<()> {
+        self.device
+            .change_numbers(name::CCD_GAIN_PROPERTY, &[(name::CCD_GAIN_ITEM, gain)])
+            .await?;
+        Ok(())
+    }
+
+    async fn gain(&self) -> Result<f64> {
+        self.device
+            .number(name::CCD_GAIN_PROPERTY, name::CCD_GAIN_ITEM)
+            .await
+    }
+
+    async fn set_offset(&mut self, offset: f64) -> Result<()> {
+        self.device
+            .change_numbers(
+                name::CCD_OFFSET_PROPERTY,
+                &[(name::CCD_OFFSET_ITEM, offset)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn offset(&self) -> Result<f64> {
+        self.device
+            .number(name::CCD_OFFSET_PROPERTY, name::CCD_OFFSET_ITEM)
+            .await
+    }
+}
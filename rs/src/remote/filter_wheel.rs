@@ -0,0 +1,165 @@
+//! FilterWheel implementation for remote filter wheels.
+
+use super::{impl_remote_device, RemoteDevice, MOTION_TIMEOUT};
+use crate::RsClientStrategy;
+use libindigo::device::traits::{FilterInfo, FilterWheel};
+use libindigo::device::DeviceInterface;
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
+use libindigo::types::{PropertyState, PropertyValue};
+
+/// A filter wheel on a remote INDIGO server.
+///
+/// Implements [`FilterWheel`] using the standard `WHEEL_SLOT`,
+/// `WHEEL_SLOT_NAME` and `WHEEL_SLOT_OFFSET` properties. Filter changes
+/// complete when the server reports `WHEEL_SLOT` as no longer busy.
+pub struct RemoteFilterWheel {
+    device: RemoteDevice,
+}
+
+impl RemoteFilterWheel {
+    /// Creates a filter wheel for a device defined on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device is not defined.
+    pub async fn new(client: &RsClientStrategy, device: &str) -> Result<Self> {
+        Ok(RemoteDevice::new(client, device).await?.into())
+    }
+
+    /// Gets the underlying remote device.
+    pub fn device(&self) -> &RemoteDevice {
+        &self.device
+    }
+
+    /// Gets the underlying remote device for direct property access.
+    pub fn device_mut(&mut self) -> &mut RemoteDevice {
+        &mut self.device
+    }
+
+    /// Checks that a slot number is valid for this wheel.
+    async fn check_slot(&self, slot: u32) -> Result<()> {
+        let count = self.slot_count().await?;
+        if slot == 0 || slot > count {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Filter slot {} is outside 1..={}",
+                slot, count
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Gets the `WHEEL_SLOT_NAME` item name for a slot.
+fn name_item(slot: u32) -> String {
+    name::WHEEL_SLOT_NAME_ITEM.replace("%d", &slot.to_string())
+}
+
+/// Gets the `WHEEL_SLOT_OFFSET` item name for a slot.
+fn offset_item(slot: u32) -> String {
+    name::WHEEL_SLOT_OFFSET_ITEM.replace("%d", &slot.to_string())
+}
+
+impl From<RemoteDevice> for RemoteFilterWheel {
+    fn from(device: RemoteDevice) -> Self {
+        RemoteFilterWheel { device }
+    }
+}
+
+impl_remote_device!(
+    RemoteFilterWheel,
+    dyn FilterWheel,
+    DeviceInterface::FilterWheel
+);
+
+#[async_trait::async_trait]
+impl FilterWheel for RemoteFilterWheel {
+    async fn slot_count(&self) -> Result<u32> {
+        let property = self.device.property(name::WHEEL_SLOT_PROPERTY).await?;
+        match property.items.get(name::WHEEL_SLOT_ITEM).map(|i| &i.value) {
+            Some(PropertyValue::Number { max, .. }) => Ok(max.max(0.0) as u32),
+            _ => Err(IndigoError::PropertyNotFound(format!(
+                "{}.{}",
+                name::WHEEL_SLOT_PROPERTY,
+                name::WHEEL_SLOT_ITEM
+            ))),
+        }
+    }
+
+    async fn filters(&self) -> Result<Vec<FilterInfo>> {
+        let count = self.slot_count().await?;
+        let names = self
+            .device
+            .find_property(name::WHEEL_SLOT_NAME_PROPERTY)
+            .await;
+        let offsets = self
+            .device
+            .find_property(name::WHEEL_SLOT_OFFSET_PROPERTY)
+            .await;
+
+        Ok((1..=count)
+            .map(|slot| FilterInfo {
+                slot,
+                name: names
+                    .as_ref()
+                    .and_then(|p| super::text_item(p, &name_item(slot)).ok())
+                    .unwrap_or_else(|| format!("Filter #{}", slot)),
+                offset: offsets
+                    .as_ref()
+                    .and_then(|p| super::number_item(p, &offset_item(slot)).ok())
+                    .unwrap_or(0.0) as i32,
+            })
+            .collect())
+    }
+
+    async fn select_filter(&mut self, slot: u32) -> Result<()> {
+        self.check_slot(slot).await?;
+        let request = self
+            .device
+            .number_request(
+                name::WHEEL_SLOT_PROPERTY,
+                &[(name::WHEEL_SLOT_ITEM, slot as f64)],
+            )
+            .await?;
+        self.device.change(request, Some(MOTION_TIMEOUT)).await?;
+        Ok(())
+    }
+
+    async fn current_slot(&self) -> Result<u32> {
+        let slot = self
+            .device
+            .number(name::WHEEL_SLOT_PROPERTY, name::WHEEL_SLOT_ITEM)
+            .await?;
+        Ok(slot.max(0.0) as u32)
+    }
+
+    async fn current_filter_name(&self) -> Result<String> {
+        let slot = self.current_slot().await?;
+        self.device
+            .text(name::WHEEL_SLOT_NAME_PROPERTY, &name_item(slot))
+            .await
+    }
+
+    async fn is_moving(&self) -> Result<bool> {
+        Ok(self.device.state(name::WHEEL_SLOT_PROPERTY).await? == PropertyState::Busy)
+    }
+
+    async fn set_filter_name(&mut self, slot: u32, name: &str) -> Result<()> {
+        self.check_slot(slot).await?;
+        self.device
+            .change_texts(name::WHEEL_SLOT_NAME_PROPERTY, &[(&name_item(slot), name)])
+            .await?;
+        Ok(())
+    }
+
+    async fn set_filter_offset(&mut self, slot: u32, offset: i32) -> Result<()> {
+        self.check_slot(slot).await?;
+        self.device
+            .change_numbers(
+                name::WHEEL_SLOT_OFFSET_PROPERTY,
+                &[(&offset_item(slot), offset as f64)],
+            )
+            .await?;
+        Ok(())
+    }
+}
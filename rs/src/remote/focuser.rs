@@ -0,0 +1,249 @@
+//! Focuser implementation for remote focusers.
+
+use super::{impl_remote_device, RemoteDevice, MOTION_TIMEOUT};
+use crate::RsClientStrategy;
+use libindigo::device::traits::{Focuser, FocuserInfo};
+use libindigo::device::DeviceInterface;
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
+use libindigo::types::{PropertyPerm, PropertyState, PropertyValue};
+
+/// A focuser on a remote INDIGO server.
+///
+/// Implements [`Focuser`] using the standard `FOCUSER_*` properties. Moves
+/// complete when the server reports the position or steps property as no
+/// longer busy. Temperature compensation is switched with `FOCUSER_MODE`.
+pub struct RemoteFocuser {
+    device: RemoteDevice,
+}
+
+impl RemoteFocuser {
+    /// Creates a focuser for a device defined on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device is not defined.
+    pub async fn new(client: &RsClientStrategy, device: &str) -> Result<Self> {
+        Ok(RemoteDevice::new(client, device).await?.into())
+    }
+
+    /// Gets the underlying remote device.
+    pub fn device(&self) -> &RemoteDevice {
+        &self.device
+    }
+
+    /// Gets the underlying remote device for direct property access.
+    pub fn device_mut(&mut self) -> &mut RemoteDevice {
+        &mut self.device
+    }
+
+    /// Gets the `(min, max)` limits of the speed item.
+    async fn speed_range(&self) -> Result<(f64, f64)> {
+        let property = self.device.property(name::FOCUSER_SPEED_PROPERTY).await?;
+        match property
+            .items
+            .get(name::FOCUSER_SPEED_ITEM)
+            .map(|i| &i.value)
+        {
+            Some(PropertyValue::Number { min, max, .. }) if min < max => Ok((*min, *max)),
+            Some(PropertyValue::Number { .. }) => Err(IndigoError::InvalidState(format!(
+                "{}.{} has no speed range",
+                self.device.name(),
+                name::FOCUSER_SPEED_PROPERTY
+            ))),
+            _ => Err(IndigoError::PropertyNotFound(format!(
+                "{}.{}",
+                name::FOCUSER_SPEED_PROPERTY,
+                name::FOCUSER_SPEED_ITEM
+            ))),
+        }
+    }
+}
+
+impl From<RemoteDevice> for RemoteFocuser {
+    fn from(device: RemoteDevice) -> Self {
+        RemoteFocuser { device }
+    }
+}
+
+impl_remote_device!(RemoteFocuser, dyn Focuser, DeviceInterface::Focuser);
+
+#[async_trait::async_trait]
+impl Focuser for RemoteFocuser {
+    async fn focuser_info(&self) -> Result<FocuserInfo> {
+        let position = self
+            .device
+            .find_property(name::FOCUSER_POSITION_PROPERTY)
+            .await;
+        let limit = match self
+            .device
+            .number(
+                name::FOCUSER_LIMITS_PROPERTY,
+                name::FOCUSER_LIMITS_MAX_POSITION_ITEM,
+            )
+            .await
+        {
+            Ok(max) => Some(max),
+            Err(_) => position
+                .as_ref()
+                .and_then(|p| p.items.get(name::FOCUSER_POSITION_ITEM))
+                .and_then(|item| match item.value {
+                    PropertyValue::Number { max, .. } => Some(max),
+                    _ => None,
+                }),
+        };
+
+        Ok(FocuserInfo {
+            max_position: limit.unwrap_or(0.0).max(0.0) as u32,
+            has_absolute: position.is_some_and(|p| p.perm != PropertyPerm::ReadOnly),
+            has_temperature_compensation: self
+                .device
+                .find_property(name::FOCUSER_COMPENSATION_PROPERTY)
+                .await
+                .is_some(),
+        })
+    }
+
+    async fn move_to(&mut self, position: u32) -> Result<()> {
+        let request = self
+            .device
+            .number_request(
+                name::FOCUSER_POSITION_PROPERTY,
+                &[(name::FOCUSER_POSITION_ITEM, position as f64)],
+            )
+            .await?;
+        self.device.change(request, Some(MOTION_TIMEOUT)).await?;
+        Ok(())
+    }
+
+    async fn move_relative(&mut self, steps: i32) -> Result<()> {
+        if steps == 0 {
+            return Ok(());
+        }
+        let direction = if steps > 0 {
+            name::FOCUSER_DIRECTION_MOVE_OUTWARD_ITEM
+        } else {
+            name::FOCUSER_DIRECTION_MOVE_INWARD_ITEM
+        };
+        self.device
+            .change_switches(name::FOCUSER_DIRECTION_PROPERTY, &[(direction, true)])
+            .await?;
+
+        let request = self
+            .device
+            .number_request(
+                name::FOCUSER_STEPS_PROPERTY,
+                &[(name::FOCUSER_STEPS_ITEM, steps.unsigned_abs() as f64)],
+            )
+            .await?;
+        self.device.change(request, Some(MOTION_TIMEOUT)).await?;
+        Ok(())
+    }
+
+    async fn abort_move(&mut self) -> Result<()> {
+        self.device
+            .change_switches(
+                name::FOCUSER_ABORT_MOTION_PROPERTY,
+                &[(name::FOCUSER_ABORT_MOTION_ITEM, true)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn position(&self) -> Result<u32> {
+        let position = self
+            .device
+            .number(name::FOCUSER_POSITION_PROPERTY, name::FOCUSER_POSITION_ITEM)
+            .await?;
+        Ok(position.max(0.0) as u32)
+    }
+
+    async fn is_moving(&self) -> Result<bool> {
+        for property in [
+            name::FOCUSER_POSITION_PROPERTY,
+            name::FOCUSER_STEPS_PROPERTY,
+        ] {
+            if let Some(property) = self.device.find_property(property).await {
+                if property.state == PropertyState::Busy {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    async fn temperature(&self) -> Result<f64> {
+        self.device
+            .number(
+                name::FOCUSER_TEMPERATURE_PROPERTY,
+                name::FOCUSER_TEMPERATURE_ITEM,
+            )
+            .await
+    }
+
+    async fn set_temperature_compensation(&mut self, enabled: bool) -> Result<()> {
+        let mode = if enabled {
+            name::FOCUSER_MODE_AUTOMATIC_ITEM
+        } else {
+            name::FOCUSER_MODE_MANUAL_ITEM
+        };
+        self.device
+            .change_switches(name::FOCUSER_MODE_PROPERTY, &[(mode, true)])
+            .await?;
+        Ok(())
+    }
+
+    async fn temperature_compensation(&self) -> Result<bool> {
+        self.device
+            .switch(
+                name::FOCUSER_MODE_PROPERTY,
+                name::FOCUSER_MODE_AUTOMATIC_ITEM,
+            )
+            .await
+    }
+
+    async fn set_backlash(&mut self, steps: u32) -> Result<()> {
+        self.device
+            .change_numbers(
+                name::FOCUSER_BACKLASH_PROPERTY,
+                &[(name::FOCUSER_BACKLASH_ITEM, steps as f64)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn backlash(&self) -> Result<u32> {
+        let backlash = self
+            .device
+            .number(name::FOCUSER_BACKLASH_PROPERTY, name::FOCUSER_BACKLASH_ITEM)
+            .await?;
+        Ok(backlash.max(0.0) as u32)
+    }
+
+    async fn set_speed(&mut self, speed: f64) -> Result<()> {
+        if !(0.0..=1.0).contains(&speed) {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Focuser speed {} is outside [0.0, 1.0]",
+                speed
+            )));
+        }
+        let (min, max) = self.speed_range().await?;
+        let value = min + speed * (max - min);
+        self.device
+            .change_numbers(
+                name::FOCUSER_SPEED_PROPERTY,
+                &[(name::FOCUSER_SPEED_ITEM, value.clamp(min, max))],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn speed(&self) -> Result<f64> {
+        let (min, max) = self.speed_range().await?;
+        let value = self
+            .device
+            .number(name::FOCUSER_SPEED_PROPERTY, name::FOCUSER_SPEED_ITEM)
+            .await?;
+        Ok(((value - min) / (max - min)).clamp(0.0, 1.0))
+    }
+}
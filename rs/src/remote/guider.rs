@@ -0,0 +1,179 @@
+//! Guider implementation for remote guider ports.
+
+use super::{impl_remote_device, RemoteDevice};
+use crate::RsClientStrategy;
+use libindigo::client::outcome::{wait_for_outcome, DEFAULT_CHANGE_TIMEOUT};
+use libindigo::client::ClientStrategy;
+use libindigo::device::traits::{GuideDirection, GuidePulse, Guider};
+use libindigo::device::DeviceInterface;
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
+use libindigo::types::{Property, PropertyState};
+use std::future::Future;
+use std::time::Duration;
+
+/// A guider port on a remote INDIGO server.
+///
+/// Implements [`Guider`] using the standard `GUIDER_GUIDE_DEC`,
+/// `GUIDER_GUIDE_RA` and `GUIDER_RATE` properties. A pulse completes when
+/// the server reports its guide property as no longer busy.
+pub struct RemoteGuider {
+    device: RemoteDevice,
+}
+
+impl RemoteGuider {
+    /// Creates a guider for a device defined on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device is not defined.
+    pub async fn new(client: &RsClientStrategy, device: &str) -> Result<Self> {
+        Ok(RemoteDevice::new(client, device).await?.into())
+    }
+
+    /// Gets the underlying remote device.
+    pub fn device(&self) -> &RemoteDevice {
+        &self.device
+    }
+
+    /// Gets the underlying remote device for direct property access.
+    pub fn device_mut(&mut self) -> &mut RemoteDevice {
+        &mut self.device
+    }
+
+    /// Builds the guide request for a pulse.
+    ///
+    /// The opposite direction on the same axis is set to zero.
+    async fn pulse_request(&self, pulse: GuidePulse) -> Result<Property> {
+        let (property, on, off) = match pulse.direction {
+            GuideDirection::North => (
+                name::GUIDER_GUIDE_DEC_PROPERTY,
+                name::GUIDER_GUIDE_NORTH_ITEM,
+                name::GUIDER_GUIDE_SOUTH_ITEM,
+            ),
+            GuideDirection::South => (
+                name::GUIDER_GUIDE_DEC_PROPERTY,
+                name::GUIDER_GUIDE_SOUTH_ITEM,
+                name::GUIDER_GUIDE_NORTH_ITEM,
+            ),
+            GuideDirection::East => (
+                name::GUIDER_GUIDE_RA_PROPERTY,
+                name::GUIDER_GUIDE_EAST_ITEM,
+                name::GUIDER_GUIDE_WEST_ITEM,
+            ),
+            GuideDirection::West => (
+                name::GUIDER_GUIDE_RA_PROPERTY,
+                name::GUIDER_GUIDE_WEST_ITEM,
+                name::GUIDER_GUIDE_EAST_ITEM,
+            ),
+        };
+        self.device
+            .number_request(property, &[(on, pulse.duration_ms as f64), (off, 0.0)])
+            .await
+    }
+
+    /// Gets the timeout for a pulse of the given length.
+    fn pulse_timeout(pulse: &GuidePulse) -> Duration {
+        Duration::from_millis(pulse.duration_ms as u64) + DEFAULT_CHANGE_TIMEOUT
+    }
+
+    /// Prepares a pulse, returning a future that sends it and waits for it to
+    /// complete.
+    async fn start_pulse(&self, pulse: GuidePulse) -> Result<impl Future<Output = Result<()>>> {
+        let request = self.pulse_request(pulse).await?;
+        let mut client = self.device.client().shared();
+        let mut events = client.subscribe_events().await?;
+        let device = self.device.name().to_string();
+        Ok(async move {
+            let property = request.name.clone();
+            let outcome = wait_for_outcome(
+                &mut events,
+                &device,
+                &property,
+                Self::pulse_timeout(&pulse),
+                client.send_property(request),
+            )
+            .await?;
+            if outcome.is_alert() {
+                return Err(IndigoError::InvalidState(format!(
+                    "{} reported an alert for {}: {}",
+                    device,
+                    property,
+                    outcome.message.as_deref().unwrap_or("no message")
+                )));
+            }
+            Ok(())
+        })
+    }
+}
+
+impl From<RemoteDevice> for RemoteGuider {
+    fn from(device: RemoteDevice) -> Self {
+        RemoteGuider { device }
+    }
+}
+
+impl_remote_device!(RemoteGuider, dyn Guider, DeviceInterface::Guider);
+
+#[async_trait::async_trait]
+impl Guider for RemoteGuider {
+    async fn guide(&mut self, pulse: GuidePulse) -> Result<()> {
+        let request = self.pulse_request(pulse).await?;
+        self.device
+            .change(request, Some(Self::pulse_timeout(&pulse)))
+            .await?;
+        Ok(())
+    }
+
+    async fn guide_dual(
+        &mut self,
+        ra_pulse: Option<GuidePulse>,
+        dec_pulse: Option<GuidePulse>,
+    ) -> Result<()> {
+        let (ra, dec) = match (ra_pulse, dec_pulse) {
+            (Some(ra), Some(dec)) => (ra, dec),
+            (Some(pulse), None) | (None, Some(pulse)) => return self.guide(pulse).await,
+            (None, None) => return Ok(()),
+        };
+
+        // Run both pulses at the same time, each with its own event stream so
+        // neither misses its result.
+        let ra = self.start_pulse(ra).await?;
+        let dec = self.start_pulse(dec).await?;
+        tokio::try_join!(ra, dec)?;
+        Ok(())
+    }
+
+    async fn is_guiding(&self) -> Result<bool> {
+        for property in [
+            name::GUIDER_GUIDE_DEC_PROPERTY,
+            name::GUIDER_GUIDE_RA_PROPERTY,
+        ] {
+            if self.device.state(property).await? == PropertyState::Busy {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    async fn set_guide_rate(&mut self, ra_rate: f64, dec_rate: f64) -> Result<()> {
+        // INDIGO guide rates are percentages of sidereal rate
+        let rate = self.device.property(name::GUIDER_RATE_PROPERTY).await?;
+        let mut values = vec![(name::GUIDER_RATE_ITEM, ra_rate * 100.0)];
+        if rate.items.contains_key(name::GUIDER_DEC_RATE_ITEM) {
+            values.push((name::GUIDER_DEC_RATE_ITEM, dec_rate * 100.0));
+        }
+        self.device
+            .change_numbers(name::GUIDER_RATE_PROPERTY, &values)
+            .await?;
+        Ok(())
+    }
+
+    async fn guide_rate(&self) -> Result<(f64, f64)> {
+        let rate = self.device.property(name::GUIDER_RATE_PROPERTY).await?;
+        let ra = super::number_item(&rate, name::GUIDER_RATE_ITEM)?;
+        // Devices without a separate declination rate use one rate for both axes
+        let dec = super::number_item(&rate, name::GUIDER_DEC_RATE_ITEM).unwrap_or(ra);
+        Ok((ra / 100.0, dec / 100.0))
+    }
+}
@@ -0,0 +1,775 @@
+//! Remote Device Implementations
+//!
+//! This module implements the high-level device traits from
+//! [`libindigo::device::traits`] for devices on a remote INDIGO server,
+//! backed by an [`RsClientStrategy`].
+//!
+//! # Overview
+//!
+//! Each remote device maps trait methods to the standard INDIGO properties
+//! of its device class:
+//!
+//! - **Reads** are answered from the client's property cache, so they never
+//!   block on the server
+//! - **Writes** send a `newXXXVector` message and wait for the server to
+//!   report `Ok` or `Alert` for the property
+//!
+//! Device metadata (name, description and driver version) is held in a
+//! [`DeviceProxy`] so it can be returned synchronously. The connection state
+//! is read from the client on every call, so it follows the `CONNECTION`
+//! property as the server reports it.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo::device::traits::{Camera, Device};
+//! use libindigo_rs::{remote::RemoteCamera, RsClientStrategy};
+//!
+//! let mut strategy = RsClientStrategy::new();
+//! strategy.connect("localhost:7624").await?;
+//!
+//! let mut camera = RemoteCamera::new(&strategy, "CCD Simulator").await?;
+//! camera.connect().await?;
+//! camera.start_exposure(5.0).await?;
+//! let image = camera.download_image().await?;
+//! ```
+//!
+//! Devices can also be opened by trait, checked against the interfaces
+//! the device reports:
+//!
+//! ```ignore
+//! let mut mount = strategy.mount("Mount Simulator").await?;
+//! let cameras = strategy.cameras().await?;
+//! ```
+
+mod camera;
+mod filter_wheel;
+mod focuser;
+mod guider;
+mod mount;
+
+pub use camera::RemoteCamera;
+pub use filter_wheel::RemoteFilterWheel;
+pub use focuser::RemoteFocuser;
+pub use guider::RemoteGuider;
+pub use mount::RemoteMount;
+
+use crate::RsClientStrategy;
+use libindigo::client::outcome::wait_for_completion;
+use libindigo::client::{ClientStrategy, PropertyEvent, PropertyOutcome};
+use libindigo::device::traits::{
+    Camera, DeviceHandle, DeviceProxy, FilterWheel, Focuser, Guider, Mount, TypedDevice,
+};
+use libindigo::device::DeviceInterface;
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
+use libindigo::types::{
+    Property, PropertyPerm, PropertyState, PropertyValue, SwitchRule, SwitchState,
+};
+use std::time::Duration;
+
+/// Timeout for slews, parking and other moves that can take minutes.
+const MOTION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A device trait that can be backed by a remote device.
+///
+/// Implemented for the trait objects of the device traits, such as
+/// `dyn Camera`, so devices can be opened by trait with
+/// [`RsClientStrategy::device_as`].
+pub trait RemoteInterface: TypedDevice {
+    /// Wraps a remote device in the implementation of the trait.
+    fn wrap(device: RemoteDevice) -> Box<Self>;
+}
+
+/// Typed device accessors.
+///
+/// Devices are matched by the `DEVICE_INTERFACE` bitmask of their `INFO`
+/// property, so the device must be defined before it can be opened.
+impl RsClientStrategy {
+    /// Opens a device as a device trait object.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use libindigo::device::traits::Camera;
+    ///
+    /// let mut camera = strategy.device_as::<dyn Camera>("CCD Simulator").await?;
+    /// camera.start_exposure(5.0).await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device is not defined,
+    /// or [`IndigoError::InterfaceMismatch`] if it does not implement the
+    /// interface of `T`.
+    pub async fn device_as<T>(&self, device: &str) -> Result<Box<T>>
+    where
+        T: RemoteInterface + ?Sized,
+    {
+        let interface = self
+            .device(device)
+            .await
+            .ok_or_else(|| IndigoError::DeviceNotFound(device.to_string()))?
+            .info
+            .interface;
+        if !T::INTERFACE.is_in(interface) {
+            return Err(IndigoError::InterfaceMismatch(format!(
+                "{} is not a {:?} device (interfaces: {:?})",
+                device,
+                T::INTERFACE,
+                DeviceInterface::decode(interface)
+            )));
+        }
+        Ok(T::wrap(RemoteDevice::new(self, device).await?))
+    }
+
+    /// Opens all devices implementing the interface of `T`, sorted by name.
+    pub async fn devices_as<T>(&self) -> Result<Vec<Box<T>>>
+    where
+        T: RemoteInterface + ?Sized,
+    {
+        let mut names: Vec<String> = self
+            .devices()
+            .await
+            .into_iter()
+            .filter(|d| T::INTERFACE.is_in(d.info.interface))
+            .map(|d| d.name)
+            .collect();
+        names.sort();
+
+        let mut devices = Vec::with_capacity(names.len());
+        for name in names {
+            devices.push(T::wrap(RemoteDevice::new(self, &name).await?));
+        }
+        Ok(devices)
+    }
+
+    /// Opens a camera by device name.
+    pub async fn camera(&self, device: &str) -> Result<Box<dyn Camera>> {
+        self.device_as(device).await
+    }
+
+    /// Opens all cameras.
+    pub async fn cameras(&self) -> Result<Vec<Box<dyn Camera>>> {
+        self.devices_as().await
+    }
+
+    /// Opens a mount by device name.
+    pub async fn mount(&self, device: &str) -> Result<Box<dyn Mount>> {
+        self.device_as(device).await
+    }
+
+    /// Opens all mounts.
+    pub async fn mounts(&self) -> Result<Vec<Box<dyn Mount>>> {
+        self.devices_as().await
+    }
+
+    /// Opens a focuser by device name.
+    pub async fn focuser(&self, device: &str) -> Result<Box<dyn Focuser>> {
+        self.device_as(device).await
+    }
+
+    /// Opens all focusers.
+    pub async fn focusers(&self) -> Result<Vec<Box<dyn Focuser>>> {
+        self.devices_as().await
+    }
+
+    /// Opens a filter wheel by device name.
+    pub async fn filter_wheel(&self, device: &str) -> Result<Box<dyn FilterWheel>> {
+        self.device_as(device).await
+    }
+
+    /// Opens all filter wheels.
+    pub async fn filter_wheels(&self) -> Result<Vec<Box<dyn FilterWheel>>> {
+        self.devices_as().await
+    }
+
+    /// Opens a guider by device name.
+    pub async fn guider(&self, device: &str) -> Result<Box<dyn Guider>> {
+        self.device_as(device).await
+    }
+
+    /// Opens all guiders.
+    pub async fn guiders(&self) -> Result<Vec<Box<dyn Guider>>> {
+        self.devices_as().await
+    }
+
+    /// Opens devices of an interface as handles, for
+    /// [`ClientStrategy::open_device`] and [`ClientStrategy::open_devices`].
+    pub(crate) async fn open_handles(
+        &self,
+        device: Option<&str>,
+        interface: DeviceInterface,
+    ) -> Result<Vec<DeviceHandle>> {
+        async fn open<T: RemoteInterface + ?Sized>(
+            client: &RsClientStrategy,
+            device: Option<&str>,
+            handle: fn(Box<T>) -> DeviceHandle,
+        ) -> Result<Vec<DeviceHandle>> {
+            let devices = match device {
+                Some(device) => vec![client.device_as::<T>(device).await?],
+                None => client.devices_as::<T>().await?,
+            };
+            Ok(devices.into_iter().map(handle).collect())
+        }
+
+        match interface {
+            DeviceInterface::Ccd => open::<dyn Camera>(self, device, DeviceHandle::Camera).await,
+            DeviceInterface::FilterWheel => {
+                open::<dyn FilterWheel>(self, device, DeviceHandle::FilterWheel).await
+            }
+            DeviceInterface::Focuser => {
+                open::<dyn Focuser>(self, device, DeviceHandle::Focuser).await
+            }
+            DeviceInterface::Mount => open::<dyn Mount>(self, device, DeviceHandle::Mount).await,
+            DeviceInterface::Guider => open::<dyn Guider>(self, device, DeviceHandle::Guider).await,
+            _ => Err(IndigoError::NotSupported(format!(
+                "Remote {:?} devices",
+                interface
+            ))),
+        }
+    }
+}
+
+/// A device on a remote INDIGO server.
+///
+/// This is the shared building block of the typed remote devices such as
+/// [`RemoteCamera`]. It can also be used directly to work with properties
+/// that the device traits don't cover.
+pub struct RemoteDevice {
+    /// Cached device metadata.
+    proxy: DeviceProxy,
+    /// Client handle sharing the connection and property cache.
+    client: RsClientStrategy,
+}
+
+impl RemoteDevice {
+    /// Creates a handle for a device defined on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the server has not defined
+    /// any properties for the device.
+    pub async fn new(client: &RsClientStrategy, device: &str) -> Result<Self> {
+        let mut remote = RemoteDevice {
+            proxy: DeviceProxy::new(device),
+            client: client.shared(),
+        };
+        remote.refresh().await?;
+        Ok(remote)
+    }
+
+    /// Gets the device name.
+    pub fn name(&self) -> &str {
+        self.proxy.device_name()
+    }
+
+    /// Gets the cached device metadata.
+    pub fn proxy(&self) -> &DeviceProxy {
+        &self.proxy
+    }
+
+    /// Refreshes the cached metadata from the client's property cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device no longer exists.
+    pub async fn refresh(&mut self) -> Result<()> {
+        let device = self
+            .client
+            .device(self.proxy.device_name())
+            .await
+            .ok_or_else(|| IndigoError::DeviceNotFound(self.proxy.device_name().to_string()))?;
+
+        self.proxy.clear_cache();
+        for property in device.properties.into_values() {
+            self.proxy.cache_property(property);
+        }
+
+        let connected = self
+            .proxy
+            .get_switch(name::CONNECTION_PROPERTY, name::CONNECTION_CONNECTED_ITEM)
+            .unwrap_or(false);
+        self.proxy.set_connected(connected);
+
+        if let Some(description) = [name::INFO_DEVICE_MODEL_ITEM, name::INFO_DEVICE_DRIVER_ITEM]
+            .iter()
+            .find_map(|item| self.proxy.get_text(name::INFO_PROPERTY, item).ok())
+            .filter(|s| !s.is_empty())
+        {
+            self.proxy.set_description(description);
+        }
+        if let Some(version) = device.info.driver_version.or(device.info.version) {
+            self.proxy.set_driver_version(version);
+        }
+        Ok(())
+    }
+
+    /// Connects the device by switching its `CONNECTION` property.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device reports an alert or does not respond.
+    pub async fn connect(&mut self) -> Result<()> {
+        self.change_switches(
+            name::CONNECTION_PROPERTY,
+            &[(name::CONNECTION_CONNECTED_ITEM, true)],
+        )
+        .await?;
+        self.refresh().await
+    }
+
+    /// Disconnects the device by switching its `CONNECTION` property.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device reports an alert or does not respond.
+    pub async fn disconnect(&mut self) -> Result<()> {
+        self.change_switches(
+            name::CONNECTION_PROPERTY,
+            &[(name::CONNECTION_DISCONNECTED_ITEM, true)],
+        )
+        .await?;
+        self.refresh().await
+    }
+
+    /// Gets the current value of a property from the property cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::PropertyNotFound`] if the property is not defined.
+    pub async fn property(&self, property: &str) -> Result<Property> {
+        self.client
+            .property(self.name(), property)
+            .await
+            .ok_or_else(|| IndigoError::PropertyNotFound(format!("{}.{}", self.name(), property)))
+    }
+
+    /// Gets the current value of a property, or `None` if it is not defined.
+    pub async fn find_property(&self, property: &str) -> Option<Property> {
+        self.client.property(self.name(), property).await
+    }
+
+    /// Gets all properties of the device from the property cache.
+    pub async fn properties(&self) -> Vec<Property> {
+        self.client
+            .device(self.name())
+            .await
+            .map(|device| device.properties.into_values().collect())
+            .unwrap_or_default()
+    }
+
+    /// Gets the state of a property.
+    pub async fn state(&self, property: &str) -> Result<PropertyState> {
+        Ok(self.property(property).await?.state)
+    }
+
+    /// Gets a number item value.
+    pub async fn number(&self, property: &str, item: &str) -> Result<f64> {
+        number_item(&self.property(property).await?, item)
+    }
+
+    /// Gets a text item value.
+    pub async fn text(&self, property: &str, item: &str) -> Result<String> {
+        text_item(&self.property(property).await?, item)
+    }
+
+    /// Gets a switch item value.
+    pub async fn switch(&self, property: &str, item: &str) -> Result<bool> {
+        switch_item(&self.property(property).await?, item)
+    }
+
+    /// Sends new number values without waiting for the result.
+    pub async fn send_numbers(&mut self, property: &str, values: &[(&str, f64)]) -> Result<()> {
+        let request = self.number_request(property, values).await?;
+        self.client.send_property(request).await
+    }
+
+    /// Changes number values and waits for the result.
+    ///
+    /// Values are checked against the limits from the property definition
+    /// before they are sent.
+    pub async fn change_numbers(
+        &mut self,
+        property: &str,
+        values: &[(&str, f64)],
+    ) -> Result<Property> {
+        let request = self.number_request(property, values).await?;
+        self.change(request, None).await
+    }
+
+    /// Changes text values and waits for the result.
+    pub async fn change_texts(
+        &mut self,
+        property: &str,
+        values: &[(&str, &str)],
+    ) -> Result<Property> {
+        let definition = self.writable(property).await?;
+        let values = values
+            .iter()
+            .map(|(item, value)| (*item, PropertyValue::Text(value.to_string())))
+            .collect::<Vec<_>>();
+        let request = request_from(definition, values)?;
+        self.change(request, None).await
+    }
+
+    /// Changes switch values and waits for the result.
+    ///
+    /// For `OneOfMany` and `AtMostOne` switches, turning an item on turns all
+    /// other items of the property off.
+    pub async fn change_switches(
+        &mut self,
+        property: &str,
+        values: &[(&str, bool)],
+    ) -> Result<Property> {
+        let request = self.switch_request(property, values).await?;
+        self.change(request, None).await
+    }
+
+    /// Sends switch values without waiting for the result.
+    pub async fn send_switches(&mut self, property: &str, values: &[(&str, bool)]) -> Result<()> {
+        let request = self.switch_request(property, values).await?;
+        self.client.send_property(request).await
+    }
+
+    /// Waits for a property to reach the given state.
+    ///
+    /// Returns immediately if the property is already in that state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::Timeout`] if the state is not reached in time,
+    /// or [`IndigoError::PropertyNotFound`] if the property is deleted.
+    pub async fn wait_for_state(
+        &self,
+        property: &str,
+        state: PropertyState,
+        timeout: Duration,
+    ) -> Result<Property> {
+        // Subscribe before checking the cache so no update is missed
+        let mut events = self.client.subscribe_events().await?;
+        if let Some(current) = self.find_property(property).await {
+            if current.state == state {
+                return Ok(current);
+            }
+        }
+
+        let device = self.name();
+        let wait = async {
+            while let Some(event) = events.recv().await {
+                match event {
+                    PropertyEvent::Updated(p) | PropertyEvent::Defined(p)
+                        if p.device == device && p.name == property && p.state == state =>
+                    {
+                        return Ok(p);
+                    }
+                    PropertyEvent::Deleted {
+                        device: d, name: n, ..
+                    } if d == device && n.as_deref().is_none_or(|n| n == property) => {
+                        return Err(IndigoError::PropertyNotFound(format!(
+                            "{}.{}",
+                            device, property
+                        )));
+                    }
+                    _ => {}
+                }
+            }
+            Err(IndigoError::ConnectionError(
+                "Event stream closed".to_string(),
+            ))
+        };
+
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            IndigoError::Timeout(format!(
+                "{}.{} did not reach state {} within {:?}",
+                device, property, state, timeout
+            ))
+        })?
+    }
+
+    /// Gets a client handle sharing this device's connection.
+    pub(crate) fn client(&self) -> &RsClientStrategy {
+        &self.client
+    }
+
+    /// Gets a mutable client handle sharing this device's connection.
+    pub(crate) fn client_mut(&mut self) -> &mut RsClientStrategy {
+        &mut self.client
+    }
+
+    /// Sends a request and waits for the result, treating alerts as errors.
+    ///
+    /// Without a `timeout`, the property's own timeout or the default applies.
+    async fn change(&mut self, request: Property, timeout: Option<Duration>) -> Result<Property> {
+        let name = request.name.clone();
+        let outcome = self.client.change_property(request, timeout).await?;
+        self.result(&name, outcome)
+    }
+
+    /// Sends a request for a long-running operation and waits until the
+    /// server has been busy with it and finished, treating alerts as errors.
+    ///
+    /// Unlike [`change`](Self::change), `Ok` updates before the server
+    /// reports the property as busy are ignored, unless the property already
+    /// has the requested values.
+    async fn complete(&mut self, request: Property, timeout: Duration) -> Result<Property> {
+        let name = request.name.clone();
+        let mut events = self.client.subscribe_events().await?;
+        let outcome = wait_for_completion(
+            &mut events,
+            &request.clone(),
+            timeout,
+            self.client.send_property(request),
+        )
+        .await?;
+        self.result(&name, outcome)
+    }
+
+    /// Gets the property of a change result, treating alerts as errors.
+    fn result(&self, name: &str, outcome: PropertyOutcome) -> Result<Property> {
+        if outcome.is_alert() {
+            return Err(IndigoError::InvalidState(format!(
+                "{} reported an alert for {}: {}",
+                self.name(),
+                name,
+                outcome.message.as_deref().unwrap_or("no message")
+            )));
+        }
+        Ok(outcome.property)
+    }
+
+    /// Gets a property definition, checking that it can be written.
+    async fn writable(&self, property: &str) -> Result<Property> {
+        let definition = self.property(property).await?;
+        if definition.perm == PropertyPerm::ReadOnly {
+            return Err(IndigoError::InvalidParameter(format!(
+                "{}.{} is read-only",
+                self.name(),
+                property
+            )));
+        }
+        Ok(definition)
+    }
+
+    /// Builds a number request, checking values against the definition's limits.
+    async fn number_request(&self, property: &str, values: &[(&str, f64)]) -> Result<Property> {
+        let definition = self.writable(property).await?;
+        let mut updates = Vec::with_capacity(values.len());
+        for (item, value) in values {
+            let Some(PropertyValue::Number {
+                min,
+                max,
+                step,
+                format,
+                ..
+            }) = definition.items.get(*item).map(|i| &i.value)
+            else {
+                return Err(IndigoError::PropertyNotFound(format!(
+                    "{}.{}.{}",
+                    self.name(),
+                    property,
+                    item
+                )));
+            };
+            if min < max && !(*min..=*max).contains(value) {
+                return Err(IndigoError::InvalidParameter(format!(
+                    "{}.{} = {} is outside [{}, {}]",
+                    property, item, value, min, max
+                )));
+            }
+            updates.push((
+                *item,
+                PropertyValue::Number {
+                    value: *value,
+                    min: *min,
+                    max: *max,
+                    step: *step,
+                    format: format.clone(),
+                },
+            ));
+        }
+        request_from(definition, updates)
+    }
+
+    /// Builds a switch request, applying the property's switch rule.
+    async fn switch_request(&self, property: &str, values: &[(&str, bool)]) -> Result<Property> {
+        let definition = self.writable(property).await?;
+        let exclusive = matches!(
+            definition.switch_rule,
+            Some(SwitchRule::OneOfMany) | Some(SwitchRule::AtMostOne)
+        );
+
+        let mut updates: Vec<(&str, PropertyValue)> = values
+            .iter()
+            .map(|(item, on)| (*item, switch_value(*on)))
+            .collect();
+        if exclusive && values.iter().any(|(_, on)| *on) {
+            for item in definition.items.keys() {
+                if !values.iter().any(|(name, _)| name == item) {
+                    updates.push((item.as_str(), switch_value(false)));
+                }
+            }
+        }
+
+        let updates = updates
+            .into_iter()
+            .map(|(item, value)| (item.to_string(), value))
+            .collect::<Vec<_>>();
+        request_from(
+            definition,
+            updates.iter().map(|(i, v)| (i.as_str(), v.clone())),
+        )
+    }
+}
+
+/// Builds a request property containing only the given items.
+fn request_from<'a>(
+    definition: Property,
+    values: impl IntoIterator<Item = (&'a str, PropertyValue)>,
+) -> Result<Property> {
+    let mut request = Property {
+        items: Default::default(),
+        ..definition.clone()
+    };
+    for (item, value) in values {
+        let mut entry = definition.items.get(item).cloned().ok_or_else(|| {
+            IndigoError::PropertyNotFound(format!(
+                "{}.{}.{}",
+                definition.device, definition.name, item
+            ))
+        })?;
+        entry.value = value;
+        request.items.insert(entry.name.clone(), entry);
+    }
+    Ok(request)
+}
+
+fn switch_value(on: bool) -> PropertyValue {
+    PropertyValue::Switch {
+        state: if on {
+            SwitchState::On
+        } else {
+            SwitchState::Off
+        },
+    }
+}
+
+/// Gets an item value from a property.
+fn item<'a>(property: &'a Property, item: &str) -> Result<&'a PropertyValue> {
+    property
+        .items
+        .get(item)
+        .map(|i| &i.value)
+        .ok_or_else(|| IndigoError::PropertyNotFound(format!("{}.{}", property.name, item)))
+}
+
+/// Gets a number item value from a property.
+fn number_item(property: &Property, name: &str) -> Result<f64> {
+    match item(property, name)? {
+        PropertyValue::Number { value, .. } => Ok(*value),
+        _ => Err(IndigoError::InvalidParameter(format!(
+            "Property {}.{} is not a number",
+            property.name, name
+        ))),
+    }
+}
+
+/// Gets a text item value from a property.
+fn text_item(property: &Property, name: &str) -> Result<String> {
+    match item(property, name)? {
+        PropertyValue::Text(s) => Ok(s.clone()),
+        _ => Err(IndigoError::InvalidParameter(format!(
+            "Property {}.{} is not text",
+            property.name, name
+        ))),
+    }
+}
+
+/// Gets a switch item value from a property.
+fn switch_item(property: &Property, name: &str) -> Result<bool> {
+    match item(property, name)? {
+        PropertyValue::Switch { state } => Ok(*state == SwitchState::On),
+        _ => Err(IndigoError::InvalidParameter(format!(
+            "Property {}.{} is not a switch",
+            property.name, name
+        ))),
+    }
+}
+
+/// Gets the name of the first switch item that is on.
+fn selected_item(property: &Property) -> Option<&str> {
+    property.items.values().find_map(|i| match i.value {
+        PropertyValue::Switch {
+            state: SwitchState::On,
+        } => Some(i.name.as_str()),
+        _ => None,
+    })
+}
+
+/// Implements the base [`Device`](libindigo::device::traits::Device) trait
+/// for a remote device type with a `device: RemoteDevice` field, and
+/// [`RemoteInterface`] for the device trait it implements.
+macro_rules! impl_remote_device {
+    ($type:ty, $trait:ty, $interface:expr) => {
+        impl $crate::remote::RemoteInterface for $trait {
+            fn wrap(device: $crate::remote::RemoteDevice) -> Box<Self> {
+                Box::new(<$type>::from(device))
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl libindigo::device::traits::Device for $type {
+            fn name(&self) -> &str {
+                self.device.name()
+            }
+
+            fn device_type(&self) -> libindigo::device::DeviceInterface {
+                $interface
+            }
+
+            async fn connect(&mut self) -> libindigo::error::Result<()> {
+                self.device.connect().await
+            }
+
+            async fn disconnect(&mut self) -> libindigo::error::Result<()> {
+                self.device.disconnect().await
+            }
+
+            fn is_connected(&self) -> bool {
+                self.device.client().is_device_connected(self.device.name())
+            }
+
+            fn description(&self) -> Option<&str> {
+                self.device.proxy().description()
+            }
+
+            fn driver_version(&self) -> Option<&str> {
+                self.device.proxy().driver_version()
+            }
+
+            async fn get_property(
+                &self,
+                name: &str,
+            ) -> libindigo::error::Result<Option<libindigo::types::Property>> {
+                Ok(self.device.find_property(name).await)
+            }
+
+            async fn get_properties(
+                &self,
+            ) -> libindigo::error::Result<Vec<libindigo::types::Property>> {
+                Ok(self.device.properties().await)
+            }
+
+            async fn wait_for_property_state(
+                &self,
+                name: &str,
+                state: libindigo::types::PropertyState,
+                timeout: std::time::Duration,
+            ) -> libindigo::error::Result<libindigo::types::Property> {
+                self.device.wait_for_state(name, state, timeout).await
+            }
+        }
+    };
+}
+
+pub(crate) use impl_remote_device;
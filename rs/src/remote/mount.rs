@@ -0,0 +1,355 @@
+//! Mount implementation for remote telescope mounts.
+
+use super::{impl_remote_device, selected_item, RemoteDevice, MOTION_TIMEOUT};
+use crate::RsClientStrategy;
+use libindigo::device::traits::{
+    AxisDirection, Coordinates, Mount, MountAxis, MountType, SlewRate, TrackingMode,
+};
+use libindigo::device::DeviceInterface;
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
+use libindigo::types::PropertyState;
+
+/// A telescope mount on a remote INDIGO server.
+///
+/// Implements [`Mount`] using the standard `MOUNT_*` and
+/// `GEOGRAPHIC_COORDINATES` properties. Slews complete once the server has
+/// reported the coordinates as busy and then as no longer busy; parking
+/// completes when the park property is no longer busy. Custom tracking rates
+/// are not supported.
+pub struct RemoteMount {
+    device: RemoteDevice,
+}
+
+impl RemoteMount {
+    /// Creates a mount for a device defined on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device is not defined.
+    pub async fn new(client: &RsClientStrategy, device: &str) -> Result<Self> {
+        Ok(RemoteDevice::new(client, device).await?.into())
+    }
+
+    /// Gets the underlying remote device.
+    pub fn device(&self) -> &RemoteDevice {
+        &self.device
+    }
+
+    /// Gets the underlying remote device for direct property access.
+    pub fn device_mut(&mut self) -> &mut RemoteDevice {
+        &mut self.device
+    }
+
+    /// Selects what the mount does when new coordinates are set.
+    async fn on_coordinates_set(&mut self, item: &str) -> Result<()> {
+        self.device
+            .change_switches(name::MOUNT_ON_COORDINATES_SET_PROPERTY, &[(item, true)])
+            .await?;
+        Ok(())
+    }
+
+    /// Sets new target coordinates and waits until the mount has been busy
+    /// slewing and is no longer busy.
+    async fn goto(&mut self, property: &str, values: &[(&str, f64)]) -> Result<()> {
+        let request = self.device.number_request(property, values).await?;
+        self.device.complete(request, MOTION_TIMEOUT).await?;
+        Ok(())
+    }
+
+    /// Custom rates are indices, while INDIGO sets a custom rate as a number
+    /// in `MOUNT_CUSTOM_TRACKING_RATE`, so the two can't be mapped.
+    fn custom_rates_not_supported() -> IndigoError {
+        IndigoError::NotSupported("Custom tracking rates of remote mounts".to_string())
+    }
+
+    /// Gets the motion property and item for moving an axis.
+    fn motion(axis: MountAxis, direction: AxisDirection) -> (&'static str, &'static str) {
+        match (axis, direction) {
+            (MountAxis::Primary, AxisDirection::Forward) => {
+                (name::MOUNT_MOTION_RA_PROPERTY, name::MOUNT_MOTION_WEST_ITEM)
+            }
+            (MountAxis::Primary, AxisDirection::Reverse) => {
+                (name::MOUNT_MOTION_RA_PROPERTY, name::MOUNT_MOTION_EAST_ITEM)
+            }
+            (MountAxis::Secondary, AxisDirection::Forward) => (
+                name::MOUNT_MOTION_DEC_PROPERTY,
+                name::MOUNT_MOTION_NORTH_ITEM,
+            ),
+            (MountAxis::Secondary, AxisDirection::Reverse) => (
+                name::MOUNT_MOTION_DEC_PROPERTY,
+                name::MOUNT_MOTION_SOUTH_ITEM,
+            ),
+        }
+    }
+}
+
+impl From<RemoteDevice> for RemoteMount {
+    fn from(device: RemoteDevice) -> Self {
+        RemoteMount { device }
+    }
+}
+
+impl_remote_device!(RemoteMount, dyn Mount, DeviceInterface::Mount);
+
+#[async_trait::async_trait]
+impl Mount for RemoteMount {
+    async fn mount_type(&self) -> Result<MountType> {
+        let equatorial = self
+            .device
+            .find_property(name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY)
+            .await;
+        let horizontal = self
+            .device
+            .find_property(name::MOUNT_HORIZONTAL_COORDINATES_PROPERTY)
+            .await;
+        Ok(match (equatorial, horizontal) {
+            (Some(_), _) => MountType::Equatorial,
+            (None, Some(_)) => MountType::AltAz,
+            (None, None) => MountType::Unknown,
+        })
+    }
+
+    async fn slew_to(&mut self, coords: Coordinates) -> Result<()> {
+        // Keep the current tracking state after the slew
+        let item = if self.is_tracking().await.unwrap_or(false) {
+            name::MOUNT_ON_COORDINATES_SET_TRACK_ITEM
+        } else {
+            name::MOUNT_ON_COORDINATES_SET_SLEW_ITEM
+        };
+        self.on_coordinates_set(item).await?;
+        self.goto(
+            name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY,
+            &[
+                (name::MOUNT_EQUATORIAL_COORDINATES_RA_ITEM, coords.ra),
+                (name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM, coords.dec),
+            ],
+        )
+        .await
+    }
+
+    async fn slew_to_altaz(&mut self, alt: f64, az: f64) -> Result<()> {
+        self.on_coordinates_set(name::MOUNT_ON_COORDINATES_SET_SLEW_ITEM)
+            .await?;
+        self.goto(
+            name::MOUNT_HORIZONTAL_COORDINATES_PROPERTY,
+            &[
+                (name::MOUNT_HORIZONTAL_COORDINATES_ALT_ITEM, alt),
+                (name::MOUNT_HORIZONTAL_COORDINATES_AZ_ITEM, az),
+            ],
+        )
+        .await
+    }
+
+    async fn sync_to(&mut self, coords: Coordinates) -> Result<()> {
+        self.on_coordinates_set(name::MOUNT_ON_COORDINATES_SET_SYNC_ITEM)
+            .await?;
+        self.device
+            .change_numbers(
+                name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY,
+                &[
+                    (name::MOUNT_EQUATORIAL_COORDINATES_RA_ITEM, coords.ra),
+                    (name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM, coords.dec),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn abort_slew(&mut self) -> Result<()> {
+        self.device
+            .change_switches(
+                name::MOUNT_ABORT_MOTION_PROPERTY,
+                &[(name::MOUNT_ABORT_MOTION_ITEM, true)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn coordinates(&self) -> Result<Coordinates> {
+        let property = self
+            .device
+            .property(name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY)
+            .await?;
+        Ok(Coordinates::new(
+            super::number_item(&property, name::MOUNT_EQUATORIAL_COORDINATES_RA_ITEM)?,
+            super::number_item(&property, name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM)?,
+        ))
+    }
+
+    async fn altaz(&self) -> Result<(f64, f64)> {
+        let property = self
+            .device
+            .property(name::MOUNT_HORIZONTAL_COORDINATES_PROPERTY)
+            .await?;
+        Ok((
+            super::number_item(&property, name::MOUNT_HORIZONTAL_COORDINATES_ALT_ITEM)?,
+            super::number_item(&property, name::MOUNT_HORIZONTAL_COORDINATES_AZ_ITEM)?,
+        ))
+    }
+
+    async fn is_slewing(&self) -> Result<bool> {
+        Ok(self
+            .device
+            .state(name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY)
+            .await?
+            == PropertyState::Busy)
+    }
+
+    async fn set_tracking(&mut self, mode: TrackingMode) -> Result<()> {
+        let rate = match mode {
+            TrackingMode::Off => {
+                self.device
+                    .change_switches(
+                        name::MOUNT_TRACKING_PROPERTY,
+                        &[(name::MOUNT_TRACKING_OFF_ITEM, true)],
+                    )
+                    .await?;
+                return Ok(());
+            }
+            TrackingMode::Sidereal => name::MOUNT_TRACK_RATE_SIDEREAL_ITEM,
+            TrackingMode::Solar => name::MOUNT_TRACK_RATE_SOLAR_ITEM,
+            TrackingMode::Lunar => name::MOUNT_TRACK_RATE_LUNAR_ITEM,
+            TrackingMode::Custom(_) => return Err(Self::custom_rates_not_supported()),
+        };
+        self.device
+            .change_switches(name::MOUNT_TRACK_RATE_PROPERTY, &[(rate, true)])
+            .await?;
+        self.device
+            .change_switches(
+                name::MOUNT_TRACKING_PROPERTY,
+                &[(name::MOUNT_TRACKING_ON_ITEM, true)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn tracking(&self) -> Result<TrackingMode> {
+        if !self.is_tracking().await? {
+            return Ok(TrackingMode::Off);
+        }
+        let rate = self
+            .device
+            .property(name::MOUNT_TRACK_RATE_PROPERTY)
+            .await?;
+        match selected_item(&rate) {
+            Some(name::MOUNT_TRACK_RATE_SIDEREAL_ITEM) => Ok(TrackingMode::Sidereal),
+            Some(name::MOUNT_TRACK_RATE_SOLAR_ITEM) => Ok(TrackingMode::Solar),
+            Some(name::MOUNT_TRACK_RATE_LUNAR_ITEM) => Ok(TrackingMode::Lunar),
+            Some(name::MOUNT_TRACK_RATE_CUSTOM_ITEM) => Err(Self::custom_rates_not_supported()),
+            Some(item) => Err(IndigoError::NotSupported(format!(
+                "Track rate {} of {}",
+                item,
+                self.device.name()
+            ))),
+            None => Err(IndigoError::InvalidState(format!(
+                "{} has no track rate selected",
+                self.device.name()
+            ))),
+        }
+    }
+
+    async fn is_tracking(&self) -> Result<bool> {
+        self.device
+            .switch(name::MOUNT_TRACKING_PROPERTY, name::MOUNT_TRACKING_ON_ITEM)
+            .await
+    }
+
+    async fn park(&mut self) -> Result<()> {
+        let request = self
+            .device
+            .switch_request(
+                name::MOUNT_PARK_PROPERTY,
+                &[(name::MOUNT_PARK_PARKED_ITEM, true)],
+            )
+            .await?;
+        self.device.complete(request, MOTION_TIMEOUT).await?;
+        Ok(())
+    }
+
+    async fn unpark(&mut self) -> Result<()> {
+        self.device
+            .change_switches(
+                name::MOUNT_PARK_PROPERTY,
+                &[(name::MOUNT_PARK_UNPARKED_ITEM, true)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn is_parked(&self) -> Result<bool> {
+        self.device
+            .switch(name::MOUNT_PARK_PROPERTY, name::MOUNT_PARK_PARKED_ITEM)
+            .await
+    }
+
+    async fn set_park_position(&mut self) -> Result<()> {
+        self.device
+            .change_switches(
+                name::MOUNT_PARK_SET_PROPERTY,
+                &[(name::MOUNT_PARK_SET_CURRENT_ITEM, true)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn move_axis(
+        &mut self,
+        axis: MountAxis,
+        direction: AxisDirection,
+        rate: SlewRate,
+    ) -> Result<()> {
+        let rate = match rate {
+            SlewRate::Guide => name::MOUNT_SLEW_RATE_GUIDE_ITEM,
+            SlewRate::Centering => name::MOUNT_SLEW_RATE_CENTERING_ITEM,
+            SlewRate::Find => name::MOUNT_SLEW_RATE_FIND_ITEM,
+            SlewRate::Max => name::MOUNT_SLEW_RATE_MAX_ITEM,
+        };
+        self.device
+            .change_switches(name::MOUNT_SLEW_RATE_PROPERTY, &[(rate, true)])
+            .await?;
+
+        // The motion property stays busy while moving, so don't wait
+        let (property, item) = Self::motion(axis, direction);
+        self.device.send_switches(property, &[(item, true)]).await
+    }
+
+    async fn stop_axis(&mut self, axis: MountAxis) -> Result<()> {
+        let (property, forward) = Self::motion(axis, AxisDirection::Forward);
+        let (_, reverse) = Self::motion(axis, AxisDirection::Reverse);
+        self.device
+            .change_switches(property, &[(forward, false), (reverse, false)])
+            .await?;
+        Ok(())
+    }
+
+    async fn sidereal_time(&self) -> Result<f64> {
+        self.device
+            .number(name::MOUNT_LST_TIME_PROPERTY, name::MOUNT_LST_TIME_ITEM)
+            .await
+    }
+
+    async fn set_location(&mut self, latitude: f64, longitude: f64) -> Result<()> {
+        self.device
+            .change_numbers(
+                name::GEOGRAPHIC_COORDINATES_PROPERTY,
+                &[
+                    (name::GEOGRAPHIC_COORDINATES_LATITUDE_ITEM, latitude),
+                    (name::GEOGRAPHIC_COORDINATES_LONGITUDE_ITEM, longitude),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn location(&self) -> Result<(f64, f64)> {
+        let property = self
+            .device
+            .property(name::GEOGRAPHIC_COORDINATES_PROPERTY)
+            .await?;
+        Ok((
+            super::number_item(&property, name::GEOGRAPHIC_COORDINATES_LATITUDE_ITEM)?,
+            super::number_item(&property, name::GEOGRAPHIC_COORDINATES_LONGITUDE_ITEM)?,
+        ))
+    }
+}
@@ -0,0 +1,176 @@
+//! Per-client connection handling for the INDIGO server.
+
+use super::ServerState;
+use crate::protocol::{BLOBEnable, EnableBLOB, ProtocolMessage};
+use crate::transport::{ReadTransport, Transport, WriteTransport};
+use libindigo::error::{IndigoError, Result};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// A client connected to the server.
+pub(super) struct Connection {
+    /// Read half of the client connection.
+    reader: ReadTransport,
+    /// Client-specific state, kept apart so it can be used while reading.
+    session: Session,
+}
+
+/// What the server knows about a connected client.
+struct Session {
+    /// Write half of the client connection.
+    writer: WriteTransport,
+    /// Shared server state.
+    state: Arc<ServerState>,
+    /// Whether the client has asked for properties, and so receives updates.
+    subscribed: bool,
+    /// BLOB modes requested by the client, by device and property.
+    blob_modes: HashMap<(String, Option<String>), BLOBEnable>,
+}
+
+impl Connection {
+    /// Sets up a connection for an accepted client.
+    pub(super) fn new(stream: TcpStream, state: Arc<ServerState>) -> Result<Self> {
+        let (reader, writer) = Transport::from_stream(stream).split()?;
+        Ok(Connection {
+            reader,
+            session: Session {
+                writer,
+                state,
+                subscribed: false,
+                blob_modes: HashMap::new(),
+            },
+        })
+    }
+
+    /// Serves the client until it disconnects or the server shuts down.
+    pub(super) async fn run(self, peer: SocketAddr, mut shutdown: broadcast::Receiver<()>) {
+        let Connection {
+            mut reader,
+            mut session,
+        } = self;
+        let mut updates = session.state.updates.subscribe();
+
+        loop {
+            tokio::select! {
+                received = reader.receive_message() => match received {
+                    Ok(message) => {
+                        // Answer in the protocol the client speaks
+                        session.writer.set_protocol(reader.protocol());
+                        if let Err(e) = session.handle(message).await {
+                            tracing::warn!("Failed to handle request from {}: {}", peer, e);
+                        }
+                    }
+                    Err(IndigoError::ConnectionError(_)) => break,
+                    // Idle clients are normal
+                    Err(IndigoError::Timeout(_)) => {}
+                    Err(e) => tracing::warn!("Invalid message from {}: {}", peer, e),
+                },
+                update = updates.recv() => match update {
+                    Ok(message) => {
+                        if session.wants(&message) {
+                            if let Err(e) = session.writer.send_message(&message).await {
+                                tracing::debug!("Failed to send update to {}: {}", peer, e);
+                                break;
+                            }
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Client {} missed {} updates", peer, skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = shutdown.recv() => break,
+            }
+        }
+        tracing::info!("Client {} disconnected", peer);
+    }
+}
+
+impl Session {
+    /// Handles a message received from the client.
+    async fn handle(&mut self, message: ProtocolMessage) -> Result<()> {
+        match message {
+            ProtocolMessage::GetProperties(request) => {
+                self.subscribed = true;
+                let definitions = self
+                    .state
+                    .definitions(request.device.as_deref(), request.name.as_deref())
+                    .await;
+                for definition in definitions {
+                    self.writer.send_message(&definition).await?;
+                }
+                Ok(())
+            }
+            ProtocolMessage::EnableBLOB(request) => {
+                self.record_blob_mode(&request);
+                self.state.enable_blob(&request).await
+            }
+            ProtocolMessage::NewTextVector(_)
+            | ProtocolMessage::NewNumberVector(_)
+            | ProtocolMessage::NewSwitchVector(_)
+            | ProtocolMessage::NewBLOBVector(_) => self.state.change(message).await,
+            other => {
+                tracing::debug!("Ignoring client message {:?}", other);
+                Ok(())
+            }
+        }
+    }
+
+    /// Records the BLOB mode requested for a device or property.
+    fn record_blob_mode(&mut self, request: &EnableBLOB) {
+        self.blob_modes.insert(
+            (request.device.clone(), request.name.clone()),
+            request.value,
+        );
+    }
+
+    /// Gets the BLOB mode for a property, falling back to the device mode.
+    ///
+    /// BLOBs are not sent unless the client has enabled them.
+    fn blob_mode(&self, device: &str, name: Option<&str>) -> BLOBEnable {
+        name.and_then(|name| {
+            self.blob_modes
+                .get(&(device.to_string(), Some(name.to_string())))
+        })
+        .or_else(|| self.blob_modes.get(&(device.to_string(), None)))
+        .copied()
+        .unwrap_or(BLOBEnable::Never)
+    }
+
+    /// Whether a broadcast message should be sent to this client.
+    ///
+    /// A client that asked for BLOBs only gets nothing else of the device.
+    fn wants(&self, message: &ProtocolMessage) -> bool {
+        match message {
+            _ if !self.subscribed => false,
+            ProtocolMessage::SetBLOBVector(v) => {
+                self.blob_mode(&v.attrs.device, Some(&v.attrs.name)) != BLOBEnable::Never
+            }
+            message => target(message)
+                .is_none_or(|(device, name)| self.blob_mode(device, name) != BLOBEnable::Only),
+        }
+    }
+}
+
+/// Gets the device and property a server message is about.
+fn target(message: &ProtocolMessage) -> Option<(&str, Option<&str>)> {
+    let (device, name) = match message {
+        ProtocolMessage::DefTextVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::DefNumberVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::DefSwitchVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::DefLightVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::DefBLOBVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::SetTextVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::SetNumberVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::SetSwitchVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::SetLightVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::SetBLOBVector(v) => (&v.attrs.device, &v.attrs.name),
+        ProtocolMessage::DelProperty(m) => return Some((&m.device, m.name.as_deref())),
+        ProtocolMessage::Message(m) => return m.device.as_deref().map(|device| (device, None)),
+        _ => return None,
+    };
+    Some((device, Some(name)))
+}
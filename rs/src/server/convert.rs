@@ -0,0 +1,476 @@
+//! Conversions between driver properties and protocol messages.
+//!
+//! Drivers work with domain [`Property`] values; clients speak protocol
+//! messages. Driver notifications become `defXXXVector`, `setXXXVector`,
+//! `delProperty` and `message` messages, and client requests (`newXXXVector`) are
+//! turned back into properties for [`DeviceDriver::change_property`].
+//!
+//! Items are sent sorted by name, as properties don't keep their order.
+//!
+//! [`DeviceDriver::change_property`]: libindigo::device::DeviceDriver::change_property
+
+use crate::protocol::{
+    encode_blob, DefBLOB, DefBLOBVector, DefLight, DefLightVector, DefNumber, DefNumberVector,
+    DefSwitch, DefSwitchVector, DefText, DefTextVector, DelProperty, Message, OneBLOB, OneLight,
+    OneNumber, OneSwitch, OneText, ProtocolMessage, SetBLOBVector, SetLightVector, SetNumberVector,
+    SetSwitchVector, SetTextVector, SetVectorAttributes, SwitchRule as ProtocolSwitchRule,
+    SwitchState as ProtocolSwitchState, VectorAttributes,
+};
+use libindigo::device::DeviceNotification;
+use libindigo::error::{IndigoError, Result};
+use libindigo::types::{
+    LightState, Property, PropertyItem, PropertyState, PropertyType, PropertyValue, SwitchRule,
+    SwitchState,
+};
+
+/// Builds the `defXXXVector` message defining a property.
+pub(crate) fn define_message(property: &Property) -> ProtocolMessage {
+    let attrs = VectorAttributes {
+        device: property.device.clone(),
+        name: property.name.clone(),
+        label: property.label.clone(),
+        group: property.group.clone(),
+        state: property.state,
+        timeout: property.timeout,
+        timestamp: property.timestamp.clone(),
+        message: property.message.clone(),
+    };
+    let items = sorted_items(property);
+
+    match property.property_type {
+        PropertyType::Text => ProtocolMessage::DefTextVector(DefTextVector {
+            attrs,
+            perm: property.perm,
+            elements: items
+                .into_iter()
+                .filter_map(|item| match &item.value {
+                    PropertyValue::Text(value) => Some(DefText {
+                        name: item.name.clone(),
+                        label: item.label.clone(),
+                        value: value.clone(),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+        PropertyType::Number => ProtocolMessage::DefNumberVector(DefNumberVector {
+            attrs,
+            perm: property.perm,
+            elements: items
+                .into_iter()
+                .filter_map(|item| match &item.value {
+                    PropertyValue::Number {
+                        value,
+                        min,
+                        max,
+                        step,
+                        format,
+                    } => Some(DefNumber {
+                        name: item.name.clone(),
+                        label: item.label.clone(),
+                        format: format.clone(),
+                        min: *min,
+                        max: *max,
+                        step: *step,
+                        value: *value,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+        PropertyType::Switch => ProtocolMessage::DefSwitchVector(DefSwitchVector {
+            attrs,
+            perm: property.perm,
+            rule: match property.switch_rule.unwrap_or(SwitchRule::OneOfMany) {
+                SwitchRule::OneOfMany => ProtocolSwitchRule::OneOfMany,
+                SwitchRule::AtMostOne => ProtocolSwitchRule::AtMostOne,
+                SwitchRule::AnyOfMany => ProtocolSwitchRule::AnyOfMany,
+            },
+            elements: items
+                .into_iter()
+                .filter_map(|item| match &item.value {
+                    PropertyValue::Switch { state } => Some(DefSwitch {
+                        name: item.name.clone(),
+                        label: item.label.clone(),
+                        value: switch_state(*state),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+        PropertyType::Light => ProtocolMessage::DefLightVector(DefLightVector {
+            attrs,
+            elements: items
+                .into_iter()
+                .filter_map(|item| match &item.value {
+                    PropertyValue::Light { state } => Some(DefLight {
+                        name: item.name.clone(),
+                        label: item.label.clone(),
+                        value: light_state(*state),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+        // BLOB definitions never carry data
+        PropertyType::Blob => ProtocolMessage::DefBLOBVector(DefBLOBVector {
+            attrs,
+            perm: property.perm,
+            elements: items
+                .into_iter()
+                .map(|item| DefBLOB {
+                    name: item.name.clone(),
+                    label: item.label.clone(),
+                })
+                .collect(),
+        }),
+    }
+}
+
+/// Builds the `setXXXVector` message for new values of a defined property.
+pub(crate) fn update_message(
+    definition: &Property,
+    state: PropertyState,
+    values: &[(String, PropertyValue)],
+    message: Option<String>,
+) -> ProtocolMessage {
+    let attrs = SetVectorAttributes {
+        device: definition.device.clone(),
+        name: definition.name.clone(),
+        state: Some(state),
+        timeout: definition.timeout,
+        timestamp: None,
+        message,
+    };
+
+    match definition.property_type {
+        PropertyType::Text => ProtocolMessage::SetTextVector(SetTextVector {
+            attrs,
+            elements: values
+                .iter()
+                .filter_map(|(name, value)| match value {
+                    PropertyValue::Text(value) => Some(OneText {
+                        name: name.clone(),
+                        value: value.clone(),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+        PropertyType::Number => ProtocolMessage::SetNumberVector(SetNumberVector {
+            attrs,
+            elements: values
+                .iter()
+                .filter_map(|(name, value)| match value {
+                    PropertyValue::Number { value, .. } => Some(OneNumber {
+                        name: name.clone(),
+                        value: *value,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+        PropertyType::Switch => ProtocolMessage::SetSwitchVector(SetSwitchVector {
+            attrs,
+            elements: values
+                .iter()
+                .filter_map(|(name, value)| match value {
+                    PropertyValue::Switch { state } => Some(OneSwitch {
+                        name: name.clone(),
+                        value: switch_state(*state),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+        PropertyType::Light => ProtocolMessage::SetLightVector(SetLightVector {
+            attrs,
+            elements: values
+                .iter()
+                .filter_map(|(name, value)| match value {
+                    PropertyValue::Light { state } => Some(OneLight {
+                        name: name.clone(),
+                        value: light_state(*state),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+        PropertyType::Blob => ProtocolMessage::SetBLOBVector(SetBLOBVector {
+            attrs,
+            elements: values
+                .iter()
+                .filter_map(|(name, value)| match value {
+                    PropertyValue::Blob { data, format, .. } => Some(OneBLOB {
+                        name: name.clone(),
+                        size: data.len(),
+                        format: format.clone(),
+                        value: encode_blob(data),
+                        data: None,
+                        compressed_size: None,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }),
+    }
+}
+
+/// Builds the `setXXXVector` message with all current values of a property.
+pub(crate) fn state_message(property: &Property, message: Option<String>) -> ProtocolMessage {
+    let values: Vec<_> = sorted_items(property)
+        .into_iter()
+        .map(|item| (item.name.clone(), item.value.clone()))
+        .collect();
+    update_message(property, property.state, &values, message)
+}
+
+/// Builds the `delProperty` message for a property, or a whole device.
+pub(crate) fn delete_message(device: &str, name: Option<&str>) -> ProtocolMessage {
+    ProtocolMessage::DelProperty(DelProperty {
+        device: device.to_string(),
+        name: name.map(str::to_string),
+        timestamp: None,
+        message: None,
+    })
+}
+
+/// Builds the message sending a driver notification to clients.
+pub(crate) fn notification_message(notification: DeviceNotification) -> ProtocolMessage {
+    match notification {
+        DeviceNotification::Defined(property) => define_message(&property),
+        DeviceNotification::Updated(property) => state_message(&property, property.message.clone()),
+        DeviceNotification::Deleted { device, name } => delete_message(&device, name.as_deref()),
+        DeviceNotification::Message { device, message } => ProtocolMessage::Message(Message {
+            device: Some(device),
+            timestamp: None,
+            message: Some(message),
+        }),
+    }
+}
+
+/// Gets the device and property addressed by a `newXXXVector` message.
+pub(crate) fn request_target(message: &ProtocolMessage) -> Option<(&str, &str)> {
+    let attrs = match message {
+        ProtocolMessage::NewTextVector(v) => &v.attrs,
+        ProtocolMessage::NewNumberVector(v) => &v.attrs,
+        ProtocolMessage::NewSwitchVector(v) => &v.attrs,
+        ProtocolMessage::NewBLOBVector(v) => &v.attrs,
+        _ => return None,
+    };
+    Some((attrs.device.as_str(), attrs.name.as_str()))
+}
+
+/// Builds the property passed to a driver for a `newXXXVector` request.
+///
+/// The property carries the metadata of the definition and only the items
+/// named in the request, with the requested values.
+///
+/// # Errors
+///
+/// Returns [`IndigoError::InvalidParameter`] if the request type doesn't
+/// match the definition or names an item the property doesn't have.
+pub(crate) fn requested_property(
+    definition: &Property,
+    message: ProtocolMessage,
+) -> Result<Property> {
+    let values: Vec<(String, PropertyValue)> = match (definition.property_type, message) {
+        (PropertyType::Text, ProtocolMessage::NewTextVector(v)) => v
+            .elements
+            .into_iter()
+            .map(|e| (e.name, PropertyValue::Text(e.value)))
+            .collect(),
+        (PropertyType::Number, ProtocolMessage::NewNumberVector(v)) => v
+            .elements
+            .into_iter()
+            .map(|e| (e.name, PropertyValue::number(e.value)))
+            .collect(),
+        (PropertyType::Switch, ProtocolMessage::NewSwitchVector(v)) => v
+            .elements
+            .into_iter()
+            .map(|e| {
+                let state = match e.value {
+                    ProtocolSwitchState::On => SwitchState::On,
+                    ProtocolSwitchState::Off => SwitchState::Off,
+                };
+                (e.name, PropertyValue::Switch { state })
+            })
+            .collect(),
+        (PropertyType::Blob, ProtocolMessage::NewBLOBVector(v)) => v
+            .elements
+            .into_iter()
+            .map(|mut e| {
+                let data = e.take_data()?;
+                Ok((e.name, PropertyValue::blob(data, e.format)))
+            })
+            .collect::<Result<_>>()?,
+        (property_type, _) => {
+            return Err(IndigoError::InvalidParameter(format!(
+                "{}.{} is a {:?} property",
+                definition.device, definition.name, property_type
+            )))
+        }
+    };
+
+    let mut property = Property {
+        items: Default::default(),
+        ..definition.clone()
+    };
+    for (name, value) in values {
+        let defined = definition.items.get(&name).ok_or_else(|| {
+            IndigoError::InvalidParameter(format!(
+                "{}.{} has no item {}",
+                definition.device, definition.name, name
+            ))
+        })?;
+        let value = match (value, &defined.value) {
+            // Keep the limits and format of the definition
+            (
+                PropertyValue::Number { value, .. },
+                PropertyValue::Number {
+                    min,
+                    max,
+                    step,
+                    format,
+                    ..
+                },
+            ) => PropertyValue::Number {
+                value,
+                min: *min,
+                max: *max,
+                step: *step,
+                format: format.clone(),
+            },
+            (value, _) => value,
+        };
+        property.items.insert(
+            name.clone(),
+            PropertyItem {
+                name,
+                label: defined.label.clone(),
+                value,
+            },
+        );
+    }
+    Ok(property)
+}
+
+/// Gets the items of a property sorted by name.
+fn sorted_items(property: &Property) -> Vec<&PropertyItem> {
+    let mut items: Vec<_> = property.items.values().collect();
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+    items
+}
+
+/// Converts a switch state to its protocol representation.
+fn switch_state(state: SwitchState) -> ProtocolSwitchState {
+    match state {
+        SwitchState::On => ProtocolSwitchState::On,
+        SwitchState::Off => ProtocolSwitchState::Off,
+    }
+}
+
+/// Converts a light state to its protocol representation.
+fn light_state(state: LightState) -> PropertyState {
+    match state {
+        LightState::Idle => PropertyState::Idle,
+        LightState::Ok => PropertyState::Ok,
+        LightState::Busy => PropertyState::Busy,
+        LightState::Alert => PropertyState::Alert,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{NewNumberVector, NewVectorAttributes};
+    use libindigo::types::PropertyPerm;
+
+    fn exposure() -> Property {
+        Property::builder()
+            .device("Rust Camera")
+            .name("CCD_EXPOSURE")
+            .group("Camera")
+            .label("Exposure")
+            .property_type(PropertyType::Number)
+            .perm(PropertyPerm::ReadWrite)
+            .item(PropertyItem::new(
+                "EXPOSURE",
+                "Duration",
+                PropertyValue::Number {
+                    value: 1.0,
+                    min: 0.0,
+                    max: 3600.0,
+                    step: 0.001,
+                    format: "%.3f".to_string(),
+                },
+            ))
+            .build()
+            .unwrap()
+    }
+
+    fn new_exposure(item: &str, value: f64) -> ProtocolMessage {
+        ProtocolMessage::NewNumberVector(NewNumberVector {
+            attrs: NewVectorAttributes {
+                device: "Rust Camera".to_string(),
+                name: "CCD_EXPOSURE".to_string(),
+                timestamp: None,
+            },
+            elements: vec![OneNumber {
+                name: item.to_string(),
+                value,
+            }],
+        })
+    }
+
+    #[test]
+    fn test_define_message_keeps_number_limits() {
+        match define_message(&exposure()) {
+            ProtocolMessage::DefNumberVector(v) => {
+                assert_eq!(v.attrs.group, "Camera");
+                assert_eq!(v.elements.len(), 1);
+                assert_eq!(v.elements[0].max, 3600.0);
+                assert_eq!(v.elements[0].format, "%.3f");
+            }
+            other => panic!("Expected defNumberVector, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_requested_property_uses_definition_metadata() {
+        let request = new_exposure("EXPOSURE", 5.0);
+        assert_eq!(
+            request_target(&request),
+            Some(("Rust Camera", "CCD_EXPOSURE"))
+        );
+
+        let property = requested_property(&exposure(), request).unwrap();
+        assert_eq!(property.label, "Exposure");
+        match &property.items["EXPOSURE"].value {
+            PropertyValue::Number { value, max, .. } => assert_eq!((*value, *max), (5.0, 3600.0)),
+            other => panic!("Expected number, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_requested_property_rejects_unknown_items() {
+        let result = requested_property(&exposure(), new_exposure("DURATION", 5.0));
+        assert!(matches!(result, Err(IndigoError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_update_message_sends_only_changed_values() {
+        let values = vec![("EXPOSURE".to_string(), PropertyValue::number(2.5))];
+        match update_message(&exposure(), PropertyState::Busy, &values, None) {
+            ProtocolMessage::SetNumberVector(v) => {
+                assert_eq!(v.attrs.state, Some(PropertyState::Busy));
+                assert_eq!(v.elements[0].value, 2.5);
+            }
+            other => panic!("Expected setNumberVector, got {:?}", other),
+        }
+    }
+}
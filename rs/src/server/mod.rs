@@ -0,0 +1,400 @@
+//! INDIGO Device Server
+//!
+//! This module hosts [`DeviceDriver`] implementations on a TCP port, so
+//! drivers written in Rust can be used by any INDIGO client without linking
+//! the C library.
+//!
+//! # Overview
+//!
+//! The server speaks both protocols, answering each client in the protocol
+//! it uses (detected from its first message):
+//!
+//! - **`getProperties`** is answered with definitions from each driver's
+//!   [`PropertyManager`](libindigo::device::PropertyManager)
+//! - **`newXXXVector`** requests are validated and routed to the driver with
+//!   [`DriverRegistry::handle_property_change`]
+//! - **`enableBLOB`** sets which BLOBs the client receives, and is passed on
+//!   to the driver
+//!
+//! Drivers are given an outbound channel through their
+//! [`DeviceContext`](libindigo::device::DeviceContext), and every
+//! [`DeviceNotification`] they emit is broadcast to all clients as a
+//! definition, `setXXXVector`, `delProperty` or `message`. If a request is
+//! invalid or the driver returns an error, the property is set to `Alert`
+//! with the error as message.
+//!
+//! Timers and background tasks that drivers start through their context are
+//! run by the server, which calls
+//! [`DeviceDriver::on_task`](libindigo::device::DeviceDriver::on_task) when
+//! they fire.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo::device::DriverRegistry;
+//! use libindigo_rs::IndigoServer;
+//!
+//! let mut registry = DriverRegistry::new();
+//! registry.register(Box::new(MyCamera::new()))?;
+//!
+//! let server = IndigoServer::start("0.0.0.0:7624", registry).await?;
+//! // ...
+//! server.shutdown().await?;
+//! ```
+
+mod connection;
+mod convert;
+
+use connection::Connection;
+use convert::{define_message, notification_message, request_target, requested_property};
+
+use crate::protocol::{BLOBEnable, EnableBLOB, ProtocolMessage};
+use libindigo::device::{
+    DeviceDriver, DeviceNotification, DriverRegistry, NotificationSender, TaskEvent,
+};
+use libindigo::error::{IndigoError, Result};
+use libindigo::types::BlobTransferMode;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+/// Number of messages buffered for each client before it starts lagging.
+const UPDATE_BUFFER_SIZE: usize = 1024;
+
+/// An INDIGO server hosting device drivers.
+///
+/// Drivers in the registry are attached when the server starts, and
+/// detached when it shuts down.
+pub struct IndigoServer {
+    /// Address the server listens on.
+    addr: SocketAddr,
+    /// State shared with the connections.
+    state: Arc<ServerState>,
+    /// Signals the accept loop and connections to stop.
+    shutdown_tx: broadcast::Sender<()>,
+    /// Accept loop task.
+    task: Option<JoinHandle<()>>,
+}
+
+/// State shared between the server and its connections.
+pub(crate) struct ServerState {
+    /// Hosted drivers, each in a registry of its own so a slow driver
+    /// doesn't hold up the others.
+    drivers: RwLock<HashMap<String, Arc<Mutex<DriverRegistry>>>>,
+    /// Empty registry that new drivers are registered with before being
+    /// split off, giving them the server's channels.
+    registry: Mutex<DriverRegistry>,
+    /// Messages for all subscribed clients.
+    updates: broadcast::Sender<ProtocolMessage>,
+    /// Outbound channel shared with the drivers, keeping server
+    /// notifications in order with theirs.
+    notifier: NotificationSender,
+}
+
+impl IndigoServer {
+    /// Attaches the drivers of a registry and starts serving them.
+    ///
+    /// `addr` is the address to listen on, such as `"0.0.0.0:7624"`. Use
+    /// port 0 to pick a free port, and [`addr`](Self::addr) to find it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a driver fails to attach or the address can't be
+    /// bound.
+    pub async fn start(addr: &str, mut registry: DriverRegistry) -> Result<Self> {
+        let (notifier, notifications) = mpsc::unbounded_channel();
+        registry.set_notifier(Some(notifier.clone()));
+        let (task_sender, task_events) = mpsc::unbounded_channel();
+        registry.set_task_sender(Some(task_sender));
+        let mut drivers = HashMap::new();
+        for (info, attached) in registry.list_drivers() {
+            if !attached {
+                registry.attach(&info.name).await?;
+            }
+            let driver = registry.split_off(&info.name)?;
+            drivers.insert(info.name, Arc::new(Mutex::new(driver)));
+        }
+
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| IndigoError::ConnectionError(format!("Failed to bind {}: {}", addr, e)))?;
+        let addr = listener.local_addr()?;
+
+        let (updates, _) = broadcast::channel(UPDATE_BUFFER_SIZE);
+        tokio::spawn(Self::forward(notifications, updates.clone()));
+        let state = Arc::new(ServerState {
+            drivers: RwLock::new(drivers),
+            registry: Mutex::new(registry),
+            updates,
+            notifier,
+        });
+        let (shutdown_tx, _) = broadcast::channel(1);
+        tokio::spawn(Self::run_tasks(
+            task_events,
+            Arc::clone(&state),
+            shutdown_tx.subscribe(),
+        ));
+
+        let task = tokio::spawn(Self::accept(
+            listener,
+            Arc::clone(&state),
+            shutdown_tx.clone(),
+        ));
+        tracing::info!("INDIGO server listening on {}", addr);
+
+        Ok(IndigoServer {
+            addr,
+            state,
+            shutdown_tx,
+            task: Some(task),
+        })
+    }
+
+    /// Gets the address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Registers and attaches a driver, defining its properties for all
+    /// connected clients.
+    pub async fn add_driver(&self, driver: Box<dyn DeviceDriver>) -> Result<()> {
+        let name = driver.info().name;
+        if self.state.driver(&name).await.is_some() {
+            return Err(IndigoError::DriverAlreadyRegistered(name));
+        }
+        let mut registry = {
+            let mut registry = self.state.registry.lock().await;
+            registry.register(driver)?;
+            registry.split_off(&name)?
+        };
+        registry.attach(&name).await?;
+
+        let mut drivers = self.state.drivers.write().await;
+        if drivers.contains_key(&name) {
+            // Another driver of the same name was added while attaching
+            registry.detach(&name).await?;
+            return Err(IndigoError::DriverAlreadyRegistered(name));
+        }
+        drivers.insert(name, Arc::new(Mutex::new(registry)));
+        Ok(())
+    }
+
+    /// Detaches and unregisters a driver, deleting its device for all
+    /// connected clients.
+    pub async fn remove_driver(&self, name: &str) -> Result<()> {
+        let driver = self
+            .state
+            .drivers
+            .write()
+            .await
+            .remove(name)
+            .ok_or_else(|| IndigoError::DriverNotFound(name.to_string()))?;
+        driver.lock().await.detach(name).await?;
+        self.state.notify(DeviceNotification::Deleted {
+            device: name.to_string(),
+            name: None,
+        });
+        Ok(())
+    }
+
+    /// Stops the server, closing all connections and detaching all drivers.
+    pub async fn shutdown(mut self) -> Result<()> {
+        let _ = self.shutdown_tx.send(());
+        if let Some(task) = self.task.take() {
+            task.await
+                .map_err(|e| IndigoError::InvalidState(format!("Server task failed: {}", e)))?;
+        }
+        for driver in self.state.all_drivers().await {
+            driver.lock().await.detach_all().await?;
+        }
+        Ok(())
+    }
+
+    /// Accepts connections until shut down.
+    async fn accept(
+        listener: TcpListener,
+        state: Arc<ServerState>,
+        shutdown_tx: broadcast::Sender<()>,
+    ) {
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, peer)) => match Connection::new(stream, Arc::clone(&state)) {
+                        Ok(connection) => {
+                            tracing::info!("Client {} connected", peer);
+                            tokio::spawn(connection.run(peer, shutdown_tx.subscribe()));
+                        }
+                        Err(e) => tracing::warn!("Failed to set up connection from {}: {}", peer, e),
+                    },
+                    Err(e) => tracing::warn!("Failed to accept connection: {}", e),
+                },
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+        tracing::info!("INDIGO server stopped");
+    }
+
+    /// Routes the task events of the drivers until shut down.
+    ///
+    /// Each driver's events are queued for a task of its own, so they are
+    /// handled in order without waiting for other drivers.
+    async fn run_tasks(
+        mut events: mpsc::UnboundedReceiver<TaskEvent>,
+        state: Arc<ServerState>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) {
+        let mut queues = HashMap::new();
+        loop {
+            tokio::select! {
+                Some(event) = events.recv() => {
+                    let queue = queues.entry(event.device.clone()).or_insert_with(|| {
+                        let (queue, events) = mpsc::unbounded_channel();
+                        tokio::spawn(Self::run_driver_tasks(events, Arc::clone(&state)));
+                        queue
+                    });
+                    // The queue only closes when the server stops
+                    let _ = queue.send(event);
+                }
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    }
+
+    /// Handles the queued task events of a driver.
+    async fn run_driver_tasks(
+        mut events: mpsc::UnboundedReceiver<TaskEvent>,
+        state: Arc<ServerState>,
+    ) {
+        while let Some(event) = events.recv().await {
+            let Some(driver) = state.driver(&event.device).await else {
+                continue;
+            };
+            let mut registry = driver.lock().await;
+            if let Err(e) = registry.handle_task(&event).await {
+                tracing::warn!("Task of {} failed: {}", event.device, e);
+            }
+        }
+    }
+
+    /// Broadcasts driver notifications until all drivers are gone.
+    async fn forward(
+        mut notifications: mpsc::UnboundedReceiver<DeviceNotification>,
+        updates: broadcast::Sender<ProtocolMessage>,
+    ) {
+        while let Some(notification) = notifications.recv().await {
+            // Sending only fails when no client is connected
+            let _ = updates.send(notification_message(notification));
+        }
+    }
+}
+
+impl Drop for IndigoServer {
+    fn drop(&mut self) {
+        // Stop accepting connections if the server wasn't shut down
+        let _ = self.shutdown_tx.send(());
+    }
+}
+
+impl ServerState {
+    /// Gets the registry of a hosted driver.
+    async fn driver(&self, name: &str) -> Option<Arc<Mutex<DriverRegistry>>> {
+        self.drivers.read().await.get(name).cloned()
+    }
+
+    /// Gets the registries of all hosted drivers.
+    async fn all_drivers(&self) -> Vec<Arc<Mutex<DriverRegistry>>> {
+        self.drivers.read().await.values().cloned().collect()
+    }
+
+    /// Gets the definitions of all matching properties of attached drivers.
+    async fn definitions(&self, device: Option<&str>, name: Option<&str>) -> Vec<ProtocolMessage> {
+        let drivers = match device {
+            Some(device) => self.driver(device).await.into_iter().collect(),
+            None => self.all_drivers().await,
+        };
+        let mut definitions = Vec::new();
+        for driver in drivers {
+            let registry = driver.lock().await;
+            let Some((info, true)) = registry.list_drivers().pop() else {
+                continue;
+            };
+            let Some(context) = registry.context(&info.name) else {
+                continue;
+            };
+            let mut properties: Vec<_> = context
+                .properties()
+                .properties()
+                .filter(|p| name.is_none_or(|n| n == p.name))
+                .collect();
+            properties.sort_by(|a, b| a.name.cmp(&b.name));
+            definitions.extend(properties.into_iter().map(define_message));
+        }
+        definitions
+    }
+
+    /// Routes a `newXXXVector` request to its driver and publishes the result.
+    async fn change(&self, request: ProtocolMessage) -> Result<()> {
+        let (device, name) = request_target(&request)
+            .map(|(d, n)| (d.to_string(), n.to_string()))
+            .ok_or_else(|| IndigoError::InvalidParameter("Not a property request".to_string()))?;
+
+        let driver = self
+            .driver(&device)
+            .await
+            .ok_or_else(|| IndigoError::DeviceNotFound(device.clone()))?;
+        let mut registry = driver.lock().await;
+        let context = registry
+            .context(&device)
+            .ok_or_else(|| IndigoError::DeviceNotFound(device.clone()))?;
+        let definition = context
+            .properties()
+            .get_property(&name)
+            .ok_or_else(|| IndigoError::PropertyNotFound(format!("{}.{}", device, name)))?;
+
+        // Validation and alerts for driver errors are left to the registry
+        let result = match requested_property(definition, request) {
+            Ok(property) => registry.handle_property_change(&device, &property).await,
+            Err(e) => {
+                if let Some(context) = registry.context_mut(&device) {
+                    context
+                        .property_manager()
+                        .alert_property(&name, e.to_string())?;
+                }
+                Err(e)
+            }
+        };
+        if let Err(e) = result {
+            tracing::warn!("{} rejected change of {}: {}", device, name, e);
+        }
+        Ok(())
+    }
+
+    /// Passes a BLOB mode change to its driver and publishes the result.
+    async fn enable_blob(&self, request: &EnableBLOB) -> Result<()> {
+        let mode = match request.value {
+            BLOBEnable::Never => BlobTransferMode::Never,
+            BLOBEnable::Also => BlobTransferMode::Also,
+            BLOBEnable::Only => BlobTransferMode::Only,
+        };
+
+        let Some(driver) = self.driver(&request.device).await else {
+            // The request may be for a device of another server
+            return Ok(());
+        };
+        let mut registry = driver.lock().await;
+        registry
+            .handle_enable_blob(&request.device, request.name.as_deref(), mode)
+            .await
+    }
+
+    /// Sends a notification to all subscribed clients, after those already
+    /// sent by the drivers.
+    fn notify(&self, notification: DeviceNotification) {
+        // The forwarder only stops once the drivers are gone
+        let _ = self.notifier.send(notification);
+    }
+}
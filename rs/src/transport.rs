@@ -10,6 +10,7 @@
 //! - **Message Framing**: Handle XML and JSON message boundaries in the TCP stream
 //! - **Protocol Negotiation**: Support both XML and JSON protocols with auto-detection
 //! - **Buffering**: Efficient buffering of incoming and outgoing data
+//! - **BLOB Streaming**: Decode the content of BLOBs as it arrives, see [`crate::blob`]
 //! - **Error Handling**: Handle network errors and connection failures
 //!
 //! # Connection Lifecycle
@@ -32,11 +33,16 @@
 //! - Each message is a complete JSON object (e.g., `{"getProperties": {...}}`)
 //! - We track brace depth to detect message boundaries
 //! - Handle escaped braces in strings
+//! - Messages we send are newline terminated, as the INDIGO server sends them,
+//!   so peers reading JSON line by line see where each message ends
 
+use crate::blob::{BlobSinkFactory, BlobStreamer};
 use crate::protocol::{ProtocolMessage, ProtocolParser, ProtocolSerializer};
 use crate::protocol_json::{JsonProtocolParser, JsonProtocolSerializer};
 use crate::protocol_negotiation::ProtocolType;
+use crate::recording::{record, Direction, SessionRecorder};
 use libindigo::error::{IndigoError, Result};
+use std::net::SocketAddr;
 use std::time::Duration as StdDuration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
 use tokio::net::TcpStream;
@@ -54,7 +60,27 @@ const DEFAULT_READ_TIMEOUT: u64 = 30;
 /// Initial buffer size for reading.
 const INITIAL_BUFFER_SIZE: usize = 8192;
 
+/// Serializes a message for sending with the given protocol.
+///
+/// JSON messages are terminated by a newline, as sent by the INDIGO server.
+/// The terminator is whitespace to parsers framing by brace depth, while
+/// peers reading line by line, such as the mock server, need it to see the
+/// end of a request.
+fn serialize(protocol: ProtocolType, message: &ProtocolMessage) -> Result<Vec<u8>> {
+    match protocol {
+        ProtocolType::Json => {
+            let mut bytes = JsonProtocolSerializer::serialize(message)?.into_bytes();
+            bytes.push(b'\n');
+            Ok(bytes)
+        }
+        ProtocolType::Xml => ProtocolSerializer::serialize(message),
+    }
+}
+
 /// Maximum buffer size to prevent unbounded growth.
+///
+/// The content of BLOBs is streamed out of the buffer and doesn't count
+/// towards the limit.
 const MAX_BUFFER_SIZE: usize = 10 * 1024 * 1024; // 10 MB
 
 /// Connection state.
@@ -105,6 +131,10 @@ pub struct Transport {
     read_timeout: Duration,
     /// Active protocol type (JSON or XML).
     protocol: ProtocolType,
+    /// Recorder of the messages sent and received.
+    recorder: Option<SessionRecorder>,
+    /// Streamer of the content of received BLOBs.
+    blobs: BlobStreamer,
 }
 
 /// Read half of a split transport.
@@ -120,6 +150,12 @@ pub struct ReadTransport {
     read_timeout: Duration,
     /// Active protocol type (JSON or XML).
     protocol: ProtocolType,
+    /// Recorder of the messages sent and received.
+    recorder: Option<SessionRecorder>,
+    /// Streamer of the content of received BLOBs.
+    blobs: BlobStreamer,
+    /// Address of the server, from which BLOBs sent by URL are fetched.
+    peer: Option<SocketAddr>,
 }
 
 /// Write half of a split transport.
@@ -130,6 +166,8 @@ pub struct WriteTransport {
     writer: WriteHalf<TcpStream>,
     /// Active protocol type (JSON or XML).
     protocol: ProtocolType,
+    /// Recorder of the messages sent and received.
+    recorder: Option<SessionRecorder>,
 }
 
 impl Transport {
@@ -144,6 +182,20 @@ impl Transport {
             connect_timeout: Duration::from_secs(DEFAULT_CONNECT_TIMEOUT),
             read_timeout: Duration::from_secs(DEFAULT_READ_TIMEOUT),
             protocol: ProtocolType::default(), // Default to JSON
+            recorder: None,
+            blobs: BlobStreamer::new(None),
+        }
+    }
+
+    /// Wraps a TCP stream accepted by a server.
+    ///
+    /// The protocol is detected from the first message the client sends.
+    #[cfg(any(feature = "device", feature = "test-server"))]
+    pub(crate) fn from_stream(stream: TcpStream) -> Self {
+        Transport {
+            stream: Some(stream),
+            state: ConnectionState::Connected,
+            ..Self::new()
         }
     }
 
@@ -310,6 +362,20 @@ impl Transport {
         self.protocol = protocol;
     }
 
+    /// Sets the recorder of the messages sent and received.
+    ///
+    /// The recorder is shared by both halves when the transport is split.
+    pub fn set_recorder(&mut self, recorder: Option<SessionRecorder>) {
+        self.recorder = recorder;
+    }
+
+    /// Sets the factory of sinks for the content of received BLOBs.
+    ///
+    /// BLOBs without a sink are decoded into memory.
+    pub fn set_blob_sink_factory(&mut self, sinks: Option<BlobSinkFactory>) {
+        self.blobs = BlobStreamer::new(sinks);
+    }
+
     /// Splits the transport into separate read and write halves.
     ///
     /// This allows independent read and write operations, which is essential
@@ -350,6 +416,7 @@ impl Transport {
             .take()
             .ok_or_else(|| IndigoError::InvalidState("Stream not available".to_string()))?;
 
+        let peer = stream.peer_addr().ok();
         let (reader, writer) = tokio::io::split(stream);
 
         let read_transport = ReadTransport {
@@ -357,11 +424,15 @@ impl Transport {
             read_buffer: self.read_buffer,
             read_timeout: self.read_timeout,
             protocol: self.protocol,
+            recorder: self.recorder.clone(),
+            blobs: self.blobs,
+            peer,
         };
 
         let write_transport = WriteTransport {
             writer,
             protocol: self.protocol,
+            recorder: self.recorder,
         };
 
         Ok((read_transport, write_transport))
@@ -414,10 +485,7 @@ impl Transport {
         }
 
         // Serialize the message based on active protocol
-        let message_bytes: Vec<u8> = match self.protocol {
-            ProtocolType::Json => JsonProtocolSerializer::serialize(message)?.into_bytes(),
-            ProtocolType::Xml => ProtocolSerializer::serialize(message)?,
-        };
+        let message_bytes = serialize(self.protocol, message)?;
 
         // Get mutable reference to stream
         let stream = self
@@ -437,6 +505,7 @@ impl Transport {
             .await
             .map_err(|e| IndigoError::ConnectionError(format!("Failed to flush stream: {}", e)))?;
 
+        record(&self.recorder, Direction::Sent, message);
         Ok(())
     }
 
@@ -475,8 +544,15 @@ impl Transport {
         }
 
         loop {
+            // Take the content of BLOBs out of the buffer
+            self.blobs.stream(&mut self.read_buffer).await;
+
             // Try to parse a complete message from the buffer
-            if let Some(message) = self.try_parse_message()? {
+            if let Some(mut message) = self.try_parse_message()? {
+                let peer = self.stream.as_ref().and_then(|s| s.peer_addr().ok());
+                self.blobs
+                    .fetch(&mut message, peer, self.read_timeout)
+                    .await?;
                 return Ok(message);
             }
 
@@ -509,9 +585,10 @@ impl Transport {
 
             // Remove the message from the buffer
             self.read_buffer.drain(..=end_pos);
+            let blobs = self.blobs.take();
 
             // Parse the message based on active protocol
-            let message = match self.protocol {
+            let mut message = match self.protocol {
                 ProtocolType::Json => {
                     let json_str = std::str::from_utf8(&message_bytes)
                         .map_err(|e| IndigoError::ParseError(format!("Invalid UTF-8: {}", e)))?;
@@ -519,7 +596,9 @@ impl Transport {
                 }
                 ProtocolType::Xml => ProtocolParser::parse_message(&message_bytes)?,
             };
+            blobs.attach(&mut message)?;
 
+            record(&self.recorder, Direction::Received, &message);
             return Ok(Some(message));
         }
 
@@ -536,111 +615,9 @@ impl Transport {
     /// - For JSON: Track brace depth
     fn find_message_boundary(&self) -> Result<Option<usize>> {
         match self.protocol {
-            ProtocolType::Xml => self.find_xml_boundary(),
-            ProtocolType::Json => self.find_json_boundary(),
-        }
-    }
-
-    /// Finds the boundary of a complete XML message.
-    fn find_xml_boundary(&self) -> Result<Option<usize>> {
-        let mut depth = 0;
-        let mut in_tag = false;
-        let mut in_string = false;
-        let mut is_closing_tag = false;
-        let mut is_self_closing = false;
-        let mut i = 0;
-
-        while i < self.read_buffer.len() {
-            let byte = self.read_buffer[i];
-
-            match byte {
-                b'"' if in_tag && !in_string => {
-                    in_string = true;
-                }
-                b'"' if in_tag && in_string => {
-                    in_string = false;
-                }
-                b'<' if !in_string => {
-                    in_tag = true;
-                    is_closing_tag = false;
-                    is_self_closing = false;
-
-                    // Check if this is a closing tag
-                    if i + 1 < self.read_buffer.len() && self.read_buffer[i + 1] == b'/' {
-                        is_closing_tag = true;
-                    }
-                }
-                b'/' if in_tag && !in_string => {
-                    // Check if this is a self-closing tag
-                    if i + 1 < self.read_buffer.len() && self.read_buffer[i + 1] == b'>' {
-                        is_self_closing = true;
-                    }
-                }
-                b'>' if in_tag && !in_string => {
-                    in_tag = false;
-
-                    if is_closing_tag {
-                        depth -= 1;
-                    } else if !is_self_closing {
-                        depth += 1;
-                    }
-
-                    // If we've closed all tags, we have a complete message
-                    if depth == 0 && i > 0 {
-                        return Ok(Some(i));
-                    }
-                }
-                _ => {}
-            }
-
-            i += 1;
+            ProtocolType::Xml => Ok(find_xml_boundary(&self.read_buffer)),
+            ProtocolType::Json => Ok(find_json_boundary(&self.read_buffer)),
         }
-
-        Ok(None)
-    }
-
-    /// Finds the boundary of a complete JSON message.
-    ///
-    /// JSON messages are complete JSON objects. We track brace depth
-    /// to find the end of the object, handling escaped characters in strings.
-    fn find_json_boundary(&self) -> Result<Option<usize>> {
-        let mut depth = 0;
-        let mut in_string = false;
-        let mut escape_next = false;
-        let mut started = false;
-
-        for (i, &byte) in self.read_buffer.iter().enumerate() {
-            if escape_next {
-                escape_next = false;
-                continue;
-            }
-
-            match byte {
-                b'\\' if in_string => {
-                    escape_next = true;
-                }
-                b'"' if !in_string => {
-                    in_string = true;
-                }
-                b'"' if in_string => {
-                    in_string = false;
-                }
-                b'{' if !in_string => {
-                    depth += 1;
-                    started = true;
-                }
-                b'}' if !in_string => {
-                    depth -= 1;
-                    // If we've closed all braces, we have a complete message
-                    if depth == 0 && started {
-                        return Ok(Some(i));
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        Ok(None)
     }
 
     /// Reads more data from the TCP stream into the read buffer.
@@ -774,8 +751,14 @@ impl ReadTransport {
     /// - Read times out
     pub async fn receive_message(&mut self) -> Result<ProtocolMessage> {
         loop {
+            // Take the content of BLOBs out of the buffer
+            self.blobs.stream(&mut self.read_buffer).await;
+
             // Try to parse a complete message from the buffer
-            if let Some(message) = self.try_parse_message()? {
+            if let Some(mut message) = self.try_parse_message()? {
+                self.blobs
+                    .fetch(&mut message, self.peer, self.read_timeout)
+                    .await?;
                 return Ok(message);
             }
 
@@ -810,9 +793,10 @@ impl ReadTransport {
 
             // Remove the message from the buffer
             self.read_buffer.drain(..=end_pos);
+            let blobs = self.blobs.take();
 
             // Parse the message based on active protocol
-            let message = match self.protocol {
+            let mut message = match self.protocol {
                 ProtocolType::Json => {
                     let json_str = std::str::from_utf8(&message_bytes)
                         .map_err(|e| IndigoError::ParseError(format!("Invalid UTF-8: {}", e)))?;
@@ -820,7 +804,9 @@ impl ReadTransport {
                 }
                 ProtocolType::Xml => ProtocolParser::parse_message(&message_bytes)?,
             };
+            blobs.attach(&mut message)?;
 
+            record(&self.recorder, Direction::Received, &message);
             return Ok(Some(message));
         }
 
@@ -830,110 +816,11 @@ impl ReadTransport {
     /// Finds the boundary of the first complete message in the buffer.
     fn find_message_boundary(&self) -> Result<Option<usize>> {
         match self.protocol {
-            ProtocolType::Xml => self.find_xml_boundary(),
-            ProtocolType::Json => self.find_json_boundary(),
+            ProtocolType::Xml => Ok(find_xml_boundary(&self.read_buffer)),
+            ProtocolType::Json => Ok(find_json_boundary(&self.read_buffer)),
         }
     }
 
-    /// Finds the boundary of a complete XML message.
-    fn find_xml_boundary(&self) -> Result<Option<usize>> {
-        let mut depth = 0;
-        let mut in_tag = false;
-        let mut in_string = false;
-        let mut is_closing_tag = false;
-        let mut is_self_closing = false;
-        let mut i = 0;
-
-        while i < self.read_buffer.len() {
-            let byte = self.read_buffer[i];
-
-            match byte {
-                b'"' if in_tag && !in_string => {
-                    in_string = true;
-                }
-                b'"' if in_tag && in_string => {
-                    in_string = false;
-                }
-                b'<' if !in_string => {
-                    in_tag = true;
-                    is_closing_tag = false;
-                    is_self_closing = false;
-
-                    // Check if this is a closing tag
-                    if i + 1 < self.read_buffer.len() && self.read_buffer[i + 1] == b'/' {
-                        is_closing_tag = true;
-                    }
-                }
-                b'/' if in_tag && !in_string => {
-                    // Check if this is a self-closing tag
-                    if i + 1 < self.read_buffer.len() && self.read_buffer[i + 1] == b'>' {
-                        is_self_closing = true;
-                    }
-                }
-                b'>' if in_tag && !in_string => {
-                    in_tag = false;
-
-                    if is_closing_tag {
-                        depth -= 1;
-                    } else if !is_self_closing {
-                        depth += 1;
-                    }
-
-                    // If we've closed all tags, we have a complete message
-                    if depth == 0 && i > 0 {
-                        return Ok(Some(i));
-                    }
-                }
-                _ => {}
-            }
-
-            i += 1;
-        }
-
-        Ok(None)
-    }
-
-    /// Finds the boundary of a complete JSON message.
-    fn find_json_boundary(&self) -> Result<Option<usize>> {
-        let mut depth = 0;
-        let mut in_string = false;
-        let mut escape_next = false;
-        let mut started = false;
-
-        for (i, &byte) in self.read_buffer.iter().enumerate() {
-            if escape_next {
-                escape_next = false;
-                continue;
-            }
-
-            match byte {
-                b'\\' if in_string => {
-                    escape_next = true;
-                }
-                b'"' if !in_string => {
-                    in_string = true;
-                }
-                b'"' if in_string => {
-                    in_string = false;
-                }
-                b'{' if !in_string => {
-                    depth += 1;
-                    started = true;
-                }
-                b'}' if !in_string => {
-                    depth -= 1;
-                    // If we've closed all braces, we have a complete message
-                    if depth == 0 && started {
-                        return Ok(Some(i));
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        Ok(None)
-    }
-
     /// Reads more data from the TCP stream into the read buffer.
     async fn read_more_data(&mut self) -> Result<()> {
         // Check buffer size to prevent unbounded growth
@@ -1001,10 +888,7 @@ impl WriteTransport {
     /// - Write operation fails
     pub async fn send_message(&mut self, message: &ProtocolMessage) -> Result<()> {
         // Serialize the message based on active protocol
-        let message_bytes: Vec<u8> = match self.protocol {
-            ProtocolType::Json => JsonProtocolSerializer::serialize(message)?.into_bytes(),
-            ProtocolType::Xml => ProtocolSerializer::serialize(message)?,
-        };
+        let message_bytes = serialize(self.protocol, message)?;
 
         // Write the message bytes to the stream
         self.writer
@@ -1018,8 +902,176 @@ impl WriteTransport {
             .await
             .map_err(|e| IndigoError::ConnectionError(format!("Failed to flush stream: {}", e)))?;
 
+        record(&self.recorder, Direction::Sent, message);
         Ok(())
     }
+
+    /// Writes bytes to the TCP stream as they are, for a mock server that
+    /// corrupts or splits its messages on purpose.
+    #[cfg(feature = "test-server")]
+    pub(crate) async fn write_raw(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer
+            .write_all(bytes)
+            .await
+            .map_err(|e| IndigoError::ConnectionError(format!("Failed to write message: {}", e)))?;
+        self.writer
+            .flush()
+            .await
+            .map_err(|e| IndigoError::ConnectionError(format!("Failed to flush stream: {}", e)))
+    }
+}
+
+// ============================================================================
+// Message Framing
+// ============================================================================
+
+/// Finds the end of the first complete XML message in a buffer.
+///
+/// Tracks the depth of elements outside attribute values. Bytes that can't
+/// be part of a message end a message of their own, so that they fail to
+/// parse instead of stalling the stream: a closing tag without an opening
+/// one, and a tag that starts the next message after one that was cut off.
+/// That is a `<` within a tag, which XML doesn't allow, or a nested message
+/// element, which INDIGO doesn't use.
+fn find_xml_boundary(buffer: &[u8]) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_tag = false;
+    let mut in_string = false;
+    let mut is_closing_tag = false;
+    let mut is_self_closing = false;
+
+    for (i, &byte) in buffer.iter().enumerate() {
+        match byte {
+            b'"' if in_tag => {
+                in_string = !in_string;
+            }
+            b'<' if in_tag => return Some(i - 1),
+            b'<' => {
+                if depth > 0 && buffer.get(i + 1) != Some(&b'/') {
+                    let name = &buffer[i + 1..];
+                    let end = name
+                        .iter()
+                        .position(|b| b.is_ascii_whitespace() || matches!(b, b'/' | b'>'))?;
+                    if is_message_element(&name[..end]) {
+                        return Some(i - 1);
+                    }
+                }
+                in_tag = true;
+                is_closing_tag = buffer.get(i + 1) == Some(&b'/');
+                is_self_closing = false;
+            }
+            b'/' if in_tag && !in_string => {
+                if buffer.get(i + 1) == Some(&b'>') {
+                    is_self_closing = true;
+                }
+            }
+            b'>' if in_tag && !in_string => {
+                in_tag = false;
+
+                if is_closing_tag {
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                    depth -= 1;
+                } else if !is_self_closing {
+                    depth += 1;
+                }
+
+                // If we've closed all tags, we have a complete message
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Whether an XML element is a message of its own.
+fn is_message_element(name: &[u8]) -> bool {
+    name.ends_with(b"Vector")
+        || matches!(
+            name,
+            b"delProperty" | b"message" | b"getProperties" | b"enableBLOB"
+        )
+}
+
+/// Finds the end of the first complete JSON message in a buffer.
+///
+/// Messages are JSON objects, found by tracking the objects and arrays open
+/// outside strings. Bytes that can't be part of a message end a message of
+/// their own, so that they fail to parse instead of stalling the stream:
+/// text before the opening brace, a closing brace without an opening one, a
+/// line break inside a string, and an opening brace where JSON allows no
+/// value, which starts the next message after one that was cut off.
+fn find_json_boundary(buffer: &[u8]) -> Option<usize> {
+    let mut open = Vec::new();
+    let mut in_string = false;
+    let mut escape_next = false;
+    let mut garbage = false;
+    // Last byte outside strings and whitespace
+    let mut last = 0u8;
+
+    for (i, &byte) in buffer.iter().enumerate() {
+        if open.is_empty() {
+            match byte {
+                b'{' if garbage => return Some(i - 1),
+                b'{' => {
+                    open.push(byte);
+                    last = byte;
+                }
+                b'}' => return Some(i),
+                b' ' | b'\t' | b'\r' | b'\n' => {}
+                _ => garbage = true,
+            }
+            continue;
+        }
+
+        if in_string {
+            match byte {
+                _ if escape_next => escape_next = false,
+                b'\\' => escape_next = true,
+                b'"' => {
+                    in_string = false;
+                    last = byte;
+                }
+                b'\n' => return Some(i),
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b' ' | b'\t' | b'\r' | b'\n' => continue,
+            b'"' => in_string = true,
+            b'{' => {
+                // Values follow a key in objects, and an opening bracket or
+                // a comma in arrays
+                let value_allowed = match open.last() {
+                    Some(b'{') => last == b':',
+                    _ => matches!(last, b'[' | b','),
+                };
+                if !value_allowed {
+                    return Some(i - 1);
+                }
+                open.push(byte);
+            }
+            b'[' => open.push(byte),
+            b'}' | b']' => {
+                open.pop();
+                // If we've closed everything, we have a complete message
+                if open.is_empty() {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        last = byte;
+    }
+
+    None
 }
 
 // ============================================================================
@@ -1090,6 +1142,126 @@ mod tests {
         assert_eq!(boundary, Some(54)); // Index of last '>'
     }
 
+    /// Splits a stream into messages the way the receive loop does.
+    fn frames(data: &[u8], find: fn(&[u8]) -> Option<usize>) -> Vec<String> {
+        let mut buffer = data.to_vec();
+        let mut frames = Vec::new();
+        while let Some(end) = find(&buffer) {
+            frames.push(String::from_utf8_lossy(&buffer[..=end]).trim().to_string());
+            buffer.drain(..=end);
+        }
+        frames
+    }
+
+    #[test]
+    fn test_json_boundary_at_every_split() {
+        let message =
+            br#"{"setTextVector":{"device":"CCD","items":[{"name":"A","value":"} {\"q\" \\"}]}}"#;
+        let mut stream = message.to_vec();
+        stream.extend_from_slice(b"\n{\"x\":1}");
+        for split in 0..stream.len() {
+            let expected = (split >= message.len()).then_some(message.len() - 1);
+            assert_eq!(
+                find_json_boundary(&stream[..split]),
+                expected,
+                "split {}",
+                split
+            );
+        }
+    }
+
+    #[test]
+    fn test_xml_boundary_at_every_split() {
+        let message =
+            br#"<defTextVector device="a>b" name="c/>"><defText name="t">x</defText></defTextVector>"#;
+        let mut stream = message.to_vec();
+        stream.extend_from_slice(b"\n<getProperties/>");
+        for split in 0..stream.len() {
+            let expected = (split >= message.len()).then_some(message.len() - 1);
+            assert_eq!(
+                find_xml_boundary(&stream[..split]),
+                expected,
+                "split {}",
+                split
+            );
+        }
+    }
+
+    #[test]
+    fn test_json_boundary_recovers_from_malformed_messages() {
+        let next = r#"{"c":2}"#;
+        let cases: [&[u8]; 5] = [
+            // Closing brace without an opening one
+            b"}\n{\"c\":2}",
+            // Text before a message
+            b"garbage {\"c\":2}",
+            // Message cut off outside a string
+            b"{\"a\":{\"b\":1,\n{\"c\":2}",
+            // Message cut off inside a string
+            b"{\"a\":\"unterminated\n{\"c\":2}",
+            // Bytes that are not UTF-8
+            b"\xff\xfe{\"c\":2}",
+        ];
+        for data in cases {
+            let frames = frames(data, find_json_boundary);
+            assert_eq!(frames.len(), 2, "{:?}", frames);
+            assert_eq!(frames[1], next);
+        }
+
+        // Line breaks within a message are fine outside strings
+        let pretty = b"{\n  \"a\": {\n    \"b\": [\n      {}\n    ]\n  }\n}\n{\"c\":2}";
+        assert_eq!(frames(pretty, find_json_boundary)[1], next);
+        let unindented = b"{\"a\":\n{\"b\":[\n{\"c\":1},\n{}]}}\n{\"c\":2}";
+        assert_eq!(
+            frames(unindented, find_json_boundary),
+            vec!["{\"a\":\n{\"b\":[\n{\"c\":1},\n{}]}}", next]
+        );
+    }
+
+    /// Checks that the stream recovers from a message cut off at any byte,
+    /// by the time a second message follows it.
+    fn assert_recovers_from_truncation(
+        message: &[u8],
+        next: &str,
+        find: fn(&[u8]) -> Option<usize>,
+    ) {
+        for cut in 0..message.len() {
+            let mut stream = message[..cut].to_vec();
+            for _ in 0..2 {
+                stream.push(b'\n');
+                stream.extend_from_slice(next.as_bytes());
+            }
+            let frames = frames(&stream, find);
+            assert_eq!(frames.last().map(String::as_str), Some(next), "cut {}", cut);
+        }
+    }
+
+    #[test]
+    fn test_json_boundary_recovers_from_truncation() {
+        assert_recovers_from_truncation(
+            br#"{"setTextVector":{"device":"CCD","items":[{"name":"A","value":"} {\"q\" \\"},{"name":"B","value":"x"}]}}"#,
+            r#"{"c":2}"#,
+            find_json_boundary,
+        );
+    }
+
+    #[test]
+    fn test_xml_boundary_recovers_from_truncation() {
+        assert_recovers_from_truncation(
+            br#"<setTextVector device="a>b" name="c/>"><oneText name="t">x</oneText><oneText name="u"/></setTextVector>"#,
+            r#"<setNumberVector device="d" name="n"><oneNumber name="v">1</oneNumber></setNumberVector>"#,
+            find_xml_boundary,
+        );
+    }
+
+    #[test]
+    fn test_xml_boundary_recovers_from_stray_closing_tag() {
+        assert_eq!(
+            frames(b"</defTextVector>\n<getProperties/>", find_xml_boundary),
+            vec!["</defTextVector>", "<getProperties/>"]
+        );
+    }
+
     #[test]
     fn test_connection_state() {
         let transport = Transport::new();
@@ -1113,6 +1285,52 @@ mod tests {
         assert!(matches!(result.unwrap_err(), IndigoError::InvalidState(_)));
     }
 
+    #[tokio::test]
+    async fn test_json_messages_are_newline_terminated() {
+        use super::super::protocol::GetProperties;
+        use tokio::io::{AsyncBufReadExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut lines = BufReader::new(stream).lines();
+            let mut received = Vec::new();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                received.push(line);
+            }
+            received
+        });
+
+        let msg = ProtocolMessage::GetProperties(GetProperties {
+            version: Some("1.7".to_string()),
+            device: Some("CCD Simulator".to_string()),
+            name: None,
+        });
+        let (_, mut write) = Transport::connect(&addr.to_string())
+            .await
+            .unwrap()
+            .split()
+            .unwrap();
+        write.send_message(&msg).await.unwrap();
+        write.send_message(&msg).await.unwrap();
+        drop(write);
+
+        // Each message is a line of its own that parses on its own
+        let received = server.await.unwrap();
+        assert_eq!(received.len(), 2);
+        for line in received {
+            assert!(matches!(
+                JsonProtocolParser::parse_message(&line).unwrap(),
+                ProtocolMessage::GetProperties(_)
+            ));
+        }
+        assert_eq!(
+            serialize(ProtocolType::Xml, &msg).unwrap(),
+            ProtocolSerializer::serialize(&msg).unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_receive_message_not_connected() {
         let mut transport = Transport::new();
@@ -15,14 +15,19 @@
 //!     .build()?;
 //! ```
 
+use crate::client::events::PropertyEvent;
+use crate::client::outcome::PropertyOutcome;
+use crate::client::reconnect::{ConnectionEvent, ReconnectPolicy};
 use crate::client::ClientStrategy;
+use crate::device::traits::{Camera, FilterWheel, Focuser, Guider, Mount, TypedDevice};
 use crate::error::{IndigoError, Result};
 use crate::logging::LogConfig;
+use crate::types::Property;
+use std::time::Duration;
+use tokio::sync::mpsc;
 
 #[cfg(feature = "monitoring")]
 use crate::client::monitoring::{ClientEvent, MonitoringConfig};
-#[cfg(feature = "monitoring")]
-use tokio::sync::mpsc;
 
 /// Builder for constructing INDIGO clients.
 ///
@@ -42,6 +47,7 @@ use tokio::sync::mpsc;
 pub struct ClientBuilder {
     strategy: Option<Box<dyn ClientStrategy>>,
     log_config: Option<LogConfig>,
+    reconnect_policy: Option<ReconnectPolicy>,
     #[cfg(feature = "monitoring")]
     monitoring_config: Option<MonitoringConfig>,
 }
@@ -60,6 +66,7 @@ impl ClientBuilder {
         ClientBuilder {
             strategy: None,
             log_config: None,
+            reconnect_policy: None,
             #[cfg(feature = "monitoring")]
             monitoring_config: None,
         }
@@ -116,6 +123,37 @@ impl ClientBuilder {
         self
     }
 
+    /// Enables automatic reconnection with the given policy.
+    ///
+    /// When the connection to the server is lost, the client reconnects
+    /// according to the policy, re-enumerates properties and restores BLOB
+    /// transfer modes. Connection state changes can be subscribed to via
+    /// [`Client::subscribe_connection_events()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The reconnect policy to use
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use libindigo::client::{ClientBuilder, ReconnectPolicy};
+    /// use std::time::Duration;
+    ///
+    /// let client = ClientBuilder::new()
+    ///     .with_strategy(strategy)
+    ///     .with_reconnect(
+    ///         ReconnectPolicy::default()
+    ///             .with_max_delay(Duration::from_secs(30))
+    ///             .with_max_attempts(50)
+    ///     )
+    ///     .build()?;
+    /// ```
+    pub fn with_reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = Some(policy);
+        self
+    }
+
     /// Enables server monitoring with the given configuration.
     ///
     /// When monitoring is enabled, the client will track server availability
@@ -163,16 +201,10 @@ impl ClientBuilder {
     ///     .build()?;
     /// ```
     pub fn build(self) -> Result<Client> {
-        #[cfg(feature = "monitoring")]
         let mut strategy = self
             .strategy
             .ok_or_else(|| IndigoError::InvalidParameter("No strategy configured".to_string()))?;
 
-        #[cfg(not(feature = "monitoring"))]
-        let strategy = self
-            .strategy
-            .ok_or_else(|| IndigoError::InvalidParameter("No strategy configured".to_string()))?;
-
         // Initialize logging if configured
         if let Some(config) = &self.log_config {
             crate::logging::init_logging(config).map_err(|e| {
@@ -180,6 +212,11 @@ impl ClientBuilder {
             })?;
         }
 
+        // Pass reconnect policy to strategy if provided
+        if let Some(policy) = self.reconnect_policy {
+            strategy.set_reconnect_policy(policy)?;
+        }
+
         // Pass monitoring config to strategy if provided
         #[cfg(feature = "monitoring")]
         if let Some(config) = self.monitoring_config {
@@ -229,6 +266,186 @@ impl Client {
         &mut *self.strategy
     }
 
+    /// Sends a property change and waits for the server to report the result.
+    ///
+    /// See [`ClientStrategy::change_property`] for details on how the timeout
+    /// is resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::Timeout`] if the server does not report `Ok` or
+    /// `Alert` in time, or any error from sending the property.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use std::time::Duration;
+    ///
+    /// let outcome = client
+    ///     .change_property(exposure, Some(Duration::from_secs(120)))
+    ///     .await?;
+    /// if outcome.is_ok() {
+    ///     println!("Exposure complete");
+    /// }
+    /// ```
+    pub async fn change_property(
+        &mut self,
+        property: Property,
+        timeout: Option<Duration>,
+    ) -> Result<PropertyOutcome> {
+        self.strategy.change_property(property, timeout).await
+    }
+
+    /// Subscribes to property events.
+    ///
+    /// Returns a receiver for [`PropertyEvent`]s, distinguishing property
+    /// definitions, updates and deletions, as well as messages sent by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::NotSupported`] if the strategy does not provide
+    /// property events.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use libindigo::client::PropertyEvent;
+    ///
+    /// let mut events = client.subscribe_events().await?;
+    /// while let Some(event) = events.recv().await {
+    ///     match event {
+    ///         PropertyEvent::Defined(p) => println!("Defined {}.{}", p.device, p.name),
+    ///         PropertyEvent::Updated(p) => println!("Updated {}.{}", p.device, p.name),
+    ///         PropertyEvent::Deleted { device, .. } => println!("Deleted from {}", device),
+    ///         PropertyEvent::Message { message, .. } => println!("Message: {}", message),
+    ///     }
+    /// }
+    /// ```
+    pub async fn subscribe_events(&self) -> Result<mpsc::UnboundedReceiver<PropertyEvent>> {
+        self.strategy.subscribe_events().await
+    }
+
+    /// Subscribes to connection state events.
+    ///
+    /// Returns a receiver for [`ConnectionEvent`]s reporting connects,
+    /// disconnects and, when enabled with [`ClientBuilder::with_reconnect`],
+    /// reconnection attempts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::NotSupported`] if the strategy does not provide
+    /// connection events.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use libindigo::client::ConnectionEvent;
+    ///
+    /// let mut events = client.subscribe_connection_events().await?;
+    /// while let Some(event) = events.recv().await {
+    ///     match event {
+    ///         ConnectionEvent::Disconnected { reason } => println!("Lost connection: {}", reason),
+    ///         ConnectionEvent::Reconnected => println!("Back online"),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub async fn subscribe_connection_events(
+        &self,
+    ) -> Result<mpsc::UnboundedReceiver<ConnectionEvent>> {
+        self.strategy.subscribe_connection_events().await
+    }
+
+    /// Opens a device as a device trait object.
+    ///
+    /// Devices are matched by the interfaces they report, so the device must
+    /// be defined before it can be opened.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device is not defined,
+    /// [`IndigoError::InterfaceMismatch`] if it does not implement the
+    /// interface of `T`, or [`IndigoError::NotSupported`] if the strategy
+    /// can't open devices.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use libindigo::device::traits::Camera;
+    ///
+    /// let mut camera = client.device::<dyn Camera>("CCD Simulator").await?;
+    /// camera.start_exposure(5.0).await?;
+    /// ```
+    pub async fn device<T: TypedDevice + ?Sized>(&self, device: &str) -> Result<Box<T>> {
+        let handle = self.strategy.open_device(device, T::INTERFACE).await?;
+        T::from_handle(handle).ok_or_else(|| mismatch::<T>(device))
+    }
+
+    /// Opens all devices implementing the interface of `T`, sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::NotSupported`] if the strategy can't open
+    /// devices.
+    pub async fn devices<T: TypedDevice + ?Sized>(&self) -> Result<Vec<Box<T>>> {
+        self.strategy
+            .open_devices(T::INTERFACE)
+            .await?
+            .into_iter()
+            .map(|handle| T::from_handle(handle).ok_or_else(|| mismatch::<T>("A device")))
+            .collect()
+    }
+
+    /// Opens a camera by device name.
+    pub async fn camera(&self, device: &str) -> Result<Box<dyn Camera>> {
+        self.device(device).await
+    }
+
+    /// Opens all cameras.
+    pub async fn cameras(&self) -> Result<Vec<Box<dyn Camera>>> {
+        self.devices().await
+    }
+
+    /// Opens a mount by device name.
+    pub async fn mount(&self, device: &str) -> Result<Box<dyn Mount>> {
+        self.device(device).await
+    }
+
+    /// Opens all mounts.
+    pub async fn mounts(&self) -> Result<Vec<Box<dyn Mount>>> {
+        self.devices().await
+    }
+
+    /// Opens a focuser by device name.
+    pub async fn focuser(&self, device: &str) -> Result<Box<dyn Focuser>> {
+        self.device(device).await
+    }
+
+    /// Opens all focusers.
+    pub async fn focusers(&self) -> Result<Vec<Box<dyn Focuser>>> {
+        self.devices().await
+    }
+
+    /// Opens a filter wheel by device name.
+    pub async fn filter_wheel(&self, device: &str) -> Result<Box<dyn FilterWheel>> {
+        self.device(device).await
+    }
+
+    /// Opens all filter wheels.
+    pub async fn filter_wheels(&self) -> Result<Vec<Box<dyn FilterWheel>>> {
+        self.devices().await
+    }
+
+    /// Opens a guider by device name.
+    pub async fn guider(&self, device: &str) -> Result<Box<dyn Guider>> {
+        self.device(device).await
+    }
+
+    /// Opens all guiders.
+    pub async fn guiders(&self) -> Result<Vec<Box<dyn Guider>>> {
+        self.devices().await
+    }
+
     /// Subscribes to server status events.
     ///
     /// Returns a receiver for monitoring status change events. Each event indicates
@@ -266,6 +483,15 @@ impl Client {
     }
 }
 
+/// Error for a strategy that opened a device with another interface.
+fn mismatch<T: TypedDevice + ?Sized>(device: &str) -> IndigoError {
+    IndigoError::InterfaceMismatch(format!(
+        "{} was not opened as a {:?} device",
+        device,
+        T::INTERFACE
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -276,4 +502,48 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result, Err(IndigoError::InvalidParameter(_))));
     }
+
+    /// A strategy supporting only what every strategy must.
+    struct MinimalStrategy;
+
+    #[async_trait::async_trait]
+    impl ClientStrategy for MinimalStrategy {
+        async fn connect(&mut self, _url: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn enumerate_properties(&mut self, _device: Option<&str>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send_property(&mut self, _property: crate::types::Property) -> Result<()> {
+            Ok(())
+        }
+
+        async fn enable_blob(
+            &mut self,
+            _device: &str,
+            _name: Option<&str>,
+            _mode: crate::types::BlobTransferMode,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_builder_rejects_unsupported_reconnect() {
+        let result = ClientBuilder::new()
+            .with_strategy(Box::new(MinimalStrategy))
+            .with_reconnect(ReconnectPolicy::default())
+            .build();
+        assert!(matches!(result, Err(IndigoError::NotSupported(_))));
+        assert!(ClientBuilder::new()
+            .with_strategy(Box::new(MinimalStrategy))
+            .build()
+            .is_ok());
+    }
 }
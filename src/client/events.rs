@@ -0,0 +1,80 @@
+//! Property events emitted by client strategies.
+//!
+//! This module defines [`PropertyEvent`], the typed event stream that
+//! distinguishes property definitions from updates and deletions, and
+//! carries free-form messages sent by the server.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo::client::PropertyEvent;
+//!
+//! let mut events = client.subscribe_events().await?;
+//! while let Some(event) = events.recv().await {
+//!     match event {
+//!         PropertyEvent::Defined(property) => println!("+ {}.{}", property.device, property.name),
+//!         PropertyEvent::Updated(property) => println!("~ {}.{}", property.device, property.name),
+//!         PropertyEvent::Deleted { device, name: Some(name), .. } => println!("- {}.{}", device, name),
+//!         PropertyEvent::Deleted { device, name: None, .. } => println!("- {}", device),
+//!         PropertyEvent::Message { message, .. } => println!("! {}", message),
+//!     }
+//! }
+//! ```
+
+use crate::types::Property;
+
+/// Events describing changes to the properties defined by an INDIGO server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyEvent {
+    /// A property was defined (`defXXXVector`).
+    Defined(Property),
+
+    /// A property was updated (`setXXXVector`).
+    ///
+    /// The property holds the complete, up to date property, with the
+    /// values from the update merged into the metadata of its definition.
+    Updated(Property),
+
+    /// A property, or a whole device, was deleted (`delProperty`).
+    Deleted {
+        /// Device name.
+        device: String,
+        /// Property name, or `None` if all properties of the device were deleted.
+        name: Option<String>,
+        /// Optional message sent with the deletion.
+        message: Option<String>,
+        /// Optional timestamp.
+        timestamp: Option<String>,
+    },
+
+    /// A message was sent by the server (`message`).
+    Message {
+        /// Device the message relates to, if any.
+        device: Option<String>,
+        /// Message text.
+        message: String,
+        /// Optional timestamp.
+        timestamp: Option<String>,
+    },
+}
+
+impl PropertyEvent {
+    /// Returns the device name the event relates to, if any.
+    pub fn device(&self) -> Option<&str> {
+        match self {
+            PropertyEvent::Defined(property) | PropertyEvent::Updated(property) => {
+                Some(&property.device)
+            }
+            PropertyEvent::Deleted { device, .. } => Some(device),
+            PropertyEvent::Message { device, .. } => device.as_deref(),
+        }
+    }
+
+    /// Returns the property carried by `Defined` and `Updated` events.
+    pub fn property(&self) -> Option<&Property> {
+        match self {
+            PropertyEvent::Defined(property) | PropertyEvent::Updated(property) => Some(property),
+            _ => None,
+        }
+    }
+}
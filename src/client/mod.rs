@@ -31,9 +31,15 @@
 //! ```
 
 pub mod builder;
+pub mod events;
 pub mod monitoring;
+pub mod outcome;
+pub mod reconnect;
 pub mod strategy;
 
 pub use builder::{Client, ClientBuilder};
+pub use events::PropertyEvent;
 pub use monitoring::{AvailabilityStatus, ClientEvent, MonitoringConfig, MonitoringEvent};
+pub use outcome::PropertyOutcome;
+pub use reconnect::{ConnectionEvent, ReconnectPolicy};
 pub use strategy::ClientStrategy;
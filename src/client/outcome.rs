@@ -0,0 +1,433 @@
+//! Outcome of a property change request.
+//!
+//! INDIGO property changes are asynchronous: a client sends a `newXXXVector`
+//! message and the server later reports the result by updating the property
+//! state to `Ok` or `Alert`. This module provides [`PropertyOutcome`] and
+//! [`wait_for_outcome`], which pairs a change request with its result.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::time::Duration;
+//!
+//! let outcome = client
+//!     .change_property(exposure, Some(Duration::from_secs(120)))
+//!     .await?;
+//! if outcome.is_alert() {
+//!     eprintln!("Exposure failed: {:?}", outcome.message);
+//! }
+//! ```
+
+use crate::client::events::PropertyEvent;
+use crate::error::{IndigoError, Result};
+use crate::types::{Property, PropertyState, PropertyValue};
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Timeout used when neither the caller nor the property specify one.
+pub const DEFAULT_CHANGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The result of a property change, as reported by the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyOutcome {
+    /// The property as last reported by the server.
+    pub property: Property,
+    /// Message sent by the server with the result, if any.
+    pub message: Option<String>,
+}
+
+impl PropertyOutcome {
+    /// Returns the final state of the property.
+    pub fn state(&self) -> PropertyState {
+        self.property.state
+    }
+
+    /// Returns `true` if the server completed the change successfully.
+    pub fn is_ok(&self) -> bool {
+        self.property.state == PropertyState::Ok
+    }
+
+    /// Returns `true` if the server reported a failure.
+    pub fn is_alert(&self) -> bool {
+        self.property.state == PropertyState::Alert
+    }
+}
+
+/// Resolves the timeout for a property change.
+///
+/// A caller-supplied timeout takes precedence over the property's own
+/// `timeout` attribute, falling back to [`DEFAULT_CHANGE_TIMEOUT`].
+pub fn change_timeout(property: &Property, timeout: Option<Duration>) -> Duration {
+    timeout
+        .or_else(|| {
+            property
+                .timeout
+                .filter(|secs| secs.is_finite() && *secs > 0.0)
+                .map(Duration::from_secs_f64)
+        })
+        .unwrap_or(DEFAULT_CHANGE_TIMEOUT)
+}
+
+/// Sends a change request and waits for the property to reach `Ok` or `Alert`.
+///
+/// `events` must be subscribed before calling this. Events already queued
+/// were delivered before the request was sent, so their updates are stale
+/// and ignored. After sending, a `Busy` update acknowledges the request and
+/// the next `Ok` or `Alert` update is its result; a server that completes the
+/// change directly answers with `Ok` or `Alert` as the first update instead.
+/// Messages sent for the device while waiting are used as the outcome message
+/// when the property update itself carries none.
+///
+/// # Errors
+///
+/// - The error of `send`, if sending fails
+/// - [`IndigoError::Timeout`] if no final state is reported within `timeout`
+/// - [`IndigoError::PropertyNotFound`] if the property or device is deleted
+/// - [`IndigoError::ConnectionError`] if the event stream is closed
+pub async fn wait_for_outcome(
+    events: &mut mpsc::UnboundedReceiver<PropertyEvent>,
+    device: &str,
+    name: &str,
+    timeout: Duration,
+    send: impl Future<Output = Result<()>>,
+) -> Result<PropertyOutcome> {
+    wait(events, device, name, timeout, send, None).await
+}
+
+/// Sends a change request and waits until the server has been busy with it.
+///
+/// Like [`wait_for_outcome`], but for long-running operations such as slews,
+/// an `Ok` update only completes the request after a `Busy` update or when
+/// the property already has the requested values. Other `Ok` updates, such
+/// as coordinates republished while tracking, are ignored. An `Alert` update
+/// always completes the request.
+///
+/// # Errors
+///
+/// As [`wait_for_outcome`].
+pub async fn wait_for_completion(
+    events: &mut mpsc::UnboundedReceiver<PropertyEvent>,
+    request: &Property,
+    timeout: Duration,
+    send: impl Future<Output = Result<()>>,
+) -> Result<PropertyOutcome> {
+    let (device, name) = (&request.device, &request.name);
+    wait(events, device, name, timeout, send, Some(request)).await
+}
+
+/// Returns `true` if every item of a request has its value in a property.
+fn has_values(property: &Property, request: &Property) -> bool {
+    request.items.iter().all(|(name, requested)| {
+        property
+            .items
+            .get(name)
+            .is_some_and(|item| match (&item.value, &requested.value) {
+                (PropertyValue::Number { value, .. }, PropertyValue::Number { value: v, .. }) => {
+                    value == v
+                }
+                (value, requested) => value == requested,
+            })
+    })
+}
+
+async fn wait(
+    events: &mut mpsc::UnboundedReceiver<PropertyEvent>,
+    device: &str,
+    name: &str,
+    timeout: Duration,
+    send: impl Future<Output = Result<()>>,
+    request: Option<&Property>,
+) -> Result<PropertyOutcome> {
+    let is_deleted = |event: &PropertyEvent| {
+        matches!(event, PropertyEvent::Deleted {
+            device: deleted_device,
+            name: deleted_name,
+            ..
+        } if deleted_device == device && deleted_name.as_deref().is_none_or(|n| n == name))
+    };
+    let deleted = || {
+        IndigoError::PropertyNotFound(format!(
+            "{}.{} was deleted while waiting for the result",
+            device, name
+        ))
+    };
+
+    while let Ok(event) = events.try_recv() {
+        if is_deleted(&event) {
+            return Err(deleted());
+        }
+    }
+    send.await?;
+
+    let mut acknowledged = false;
+    let mut device_message = None;
+    let wait = async {
+        while let Some(event) = events.recv().await {
+            match event {
+                PropertyEvent::Updated(property)
+                    if property.device == device && property.name == name =>
+                {
+                    let done = match property.state {
+                        PropertyState::Busy => {
+                            acknowledged = true;
+                            false
+                        }
+                        PropertyState::Alert => true,
+                        PropertyState::Ok => {
+                            acknowledged || request.is_none_or(|r| has_values(&property, r))
+                        }
+                        PropertyState::Idle => false,
+                    };
+                    if done {
+                        let message = property.message.clone().or(device_message);
+                        return Ok(PropertyOutcome { property, message });
+                    }
+                }
+                PropertyEvent::Message {
+                    device: Some(message_device),
+                    message,
+                    ..
+                } if message_device == device => {
+                    device_message = Some(message);
+                }
+                event if is_deleted(&event) => return Err(deleted()),
+                _ => {}
+            }
+        }
+        Err(IndigoError::ConnectionError(format!(
+            "Event stream closed while waiting for {}.{}",
+            device, name
+        )))
+    };
+
+    tokio::time::timeout(timeout, wait).await.map_err(|_| {
+        IndigoError::Timeout(format!(
+            "No result for {}.{} within {:?}",
+            device, name, timeout
+        ))
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PropertyItem, PropertyType, SwitchState};
+
+    fn property(state: PropertyState) -> Property {
+        Property::builder()
+            .device("Mount")
+            .name("MOUNT_PARK")
+            .property_type(PropertyType::Switch)
+            .state(state)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_change_timeout_precedence() {
+        let mut prop = property(PropertyState::Idle);
+        assert_eq!(change_timeout(&prop, None), DEFAULT_CHANGE_TIMEOUT);
+
+        prop.timeout = Some(5.0);
+        assert_eq!(change_timeout(&prop, None), Duration::from_secs(5));
+        assert_eq!(
+            change_timeout(&prop, Some(Duration::from_secs(1))),
+            Duration::from_secs(1)
+        );
+    }
+
+    fn message(message: &str) -> PropertyEvent {
+        PropertyEvent::Message {
+            device: Some("Mount".to_string()),
+            message: message.to_string(),
+            timestamp: None,
+        }
+    }
+
+    /// Sends events as the server's response to the request.
+    async fn respond(
+        tx: &mpsc::UnboundedSender<PropertyEvent>,
+        events: Vec<PropertyEvent>,
+    ) -> Result<()> {
+        for event in events {
+            tx.send(event).unwrap();
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_outcome_resolves_on_final_state() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let response = vec![
+            PropertyEvent::Updated(property(PropertyState::Busy)),
+            message("Parked"),
+            PropertyEvent::Updated(property(PropertyState::Ok)),
+        ];
+
+        let outcome = wait_for_outcome(
+            &mut rx,
+            "Mount",
+            "MOUNT_PARK",
+            Duration::from_secs(1),
+            respond(&tx, response),
+        )
+        .await
+        .unwrap();
+        assert!(outcome.is_ok());
+        assert_eq!(outcome.message.as_deref(), Some("Parked"));
+
+        // A server completing the change directly answers without Busy
+        let response = vec![PropertyEvent::Updated(property(PropertyState::Alert))];
+        let outcome = wait_for_outcome(
+            &mut rx,
+            "Mount",
+            "MOUNT_PARK",
+            Duration::from_secs(1),
+            respond(&tx, response),
+        )
+        .await
+        .unwrap();
+        assert!(outcome.is_alert());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_outcome_ignores_stale_updates() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        // Delivered before the request was sent
+        let mut stale = property(PropertyState::Ok);
+        stale.message = Some("Stale".to_string());
+        tx.send(PropertyEvent::Updated(stale)).unwrap();
+
+        let mut result = property(PropertyState::Ok);
+        result.message = Some("Parked".to_string());
+        let response = vec![
+            PropertyEvent::Updated(property(PropertyState::Busy)),
+            PropertyEvent::Updated(result),
+        ];
+        let outcome = wait_for_outcome(
+            &mut rx,
+            "Mount",
+            "MOUNT_PARK",
+            Duration::from_secs(1),
+            respond(&tx, response),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome.message.as_deref(), Some("Parked"));
+    }
+
+    fn parked(state: PropertyState) -> Property {
+        let mut property = property(state);
+        let value = PropertyValue::switch(SwitchState::On);
+        property.items.insert(
+            "PARKED".to_string(),
+            PropertyItem::new("PARKED", "Parked", value),
+        );
+        property
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_completion_requires_busy() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let request = parked(PropertyState::Idle);
+        // Republished while tracking, before the server saw the request
+        let mut stale = property(PropertyState::Ok);
+        stale.message = Some("Tracking".to_string());
+        let mut result = property(PropertyState::Ok);
+        result.message = Some("Slewed".to_string());
+        let response = vec![
+            PropertyEvent::Updated(stale),
+            PropertyEvent::Updated(property(PropertyState::Busy)),
+            PropertyEvent::Updated(result),
+        ];
+
+        let outcome = wait_for_completion(
+            &mut rx,
+            &request,
+            Duration::from_secs(1),
+            respond(&tx, response),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome.message.as_deref(), Some("Slewed"));
+
+        let response = vec![PropertyEvent::Updated(property(PropertyState::Ok))];
+        let result = wait_for_completion(
+            &mut rx,
+            &request,
+            Duration::from_millis(10),
+            respond(&tx, response),
+        )
+        .await;
+        assert!(matches!(result, Err(IndigoError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_completion_without_busy() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let request = parked(PropertyState::Idle);
+
+        // Rejected requests are final
+        let response = vec![PropertyEvent::Updated(property(PropertyState::Alert))];
+        let outcome = wait_for_completion(
+            &mut rx,
+            &request,
+            Duration::from_secs(1),
+            respond(&tx, response),
+        )
+        .await
+        .unwrap();
+        assert!(outcome.is_alert());
+
+        // As are requests for the values the property already has
+        let response = vec![PropertyEvent::Updated(parked(PropertyState::Ok))];
+        let outcome = wait_for_completion(
+            &mut rx,
+            &request,
+            Duration::from_secs(1),
+            respond(&tx, response),
+        )
+        .await
+        .unwrap();
+        assert!(outcome.is_ok());
+    }
+
+    async fn wait(
+        rx: &mut mpsc::UnboundedReceiver<PropertyEvent>,
+        timeout: Duration,
+    ) -> Result<PropertyOutcome> {
+        wait_for_outcome(rx, "Mount", "MOUNT_PARK", timeout, async { Ok(()) }).await
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_outcome_errors() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let result = wait(&mut rx, Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(IndigoError::Timeout(_))));
+
+        tx.send(PropertyEvent::Deleted {
+            device: "Mount".to_string(),
+            name: None,
+            message: None,
+            timestamp: None,
+        })
+        .unwrap();
+        let result = wait(&mut rx, Duration::from_secs(1)).await;
+        assert!(matches!(result, Err(IndigoError::PropertyNotFound(_))));
+
+        let result = wait_for_outcome(
+            &mut rx,
+            "Mount",
+            "MOUNT_PARK",
+            Duration::from_secs(1),
+            async { Err(IndigoError::ConnectionError("Not connected".to_string())) },
+        )
+        .await;
+        assert!(matches!(result, Err(IndigoError::ConnectionError(_))));
+
+        drop(tx);
+        let result = wait(&mut rx, Duration::from_secs(1)).await;
+        assert!(matches!(result, Err(IndigoError::ConnectionError(_))));
+    }
+}
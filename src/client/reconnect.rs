@@ -0,0 +1,182 @@
+//! Automatic reconnection support.
+//!
+//! This module provides [`ReconnectPolicy`], which configures how a client
+//! strategy reconnects after losing its connection to the server, and
+//! [`ConnectionEvent`], which reports connection state changes.
+//!
+//! Reconnection is opt-in: strategies only reconnect when a policy has been
+//! configured with [`ClientBuilder::with_reconnect`](crate::client::ClientBuilder::with_reconnect).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo::client::{ClientBuilder, ConnectionEvent, ReconnectPolicy};
+//! use std::time::Duration;
+//!
+//! let client = ClientBuilder::new()
+//!     .with_strategy(strategy)
+//!     .with_reconnect(
+//!         ReconnectPolicy::default()
+//!             .with_initial_delay(Duration::from_secs(2))
+//!             .with_max_attempts(20),
+//!     )
+//!     .build()?;
+//!
+//! let mut events = client.subscribe_connection_events().await?;
+//! while let Some(event) = events.recv().await {
+//!     if let ConnectionEvent::Reconnecting { attempt, delay } = event {
+//!         println!("Reconnect attempt {} in {:?}", attempt, delay);
+//!     }
+//! }
+//! ```
+
+use rand::Rng;
+use std::time::Duration;
+
+/// Connection state change events.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionEvent {
+    /// Connected to the server.
+    Connected,
+    /// The connection was lost or closed.
+    Disconnected {
+        /// Why the connection ended.
+        reason: String,
+    },
+    /// A reconnection attempt is scheduled.
+    Reconnecting {
+        /// Attempt number, starting at 1.
+        attempt: u32,
+        /// Delay before the attempt is made.
+        delay: Duration,
+    },
+    /// The connection was re-established.
+    Reconnected,
+    /// Reconnection was abandoned after the maximum number of attempts.
+    ReconnectFailed {
+        /// Number of attempts that were made.
+        attempts: u32,
+    },
+}
+
+/// Policy for automatically reconnecting to a server.
+///
+/// Delays grow exponentially from `initial_delay` by `multiplier` per attempt,
+/// are capped at `max_delay`, and are randomized by up to `jitter` (a fraction
+/// of the delay) in either direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Delay before the first attempt. Default: 1 second.
+    pub initial_delay: Duration,
+    /// Upper bound for the delay between attempts. Default: 60 seconds.
+    pub max_delay: Duration,
+    /// Factor the delay grows by after each attempt. Default: 2.0.
+    pub multiplier: f64,
+    /// Random variation as a fraction of the delay, from 0.0 to 1.0. Default: 0.2.
+    pub jitter: f64,
+    /// Maximum number of attempts, or `None` to retry forever. Default: `None`.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Set the delay before the first attempt.
+    pub fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Set the upper bound for the delay between attempts.
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Set the factor the delay grows by after each attempt.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Set the random variation as a fraction of the delay (clamped to 0.0..=1.0).
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the maximum number of attempts.
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Returns `true` if another attempt is allowed after `attempts` attempts.
+    pub fn allows_attempt(&self, attempts: u32) -> bool {
+        self.max_attempts.is_none_or(|max| attempts < max)
+    }
+
+    /// Computes the delay before the given attempt (starting at 1), without jitter.
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        Duration::from_secs_f64(secs.min(self.max_delay.as_secs_f64()))
+    }
+
+    /// Computes the delay before the given attempt (starting at 1), with jitter applied.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let base = self.base_delay(attempt).as_secs_f64();
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return Duration::from_secs_f64(base);
+        }
+        let factor = rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter);
+        Duration::from_secs_f64(base * factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_delay_grows_and_caps() {
+        let policy = ReconnectPolicy::default()
+            .with_initial_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(5));
+
+        assert_eq!(policy.base_delay(1), Duration::from_secs(1));
+        assert_eq!(policy.base_delay(2), Duration::from_secs(2));
+        assert_eq!(policy.base_delay(3), Duration::from_secs(4));
+        assert_eq!(policy.base_delay(4), Duration::from_secs(5));
+        assert_eq!(policy.base_delay(100), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_jitter_stays_in_range() {
+        let policy = ReconnectPolicy::default().with_jitter(0.5);
+        for _ in 0..100 {
+            let delay = policy.delay_for_attempt(1);
+            assert!(delay >= Duration::from_millis(500));
+            assert!(delay <= Duration::from_millis(1500));
+        }
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        let policy = ReconnectPolicy::default().with_max_attempts(3);
+        assert!(policy.allows_attempt(2));
+        assert!(!policy.allows_attempt(3));
+        assert!(ReconnectPolicy::default().allows_attempt(u32::MAX - 1));
+    }
+}
@@ -3,14 +3,19 @@
 //! This module defines the [`ClientStrategy`] trait that abstracts over different
 //! client implementation strategies (FFI-based or pure Rust).
 
-use crate::error::Result;
+use crate::client::events::PropertyEvent;
+use crate::client::outcome::{change_timeout, wait_for_outcome, PropertyOutcome};
+use crate::client::reconnect::{ConnectionEvent, ReconnectPolicy};
+use crate::device::traits::DeviceHandle;
+use crate::device::DeviceInterface;
+use crate::error::{IndigoError, Result};
 use crate::types::{BlobTransferMode, Property};
 use async_trait::async_trait;
+use std::time::Duration;
+use tokio::sync::mpsc;
 
 #[cfg(feature = "monitoring")]
 use crate::client::monitoring::{ClientEvent, MonitoringConfig};
-#[cfg(feature = "monitoring")]
-use tokio::sync::mpsc;
 
 /// Strategy trait for INDIGO client implementations.
 ///
@@ -88,6 +93,57 @@ pub trait ClientStrategy: Send + Sync {
     /// Returns an error if sending fails.
     async fn send_property(&mut self, property: Property) -> Result<()>;
 
+    /// Sends a property change and waits for the server to report the result.
+    ///
+    /// Resolves when the server reports the property as `Ok` or `Alert` in
+    /// response to the request, as described for
+    /// [`wait_for_outcome`](crate::client::outcome::wait_for_outcome).
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - The property to send
+    /// * `timeout` - How long to wait for the result. If `None`, the property's
+    ///   own `timeout` attribute is used, falling back to
+    ///   [`DEFAULT_CHANGE_TIMEOUT`](crate::client::outcome::DEFAULT_CHANGE_TIMEOUT).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if sending fails, if no result is reported before the
+    /// timeout ([`IndigoError::Timeout`]), or if the property is deleted while
+    /// waiting ([`IndigoError::PropertyNotFound`]).
+    ///
+    /// # Default Implementation
+    ///
+    /// The default implementation subscribes to [`subscribe_events`](Self::subscribe_events)
+    /// before calling [`send_property`](Self::send_property), so strategies that
+    /// provide property events support this method without further work.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let outcome = strategy.change_property(park, None).await?;
+    /// println!("{} finished with state {}", outcome.property.name, outcome.state());
+    /// ```
+    async fn change_property(
+        &mut self,
+        property: Property,
+        timeout: Option<Duration>,
+    ) -> Result<PropertyOutcome> {
+        let mut events = self.subscribe_events().await?;
+        let device = property.device.clone();
+        let name = property.name.clone();
+        let timeout = change_timeout(&property, timeout);
+
+        wait_for_outcome(
+            &mut events,
+            &device,
+            &name,
+            timeout,
+            self.send_property(property),
+        )
+        .await
+    }
+
     /// Enables or configures BLOB transfer mode for a device.
     ///
     /// This method sends an `enableBLOB` message to the server to control
@@ -122,6 +178,42 @@ pub trait ClientStrategy: Send + Sync {
         mode: BlobTransferMode,
     ) -> Result<()>;
 
+    /// Sets the reconnect policy for the strategy.
+    ///
+    /// This method is called by the ClientBuilder when reconnection is enabled.
+    /// Strategies that support reconnection should re-establish lost connections
+    /// according to the policy, restoring property enumeration and BLOB modes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy can't be applied.
+    ///
+    /// # Default Implementation
+    ///
+    /// The default implementation returns [`IndigoError::NotSupported`], so
+    /// that a policy is never silently dropped.
+    fn set_reconnect_policy(&mut self, _policy: ReconnectPolicy) -> Result<()> {
+        Err(IndigoError::NotSupported(
+            "Reconnection is not supported by this strategy".to_string(),
+        ))
+    }
+
+    /// Subscribes to connection state events.
+    ///
+    /// Returns a receiver for [`ConnectionEvent`]s reporting connects,
+    /// disconnects and reconnection attempts.
+    ///
+    /// # Default Implementation
+    ///
+    /// The default implementation returns [`IndigoError::NotSupported`].
+    async fn subscribe_connection_events(
+        &self,
+    ) -> Result<mpsc::UnboundedReceiver<ConnectionEvent>> {
+        Err(IndigoError::NotSupported(
+            "Connection events are not supported by this strategy".to_string(),
+        ))
+    }
+
     /// Sets the monitoring configuration for the strategy.
     ///
     /// This method is called by the ClientBuilder when monitoring is enabled.
@@ -161,8 +253,63 @@ pub trait ClientStrategy: Send + Sync {
         None
     }
 
-    // TODO: Phase 2 - Add property stream methods
-    // fn property_stream(&self) -> PropertyStream;
+    /// Subscribes to property events.
+    ///
+    /// Returns a receiver for [`PropertyEvent`]s, distinguishing property
+    /// definitions, updates and deletions, as well as messages sent by the
+    /// server. Each call creates an independent subscriber.
+    ///
+    /// # Default Implementation
+    ///
+    /// The default implementation returns [`IndigoError::NotSupported`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use libindigo::client::PropertyEvent;
+    ///
+    /// let mut events = strategy.subscribe_events().await?;
+    /// while let Some(event) = events.recv().await {
+    ///     if let PropertyEvent::Deleted { device, name, .. } = event {
+    ///         println!("Deleted {:?} from {}", name, device);
+    ///     }
+    /// }
+    /// ```
+    async fn subscribe_events(&self) -> Result<mpsc::UnboundedReceiver<PropertyEvent>> {
+        Err(IndigoError::NotSupported(
+            "Property events are not supported by this strategy".to_string(),
+        ))
+    }
+
+    /// Opens a device as the trait object of one of its interfaces.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::DeviceNotFound`] if the device is not defined,
+    /// or [`IndigoError::InterfaceMismatch`] if it does not implement
+    /// `interface`.
+    ///
+    /// # Default Implementation
+    ///
+    /// The default implementation returns [`IndigoError::NotSupported`].
+    async fn open_device(&self, _device: &str, interface: DeviceInterface) -> Result<DeviceHandle> {
+        Err(IndigoError::NotSupported(format!(
+            "Opening {:?} devices is not supported by this strategy",
+            interface
+        )))
+    }
+
+    /// Opens all devices implementing an interface, sorted by name.
+    ///
+    /// # Default Implementation
+    ///
+    /// The default implementation returns [`IndigoError::NotSupported`].
+    async fn open_devices(&self, interface: DeviceInterface) -> Result<Vec<DeviceHandle>> {
+        Err(IndigoError::NotSupported(format!(
+            "Opening {:?} devices is not supported by this strategy",
+            interface
+        )))
+    }
 
     // TODO: Phase 2 - Add device enumeration
     // async fn enumerate_devices(&mut self) -> Result<Vec<Device>>;
@@ -0,0 +1,404 @@
+//! Driver for a [`Camera`] implementation.
+
+use super::{
+    accept, change_connection, detach_device, driver_info, ensure_connected, number,
+    publish_connection, register_device, register_settings, set_numbers, set_switches, supported,
+    switch_on, PendingSettings, Timer, READINGS_INTERVAL,
+};
+use crate::device::context::DeviceContext;
+use crate::device::driver::{DeviceDriver, DeviceInterface, DriverInfo};
+use crate::device::tasks::TaskId;
+use crate::device::traits::{BinningMode, Camera, ExposureState, FrameType};
+use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{Property, PropertyState, PropertyValue};
+use std::time::Duration;
+
+/// Camera settings, which are persistent.
+const SETTINGS: &[&str] = &[
+    name::CCD_FRAME_PROPERTY,
+    name::CCD_BIN_PROPERTY,
+    name::CCD_FRAME_TYPE_PROPERTY,
+];
+
+/// Longest wait between checks of a running exposure.
+const EXPOSURE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Shortest wait between checks of a running exposure.
+const MIN_EXPOSURE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// [`DeviceDriver`] for a [`Camera`] implementation.
+///
+/// Registers the standard CCD properties for the camera's
+/// [`CcdInfo`](crate::device::traits::CcdInfo). A `CCD_EXPOSURE` change
+/// starts an exposure, which stays `Busy` counting down until the downloaded
+/// image is published in `CCD_IMAGE`. Images are published as FITS unless
+/// another format is set with [`with_image_format`](Self::with_image_format).
+///
+/// On connect, `CCD_TEMPERATURE`, `CCD_COOLER` and `CCD_COOLER_POWER` are
+/// defined if the camera has a cooler, and `CCD_GAIN` and `CCD_OFFSET` if it
+/// supports gain and offset.
+pub struct CameraDriver<T> {
+    camera: T,
+    pending: PendingSettings,
+    image_format: String,
+    /// Next check of the running exposure
+    exposure: Option<TaskId>,
+    /// Publishes the cooler readings while connected
+    readings: Timer,
+    /// Whether the cooler properties are defined
+    cooler: bool,
+    /// Whether the gain and offset properties are defined
+    gain_offset: bool,
+}
+
+impl<T: Camera> CameraDriver<T> {
+    /// Create a driver for a camera
+    pub fn new(camera: T) -> Self {
+        Self {
+            camera,
+            pending: PendingSettings::default(),
+            image_format: ".fits".to_string(),
+            exposure: None,
+            readings: Timer::default(),
+            cooler: false,
+            gain_offset: false,
+        }
+    }
+
+    /// Set the format of the images the camera downloads, such as `".raw"`
+    pub fn with_image_format(mut self, format: impl Into<String>) -> Self {
+        self.image_format = format.into();
+        self
+    }
+
+    /// Get the wrapped camera
+    pub fn inner(&self) -> &T {
+        &self.camera
+    }
+
+    /// Get the wrapped camera mutably
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.camera
+    }
+
+    /// Publish the time left of a running exposure and the cooler readings.
+    ///
+    /// Runs periodically while the camera is connected, and does nothing
+    /// while it is disconnected.
+    pub async fn refresh(&self, ctx: &mut DeviceContext) -> Result<()> {
+        if !ctx.is_connected() {
+            return Ok(());
+        }
+        if let ExposureState::Exposing(remaining) = self.camera.exposure_state().await? {
+            set_numbers(
+                ctx,
+                name::CCD_EXPOSURE_PROPERTY,
+                PropertyState::Busy,
+                &[(name::CCD_EXPOSURE_ITEM, remaining)],
+            )?;
+        }
+        if self.cooler {
+            let temperature = self.camera.temperature().await?;
+            set_numbers(
+                ctx,
+                name::CCD_TEMPERATURE_PROPERTY,
+                PropertyState::Ok,
+                &[(name::CCD_TEMPERATURE_ITEM, temperature)],
+            )?;
+            let power = self.camera.cooler_power().await?;
+            set_numbers(
+                ctx,
+                name::CCD_COOLER_POWER_PROPERTY,
+                PropertyState::Ok,
+                &[(name::CCD_COOLER_POWER_ITEM, power)],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Defines the properties of the optional features the camera supports.
+    async fn define_features(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        if let Some(enabled) = supported(self.camera.cooler_enabled().await)? {
+            ctx.property_manager().register_ccd_cooler()?;
+            self.cooler = true;
+            set_switches(
+                ctx,
+                name::CCD_COOLER_PROPERTY,
+                PropertyState::Ok,
+                &[
+                    (name::CCD_COOLER_ON_ITEM, enabled),
+                    (name::CCD_COOLER_OFF_ITEM, !enabled),
+                ],
+            )?;
+        }
+        if let Some(gain) = supported(self.camera.gain().await)? {
+            let offset = self.camera.offset().await?;
+            ctx.property_manager().register_ccd_gain_offset()?;
+            self.gain_offset = true;
+            set_numbers(
+                ctx,
+                name::CCD_GAIN_PROPERTY,
+                PropertyState::Ok,
+                &[(name::CCD_GAIN_ITEM, gain)],
+            )?;
+            set_numbers(
+                ctx,
+                name::CCD_OFFSET_PROPERTY,
+                PropertyState::Ok,
+                &[(name::CCD_OFFSET_ITEM, offset)],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the properties of the optional features.
+    fn delete_features(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        let manager = ctx.property_manager();
+        if std::mem::take(&mut self.cooler) {
+            manager.delete_property(name::CCD_TEMPERATURE_PROPERTY)?;
+            manager.delete_property(name::CCD_COOLER_PROPERTY)?;
+            manager.delete_property(name::CCD_COOLER_POWER_PROPERTY)?;
+        }
+        if std::mem::take(&mut self.gain_offset) {
+            manager.delete_property(name::CCD_GAIN_PROPERTY)?;
+            manager.delete_property(name::CCD_OFFSET_PROPERTY)?;
+        }
+        Ok(())
+    }
+
+    /// Starts an exposure, which completes in [`check_exposure`](Self::check_exposure).
+    async fn expose(&mut self, ctx: &mut DeviceContext, duration: f64) -> Result<()> {
+        if self.exposure.is_some() {
+            return Err(IndigoError::InvalidState(
+                "An exposure is already running".to_string(),
+            ));
+        }
+        set_numbers(
+            ctx,
+            name::CCD_EXPOSURE_PROPERTY,
+            PropertyState::Busy,
+            &[(name::CCD_EXPOSURE_ITEM, duration)],
+        )?;
+        self.camera.start_exposure(duration).await?;
+        self.check_exposure(ctx).await
+    }
+
+    /// Checks the running exposure again after the time left, or
+    /// [`EXPOSURE_POLL_INTERVAL`] if that is shorter.
+    fn schedule_check(&mut self, ctx: &mut DeviceContext, remaining: f64) -> Result<()> {
+        let wait = Duration::from_secs_f64(remaining.max(0.0))
+            .clamp(MIN_EXPOSURE_POLL_INTERVAL, EXPOSURE_POLL_INTERVAL);
+        self.exposure = Some(ctx.set_timer(wait)?);
+        Ok(())
+    }
+
+    /// Counts down the running exposure, publishing the image once it
+    /// completes.
+    async fn check_exposure(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        self.exposure = None;
+        let result = match self.camera.exposure_state().await? {
+            ExposureState::Exposing(remaining) => {
+                set_numbers(
+                    ctx,
+                    name::CCD_EXPOSURE_PROPERTY,
+                    PropertyState::Busy,
+                    &[(name::CCD_EXPOSURE_ITEM, remaining)],
+                )?;
+                return self.schedule_check(ctx, remaining);
+            }
+            ExposureState::Idle | ExposureState::Complete => self.publish_image(ctx).await,
+            ExposureState::Aborted => {
+                Err(IndigoError::InvalidState("Exposure aborted".to_string()))
+            }
+            ExposureState::Error => Err(IndigoError::InvalidState("Exposure failed".to_string())),
+        };
+        if let Err(e) = &result {
+            ctx.property_manager()
+                .alert_property(name::CCD_EXPOSURE_PROPERTY, e.to_string())?;
+        }
+        result
+    }
+
+    /// Downloads the image of a complete exposure and publishes it.
+    async fn publish_image(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        let image = self.camera.download_image().await?;
+        ctx.property_manager().update_property(
+            name::CCD_IMAGE_PROPERTY,
+            PropertyState::Ok,
+            vec![(
+                name::CCD_IMAGE_ITEM.to_string(),
+                PropertyValue::blob(image, self.image_format.clone()),
+            )],
+        )?;
+        set_numbers(
+            ctx,
+            name::CCD_EXPOSURE_PROPERTY,
+            PropertyState::Ok,
+            &[(name::CCD_EXPOSURE_ITEM, 0.0)],
+        )
+    }
+
+    /// Stops the running exposure.
+    async fn abort(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        self.camera.abort_exposure().await?;
+        if let Some(task) = self.exposure.take() {
+            ctx.cancel_task(task);
+        }
+        set_switches(
+            ctx,
+            name::CCD_ABORT_EXPOSURE_PROPERTY,
+            PropertyState::Ok,
+            &[(name::CCD_ABORT_EXPOSURE_ITEM, false)],
+        )?;
+        set_numbers(
+            ctx,
+            name::CCD_EXPOSURE_PROPERTY,
+            PropertyState::Alert,
+            &[(name::CCD_EXPOSURE_ITEM, 0.0)],
+        )
+    }
+
+    /// Applies a change of a cooler, gain or offset property.
+    async fn change_feature(&mut self, ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+        match property.name.as_str() {
+            name::CCD_TEMPERATURE_PROPERTY => {
+                let target = number(property, name::CCD_TEMPERATURE_ITEM)?;
+                self.camera.set_temperature(target).await?;
+                let temperature = self.camera.temperature().await?;
+                set_numbers(
+                    ctx,
+                    name::CCD_TEMPERATURE_PROPERTY,
+                    PropertyState::Ok,
+                    &[(name::CCD_TEMPERATURE_ITEM, temperature)],
+                )
+            }
+            name::CCD_COOLER_PROPERTY => {
+                let enabled = switch_on(property, name::CCD_COOLER_ON_ITEM);
+                self.camera.set_cooler(enabled).await?;
+                accept(ctx, property)
+            }
+            name::CCD_GAIN_PROPERTY => {
+                let gain = number(property, name::CCD_GAIN_ITEM)?;
+                self.camera.set_gain(gain).await?;
+                accept(ctx, property)
+            }
+            name::CCD_OFFSET_PROPERTY => {
+                let offset = number(property, name::CCD_OFFSET_ITEM)?;
+                self.camera.set_offset(offset).await?;
+                accept(ctx, property)
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+
+    /// Applies a change of a setting to the camera.
+    async fn change_setting(&mut self, ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+        match property.name.as_str() {
+            name::CCD_FRAME_PROPERTY => {
+                self.camera
+                    .set_frame(
+                        number(property, name::CCD_FRAME_LEFT_ITEM)? as u32,
+                        number(property, name::CCD_FRAME_TOP_ITEM)? as u32,
+                        number(property, name::CCD_FRAME_WIDTH_ITEM)? as u32,
+                        number(property, name::CCD_FRAME_HEIGHT_ITEM)? as u32,
+                    )
+                    .await?;
+                accept(ctx, property)
+            }
+            name::CCD_BIN_PROPERTY => {
+                self.camera
+                    .set_binning(BinningMode::new(
+                        number(property, name::CCD_BIN_HORIZONTAL_ITEM)? as u32,
+                        number(property, name::CCD_BIN_VERTICAL_ITEM)? as u32,
+                    ))
+                    .await?;
+                accept(ctx, property)
+            }
+            name::CCD_FRAME_TYPE_PROPERTY => {
+                self.camera.set_frame_type(frame_type(property)?).await?;
+                accept(ctx, property)
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+}
+
+/// Gets the frame type selected in `CCD_FRAME_TYPE`.
+fn frame_type(property: &Property) -> Result<FrameType> {
+    [
+        (name::CCD_FRAME_TYPE_LIGHT_ITEM, FrameType::Light),
+        (name::CCD_FRAME_TYPE_BIAS_ITEM, FrameType::Bias),
+        (name::CCD_FRAME_TYPE_DARK_ITEM, FrameType::Dark),
+        (name::CCD_FRAME_TYPE_FLAT_ITEM, FrameType::Flat),
+    ]
+    .into_iter()
+    .find(|(item, _)| switch_on(property, item))
+    .map(|(_, frame_type)| frame_type)
+    .ok_or_else(|| IndigoError::NotSupported("Frame type is not supported".to_string()))
+}
+
+#[async_trait::async_trait]
+impl<T: Camera> DeviceDriver for CameraDriver<T> {
+    fn info(&self) -> DriverInfo {
+        driver_info(&self.camera, DeviceInterface::Ccd)
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        register_device(ctx, &self.info())?;
+        let info = self.camera.ccd_info().await?;
+        ctx.property_manager().register_ccd_standard(&info)?;
+        register_settings(ctx, SETTINGS)
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        if property.name == name::CONNECTION_PROPERTY {
+            let connected = change_connection(&mut self.camera, ctx, property).await?;
+            if connected {
+                for setting in self.pending.take() {
+                    self.change_setting(ctx, &setting).await?;
+                }
+                self.define_features(ctx).await?;
+                self.refresh(ctx).await?;
+                self.readings.start(ctx, READINGS_INTERVAL)?;
+            } else {
+                self.exposure = None;
+                self.delete_features(ctx)?;
+            }
+            return publish_connection(ctx, connected);
+        }
+        if SETTINGS.contains(&property.name.as_str()) {
+            if !ctx.is_connected() {
+                return self.pending.store(ctx, property);
+            }
+            return self.change_setting(ctx, property).await;
+        }
+        ensure_connected(ctx, property)?;
+
+        match property.name.as_str() {
+            name::CCD_EXPOSURE_PROPERTY => {
+                let duration = number(property, name::CCD_EXPOSURE_ITEM)?;
+                self.expose(ctx, duration).await
+            }
+            name::CCD_ABORT_EXPOSURE_PROPERTY => self.abort(ctx).await,
+            _ => self.change_feature(ctx, property).await,
+        }
+    }
+
+    async fn on_task(&mut self, ctx: &mut DeviceContext, task: TaskId) -> Result<()> {
+        if self.exposure == Some(task) {
+            self.check_exposure(ctx).await
+        } else if self.readings.is(task) {
+            self.refresh(ctx).await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        detach_device(&mut self.camera, ctx).await
+    }
+}
@@ -0,0 +1,188 @@
+//! Driver for a [`FilterWheel`] implementation.
+
+use super::{
+    accept, change_connection, detach_device, driver_info, ensure_connected, number,
+    publish_connection, register_device, register_settings, set_numbers, PendingSettings, Timer,
+    MOTION_INTERVAL,
+};
+use crate::device::context::DeviceContext;
+use crate::device::driver::{DeviceDriver, DeviceInterface, DriverInfo};
+use crate::device::tasks::TaskId;
+use crate::device::templates::slot_item;
+use crate::device::traits::FilterWheel;
+use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{Property, PropertyState, PropertyValue};
+
+/// Filter wheel settings, which are persistent.
+const SETTINGS: &[&str] = &[
+    name::WHEEL_SLOT_NAME_PROPERTY,
+    name::WHEEL_SLOT_OFFSET_PROPERTY,
+];
+
+/// [`DeviceDriver`] for a [`FilterWheel`] implementation.
+///
+/// Registers the standard filter wheel properties for the wheel's filters,
+/// moves the wheel on `WHEEL_SLOT` changes, which stay `Busy` until the wheel
+/// stops, and renames filters and sets their offsets on `WHEEL_SLOT_NAME` and
+/// `WHEEL_SLOT_OFFSET` changes.
+pub struct FilterWheelDriver<T> {
+    wheel: T,
+    pending: PendingSettings,
+    slots: u32,
+    /// Checks the wheel while it moves
+    motion: Timer,
+}
+
+impl<T: FilterWheel> FilterWheelDriver<T> {
+    /// Create a driver for a filter wheel
+    pub fn new(wheel: T) -> Self {
+        Self {
+            wheel,
+            pending: PendingSettings::default(),
+            slots: 0,
+            motion: Timer::default(),
+        }
+    }
+
+    /// Get the wrapped filter wheel
+    pub fn inner(&self) -> &T {
+        &self.wheel
+    }
+
+    /// Get the wrapped filter wheel mutably
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.wheel
+    }
+
+    /// Publish the current slot of the wheel.
+    ///
+    /// Does nothing while the wheel is disconnected.
+    pub async fn refresh(&self, ctx: &mut DeviceContext) -> Result<()> {
+        if !ctx.is_connected() {
+            return Ok(());
+        }
+        let state = if self.wheel.is_moving().await? {
+            PropertyState::Busy
+        } else {
+            PropertyState::Ok
+        };
+        let slot = self.wheel.current_slot().await?;
+        set_numbers(
+            ctx,
+            name::WHEEL_SLOT_PROPERTY,
+            state,
+            &[(name::WHEEL_SLOT_ITEM, slot as f64)],
+        )
+    }
+
+    /// Publishes the slot of a moving wheel, until it stops.
+    async fn check_motion(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        self.refresh(ctx).await?;
+        if !self.wheel.is_moving().await? {
+            self.motion.stop(ctx);
+        }
+        Ok(())
+    }
+
+    /// Gets the per-slot items of a request with their slots.
+    fn slot_values<'a>(
+        &self,
+        property: &'a Property,
+        pattern: &str,
+    ) -> Vec<(u32, &'a PropertyValue)> {
+        (1..=self.slots)
+            .filter_map(|slot| Some((slot, &property.items.get(&slot_item(pattern, slot))?.value)))
+            .collect()
+    }
+
+    /// Applies a change of a setting to the filter wheel.
+    async fn change_setting(&mut self, ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+        match property.name.as_str() {
+            name::WHEEL_SLOT_NAME_PROPERTY => {
+                for (slot, value) in self.slot_values(property, name::WHEEL_SLOT_NAME_ITEM) {
+                    if let PropertyValue::Text(filter) = value {
+                        self.wheel.set_filter_name(slot, filter).await?;
+                    }
+                }
+                accept(ctx, property)
+            }
+            name::WHEEL_SLOT_OFFSET_PROPERTY => {
+                for (slot, value) in self.slot_values(property, name::WHEEL_SLOT_OFFSET_ITEM) {
+                    if let PropertyValue::Number { value, .. } = value {
+                        self.wheel.set_filter_offset(slot, *value as i32).await?;
+                    }
+                }
+                accept(ctx, property)
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: FilterWheel> DeviceDriver for FilterWheelDriver<T> {
+    fn info(&self) -> DriverInfo {
+        driver_info(&self.wheel, DeviceInterface::FilterWheel)
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        register_device(ctx, &self.info())?;
+        let filters = self.wheel.filters().await?;
+        self.slots = filters.len() as u32;
+        ctx.property_manager().register_wheel_standard(&filters)?;
+        register_settings(ctx, SETTINGS)
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        if property.name == name::CONNECTION_PROPERTY {
+            let connected = change_connection(&mut self.wheel, ctx, property).await?;
+            if connected {
+                for setting in self.pending.take() {
+                    self.change_setting(ctx, &setting).await?;
+                }
+                self.refresh(ctx).await?;
+            }
+            return publish_connection(ctx, connected);
+        }
+        if SETTINGS.contains(&property.name.as_str()) {
+            if !ctx.is_connected() {
+                return self.pending.store(ctx, property);
+            }
+            return self.change_setting(ctx, property).await;
+        }
+        ensure_connected(ctx, property)?;
+
+        match property.name.as_str() {
+            name::WHEEL_SLOT_PROPERTY => {
+                let slot = number(property, name::WHEEL_SLOT_ITEM)?;
+                set_numbers(
+                    ctx,
+                    name::WHEEL_SLOT_PROPERTY,
+                    PropertyState::Busy,
+                    &[(name::WHEEL_SLOT_ITEM, slot)],
+                )?;
+                self.wheel.select_filter(slot as u32).await?;
+                self.motion.start(ctx, MOTION_INTERVAL)?;
+                self.check_motion(ctx).await
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+
+    async fn on_task(&mut self, ctx: &mut DeviceContext, task: TaskId) -> Result<()> {
+        if self.motion.is(task) {
+            self.check_motion(ctx).await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        detach_device(&mut self.wheel, ctx).await
+    }
+}
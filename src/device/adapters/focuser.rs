@@ -0,0 +1,359 @@
+//! Driver for a [`Focuser`] implementation.
+
+use super::{
+    accept, change_connection, current_switch_on, detach_device, driver_info, ensure_connected,
+    number, number_limits, publish_connection, register_device, register_settings, set_numbers,
+    set_switches, switch_on, PendingSettings, Timer, MOTION_INTERVAL, READINGS_INTERVAL,
+};
+use crate::device::context::DeviceContext;
+use crate::device::driver::{DeviceDriver, DeviceInterface, DriverInfo};
+use crate::device::tasks::TaskId;
+use crate::device::traits::Focuser;
+use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{Property, PropertyState};
+
+/// Focuser settings, which are persistent.
+const SETTINGS: &[&str] = &[
+    name::FOCUSER_SPEED_PROPERTY,
+    name::FOCUSER_DIRECTION_PROPERTY,
+    name::FOCUSER_BACKLASH_PROPERTY,
+    name::FOCUSER_MODE_PROPERTY,
+    name::FOCUSER_COMPENSATION_PROPERTY,
+    name::FOCUSER_LIMITS_PROPERTY,
+];
+
+/// [`DeviceDriver`] for a [`Focuser`] implementation.
+///
+/// Registers the standard focuser properties for the focuser's
+/// [`FocuserInfo`](crate::device::traits::FocuserInfo) and moves the focuser
+/// within `FOCUSER_LIMITS` on `FOCUSER_POSITION` and `FOCUSER_STEPS` changes,
+/// which stay `Busy` until the focuser stops. `FOCUSER_MODE` switches
+/// temperature compensation; the `FOCUSER_COMPENSATION` coefficient is kept
+/// as a setting only.
+pub struct FocuserDriver<T> {
+    focuser: T,
+    pending: PendingSettings,
+    /// Checks the focuser while it moves
+    motion: Timer,
+    /// Publishes the readings while connected
+    readings: Timer,
+    /// Property of the move in progress
+    moving: Option<&'static str>,
+}
+
+impl<T: Focuser> FocuserDriver<T> {
+    /// Create a driver for a focuser
+    pub fn new(focuser: T) -> Self {
+        Self {
+            focuser,
+            pending: PendingSettings::default(),
+            motion: Timer::default(),
+            readings: Timer::default(),
+            moving: None,
+        }
+    }
+
+    /// Get the wrapped focuser
+    pub fn inner(&self) -> &T {
+        &self.focuser
+    }
+
+    /// Get the wrapped focuser mutably
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.focuser
+    }
+
+    /// Publish the position, motion and temperature of the focuser.
+    ///
+    /// Runs periodically while the focuser is connected, and does nothing
+    /// while it is disconnected.
+    pub async fn refresh(&self, ctx: &mut DeviceContext) -> Result<()> {
+        if !ctx.is_connected() {
+            return Ok(());
+        }
+        if ctx
+            .properties()
+            .has_property(name::FOCUSER_POSITION_PROPERTY)
+        {
+            let state = if self.focuser.is_moving().await? {
+                PropertyState::Busy
+            } else {
+                PropertyState::Ok
+            };
+            let position = self.focuser.position().await?;
+            set_numbers(
+                ctx,
+                name::FOCUSER_POSITION_PROPERTY,
+                state,
+                &[(name::FOCUSER_POSITION_ITEM, position as f64)],
+            )?;
+        }
+        if ctx
+            .properties()
+            .has_property(name::FOCUSER_TEMPERATURE_PROPERTY)
+        {
+            let temperature = self.focuser.temperature().await?;
+            set_numbers(
+                ctx,
+                name::FOCUSER_TEMPERATURE_PROPERTY,
+                PropertyState::Ok,
+                &[(name::FOCUSER_TEMPERATURE_ITEM, temperature)],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Starts a move, which completes once the focuser stops.
+    async fn start_move(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &'static str,
+        item: &str,
+        value: f64,
+        target: i64,
+    ) -> Result<()> {
+        check_limits(ctx, target)?;
+        set_numbers(ctx, property, PropertyState::Busy, &[(item, value)])?;
+        let position = self.focuser.position().await? as i64;
+        if property == name::FOCUSER_STEPS_PROPERTY {
+            self.focuser
+                .move_relative((target - position) as i32)
+                .await?;
+        } else {
+            self.focuser.move_to(target as u32).await?;
+        }
+        self.moving = Some(property);
+        self.motion.start(ctx, MOTION_INTERVAL)?;
+        self.check_motion(ctx).await
+    }
+
+    /// Publishes the position of a moving focuser, completing the move once
+    /// it stops.
+    async fn check_motion(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        self.refresh(ctx).await?;
+        if self.focuser.is_moving().await? {
+            return Ok(());
+        }
+        self.motion.stop(ctx);
+        if let Some(property) = self.moving.take() {
+            if property == name::FOCUSER_STEPS_PROPERTY {
+                let steps = ctx
+                    .properties()
+                    .get_property(property)
+                    .map_or(Ok(0.0), |p| number(p, name::FOCUSER_STEPS_ITEM))?;
+                set_numbers(
+                    ctx,
+                    property,
+                    PropertyState::Ok,
+                    &[(name::FOCUSER_STEPS_ITEM, steps)],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Publishes the settings of a newly connected focuser.
+    async fn publish_settings(&self, ctx: &mut DeviceContext) -> Result<()> {
+        let (min, max) =
+            number_limits(ctx, name::FOCUSER_SPEED_PROPERTY, name::FOCUSER_SPEED_ITEM)?;
+        let speed = min + self.focuser.speed().await? * (max - min);
+        set_numbers(
+            ctx,
+            name::FOCUSER_SPEED_PROPERTY,
+            PropertyState::Ok,
+            &[(name::FOCUSER_SPEED_ITEM, speed.round())],
+        )?;
+        let backlash = self.focuser.backlash().await?;
+        set_numbers(
+            ctx,
+            name::FOCUSER_BACKLASH_PROPERTY,
+            PropertyState::Ok,
+            &[(name::FOCUSER_BACKLASH_ITEM, backlash as f64)],
+        )?;
+        if ctx.properties().has_property(name::FOCUSER_MODE_PROPERTY) {
+            let automatic = self.focuser.temperature_compensation().await?;
+            set_switches(
+                ctx,
+                name::FOCUSER_MODE_PROPERTY,
+                PropertyState::Ok,
+                &[
+                    (name::FOCUSER_MODE_AUTOMATIC_ITEM, automatic),
+                    (name::FOCUSER_MODE_MANUAL_ITEM, !automatic),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Applies a change of a setting to the focuser.
+    async fn change_setting(&mut self, ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+        match property.name.as_str() {
+            name::FOCUSER_SPEED_PROPERTY => {
+                let (min, max) =
+                    number_limits(ctx, name::FOCUSER_SPEED_PROPERTY, name::FOCUSER_SPEED_ITEM)?;
+                let speed = number(property, name::FOCUSER_SPEED_ITEM)?;
+                let fraction = if max > min {
+                    (speed - min) / (max - min)
+                } else {
+                    1.0
+                };
+                self.focuser.set_speed(fraction).await?;
+                accept(ctx, property)
+            }
+            name::FOCUSER_BACKLASH_PROPERTY => {
+                let backlash = number(property, name::FOCUSER_BACKLASH_ITEM)?;
+                self.focuser.set_backlash(backlash as u32).await?;
+                accept(ctx, property)
+            }
+            name::FOCUSER_MODE_PROPERTY => {
+                let automatic = switch_on(property, name::FOCUSER_MODE_AUTOMATIC_ITEM);
+                self.focuser.set_temperature_compensation(automatic).await?;
+                accept(ctx, property)
+            }
+            name::FOCUSER_LIMITS_PROPERTY => {
+                let min = number(property, name::FOCUSER_LIMITS_MIN_POSITION_ITEM)?;
+                let max = number(property, name::FOCUSER_LIMITS_MAX_POSITION_ITEM)?;
+                if min > max {
+                    return Err(IndigoError::InvalidParameter(format!(
+                        "Minimum position {} is above the maximum {}",
+                        min, max
+                    )));
+                }
+                accept(ctx, property)
+            }
+            name::FOCUSER_DIRECTION_PROPERTY | name::FOCUSER_COMPENSATION_PROPERTY => {
+                accept(ctx, property)
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Focuser> DeviceDriver for FocuserDriver<T> {
+    fn info(&self) -> DriverInfo {
+        driver_info(&self.focuser, DeviceInterface::Focuser)
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        register_device(ctx, &self.info())?;
+        let info = self.focuser.focuser_info().await?;
+        ctx.property_manager().register_focuser_standard(&info)?;
+        register_settings(ctx, SETTINGS)
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        if property.name == name::CONNECTION_PROPERTY {
+            let connected = change_connection(&mut self.focuser, ctx, property).await?;
+            if connected {
+                for setting in self.pending.take() {
+                    self.change_setting(ctx, &setting).await?;
+                }
+                self.publish_settings(ctx).await?;
+                self.refresh(ctx).await?;
+                self.readings.start(ctx, READINGS_INTERVAL)?;
+            } else {
+                self.moving = None;
+            }
+            return publish_connection(ctx, connected);
+        }
+        if SETTINGS.contains(&property.name.as_str()) {
+            if !ctx.is_connected() {
+                return self.pending.store(ctx, property);
+            }
+            return self.change_setting(ctx, property).await;
+        }
+        ensure_connected(ctx, property)?;
+
+        match property.name.as_str() {
+            name::FOCUSER_POSITION_PROPERTY => {
+                let target = number(property, name::FOCUSER_POSITION_ITEM)?;
+                self.start_move(
+                    ctx,
+                    name::FOCUSER_POSITION_PROPERTY,
+                    name::FOCUSER_POSITION_ITEM,
+                    target,
+                    target as i64,
+                )
+                .await
+            }
+            name::FOCUSER_STEPS_PROPERTY => {
+                let steps = number(property, name::FOCUSER_STEPS_ITEM)?;
+                let outward = current_switch_on(
+                    ctx,
+                    name::FOCUSER_DIRECTION_PROPERTY,
+                    name::FOCUSER_DIRECTION_MOVE_OUTWARD_ITEM,
+                );
+                let position = self.focuser.position().await? as i64;
+                let target = if outward {
+                    position + steps as i64
+                } else {
+                    position - steps as i64
+                };
+                self.start_move(
+                    ctx,
+                    name::FOCUSER_STEPS_PROPERTY,
+                    name::FOCUSER_STEPS_ITEM,
+                    steps,
+                    target,
+                )
+                .await
+            }
+            name::FOCUSER_ABORT_MOTION_PROPERTY => {
+                self.focuser.abort_move().await?;
+                self.motion.stop(ctx);
+                set_switches(
+                    ctx,
+                    name::FOCUSER_ABORT_MOTION_PROPERTY,
+                    PropertyState::Ok,
+                    &[(name::FOCUSER_ABORT_MOTION_ITEM, false)],
+                )?;
+                self.refresh(ctx).await?;
+                match self.moving.take() {
+                    Some(property) => ctx
+                        .property_manager()
+                        .alert_property(property, "Motion aborted"),
+                    None => Ok(()),
+                }
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+
+    async fn on_task(&mut self, ctx: &mut DeviceContext, task: TaskId) -> Result<()> {
+        if self.motion.is(task) {
+            self.check_motion(ctx).await
+        } else if self.readings.is(task) {
+            self.refresh(ctx).await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        detach_device(&mut self.focuser, ctx).await
+    }
+}
+
+/// Fails unless a position is within `FOCUSER_LIMITS`, if the focuser has
+/// limits.
+fn check_limits(ctx: &DeviceContext, position: i64) -> Result<()> {
+    let Some(limits) = ctx.properties().get_property(name::FOCUSER_LIMITS_PROPERTY) else {
+        return Ok(());
+    };
+    let min = number(limits, name::FOCUSER_LIMITS_MIN_POSITION_ITEM)?;
+    let max = number(limits, name::FOCUSER_LIMITS_MAX_POSITION_ITEM)?;
+    if (min..=max).contains(&(position as f64)) {
+        Ok(())
+    } else {
+        Err(IndigoError::InvalidParameter(format!(
+            "Position {} is outside the limits {} to {}",
+            position, min, max
+        )))
+    }
+}
@@ -0,0 +1,178 @@
+//! Driver for a [`Guider`] implementation.
+
+use super::{
+    accept, change_connection, detach_device, driver_info, ensure_connected, number,
+    publish_connection, register_device, register_settings, set_numbers, PendingSettings,
+};
+use crate::device::context::DeviceContext;
+use crate::device::driver::{DeviceDriver, DeviceInterface, DriverInfo};
+use crate::device::traits::{GuideDirection, GuidePulse, Guider};
+use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{Property, PropertyState};
+
+/// Guider settings, which are persistent.
+const SETTINGS: &[&str] = &[name::GUIDER_RATE_PROPERTY];
+
+/// [`DeviceDriver`] for a [`Guider`] implementation.
+///
+/// Registers the standard guider properties and sends a guide pulse for each
+/// non-zero duration of a `GUIDER_GUIDE_DEC` or `GUIDER_GUIDE_RA` change.
+/// `GUIDER_RATE` is in percent of the sidereal rate.
+pub struct GuiderDriver<T> {
+    guider: T,
+    pending: PendingSettings,
+}
+
+impl<T: Guider> GuiderDriver<T> {
+    /// Create a driver for a guider
+    pub fn new(guider: T) -> Self {
+        Self {
+            guider,
+            pending: PendingSettings::default(),
+        }
+    }
+
+    /// Get the wrapped guider
+    pub fn inner(&self) -> &T {
+        &self.guider
+    }
+
+    /// Get the wrapped guider mutably
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.guider
+    }
+
+    /// Publish the guide rates of the guider.
+    ///
+    /// Does nothing while the guider is disconnected.
+    pub async fn refresh(&self, ctx: &mut DeviceContext) -> Result<()> {
+        if !ctx.is_connected() {
+            return Ok(());
+        }
+        let (ra_rate, dec_rate) = self.guider.guide_rate().await?;
+        set_numbers(
+            ctx,
+            name::GUIDER_RATE_PROPERTY,
+            PropertyState::Ok,
+            &[
+                (name::GUIDER_RATE_ITEM, ra_rate * 100.0),
+                (name::GUIDER_DEC_RATE_ITEM, dec_rate * 100.0),
+            ],
+        )
+    }
+
+    /// Sends the guide pulses of a guide property, then clears it.
+    async fn guide(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+        directions: [(&str, GuideDirection); 2],
+    ) -> Result<()> {
+        let mut pulses = Vec::new();
+        for (item, direction) in directions {
+            let duration = number(property, item)?;
+            if duration > 0.0 {
+                pulses.push(GuidePulse::new(direction, duration as u32));
+            }
+        }
+
+        set_numbers(
+            ctx,
+            &property.name,
+            PropertyState::Busy,
+            &directions.map(|(item, _)| (item, number(property, item).unwrap_or_default())),
+        )?;
+        for pulse in pulses {
+            self.guider.guide(pulse).await?;
+        }
+        set_numbers(
+            ctx,
+            &property.name,
+            PropertyState::Ok,
+            &directions.map(|(item, _)| (item, 0.0)),
+        )
+    }
+
+    /// Applies a change of a setting to the guider.
+    async fn change_setting(&mut self, ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+        match property.name.as_str() {
+            name::GUIDER_RATE_PROPERTY => {
+                let ra_rate = number(property, name::GUIDER_RATE_ITEM)?;
+                let dec_rate = number(property, name::GUIDER_DEC_RATE_ITEM)?;
+                self.guider
+                    .set_guide_rate(ra_rate / 100.0, dec_rate / 100.0)
+                    .await?;
+                accept(ctx, property)
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Guider> DeviceDriver for GuiderDriver<T> {
+    fn info(&self) -> DriverInfo {
+        driver_info(&self.guider, DeviceInterface::Guider)
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        register_device(ctx, &self.info())?;
+        ctx.property_manager().register_guider_standard()?;
+        register_settings(ctx, SETTINGS)
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        if property.name == name::CONNECTION_PROPERTY {
+            let connected = change_connection(&mut self.guider, ctx, property).await?;
+            if connected {
+                for setting in self.pending.take() {
+                    self.change_setting(ctx, &setting).await?;
+                }
+                self.refresh(ctx).await?;
+            }
+            return publish_connection(ctx, connected);
+        }
+        if SETTINGS.contains(&property.name.as_str()) {
+            if !ctx.is_connected() {
+                return self.pending.store(ctx, property);
+            }
+            return self.change_setting(ctx, property).await;
+        }
+        ensure_connected(ctx, property)?;
+
+        match property.name.as_str() {
+            name::GUIDER_GUIDE_DEC_PROPERTY => {
+                self.guide(
+                    ctx,
+                    property,
+                    [
+                        (name::GUIDER_GUIDE_NORTH_ITEM, GuideDirection::North),
+                        (name::GUIDER_GUIDE_SOUTH_ITEM, GuideDirection::South),
+                    ],
+                )
+                .await
+            }
+            name::GUIDER_GUIDE_RA_PROPERTY => {
+                self.guide(
+                    ctx,
+                    property,
+                    [
+                        (name::GUIDER_GUIDE_EAST_ITEM, GuideDirection::East),
+                        (name::GUIDER_GUIDE_WEST_ITEM, GuideDirection::West),
+                    ],
+                )
+                .await
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        detach_device(&mut self.guider, ctx).await
+    }
+}
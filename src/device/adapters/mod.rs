@@ -0,0 +1,311 @@
+//! Device drivers backed by the high-level device traits.
+//!
+//! Each adapter wraps an implementation of one of the [`traits`](super::traits)
+//! and implements [`DeviceDriver`](super::DeviceDriver) for it. The adapter
+//! registers the standard properties of the device interface, turns property
+//! changes from clients into trait calls, and publishes what the device
+//! reports back to clients.
+//!
+//! Adapters never wait for the device in a property change. They start an
+//! exposure or motion, set the affected property `Busy` and return; a timer
+//! started through the [`DeviceContext`] then checks the device until the
+//! exposure or motion is done, so an abort can interrupt it. Trait methods
+//! that move the device may return at once or once the motion is complete,
+//! in which case the change completes at once too.
+//! Readings that change on their own, such as temperatures and positions,
+//! are published by the adapter's `refresh` method, which runs periodically
+//! while the device is connected.
+//!
+//! Settings, such as focuser backlash or filter names, are persistent and can
+//! be changed while the device is disconnected; they are applied when it
+//! connects.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo::device::{DriverRegistry, FocuserDriver};
+//!
+//! let mut registry = DriverRegistry::new();
+//! registry.register(Box::new(FocuserDriver::new(MyFocuser::open("/dev/ttyUSB0")?)))?;
+//! ```
+
+mod camera;
+mod filter_wheel;
+mod focuser;
+mod guider;
+mod mount;
+
+pub use camera::CameraDriver;
+pub use filter_wheel::FilterWheelDriver;
+pub use focuser::FocuserDriver;
+pub use guider::GuiderDriver;
+pub use mount::MountDriver;
+
+use super::context::DeviceContext;
+use super::driver::{DeviceInterface, DriverInfo};
+use super::tasks::TaskId;
+use super::traits::Device;
+use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{Property, PropertyState, PropertyValue, SwitchState};
+use std::time::Duration;
+
+/// Period of the readings published while a device is connected.
+const READINGS_INTERVAL: Duration = Duration::from_secs(1);
+/// Period of the checks of a device in motion.
+const MOTION_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Builds the driver info of a wrapped device.
+fn driver_info<D: Device + ?Sized>(device: &D, interface: DeviceInterface) -> DriverInfo {
+    DriverInfo {
+        name: device.name().to_string(),
+        description: device.description().unwrap_or_default().to_string(),
+        version: device.driver_version().unwrap_or_default().to_string(),
+        interfaces: interface as u32,
+    }
+}
+
+/// Settings changed while a device is disconnected, to apply once it
+/// connects.
+#[derive(Debug, Default)]
+struct PendingSettings(Vec<Property>);
+
+impl PendingSettings {
+    /// Keeps a setting to apply later, and publishes it.
+    fn store(&mut self, ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+        self.0.retain(|p| p.name != property.name);
+        self.0.push(property.clone());
+        accept(ctx, property)
+    }
+
+    /// Takes the settings to apply.
+    fn take(&mut self) -> Vec<Property> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// A repeating timer of an adapter.
+#[derive(Debug, Default)]
+struct Timer(Option<TaskId>);
+
+impl Timer {
+    /// Starts the timer, unless it is running.
+    fn start(&mut self, ctx: &mut DeviceContext, period: Duration) -> Result<()> {
+        if !self.0.is_some_and(|task| ctx.is_task_running(task)) {
+            self.0 = Some(ctx.set_interval(period)?);
+        }
+        Ok(())
+    }
+
+    /// Stops the timer, returning whether it was running.
+    fn stop(&mut self, ctx: &mut DeviceContext) -> bool {
+        self.0.take().is_some_and(|task| ctx.cancel_task(task))
+    }
+
+    /// Whether a task is this timer.
+    fn is(&self, task: TaskId) -> bool {
+        self.0 == Some(task)
+    }
+}
+
+/// Gets the result of an optional feature of a device, or `None` if the
+/// device doesn't support it.
+fn supported<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(IndigoError::NotSupported(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Registers the `CONNECTION`, `INFO` and `CONFIG` properties of a wrapped
+/// device.
+fn register_device(ctx: &mut DeviceContext, info: &DriverInfo) -> Result<()> {
+    let manager = ctx.property_manager();
+    manager.register_standard_connection()?;
+    manager.register_device_info(&info.description, &info.version, info.interfaces)?;
+    manager.register_config()
+}
+
+/// Marks the defined settings of a wrapped device as persistent.
+fn register_settings(ctx: &mut DeviceContext, settings: &[&str]) -> Result<()> {
+    let manager = ctx.property_manager();
+    for setting in settings {
+        if manager.has_property(setting) {
+            manager.set_persistent(setting)?;
+        }
+    }
+    Ok(())
+}
+
+/// Connects or disconnects a wrapped device as requested by a `CONNECTION`
+/// change, returning whether the device is now connected.
+///
+/// The change is published with [`publish_connection`] once the adapter has
+/// published the state of the device.
+async fn change_connection<D: Device + ?Sized>(
+    device: &mut D,
+    ctx: &mut DeviceContext,
+    property: &Property,
+) -> Result<bool> {
+    let connect = switch_on(property, name::CONNECTION_CONNECTED_ITEM);
+    if connect != ctx.is_connected() {
+        if connect {
+            device.connect().await?;
+        } else {
+            device.disconnect().await?;
+        }
+        ctx.set_connected(connect);
+    }
+    Ok(connect)
+}
+
+/// Publishes the `CONNECTION` state of a wrapped device.
+fn publish_connection(ctx: &mut DeviceContext, connected: bool) -> Result<()> {
+    set_switches(
+        ctx,
+        name::CONNECTION_PROPERTY,
+        PropertyState::Ok,
+        &[
+            (name::CONNECTION_CONNECTED_ITEM, connected),
+            (name::CONNECTION_DISCONNECTED_ITEM, !connected),
+        ],
+    )
+}
+
+/// Disconnects a wrapped device and deletes its properties.
+async fn detach_device<D: Device + ?Sized>(device: &mut D, ctx: &mut DeviceContext) -> Result<()> {
+    if ctx.is_connected() {
+        device.disconnect().await?;
+        ctx.set_connected(false);
+    }
+    ctx.property_manager().delete_all_properties();
+    Ok(())
+}
+
+/// Fails unless the device is connected.
+fn ensure_connected(ctx: &DeviceContext, property: &Property) -> Result<()> {
+    if ctx.is_connected() {
+        Ok(())
+    } else {
+        Err(IndigoError::InvalidState(format!(
+            "{} cannot change {} while disconnected",
+            property.device, property.name
+        )))
+    }
+}
+
+/// Whether a switch item is on.
+fn switch_on(property: &Property, item: &str) -> bool {
+    matches!(
+        property.items.get(item).map(|i| &i.value),
+        Some(PropertyValue::Switch {
+            state: SwitchState::On
+        })
+    )
+}
+
+/// Whether a switch item of a defined property is on.
+fn current_switch_on(ctx: &DeviceContext, property: &str, item: &str) -> bool {
+    ctx.properties()
+        .get_property(property)
+        .is_some_and(|p| switch_on(p, item))
+}
+
+/// Gets the value of a number item.
+fn number(property: &Property, item: &str) -> Result<f64> {
+    match property.items.get(item).map(|i| &i.value) {
+        Some(PropertyValue::Number { value, .. }) => Ok(*value),
+        _ => Err(IndigoError::InvalidParameter(format!(
+            "{} has no number item {}",
+            property.name, item
+        ))),
+    }
+}
+
+/// Gets the `(min, max)` limits of a number item of a defined property.
+fn number_limits(ctx: &DeviceContext, property: &str, item: &str) -> Result<(f64, f64)> {
+    match ctx
+        .properties()
+        .get_property(property)
+        .and_then(|p| p.items.get(item))
+        .map(|i| &i.value)
+    {
+        Some(PropertyValue::Number { min, max, .. }) => Ok((*min, *max)),
+        _ => Err(IndigoError::PropertyNotFound(format!(
+            "{}.{}",
+            property, item
+        ))),
+    }
+}
+
+/// Publishes new values of number items, keeping the limits and format of
+/// their definition.
+fn set_numbers(
+    ctx: &mut DeviceContext,
+    property: &str,
+    state: PropertyState,
+    values: &[(&str, f64)],
+) -> Result<()> {
+    let manager = ctx.property_manager();
+    let definition = manager
+        .get_property(property)
+        .ok_or_else(|| IndigoError::PropertyNotFound(property.to_string()))?;
+    let values = values
+        .iter()
+        .filter_map(|(item, value)| match definition.items.get(*item)?.value {
+            PropertyValue::Number {
+                min,
+                max,
+                step,
+                ref format,
+                ..
+            } => Some((
+                item.to_string(),
+                PropertyValue::Number {
+                    value: *value,
+                    min,
+                    max,
+                    step,
+                    format: format.clone(),
+                },
+            )),
+            _ => None,
+        })
+        .collect();
+    manager.update_property(property, state, values)
+}
+
+/// Publishes new states of switch items.
+fn set_switches(
+    ctx: &mut DeviceContext,
+    property: &str,
+    state: PropertyState,
+    values: &[(&str, bool)],
+) -> Result<()> {
+    let values = values
+        .iter()
+        .map(|(item, on)| {
+            let state = if *on {
+                SwitchState::On
+            } else {
+                SwitchState::Off
+            };
+            (item.to_string(), PropertyValue::switch(state))
+        })
+        .collect();
+    ctx.property_manager()
+        .update_property(property, state, values)
+}
+
+/// Publishes a changed property as requested, for settings that take effect
+/// immediately.
+fn accept(ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+    let values = property
+        .items
+        .values()
+        .map(|item| (item.name.clone(), item.value.clone()))
+        .collect();
+    ctx.property_manager()
+        .update_property(&property.name, PropertyState::Ok, values)
+}
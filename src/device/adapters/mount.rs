@@ -0,0 +1,429 @@
+//! Driver for a [`Mount`] implementation.
+
+use super::{
+    accept, change_connection, current_switch_on, detach_device, driver_info, ensure_connected,
+    number, publish_connection, register_device, register_settings, set_numbers, set_switches,
+    switch_on, PendingSettings, Timer, MOTION_INTERVAL, READINGS_INTERVAL,
+};
+use crate::device::context::DeviceContext;
+use crate::device::driver::{DeviceDriver, DeviceInterface, DriverInfo};
+use crate::device::tasks::TaskId;
+use crate::device::traits::{AxisDirection, Coordinates, Mount, MountAxis, SlewRate, TrackingMode};
+use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{Property, PropertyState};
+
+/// Mount settings, which are persistent.
+const SETTINGS: &[&str] = &[
+    name::GEOGRAPHIC_COORDINATES_PROPERTY,
+    name::MOUNT_ON_COORDINATES_SET_PROPERTY,
+    name::MOUNT_SLEW_RATE_PROPERTY,
+    name::MOUNT_TRACK_RATE_PROPERTY,
+];
+
+/// Track rate items with their tracking modes.
+const TRACK_RATES: [(&str, TrackingMode); 3] = [
+    (name::MOUNT_TRACK_RATE_SIDEREAL_ITEM, TrackingMode::Sidereal),
+    (name::MOUNT_TRACK_RATE_SOLAR_ITEM, TrackingMode::Solar),
+    (name::MOUNT_TRACK_RATE_LUNAR_ITEM, TrackingMode::Lunar),
+];
+
+/// [`DeviceDriver`] for a [`Mount`] implementation.
+///
+/// Registers the standard mount properties. A `MOUNT_EQUATORIAL_COORDINATES`
+/// change slews or syncs the mount as selected in `MOUNT_ON_COORDINATES_SET`;
+/// `TRACK` tracks at the rate selected in `MOUNT_TRACK_RATE` after the slew,
+/// `SLEW` stops tracking. Slews and parking stay `Busy` until the mount
+/// stops. Manual motion uses the rate selected in `MOUNT_SLEW_RATE`.
+pub struct MountDriver<T> {
+    mount: T,
+    pending: PendingSettings,
+    /// Checks the mount while it slews
+    motion: Timer,
+    /// Publishes the readings while connected
+    readings: Timer,
+    /// Whether the slew in progress parks the mount
+    parking: bool,
+}
+
+impl<T: Mount> MountDriver<T> {
+    /// Create a driver for a mount
+    pub fn new(mount: T) -> Self {
+        Self {
+            mount,
+            pending: PendingSettings::default(),
+            motion: Timer::default(),
+            readings: Timer::default(),
+            parking: false,
+        }
+    }
+
+    /// Get the wrapped mount
+    pub fn inner(&self) -> &T {
+        &self.mount
+    }
+
+    /// Get the wrapped mount mutably
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.mount
+    }
+
+    /// Publish the coordinates, tracking, park state and sidereal time of the
+    /// mount.
+    ///
+    /// Runs periodically while the mount is connected, and does nothing
+    /// while it is disconnected.
+    pub async fn refresh(&self, ctx: &mut DeviceContext) -> Result<()> {
+        if !ctx.is_connected() {
+            return Ok(());
+        }
+        let coordinates = self.mount.coordinates().await?;
+        let slewing = self.mount.is_slewing().await?;
+        let state = if slewing {
+            PropertyState::Busy
+        } else {
+            PropertyState::Ok
+        };
+        set_numbers(
+            ctx,
+            name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY,
+            state,
+            &[
+                (name::MOUNT_EQUATORIAL_COORDINATES_RA_ITEM, coordinates.ra),
+                (name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM, coordinates.dec),
+            ],
+        )?;
+        let tracking = self.mount.tracking().await?;
+        set_switches(
+            ctx,
+            name::MOUNT_TRACKING_PROPERTY,
+            PropertyState::Ok,
+            &[
+                (name::MOUNT_TRACKING_ON_ITEM, tracking != TrackingMode::Off),
+                (name::MOUNT_TRACKING_OFF_ITEM, tracking == TrackingMode::Off),
+            ],
+        )?;
+        if TRACK_RATES.iter().any(|(_, mode)| *mode == tracking) {
+            let rates = TRACK_RATES.map(|(item, mode)| (item, mode == tracking));
+            set_switches(
+                ctx,
+                name::MOUNT_TRACK_RATE_PROPERTY,
+                PropertyState::Ok,
+                &rates,
+            )?;
+        }
+        let (parked, state) = if self.parking && slewing {
+            (true, PropertyState::Busy)
+        } else {
+            (self.mount.is_parked().await?, PropertyState::Ok)
+        };
+        set_switches(
+            ctx,
+            name::MOUNT_PARK_PROPERTY,
+            state,
+            &[
+                (name::MOUNT_PARK_PARKED_ITEM, parked),
+                (name::MOUNT_PARK_UNPARKED_ITEM, !parked),
+            ],
+        )?;
+        let time = self.mount.sidereal_time().await?;
+        set_numbers(
+            ctx,
+            name::MOUNT_LST_TIME_PROPERTY,
+            PropertyState::Ok,
+            &[(name::MOUNT_LST_TIME_ITEM, time)],
+        )
+    }
+
+    /// Gets the tracking mode selected in `MOUNT_TRACK_RATE`.
+    fn track_rate(ctx: &DeviceContext) -> TrackingMode {
+        TRACK_RATES
+            .into_iter()
+            .find(|(item, _)| current_switch_on(ctx, name::MOUNT_TRACK_RATE_PROPERTY, item))
+            .map_or(TrackingMode::Sidereal, |(_, mode)| mode)
+    }
+
+    /// Publishes the state of a slewing mount, completing the slew once it
+    /// stops.
+    async fn check_motion(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        self.refresh(ctx).await?;
+        if !self.mount.is_slewing().await? {
+            self.motion.stop(ctx);
+            self.parking = false;
+        }
+        Ok(())
+    }
+
+    /// Parks the mount, which completes once it stops.
+    async fn park(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        set_switches(
+            ctx,
+            name::MOUNT_PARK_PROPERTY,
+            PropertyState::Busy,
+            &[
+                (name::MOUNT_PARK_PARKED_ITEM, true),
+                (name::MOUNT_PARK_UNPARKED_ITEM, false),
+            ],
+        )?;
+        self.mount.park().await?;
+        self.parking = true;
+        self.motion.start(ctx, MOTION_INTERVAL)?;
+        self.check_motion(ctx).await
+    }
+
+    /// Stops a slew, alerting the properties it was to complete.
+    async fn abort(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        self.mount.abort_slew().await?;
+        let slewing = self.motion.stop(ctx);
+        let parking = std::mem::take(&mut self.parking);
+        set_switches(
+            ctx,
+            name::MOUNT_ABORT_MOTION_PROPERTY,
+            PropertyState::Ok,
+            &[(name::MOUNT_ABORT_MOTION_ITEM, false)],
+        )?;
+        self.refresh(ctx).await?;
+        let manager = ctx.property_manager();
+        if slewing {
+            manager.alert_property(name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY, "Slew aborted")?;
+        }
+        if parking {
+            manager.alert_property(name::MOUNT_PARK_PROPERTY, "Parking aborted")?;
+        }
+        Ok(())
+    }
+
+    /// Publishes the location of a newly connected mount.
+    async fn publish_location(&self, ctx: &mut DeviceContext) -> Result<()> {
+        let (latitude, longitude) = self.mount.location().await?;
+        set_numbers(
+            ctx,
+            name::GEOGRAPHIC_COORDINATES_PROPERTY,
+            PropertyState::Ok,
+            &[
+                (name::GEOGRAPHIC_COORDINATES_LATITUDE_ITEM, latitude),
+                (name::GEOGRAPHIC_COORDINATES_LONGITUDE_ITEM, longitude),
+            ],
+        )
+    }
+
+    /// Slews or syncs to new coordinates. Slews complete once the mount
+    /// stops.
+    async fn goto(&mut self, ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+        let coordinates = Coordinates::new(
+            number(property, name::MOUNT_EQUATORIAL_COORDINATES_RA_ITEM)?,
+            number(property, name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM)?,
+        );
+        let on_set = |item| current_switch_on(ctx, name::MOUNT_ON_COORDINATES_SET_PROPERTY, item);
+        if on_set(name::MOUNT_ON_COORDINATES_SET_SYNC_ITEM) {
+            self.mount.sync_to(coordinates).await?;
+            return self.refresh(ctx).await;
+        }
+        let track = on_set(name::MOUNT_ON_COORDINATES_SET_TRACK_ITEM);
+
+        set_numbers(
+            ctx,
+            name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY,
+            PropertyState::Busy,
+            &[
+                (name::MOUNT_EQUATORIAL_COORDINATES_RA_ITEM, coordinates.ra),
+                (name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM, coordinates.dec),
+            ],
+        )?;
+        self.mount.slew_to(coordinates).await?;
+        let mode = if track {
+            Self::track_rate(ctx)
+        } else {
+            TrackingMode::Off
+        };
+        self.mount.set_tracking(mode).await?;
+        self.motion.start(ctx, MOTION_INTERVAL)?;
+        self.check_motion(ctx).await
+    }
+
+    /// Starts or stops manual motion of an axis.
+    async fn motion(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+        axis: MountAxis,
+        forward: &str,
+        reverse: &str,
+    ) -> Result<()> {
+        let direction = if switch_on(property, forward) {
+            Some(AxisDirection::Forward)
+        } else if switch_on(property, reverse) {
+            Some(AxisDirection::Reverse)
+        } else {
+            None
+        };
+        match direction {
+            Some(direction) => {
+                let rate = slew_rate(ctx);
+                self.mount.move_axis(axis, direction, rate).await?;
+            }
+            None => self.mount.stop_axis(axis).await?,
+        }
+        accept(ctx, property)
+    }
+
+    /// Applies a change of a setting to the mount.
+    async fn change_setting(&mut self, ctx: &mut DeviceContext, property: &Property) -> Result<()> {
+        match property.name.as_str() {
+            name::GEOGRAPHIC_COORDINATES_PROPERTY => {
+                self.mount
+                    .set_location(
+                        number(property, name::GEOGRAPHIC_COORDINATES_LATITUDE_ITEM)?,
+                        number(property, name::GEOGRAPHIC_COORDINATES_LONGITUDE_ITEM)?,
+                    )
+                    .await?;
+                accept(ctx, property)
+            }
+            name::MOUNT_TRACK_RATE_PROPERTY => {
+                let mode = TRACK_RATES
+                    .into_iter()
+                    .find(|(item, _)| switch_on(property, item))
+                    .map(|(_, mode)| mode)
+                    .ok_or_else(|| IndigoError::NotSupported("Track rate".to_string()))?;
+                if self.mount.is_tracking().await? {
+                    self.mount.set_tracking(mode).await?;
+                }
+                accept(ctx, property)
+            }
+            name::MOUNT_ON_COORDINATES_SET_PROPERTY | name::MOUNT_SLEW_RATE_PROPERTY => {
+                accept(ctx, property)
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+}
+
+/// Gets the rate selected in `MOUNT_SLEW_RATE`.
+fn slew_rate(ctx: &DeviceContext) -> SlewRate {
+    [
+        (name::MOUNT_SLEW_RATE_GUIDE_ITEM, SlewRate::Guide),
+        (name::MOUNT_SLEW_RATE_FIND_ITEM, SlewRate::Find),
+        (name::MOUNT_SLEW_RATE_MAX_ITEM, SlewRate::Max),
+    ]
+    .into_iter()
+    .find(|(item, _)| current_switch_on(ctx, name::MOUNT_SLEW_RATE_PROPERTY, item))
+    .map_or(SlewRate::Centering, |(_, rate)| rate)
+}
+
+#[async_trait::async_trait]
+impl<T: Mount> DeviceDriver for MountDriver<T> {
+    fn info(&self) -> DriverInfo {
+        driver_info(&self.mount, DeviceInterface::Mount)
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        let info = self.info();
+        register_device(ctx, &info)?;
+        ctx.property_manager()
+            .register_mount_standard(&info.description, &info.name)?;
+        register_settings(ctx, SETTINGS)
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        if property.name == name::CONNECTION_PROPERTY {
+            let connected = change_connection(&mut self.mount, ctx, property).await?;
+            if connected {
+                for setting in self.pending.take() {
+                    self.change_setting(ctx, &setting).await?;
+                }
+                self.publish_location(ctx).await?;
+                self.refresh(ctx).await?;
+                self.readings.start(ctx, READINGS_INTERVAL)?;
+            } else {
+                self.parking = false;
+            }
+            return publish_connection(ctx, connected);
+        }
+        if SETTINGS.contains(&property.name.as_str()) {
+            if !ctx.is_connected() {
+                return self.pending.store(ctx, property);
+            }
+            return self.change_setting(ctx, property).await;
+        }
+        ensure_connected(ctx, property)?;
+
+        match property.name.as_str() {
+            name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY => self.goto(ctx, property).await,
+            name::MOUNT_ABORT_MOTION_PROPERTY => self.abort(ctx).await,
+            name::MOUNT_PARK_PROPERTY => {
+                if switch_on(property, name::MOUNT_PARK_PARKED_ITEM) {
+                    return self.park(ctx).await;
+                }
+                self.mount.unpark().await?;
+                self.refresh(ctx).await
+            }
+            name::MOUNT_PARK_SET_PROPERTY => {
+                if switch_on(property, name::MOUNT_PARK_SET_DEFAULT_ITEM) {
+                    return Err(IndigoError::NotSupported(
+                        "Resetting the park position".to_string(),
+                    ));
+                }
+                if switch_on(property, name::MOUNT_PARK_SET_CURRENT_ITEM) {
+                    self.mount.set_park_position().await?;
+                }
+                set_switches(
+                    ctx,
+                    name::MOUNT_PARK_SET_PROPERTY,
+                    PropertyState::Ok,
+                    &[
+                        (name::MOUNT_PARK_SET_DEFAULT_ITEM, false),
+                        (name::MOUNT_PARK_SET_CURRENT_ITEM, false),
+                    ],
+                )
+            }
+            name::MOUNT_TRACKING_PROPERTY => {
+                let mode = if switch_on(property, name::MOUNT_TRACKING_ON_ITEM) {
+                    Self::track_rate(ctx)
+                } else {
+                    TrackingMode::Off
+                };
+                self.mount.set_tracking(mode).await?;
+                self.refresh(ctx).await
+            }
+            name::MOUNT_MOTION_DEC_PROPERTY => {
+                self.motion(
+                    ctx,
+                    property,
+                    MountAxis::Secondary,
+                    name::MOUNT_MOTION_NORTH_ITEM,
+                    name::MOUNT_MOTION_SOUTH_ITEM,
+                )
+                .await
+            }
+            name::MOUNT_MOTION_RA_PROPERTY => {
+                self.motion(
+                    ctx,
+                    property,
+                    MountAxis::Primary,
+                    name::MOUNT_MOTION_WEST_ITEM,
+                    name::MOUNT_MOTION_EAST_ITEM,
+                )
+                .await
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+
+    async fn on_task(&mut self, ctx: &mut DeviceContext, task: TaskId) -> Result<()> {
+        if self.motion.is(task) {
+            self.check_motion(ctx).await
+        } else if self.readings.is(task) {
+            self.refresh(ctx).await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        detach_device(&mut self.mount, ctx).await
+    }
+}
@@ -0,0 +1,289 @@
+//! Persistence of device settings with the standard `CONFIG` property.
+//!
+//! Drivers mark the read-write properties that hold settings as persistent
+//! with [`PropertyManager::set_persistent`]. Their values are saved to a
+//! per-device file in the configuration directory, one `PROPERTY.ITEM=value`
+//! line per item, and read back as property change requests. The
+//! [`DriverRegistry`](super::DriverRegistry) routes those requests to the
+//! driver on attach and on `CONFIG.LOAD`. `CONFIG.DEFAULT` routes the values
+//! the properties had when they were marked persistent.
+
+use super::property_manager::PropertyManager;
+use super::templates::{switch, MAIN_GROUP};
+use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{Property, PropertyPerm, PropertyType, PropertyValue, SwitchRule, SwitchState};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Item of the `CONFIG` property that restores the default settings.
+pub const CONFIG_DEFAULT_ITEM: &str = "DEFAULT";
+
+/// Extension of device configuration files.
+const CONFIG_EXTENSION: &str = "config";
+
+/// Persistent properties and configuration file of a device.
+#[derive(Debug, Default)]
+pub(super) struct Persistence {
+    /// Configuration file, if persistence is enabled
+    path: Option<PathBuf>,
+    /// Default values of the persistent properties
+    defaults: BTreeMap<String, Vec<(String, PropertyValue)>>,
+}
+
+impl PropertyManager {
+    /// Register the standard `CONFIG` property with `LOAD`, `SAVE`, `DEFAULT`
+    /// and `REMOVE` items.
+    pub fn register_config(&mut self) -> Result<()> {
+        self.define_switches(
+            name::CONFIG_PROPERTY,
+            MAIN_GROUP,
+            "Configuration control",
+            SwitchRule::AtMostOne,
+            vec![
+                switch(name::CONFIG_LOAD_ITEM, "Load", false),
+                switch(name::CONFIG_SAVE_ITEM, "Save", false),
+                switch(CONFIG_DEFAULT_ITEM, "Default", false),
+                switch(name::CONFIG_REMOVE_ITEM, "Remove", false),
+            ],
+        )
+    }
+
+    /// Mark a property as persistent, with its current values as defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::PropertyNotFound`] if the property is not
+    /// defined, or [`IndigoError::InvalidParameter`] if it is read-only or a
+    /// BLOB property.
+    pub fn set_persistent(&mut self, name: &str) -> Result<()> {
+        let property = self
+            .get_property(name)
+            .ok_or_else(|| IndigoError::PropertyNotFound(name.to_string()))?;
+        if property.perm == PropertyPerm::ReadOnly || property.property_type == PropertyType::Blob {
+            return Err(IndigoError::InvalidParameter(format!(
+                "{} cannot be persistent",
+                name
+            )));
+        }
+        let defaults = property
+            .items
+            .values()
+            .map(|item| (item.name.clone(), item.value.clone()))
+            .collect();
+        self.persistence.defaults.insert(name.to_string(), defaults);
+        Ok(())
+    }
+
+    /// Check if a property is persistent
+    pub fn is_persistent(&self, name: &str) -> bool {
+        self.persistence.defaults.contains_key(name)
+    }
+
+    /// Set the directory the configuration file of the device is kept in.
+    ///
+    /// Pass `None` to disable persistence.
+    pub fn set_config_dir(&mut self, dir: Option<&Path>) {
+        self.persistence.path = dir.map(|dir| {
+            let file: String = self
+                .device_name()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            dir.join(file).with_extension(CONFIG_EXTENSION)
+        });
+    }
+
+    /// Get the configuration file of the device, if persistence is enabled
+    pub fn config_path(&self) -> Option<&Path> {
+        self.persistence.path.as_deref()
+    }
+
+    /// Save the values of the persistent properties to the configuration
+    /// file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::InvalidState`] if persistence is not enabled, or
+    /// [`IndigoError::IoError`] if the file cannot be written.
+    pub fn save_config(&self) -> Result<()> {
+        let path = self.config_path().ok_or_else(|| {
+            IndigoError::InvalidState(format!("{} has no configuration file", self.device_name()))
+        })?;
+
+        let mut contents = format!("# Configuration of {}\n", self.device_name());
+        for name in self.persistence.defaults.keys() {
+            let Some(property) = self.get_property(name) else {
+                continue;
+            };
+            let mut items: Vec<_> = property.items.values().collect();
+            items.sort_by(|a, b| a.name.cmp(&b.name));
+            for item in items {
+                if let Some(value) = format_value(&item.value) {
+                    contents.push_str(&format!("{}.{}={}\n", name, item.name, value));
+                }
+            }
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Read the configuration file as change requests for the persistent
+    /// properties.
+    ///
+    /// Returns no requests if persistence is not enabled or the file does not
+    /// exist. Lines for properties or items that are not persistent or not
+    /// defined are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::IoError`] if the file cannot be read, or
+    /// [`IndigoError::ParseError`] if a line or value is malformed.
+    pub fn load_config(&self) -> Result<Vec<Property>> {
+        let Some(path) = self.config_path() else {
+            return Ok(Vec::new());
+        };
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut requests: Vec<Property> = Vec::new();
+        for line in contents.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| IndigoError::ParseError(format!("Invalid config line: {}", line)))?;
+            let (property, item) = key
+                .split_once('.')
+                .ok_or_else(|| IndigoError::ParseError(format!("Invalid config key: {}", key)))?;
+            let Some(definition) = self
+                .get_property(property)
+                .filter(|_| self.is_persistent(property))
+            else {
+                tracing::debug!("Skipping config of {}.{}", self.device_name(), property);
+                continue;
+            };
+            let Some(current) = definition.items.get(item) else {
+                tracing::debug!("Skipping config of {}.{}", property, item);
+                continue;
+            };
+
+            let mut item = current.clone();
+            item.value = parse_value(&current.value, value)?;
+            match requests.iter_mut().find(|r| r.name == property) {
+                Some(request) => {
+                    request.items.insert(item.name.clone(), item);
+                }
+                None => {
+                    let mut request = definition.clone();
+                    request.items.clear();
+                    request.items.insert(item.name.clone(), item);
+                    requests.push(request);
+                }
+            }
+        }
+        Ok(requests)
+    }
+
+    /// Get change requests that restore the persistent properties to their
+    /// defaults.
+    pub fn default_config(&self) -> Vec<Property> {
+        self.persistence
+            .defaults
+            .iter()
+            .filter_map(|(name, defaults)| {
+                let mut request = self.get_property(name)?.clone();
+                for (item, value) in defaults {
+                    if let Some(item) = request.items.get_mut(item) {
+                        item.value = value.clone();
+                    }
+                }
+                Some(request)
+            })
+            .collect()
+    }
+
+    /// Remove the configuration file, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::IoError`] if the file cannot be removed.
+    pub fn remove_config(&self) -> Result<()> {
+        match self.config_path().map(fs::remove_file) {
+            Some(Err(e)) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Formats a value for the configuration file; BLOBs and lights are not
+/// saved.
+fn format_value(value: &PropertyValue) -> Option<String> {
+    match value {
+        PropertyValue::Text(text) => Some(
+            text.replace('\\', "\\\\")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r"),
+        ),
+        PropertyValue::Number { value, .. } => Some(value.to_string()),
+        PropertyValue::Switch { state } => Some(state.to_string()),
+        _ => None,
+    }
+}
+
+/// Parses a value from the configuration file, as the type of the current
+/// value.
+fn parse_value(current: &PropertyValue, value: &str) -> Result<PropertyValue> {
+    let invalid = || IndigoError::ParseError(format!("Invalid config value: {}", value));
+    Ok(match current {
+        PropertyValue::Text(_) => PropertyValue::Text(unescape(value)),
+        PropertyValue::Number {
+            min,
+            max,
+            step,
+            format,
+            ..
+        } => PropertyValue::Number {
+            value: value.parse().map_err(|_| invalid())?,
+            min: *min,
+            max: *max,
+            step: *step,
+            format: format.clone(),
+        },
+        PropertyValue::Switch { .. } => PropertyValue::switch(match value {
+            "On" => SwitchState::On,
+            "Off" => SwitchState::Off,
+            _ => return Err(invalid()),
+        }),
+        _ => return Err(invalid()),
+    })
+}
+
+/// Reverses the escaping of text values.
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
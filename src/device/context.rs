@@ -1,15 +1,27 @@
 //! Runtime context for device drivers.
 
+use super::notification::NotificationSender;
 use super::property_manager::PropertyManager;
+use super::tasks::{TaskId, TaskSender, Tasks};
+use crate::error::Result;
+use std::future::Future;
+use std::time::Duration;
 
 /// Runtime context passed to device driver callbacks.
 ///
 /// Provides access to the property manager and other runtime services
 /// that device drivers need during their lifecycle.
+///
+/// Timers and background tasks started with [`set_timer`](Self::set_timer),
+/// [`set_interval`](Self::set_interval) and [`spawn`](Self::spawn) call
+/// [`DeviceDriver::on_task`](super::DeviceDriver::on_task) when they fire.
+/// They are cancelled when the device disconnects or the driver detaches.
 pub struct DeviceContext {
     property_manager: PropertyManager,
     /// Whether the device is currently connected
     connected: bool,
+    /// Timers and background tasks of the driver
+    tasks: Tasks,
 }
 
 impl DeviceContext {
@@ -18,6 +30,7 @@ impl DeviceContext {
         Self {
             property_manager: PropertyManager::new(device_name),
             connected: false,
+            tasks: Tasks::default(),
         }
     }
 
@@ -31,13 +44,99 @@ impl DeviceContext {
         &self.property_manager
     }
 
+    /// Set the outbound channel that property changes and messages are sent to
+    /// (called by the runtime hosting the driver).
+    pub fn set_notifier(&mut self, notifier: Option<NotificationSender>) {
+        self.property_manager.set_notifier(notifier);
+    }
+
+    /// Send a standalone message to clients
+    pub fn send_message(&self, message: impl Into<String>) {
+        self.property_manager.send_message(message);
+    }
+
     /// Check if the device is currently connected
     pub fn is_connected(&self) -> bool {
         self.connected
     }
 
-    /// Set the connected state (called by the runtime, not usually by drivers directly)
+    /// Set the connected state (called by the runtime, not usually by drivers directly).
+    ///
+    /// Disconnecting cancels all tasks.
     pub fn set_connected(&mut self, connected: bool) {
+        if self.connected && !connected {
+            self.tasks.cancel_all();
+        }
         self.connected = connected;
     }
+
+    /// Set the channel that task events are sent to (called by the runtime
+    /// hosting the driver).
+    pub fn set_task_sender(&mut self, sender: Option<TaskSender>) {
+        self.tasks.set_sender(sender);
+    }
+
+    /// Fire a task once after a delay.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::InvalidState`](crate::error::IndigoError::InvalidState)
+    /// if there is no runtime to run tasks.
+    pub fn set_timer(&mut self, delay: Duration) -> Result<TaskId> {
+        let device = self.property_manager.device_name().to_string();
+        self.tasks.set_timer(&device, delay)
+    }
+
+    /// Fire a task every period, starting one period from now.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::InvalidParameter`](crate::error::IndigoError::InvalidParameter)
+    /// if the period is zero, or
+    /// [`IndigoError::InvalidState`](crate::error::IndigoError::InvalidState)
+    /// if there is no runtime to run tasks.
+    pub fn set_interval(&mut self, period: Duration) -> Result<TaskId> {
+        let device = self.property_manager.device_name().to_string();
+        self.tasks.set_interval(&device, period)
+    }
+
+    /// Run a future in the background, firing the task when it completes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::InvalidState`](crate::error::IndigoError::InvalidState)
+    /// if there is no runtime to run tasks.
+    pub fn spawn<F>(&mut self, future: F) -> Result<TaskId>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let device = self.property_manager.device_name().to_string();
+        self.tasks.spawn(&device, future)
+    }
+
+    /// Cancel a task, returning whether it was running
+    pub fn cancel_task(&mut self, task: TaskId) -> bool {
+        self.tasks.cancel(task)
+    }
+
+    /// Cancel all tasks
+    pub fn cancel_tasks(&mut self) {
+        self.tasks.cancel_all();
+    }
+
+    /// Check if a task is still running
+    pub fn is_task_running(&self, task: TaskId) -> bool {
+        self.tasks.is_running(task)
+    }
+
+    /// Get the number of running tasks
+    pub fn task_count(&self) -> usize {
+        self.tasks.count()
+    }
+
+    /// Accept an event of a task, returning whether the driver should be
+    /// called for it (called by the runtime).
+    pub(super) fn task_fired(&mut self, task: TaskId) -> bool {
+        self.tasks.fired(task)
+    }
 }
@@ -1,6 +1,7 @@
 //! Core DeviceDriver trait and related types.
 
 use super::context::DeviceContext;
+use super::tasks::TaskId;
 use crate::error::Result;
 use crate::types::{BlobTransferMode, Property};
 
@@ -53,10 +54,45 @@ pub enum DeviceInterface {
 }
 
 impl DeviceInterface {
+    /// All specific interfaces, in bit order
+    pub const ALL: [DeviceInterface; 13] = [
+        DeviceInterface::Ccd,
+        DeviceInterface::FilterWheel,
+        DeviceInterface::Focuser,
+        DeviceInterface::Mount,
+        DeviceInterface::Guider,
+        DeviceInterface::Dome,
+        DeviceInterface::Gps,
+        DeviceInterface::Weather,
+        DeviceInterface::Aux,
+        DeviceInterface::Rotator,
+        DeviceInterface::Ao,
+        DeviceInterface::FlatPanel,
+        DeviceInterface::PowerBoard,
+    ];
+
     /// Combine multiple interfaces into a bitmask
     pub fn combine(interfaces: &[DeviceInterface]) -> u32 {
         interfaces.iter().fold(0u32, |acc, i| acc | *i as u32)
     }
+
+    /// Decode a bitmask into the interfaces it contains
+    ///
+    /// Unknown bits are ignored. A mask without known bits decodes to an
+    /// empty list.
+    pub fn decode(mask: u32) -> Vec<DeviceInterface> {
+        Self::ALL
+            .into_iter()
+            .filter(|interface| interface.is_in(mask))
+            .collect()
+    }
+
+    /// Whether this interface is set in a bitmask
+    ///
+    /// [`DeviceInterface::General`] is contained in every mask.
+    pub fn is_in(self, mask: u32) -> bool {
+        mask & self as u32 == self as u32
+    }
 }
 
 /// The core Device Driver trait — the SPI for implementing INDIGO devices in Rust.
@@ -69,7 +105,8 @@ impl DeviceInterface {
 /// 1. `attach()` — Called when the driver is loaded. Register properties here.
 /// 2. `change_property()` — Called when a client modifies a property value.
 /// 3. `enable_blob()` — Called when a client requests BLOB mode change.
-/// 4. `detach()` — Called when the driver is being unloaded. Clean up resources.
+/// 4. `on_task()` — Called when a timer or background task of the driver fires.
+/// 5. `detach()` — Called when the driver is being unloaded. Clean up resources.
 ///
 /// # Example
 ///
@@ -133,6 +170,12 @@ pub trait DeviceDriver: Send + Sync {
         Ok(())
     }
 
+    /// Called when a timer or background task started through the context
+    /// fires. Default implementation does nothing.
+    async fn on_task(&mut self, _ctx: &mut DeviceContext, _task: TaskId) -> Result<()> {
+        Ok(())
+    }
+
     /// Called when the driver is being detached/unloaded.
     /// Clean up resources here.
     async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()>;
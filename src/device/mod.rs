@@ -3,21 +3,49 @@
 //! This module provides the foundational traits and types needed to write
 //! INDIGO device drivers. Device drivers implement the [`DeviceDriver`] trait
 //! and use the [`PropertyManager`] to register and manage device properties.
+//! The standard property sets of each device interface are registered with
+//! `register_ccd_standard`, `register_mount_standard` and the like.
+//! Settings that should survive restarts are marked persistent, and are
+//! saved and loaded through the standard `CONFIG` property. Polling loops
+//! run as timers and background tasks of the [`DeviceContext`], which are
+//! cancelled when the device disconnects or the driver detaches.
 //!
 //! ## High-Level Device Traits
 //!
 //! The [`traits`] module provides high-level, ergonomic traits for specific
 //! device types (Camera, Mount, Focuser, etc.) that abstract away low-level
-//! property manipulation.
+//! property manipulation. The adapters [`FocuserDriver`], [`CameraDriver`],
+//! [`MountDriver`], [`FilterWheelDriver`] and [`GuiderDriver`] turn an
+//! implementation of these traits into a [`DeviceDriver`].
+//!
+//! [`CcdSimulator`], [`MountSimulator`], [`FocuserSimulator`] and
+//! [`FilterWheelSimulator`] implement the traits without hardware, for
+//! development and testing.
 
+mod adapters;
+mod config;
 mod context;
 mod driver;
+mod notification;
 mod property_manager;
 mod registry;
+mod simulators;
+mod tasks;
+mod templates;
+mod validation;
 
 pub mod traits;
 
+pub use adapters::{CameraDriver, FilterWheelDriver, FocuserDriver, GuiderDriver, MountDriver};
+pub use config::CONFIG_DEFAULT_ITEM;
 pub use context::DeviceContext;
 pub use driver::{DeviceDriver, DeviceInterface, DriverInfo};
+pub use notification::{DeviceNotification, NotificationSender};
 pub use property_manager::{PropertyManager, PropertyUpdate};
 pub use registry::DriverRegistry;
+pub use simulators::{CcdSimulator, FilterWheelSimulator, FocuserSimulator, MountSimulator};
+pub use tasks::{TaskEvent, TaskId, TaskSender};
+pub use templates::{
+    CCD_IMAGE_GROUP, CCD_MAIN_GROUP, FOCUSER_MAIN_GROUP, GUIDER_MAIN_GROUP, MAIN_GROUP,
+    MOUNT_MAIN_GROUP, WHEEL_MAIN_GROUP,
+};
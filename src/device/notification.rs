@@ -0,0 +1,40 @@
+//! Outbound notifications from device drivers to clients.
+
+use crate::types::Property;
+use tokio::sync::mpsc;
+
+/// Sender half of a driver's outbound notification channel.
+pub type NotificationSender = mpsc::UnboundedSender<DeviceNotification>;
+
+/// A change made by a device driver, to be sent to clients.
+///
+/// Notifications are emitted by the [`PropertyManager`](super::PropertyManager)
+/// when a [`DeviceContext`](super::DeviceContext) has an outbound channel, and
+/// are typically translated into protocol messages by a server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceNotification {
+    /// A property was defined (`defXXXVector`).
+    Defined(Property),
+
+    /// A property was updated (`setXXXVector`).
+    ///
+    /// The property holds the metadata of its definition, the new state and
+    /// message, and only the items that were updated.
+    Updated(Property),
+
+    /// A property, or a whole device, was deleted (`delProperty`).
+    Deleted {
+        /// Device name.
+        device: String,
+        /// Property name, or `None` if all properties of the device were deleted.
+        name: Option<String>,
+    },
+
+    /// A standalone message from the device (`message`).
+    Message {
+        /// Device name.
+        device: String,
+        /// Message text.
+        message: String,
+    },
+}
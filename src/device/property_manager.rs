@@ -1,8 +1,10 @@
 //! Property lifecycle management for device drivers.
 
+use super::config::Persistence;
+use super::notification::{DeviceNotification, NotificationSender};
 use crate::error::{IndigoError, Result};
 use crate::types::{
-    Property, PropertyItem, PropertyPerm, PropertyState, PropertyType, PropertyValue,
+    Property, PropertyItem, PropertyPerm, PropertyState, PropertyType, PropertyValue, SwitchRule,
 };
 use std::collections::HashMap;
 
@@ -11,6 +13,11 @@ use std::collections::HashMap;
 /// The PropertyManager maintains the canonical state of all properties
 /// owned by a device driver and provides methods to define, update,
 /// and remove properties.
+///
+/// When an outbound channel is set with [`set_notifier`](Self::set_notifier),
+/// every change is sent as a [`DeviceNotification`]. Otherwise updates are
+/// queued, to be collected with
+/// [`drain_pending_updates`](Self::drain_pending_updates).
 pub struct PropertyManager {
     /// Device name this manager belongs to
     device_name: String,
@@ -18,6 +25,10 @@ pub struct PropertyManager {
     properties: HashMap<String, Property>,
     /// Pending property updates to be sent to clients
     pending_updates: Vec<PropertyUpdate>,
+    /// Outbound channel for notifications to clients
+    notifier: Option<NotificationSender>,
+    /// Persistent properties and configuration file
+    pub(super) persistence: Persistence,
 }
 
 /// A pending property update to be sent to clients
@@ -26,6 +37,7 @@ pub struct PropertyUpdate {
     pub property_name: String,
     pub values: Vec<(String, PropertyValue)>,
     pub state: PropertyState,
+    pub message: Option<String>,
 }
 
 impl PropertyManager {
@@ -35,6 +47,28 @@ impl PropertyManager {
             device_name: device_name.into(),
             properties: HashMap::new(),
             pending_updates: Vec::new(),
+            notifier: None,
+            persistence: Persistence::default(),
+        }
+    }
+
+    /// Set the outbound channel that changes are sent to.
+    ///
+    /// Pass `None` to go back to queueing updates.
+    pub fn set_notifier(&mut self, notifier: Option<NotificationSender>) {
+        self.notifier = notifier;
+    }
+
+    /// Check if changes are sent to an outbound channel
+    pub fn has_notifier(&self) -> bool {
+        self.notifier.is_some()
+    }
+
+    /// Send a notification, if there is an outbound channel
+    fn notify(&self, notification: DeviceNotification) {
+        if let Some(notifier) = &self.notifier {
+            // The receiver is gone when nobody listens to the device anymore
+            let _ = notifier.send(notification);
         }
     }
 
@@ -49,6 +83,7 @@ impl PropertyManager {
         if self.properties.contains_key(&property.name) {
             return Err(IndigoError::PropertyAlreadyExists(property.name.clone()));
         }
+        self.notify(DeviceNotification::Defined(property.clone()));
         self.properties.insert(property.name.clone(), property);
         Ok(())
     }
@@ -82,6 +117,7 @@ impl PropertyManager {
             state: PropertyState::Idle,
             perm: PropertyPerm::ReadWrite,
             property_type: PropertyType::Switch,
+            switch_rule: Some(SwitchRule::OneOfMany),
             items,
             timeout: None,
             timestamp: None,
@@ -127,6 +163,7 @@ impl PropertyManager {
             state: PropertyState::Ok,
             perm: PropertyPerm::ReadOnly,
             property_type: PropertyType::Text,
+            switch_rule: None,
             items,
             timeout: None,
             timestamp: None,
@@ -145,12 +182,23 @@ impl PropertyManager {
         self.properties.get_mut(name)
     }
 
-    /// Update property state and values, queueing the update for sending to clients.
+    /// Update property state and values, sending the update to clients.
     pub fn update_property(
         &mut self,
         name: &str,
         state: PropertyState,
         values: Vec<(String, PropertyValue)>,
+    ) -> Result<()> {
+        self.update_property_with_message(name, state, values, None)
+    }
+
+    /// Update property state and values, sending the update to clients with a message.
+    pub fn update_property_with_message(
+        &mut self,
+        name: &str,
+        state: PropertyState,
+        values: Vec<(String, PropertyValue)>,
+        message: Option<String>,
     ) -> Result<()> {
         let property = self
             .properties
@@ -165,29 +213,77 @@ impl PropertyManager {
             }
         }
 
-        self.pending_updates.push(PropertyUpdate {
-            property_name: name.to_string(),
-            values,
-            state,
-        });
+        property.message = message.clone();
+
+        if self.notifier.is_some() {
+            let mut updated = Property {
+                items: HashMap::new(),
+                ..property.clone()
+            };
+            for (item_name, _) in &values {
+                if let Some(item) = property.items.get(item_name) {
+                    updated.items.insert(item_name.clone(), item.clone());
+                }
+            }
+            self.notify(DeviceNotification::Updated(updated));
+        } else {
+            self.pending_updates.push(PropertyUpdate {
+                property_name: name.to_string(),
+                values,
+                state,
+                message,
+            });
+        }
 
         Ok(())
     }
 
+    /// Set a property to `Alert`, keeping its values, and send it to clients
+    /// with a message explaining what went wrong.
+    pub fn alert_property(&mut self, name: &str, message: impl Into<String>) -> Result<()> {
+        let values = self
+            .properties
+            .get(name)
+            .ok_or_else(|| IndigoError::PropertyNotFound(name.to_string()))?
+            .items
+            .values()
+            .map(|item| (item.name.clone(), item.value.clone()))
+            .collect();
+        self.update_property_with_message(name, PropertyState::Alert, values, Some(message.into()))
+    }
+
     /// Remove a property definition
     pub fn delete_property(&mut self, name: &str) -> Result<()> {
         self.properties
             .remove(name)
             .ok_or_else(|| IndigoError::PropertyNotFound(name.to_string()))?;
+        self.notify(DeviceNotification::Deleted {
+            device: self.device_name.clone(),
+            name: Some(name.to_string()),
+        });
         Ok(())
     }
 
     /// Remove all properties
     pub fn delete_all_properties(&mut self) {
+        if !self.properties.is_empty() {
+            self.notify(DeviceNotification::Deleted {
+                device: self.device_name.clone(),
+                name: None,
+            });
+        }
         self.properties.clear();
         self.pending_updates.clear();
     }
 
+    /// Send a standalone message to clients
+    pub fn send_message(&self, message: impl Into<String>) {
+        self.notify(DeviceNotification::Message {
+            device: self.device_name.clone(),
+            message: message.into(),
+        });
+    }
+
     /// Get and clear pending updates (consumed by the runtime to send to clients)
     pub fn drain_pending_updates(&mut self) -> Vec<PropertyUpdate> {
         std::mem::take(&mut self.pending_updates)
@@ -1,9 +1,15 @@
 //! Driver registration and lifecycle management.
 
+use super::config::CONFIG_DEFAULT_ITEM;
 use super::context::DeviceContext;
 use super::driver::{DeviceDriver, DriverInfo};
+use super::notification::NotificationSender;
+use super::tasks::{TaskEvent, TaskSender};
 use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{BlobTransferMode, Property, PropertyState, PropertyValue, SwitchState};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Entry in the driver registry
 struct DriverEntry {
@@ -18,6 +24,12 @@ struct DriverEntry {
 /// and routing of property changes to the appropriate drivers.
 pub struct DriverRegistry {
     drivers: HashMap<String, DriverEntry>,
+    /// Outbound channel given to the context of every driver
+    notifier: Option<NotificationSender>,
+    /// Directory of the configuration files of the drivers
+    config_dir: Option<PathBuf>,
+    /// Channel given to the context of every driver for its task events
+    task_sender: Option<TaskSender>,
 }
 
 impl DriverRegistry {
@@ -25,16 +37,59 @@ impl DriverRegistry {
     pub fn new() -> Self {
         Self {
             drivers: HashMap::new(),
+            notifier: None,
+            config_dir: None,
+            task_sender: None,
         }
     }
 
+    /// Set the outbound channel that all drivers, including those registered
+    /// later, send their notifications to.
+    pub fn set_notifier(&mut self, notifier: Option<NotificationSender>) {
+        for entry in self.drivers.values_mut() {
+            entry.context.set_notifier(notifier.clone());
+        }
+        self.notifier = notifier;
+    }
+
+    /// Set the directory that all drivers, including those registered later,
+    /// keep their configuration files in.
+    ///
+    /// Pass `None` to disable persistence.
+    pub fn set_config_dir(&mut self, dir: Option<PathBuf>) {
+        for entry in self.drivers.values_mut() {
+            entry
+                .context
+                .property_manager()
+                .set_config_dir(dir.as_deref());
+        }
+        self.config_dir = dir;
+    }
+
+    /// Set the channel that all drivers, including those registered later,
+    /// send their task events to.
+    ///
+    /// The events are to be passed back to
+    /// [`handle_task`](Self::handle_task).
+    pub fn set_task_sender(&mut self, sender: Option<TaskSender>) {
+        for entry in self.drivers.values_mut() {
+            entry.context.set_task_sender(sender.clone());
+        }
+        self.task_sender = sender;
+    }
+
     /// Register a device driver. The driver is not yet attached.
     pub fn register(&mut self, driver: Box<dyn DeviceDriver>) -> Result<()> {
         let info = driver.info();
         if self.drivers.contains_key(&info.name) {
             return Err(IndigoError::DriverAlreadyRegistered(info.name));
         }
-        let context = DeviceContext::new(&info.name);
+        let mut context = DeviceContext::new(&info.name);
+        context.set_notifier(self.notifier.clone());
+        context.set_task_sender(self.task_sender.clone());
+        context
+            .property_manager()
+            .set_config_dir(self.config_dir.as_deref());
         self.drivers.insert(
             info.name.clone(),
             DriverEntry {
@@ -46,7 +101,8 @@ impl DriverRegistry {
         Ok(())
     }
 
-    /// Attach (initialize) a registered driver
+    /// Attach (initialize) a registered driver, then apply its saved
+    /// configuration
     pub async fn attach(&mut self, name: &str) -> Result<()> {
         let entry = self
             .drivers
@@ -57,6 +113,10 @@ impl DriverRegistry {
         }
         entry.driver.attach(&mut entry.context).await?;
         entry.attached = true;
+        match entry.context.properties().load_config() {
+            Ok(requests) => entry.apply_config(requests).await,
+            Err(e) => tracing::warn!("Could not load configuration of {}: {}", name, e),
+        }
         Ok(())
     }
 
@@ -69,7 +129,7 @@ impl DriverRegistry {
         Ok(())
     }
 
-    /// Detach a driver, cleaning up its resources
+    /// Detach a driver, cleaning up its resources and cancelling its tasks
     pub async fn detach(&mut self, name: &str) -> Result<()> {
         let entry = self
             .drivers
@@ -78,7 +138,9 @@ impl DriverRegistry {
         if !entry.attached {
             return Err(IndigoError::DriverNotAttached(name.to_string()));
         }
-        entry.driver.detach(&mut entry.context).await?;
+        let result = entry.driver.detach(&mut entry.context).await;
+        entry.context.cancel_tasks();
+        result?;
         entry.attached = false;
         Ok(())
     }
@@ -97,7 +159,16 @@ impl DriverRegistry {
         Ok(())
     }
 
-    /// Route a property change to the appropriate driver
+    /// Route a property change to the appropriate driver.
+    ///
+    /// The request is validated and merged into the full property with
+    /// [`PropertyManager::validate_change`](super::PropertyManager::validate_change)
+    /// first. If the request is invalid, or the driver returns an error, the
+    /// property is set to `Alert` with the error as message.
+    ///
+    /// Changes of a registered `CONFIG` property are handled here: the
+    /// persistent properties are saved, loaded, restored to their defaults or
+    /// their configuration file removed.
     pub async fn handle_property_change(
         &mut self,
         device: &str,
@@ -110,12 +181,66 @@ impl DriverRegistry {
         if !entry.attached {
             return Err(IndigoError::DriverNotAttached(device.to_string()));
         }
+
+        if property.name == name::CONFIG_PROPERTY
+            && entry.context.properties().has_property(&property.name)
+        {
+            let result = entry.change_config(property).await;
+            if let Err(e) = &result {
+                let _ = entry
+                    .context
+                    .property_manager()
+                    .alert_property(&property.name, e.to_string());
+            }
+            return result;
+        }
+        entry.change(property).await
+    }
+
+    /// Route a BLOB mode change to the appropriate driver
+    pub async fn handle_enable_blob(
+        &mut self,
+        device: &str,
+        name: Option<&str>,
+        mode: BlobTransferMode,
+    ) -> Result<()> {
+        let entry = self
+            .drivers
+            .get_mut(device)
+            .ok_or_else(|| IndigoError::DriverNotFound(device.to_string()))?;
+        if !entry.attached {
+            return Err(IndigoError::DriverNotAttached(device.to_string()));
+        }
         entry
             .driver
-            .change_property(&mut entry.context, property)
+            .enable_blob(&mut entry.context, device, name, mode)
             .await
     }
 
+    /// Route a task event to the driver that started the task.
+    ///
+    /// Events of tasks that were cancelled, or of drivers that are no longer
+    /// attached, are ignored.
+    pub async fn handle_task(&mut self, event: &TaskEvent) -> Result<()> {
+        let Some(entry) = self.drivers.get_mut(&event.device) else {
+            return Ok(());
+        };
+        if !entry.attached || !entry.context.task_fired(event.task) {
+            return Ok(());
+        }
+        entry.driver.on_task(&mut entry.context, event.task).await
+    }
+
+    /// Get the runtime context of a registered driver
+    pub fn context(&self, name: &str) -> Option<&DeviceContext> {
+        self.drivers.get(name).map(|e| &e.context)
+    }
+
+    /// Get mutable access to the runtime context of a registered driver
+    pub fn context_mut(&mut self, name: &str) -> Option<&mut DeviceContext> {
+        self.drivers.get_mut(name).map(|e| &mut e.context)
+    }
+
     /// Get information about all registered drivers
     pub fn list_drivers(&self) -> Vec<(DriverInfo, bool)> {
         self.drivers
@@ -151,6 +276,94 @@ impl DriverRegistry {
         self.drivers.remove(name);
         Ok(())
     }
+
+    /// Move a driver, attached or not, to a registry of its own that has the
+    /// same notifier, configuration directory and task sender.
+    ///
+    /// Servers use this to lock each driver separately, so a slow driver
+    /// doesn't hold up the others.
+    pub fn split_off(&mut self, name: &str) -> Result<DriverRegistry> {
+        let entry = self
+            .drivers
+            .remove(name)
+            .ok_or_else(|| IndigoError::DriverNotFound(name.to_string()))?;
+        Ok(Self {
+            drivers: HashMap::from([(name.to_string(), entry)]),
+            notifier: self.notifier.clone(),
+            config_dir: self.config_dir.clone(),
+            task_sender: self.task_sender.clone(),
+        })
+    }
+}
+
+impl DriverEntry {
+    /// Validate a property change and route it to the driver, setting the
+    /// property to `Alert` if it fails.
+    async fn change(&mut self, property: &Property) -> Result<()> {
+        let result = match self.context.properties().validate_change(property) {
+            Ok(merged) => {
+                self.driver
+                    .change_property(&mut self.context, &merged)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &result {
+            // The driver may have deleted the property
+            let _ = self
+                .context
+                .property_manager()
+                .alert_property(&property.name, e.to_string());
+        }
+        result
+    }
+
+    /// Route configuration requests to the driver, logging those that fail.
+    async fn apply_config(&mut self, requests: Vec<Property>) {
+        for request in requests {
+            if let Err(e) = self.change(&request).await {
+                tracing::warn!(
+                    "Could not apply configuration of {}.{}: {}",
+                    request.device,
+                    request.name,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Handle a change of the `CONFIG` property.
+    async fn change_config(&mut self, property: &Property) -> Result<()> {
+        let merged = self.context.properties().validate_change(property)?;
+        let on = |item: &str| {
+            merged.items.get(item).map(|i| &i.value)
+                == Some(&PropertyValue::switch(SwitchState::On))
+        };
+
+        let manager = self.context.properties();
+        if on(name::CONFIG_SAVE_ITEM) {
+            manager.save_config()?;
+        } else if on(name::CONFIG_LOAD_ITEM) {
+            let requests = manager.load_config()?;
+            self.apply_config(requests).await;
+        } else if on(CONFIG_DEFAULT_ITEM) {
+            let requests = manager.default_config();
+            self.apply_config(requests).await;
+        } else if on(name::CONFIG_REMOVE_ITEM) {
+            manager.remove_config()?;
+        }
+
+        let values = merged
+            .items
+            .keys()
+            .map(|item| (item.clone(), PropertyValue::switch(SwitchState::Off)))
+            .collect();
+        self.context.property_manager().update_property(
+            name::CONFIG_PROPERTY,
+            PropertyState::Ok,
+            values,
+        )
+    }
 }
 
 impl Default for DriverRegistry {
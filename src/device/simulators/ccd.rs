@@ -0,0 +1,433 @@
+//! Simulated CCD camera.
+
+use super::simulator_device;
+use crate::device::traits::{BinningMode, Camera, CcdInfo, ExposureState, FrameType};
+use crate::device::DeviceInterface;
+use crate::error::{IndigoError, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f64::consts::PI;
+use tokio::time::Instant;
+
+/// Sensor width of a new camera in pixels.
+const WIDTH: u32 = 1280;
+/// Sensor height of a new camera in pixels.
+const HEIGHT: u32 = 1024;
+/// Pixel size in microns.
+const PIXEL_SIZE: f64 = 5.2;
+/// Highest binning on either axis.
+const MAX_BIN: u32 = 4;
+/// Ambient temperature in Celsius.
+const AMBIENT_TEMPERATURE: f64 = 20.0;
+/// Sensor pixels per star.
+const PIXELS_PER_STAR: u32 = 5000;
+/// Standard deviation of the star images in pixels.
+const STAR_SIGMA: f64 = 1.5;
+/// Sky background in electrons per second and pixel.
+const SKY_RATE: f64 = 20.0;
+/// Dark current at ambient temperature in electrons per second and pixel,
+/// halving for every [`DARK_HALVING`] degrees of cooling.
+const DARK_RATE: f64 = 1.0;
+/// Degrees of cooling that halve the dark current.
+const DARK_HALVING: f64 = 6.0;
+/// Illumination of flat frames in electrons per second and pixel.
+const FLAT_RATE: f64 = 20000.0;
+/// Read noise in electrons.
+const READ_NOISE: f64 = 5.0;
+
+/// A star of the simulated field, in sensor pixels.
+#[derive(Debug, Clone, Copy)]
+struct Star {
+    x: f64,
+    y: f64,
+    /// Brightness in electrons per second
+    flux: f64,
+}
+
+/// Exposure in progress or completed.
+#[derive(Debug, Clone, Copy)]
+struct Exposure {
+    duration: f64,
+    started: Instant,
+    aborted: bool,
+}
+
+/// Simulated CCD camera.
+///
+/// Light frames show a fixed field of stars on a sky background; the
+/// collected signal, the sky and the dark current grow with the exposure
+/// time, and every frame has shot and read noise. Images are downloaded as
+/// 16 bit FITS files, honouring the frame, binning, gain and offset.
+pub struct CcdSimulator {
+    name: String,
+    connected: bool,
+    info: CcdInfo,
+    stars: Vec<Star>,
+    frame: (u32, u32, u32, u32),
+    binning: BinningMode,
+    frame_type: FrameType,
+    exposure: Option<Exposure>,
+    target_temperature: f64,
+    cooler: bool,
+    gain: f64,
+    offset: f64,
+}
+
+impl CcdSimulator {
+    /// Create a simulated camera with a 1280×1024 sensor
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            connected: false,
+            info: CcdInfo {
+                width: WIDTH,
+                height: HEIGHT,
+                pixel_size: PIXEL_SIZE,
+                max_bin_x: MAX_BIN,
+                max_bin_y: MAX_BIN,
+                bits_per_pixel: 16,
+            },
+            stars: star_field(WIDTH, HEIGHT, 0),
+            frame: (0, 0, WIDTH, HEIGHT),
+            binning: BinningMode::default(),
+            frame_type: FrameType::Light,
+            exposure: None,
+            target_temperature: AMBIENT_TEMPERATURE,
+            cooler: false,
+            gain: 100.0,
+            offset: 100.0,
+        }
+    }
+
+    /// Set the sensor size in pixels, resetting the frame to the full sensor
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.info.width = width;
+        self.info.height = height;
+        self.frame = (0, 0, width, height);
+        self.stars = star_field(width, height, 0);
+        self
+    }
+
+    /// Set the seed of the star field
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.stars = star_field(self.info.width, self.info.height, seed);
+        self
+    }
+
+    /// Gets the sensor temperature in Celsius.
+    fn sensor_temperature(&self) -> f64 {
+        if self.cooler {
+            self.target_temperature
+        } else {
+            AMBIENT_TEMPERATURE
+        }
+    }
+
+    /// Renders the frame in electrons per binned pixel, without noise.
+    fn render(&self, duration: f64) -> (usize, usize, Vec<f64>) {
+        let (left, top, width, height) = self.frame;
+        let (bin_x, bin_y) = (self.binning.x, self.binning.y);
+        let (columns, rows) = ((width / bin_x) as usize, (height / bin_y) as usize);
+        let binned = (bin_x * bin_y) as f64;
+
+        let dark =
+            DARK_RATE * 2f64.powf((self.sensor_temperature() - AMBIENT_TEMPERATURE) / DARK_HALVING);
+        let background = match self.frame_type {
+            FrameType::Bias => 0.0,
+            FrameType::Dark => dark * duration,
+            FrameType::Light => (SKY_RATE + dark) * duration,
+            FrameType::Flat => (FLAT_RATE + dark) * duration,
+        };
+        let mut pixels = vec![background * binned; columns * rows];
+        if self.frame_type != FrameType::Light {
+            return (columns, rows, pixels);
+        }
+
+        let reach = (STAR_SIGMA * 4.0).ceil() as i64;
+        let norm = 1.0 / (2.0 * PI * STAR_SIGMA * STAR_SIGMA);
+        for star in &self.stars {
+            let (cx, cy) = (star.x.round() as i64, star.y.round() as i64);
+            for y in cy - reach..=cy + reach {
+                for x in cx - reach..=cx + reach {
+                    let (fx, fy) = (x - left as i64, y - top as i64);
+                    if fx < 0 || fy < 0 {
+                        continue;
+                    }
+                    let (column, row) =
+                        ((fx as u32 / bin_x) as usize, (fy as u32 / bin_y) as usize);
+                    if column >= columns || row >= rows {
+                        continue;
+                    }
+                    let (dx, dy) = (x as f64 + 0.5 - star.x, y as f64 + 0.5 - star.y);
+                    let weight =
+                        norm * (-(dx * dx + dy * dy) / (2.0 * STAR_SIGMA * STAR_SIGMA)).exp();
+                    pixels[row * columns + column] += star.flux * duration * weight;
+                }
+            }
+        }
+        (columns, rows, pixels)
+    }
+}
+
+impl Default for CcdSimulator {
+    fn default() -> Self {
+        Self::new("CCD Simulator")
+    }
+}
+
+simulator_device!(CcdSimulator, DeviceInterface::Ccd, "CCD Simulator");
+
+/// Generates a random field of stars for a sensor.
+fn star_field(width: u32, height: u32, seed: u64) -> Vec<Star> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let count = (width as u64 * height as u64 / PIXELS_PER_STAR as u64).max(1);
+    (0..count)
+        .map(|_| Star {
+            x: rng.gen_range(0.0..width.max(1) as f64),
+            y: rng.gen_range(0.0..height.max(1) as f64),
+            // Faint stars are far more common than bright ones
+            flux: 10f64.powf(rng.gen_range(2.0..5.0)),
+        })
+        .collect()
+}
+
+/// Draws a normally distributed number with the Box-Muller transform.
+fn gaussian(rng: &mut impl Rng) -> f64 {
+    let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+/// Appends a FITS header card, with strings starting in column 11 and other
+/// values ending in column 30.
+fn card(header: &mut String, keyword: &str, value: &str) {
+    let card = if value.starts_with('\'') {
+        format!("{:<8}= {}", keyword, value)
+    } else {
+        format!("{:<8}= {:>20}", keyword, value)
+    };
+    header.push_str(&format!("{:<80.80}", card));
+}
+
+/// Encodes pixels as a 16 bit FITS image.
+fn fits(columns: usize, rows: usize, pixels: &[u16], keywords: &[(&str, String)]) -> Vec<u8> {
+    const BLOCK: usize = 2880;
+    let mut header = String::new();
+    card(&mut header, "SIMPLE", "T");
+    card(&mut header, "BITPIX", "16");
+    card(&mut header, "NAXIS", "2");
+    card(&mut header, "NAXIS1", &columns.to_string());
+    card(&mut header, "NAXIS2", &rows.to_string());
+    card(&mut header, "BZERO", "32768");
+    card(&mut header, "BSCALE", "1");
+    for (keyword, value) in keywords {
+        card(&mut header, keyword, value);
+    }
+    header.push_str(&format!("{:<80}", "END"));
+    let padded = header.len().div_ceil(BLOCK) * BLOCK;
+    header.push_str(&" ".repeat(padded - header.len()));
+
+    let mut data = header.into_bytes();
+    for pixel in pixels {
+        // Unsigned values are stored as signed with BZERO
+        data.extend_from_slice(&((*pixel as i32 - 32768) as i16).to_be_bytes());
+    }
+    data.resize(data.len().div_ceil(BLOCK) * BLOCK, 0);
+    data
+}
+
+#[async_trait::async_trait]
+impl Camera for CcdSimulator {
+    async fn ccd_info(&self) -> Result<CcdInfo> {
+        Ok(self.info.clone())
+    }
+
+    async fn start_exposure(&mut self, duration: f64) -> Result<()> {
+        if duration.is_nan() || duration < 0.0 {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Invalid exposure time {}",
+                duration
+            )));
+        }
+        if matches!(self.exposure_state().await?, ExposureState::Exposing(_)) {
+            return Err(IndigoError::InvalidState(format!(
+                "{} is already exposing",
+                self.name
+            )));
+        }
+        self.exposure = Some(Exposure {
+            duration,
+            started: Instant::now(),
+            aborted: false,
+        });
+        Ok(())
+    }
+
+    async fn abort_exposure(&mut self) -> Result<()> {
+        if let Some(exposure) = &mut self.exposure {
+            exposure.aborted = true;
+        }
+        Ok(())
+    }
+
+    async fn exposure_state(&self) -> Result<ExposureState> {
+        Ok(match self.exposure {
+            None => ExposureState::Idle,
+            Some(exposure) if exposure.aborted => ExposureState::Aborted,
+            Some(exposure) => {
+                let remaining = exposure.duration - exposure.started.elapsed().as_secs_f64();
+                if remaining > 0.0 {
+                    ExposureState::Exposing(remaining)
+                } else {
+                    ExposureState::Complete
+                }
+            }
+        })
+    }
+
+    async fn download_image(&self) -> Result<Vec<u8>> {
+        let duration = match (self.exposure, self.exposure_state().await?) {
+            (Some(exposure), ExposureState::Complete) => exposure.duration,
+            _ => {
+                return Err(IndigoError::InvalidState(format!(
+                    "{} has no image to download",
+                    self.name
+                )))
+            }
+        };
+        let (columns, rows, electrons) = self.render(duration);
+
+        let mut rng = StdRng::from_entropy();
+        let adu_per_electron = self.gain / 100.0;
+        let max = ((1u32 << self.info.bits_per_pixel.min(16)) - 1) as f64;
+        let pixels: Vec<u16> = electrons
+            .iter()
+            .map(|signal| {
+                let noise = (signal + READ_NOISE * READ_NOISE).sqrt() * gaussian(&mut rng);
+                let adu = self.offset + (signal + noise) * adu_per_electron;
+                adu.round().clamp(0.0, max) as u16
+            })
+            .collect();
+
+        let image_type = match self.frame_type {
+            FrameType::Light => "Light Frame",
+            FrameType::Bias => "Bias Frame",
+            FrameType::Dark => "Dark Frame",
+            FrameType::Flat => "Flat Field",
+        };
+        Ok(fits(
+            columns,
+            rows,
+            &pixels,
+            &[
+                ("EXPTIME", format!("{:.3}", duration)),
+                ("IMAGETYP", format!("'{:<8}'", image_type)),
+                ("XBINNING", self.binning.x.to_string()),
+                ("YBINNING", self.binning.y.to_string()),
+                (
+                    "XPIXSZ",
+                    format!("{:.2}", self.info.pixel_size * self.binning.x as f64),
+                ),
+                (
+                    "YPIXSZ",
+                    format!("{:.2}", self.info.pixel_size * self.binning.y as f64),
+                ),
+                ("CCD-TEMP", format!("{:.1}", self.sensor_temperature())),
+                ("GAIN", format!("{:.0}", self.gain)),
+                ("OFFSET", format!("{:.0}", self.offset)),
+                (
+                    "INSTRUME",
+                    format!("'{:<8}'", self.name.replace('\'', "''")),
+                ),
+            ],
+        ))
+    }
+
+    async fn set_frame_type(&mut self, frame_type: FrameType) -> Result<()> {
+        self.frame_type = frame_type;
+        Ok(())
+    }
+
+    async fn frame_type(&self) -> Result<FrameType> {
+        Ok(self.frame_type)
+    }
+
+    async fn set_binning(&mut self, binning: BinningMode) -> Result<()> {
+        if !(1..=self.info.max_bin_x).contains(&binning.x)
+            || !(1..=self.info.max_bin_y).contains(&binning.y)
+        {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Binning {}x{} is not supported",
+                binning.x, binning.y
+            )));
+        }
+        self.binning = binning;
+        Ok(())
+    }
+
+    async fn binning(&self) -> Result<BinningMode> {
+        Ok(self.binning)
+    }
+
+    async fn set_frame(&mut self, x: u32, y: u32, width: u32, height: u32) -> Result<()> {
+        if width == 0
+            || height == 0
+            || x.saturating_add(width) > self.info.width
+            || y.saturating_add(height) > self.info.height
+        {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Frame {}x{} at {},{} is outside the sensor",
+                width, height, x, y
+            )));
+        }
+        self.frame = (x, y, width, height);
+        Ok(())
+    }
+
+    async fn frame(&self) -> Result<(u32, u32, u32, u32)> {
+        Ok(self.frame)
+    }
+
+    async fn set_temperature(&mut self, target: f64) -> Result<()> {
+        self.target_temperature = target;
+        Ok(())
+    }
+
+    async fn temperature(&self) -> Result<f64> {
+        Ok(self.sensor_temperature())
+    }
+
+    async fn set_cooler(&mut self, enabled: bool) -> Result<()> {
+        self.cooler = enabled;
+        Ok(())
+    }
+
+    async fn cooler_enabled(&self) -> Result<bool> {
+        Ok(self.cooler)
+    }
+
+    async fn cooler_power(&self) -> Result<f64> {
+        if !self.cooler {
+            return Ok(0.0);
+        }
+        Ok(((AMBIENT_TEMPERATURE - self.target_temperature) * 2.0).clamp(0.0, 100.0))
+    }
+
+    async fn set_gain(&mut self, gain: f64) -> Result<()> {
+        self.gain = gain;
+        Ok(())
+    }
+
+    async fn gain(&self) -> Result<f64> {
+        Ok(self.gain)
+    }
+
+    async fn set_offset(&mut self, offset: f64) -> Result<()> {
+        self.offset = offset;
+        Ok(())
+    }
+
+    async fn offset(&self) -> Result<f64> {
+        Ok(self.offset)
+    }
+}
@@ -0,0 +1,228 @@
+//! Simulated focuser.
+
+use super::{simulator_device, Motion};
+use crate::device::traits::{Focuser, FocuserInfo};
+use crate::device::DeviceInterface;
+use crate::error::{IndigoError, Result};
+
+/// Travel of a new focuser in steps.
+const MAX_POSITION: u32 = 10000;
+/// Mechanical play of a new focuser in steps.
+const PLAY: u32 = 40;
+/// Steps per second at the lowest speed.
+const MIN_STEP_RATE: f64 = 100.0;
+/// Steps per second at the highest speed.
+const MAX_STEP_RATE: f64 = 5000.0;
+/// Ambient temperature in Celsius.
+const TEMPERATURE: f64 = 15.0;
+
+/// Move of the focuser motor.
+#[derive(Debug, Clone, Copy)]
+struct Move {
+    /// Position, motor and drawtube at the start of the move
+    from: (u32, f64, f64),
+    /// Steps added to compensate for the backlash
+    compensation: f64,
+    outward: bool,
+    /// Steps of the motor, including the compensation
+    motion: Motion,
+}
+
+/// Simulated focuser with mechanical backlash.
+///
+/// The drawtube only follows the motor once the [`play`](Self::with_play) of
+/// the gears is taken up, so after a change of direction it moves less than
+/// the position changes. The [backlash](Focuser::set_backlash) setting
+/// compensates for this by turning the motor further on a change of
+/// direction; when it matches the play, the drawtube moves as far as the
+/// position changes. Moves take time at the [speed](Focuser::set_speed) of
+/// the motor, and an aborted move stops where it is.
+pub struct FocuserSimulator {
+    name: String,
+    connected: bool,
+    max_position: u32,
+    play: f64,
+    /// Position reported to clients
+    position: u32,
+    /// Position of the motor, which includes the backlash compensation
+    motor: f64,
+    /// Position of the drawtube, within `play` below the motor
+    drawtube: f64,
+    /// Whether the last move was outward
+    outward: Option<bool>,
+    /// Move in progress
+    moving: Option<Move>,
+    backlash: u32,
+    speed: f64,
+    compensation: bool,
+}
+
+impl FocuserSimulator {
+    /// Create a simulated focuser at the middle of its travel
+    pub fn new(name: impl Into<String>) -> Self {
+        let position = MAX_POSITION / 2;
+        Self {
+            name: name.into(),
+            connected: false,
+            max_position: MAX_POSITION,
+            play: PLAY as f64,
+            position,
+            motor: position as f64,
+            drawtube: position as f64,
+            outward: None,
+            moving: None,
+            backlash: 0,
+            speed: 1.0,
+            compensation: false,
+        }
+    }
+
+    /// Set the mechanical play of the gears in steps
+    pub fn with_play(mut self, steps: u32) -> Self {
+        self.play = steps as f64;
+        self
+    }
+
+    /// Get the position of the drawtube, which lags the motor by up to the
+    /// play of the gears
+    pub fn drawtube_position(&self) -> f64 {
+        self.current().2
+    }
+
+    /// Gets the position, motor and drawtube including the move in progress.
+    fn current(&self) -> (u32, f64, f64) {
+        let Some(moving) = &self.moving else {
+            return (self.position, self.motor, self.drawtube);
+        };
+        let (position, motor, drawtube) = moving.from;
+        let steps = moving.motion.covered();
+        let moved = (steps - moving.compensation).max(0.0).round() as u32;
+        // The motor pushes the drawtube at one end of the play
+        if moving.outward {
+            let motor = motor + steps;
+            (position + moved, motor, drawtube.max(motor - self.play))
+        } else {
+            let motor = motor - steps;
+            (position - moved, motor, drawtube.min(motor))
+        }
+    }
+
+    /// Ends the move in progress where it is.
+    fn stop(&mut self) {
+        (self.position, self.motor, self.drawtube) = self.current();
+        self.moving = None;
+    }
+
+    /// Gets the motor speed in steps per second.
+    fn step_rate(&self) -> f64 {
+        MIN_STEP_RATE + (MAX_STEP_RATE - MIN_STEP_RATE) * self.speed
+    }
+}
+
+impl Default for FocuserSimulator {
+    fn default() -> Self {
+        Self::new("Focuser Simulator")
+    }
+}
+
+simulator_device!(
+    FocuserSimulator,
+    DeviceInterface::Focuser,
+    "Focuser Simulator"
+);
+
+#[async_trait::async_trait]
+impl Focuser for FocuserSimulator {
+    async fn focuser_info(&self) -> Result<FocuserInfo> {
+        Ok(FocuserInfo {
+            max_position: self.max_position,
+            has_absolute: true,
+            has_temperature_compensation: true,
+        })
+    }
+
+    async fn move_to(&mut self, position: u32) -> Result<()> {
+        if position > self.max_position {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Position {} is beyond {}",
+                position, self.max_position
+            )));
+        }
+        self.stop();
+        if position == self.position {
+            return Ok(());
+        }
+
+        let outward = position > self.position;
+        let compensation = if self.outward.is_some_and(|last| last != outward) {
+            self.backlash as f64
+        } else {
+            0.0
+        };
+        let steps = (position as f64 - self.position as f64).abs() + compensation;
+        self.moving = Some(Move {
+            from: (self.position, self.motor, self.drawtube),
+            compensation,
+            outward,
+            motion: Motion::start(steps, self.step_rate()),
+        });
+        self.outward = Some(outward);
+        Ok(())
+    }
+
+    async fn move_relative(&mut self, steps: i32) -> Result<()> {
+        self.stop();
+        let position = (self.position as i64 + steps as i64).clamp(0, self.max_position as i64);
+        self.move_to(position as u32).await
+    }
+
+    async fn abort_move(&mut self) -> Result<()> {
+        self.stop();
+        Ok(())
+    }
+
+    async fn position(&self) -> Result<u32> {
+        Ok(self.current().0)
+    }
+
+    async fn is_moving(&self) -> Result<bool> {
+        Ok(self.moving.is_some_and(|moving| !moving.motion.is_done()))
+    }
+
+    async fn temperature(&self) -> Result<f64> {
+        Ok(TEMPERATURE)
+    }
+
+    async fn set_temperature_compensation(&mut self, enabled: bool) -> Result<()> {
+        self.compensation = enabled;
+        Ok(())
+    }
+
+    async fn temperature_compensation(&self) -> Result<bool> {
+        Ok(self.compensation)
+    }
+
+    async fn set_backlash(&mut self, steps: u32) -> Result<()> {
+        self.backlash = steps;
+        Ok(())
+    }
+
+    async fn backlash(&self) -> Result<u32> {
+        Ok(self.backlash)
+    }
+
+    async fn set_speed(&mut self, speed: f64) -> Result<()> {
+        if !(0.0..=1.0).contains(&speed) {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Speed {} is not between 0 and 1",
+                speed
+            )));
+        }
+        self.speed = speed;
+        Ok(())
+    }
+
+    async fn speed(&self) -> Result<f64> {
+        Ok(self.speed)
+    }
+}
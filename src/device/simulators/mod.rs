@@ -0,0 +1,129 @@
+//! Simulated devices for developing and testing without hardware.
+//!
+//! The simulators implement the high-level device [`traits`](super::traits)
+//! and behave like real hardware in time: the [`CcdSimulator`] renders a
+//! synthetic star field whose signal grows with the exposure time, the
+//! [`MountSimulator`] slews at a finite rate, the [`FocuserSimulator`] has
+//! mechanical backlash, and the [`FilterWheelSimulator`] takes time to turn.
+//! Motion starts at once and reports its progress until it ends or is
+//! aborted. They are hosted as drivers by the adapters, like any other
+//! device.
+//!
+//! # Example
+//!
+//! ```
+//! use libindigo::device::{
+//!     CameraDriver, CcdSimulator, DriverRegistry, FilterWheelDriver, FilterWheelSimulator,
+//!     FocuserDriver, FocuserSimulator, MountDriver, MountSimulator,
+//! };
+//!
+//! let mut registry = DriverRegistry::new();
+//! registry.register(Box::new(CameraDriver::new(CcdSimulator::default())))?;
+//! registry.register(Box::new(MountDriver::new(MountSimulator::default())))?;
+//! registry.register(Box::new(FocuserDriver::new(FocuserSimulator::default())))?;
+//! registry.register(Box::new(FilterWheelDriver::new(FilterWheelSimulator::default())))?;
+//! # Ok::<(), libindigo::error::IndigoError>(())
+//! ```
+
+mod ccd;
+mod focuser;
+mod mount;
+mod wheel;
+
+pub use ccd::CcdSimulator;
+pub use focuser::FocuserSimulator;
+pub use mount::MountSimulator;
+pub use wheel::FilterWheelSimulator;
+
+/// Driver version reported by the simulators.
+const SIMULATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Implements [`Device`](crate::device::traits::Device) for a simulator with
+/// `name` and `connected` fields.
+macro_rules! simulator_device {
+    ($simulator:ty, $interface:expr, $description:expr) => {
+        #[async_trait::async_trait]
+        impl crate::device::traits::Device for $simulator {
+            fn name(&self) -> &str {
+                &self.name
+            }
+
+            fn device_type(&self) -> crate::device::DeviceInterface {
+                $interface
+            }
+
+            async fn connect(&mut self) -> crate::error::Result<()> {
+                self.connected = true;
+                Ok(())
+            }
+
+            async fn disconnect(&mut self) -> crate::error::Result<()> {
+                self.connected = false;
+                Ok(())
+            }
+
+            fn is_connected(&self) -> bool {
+                self.connected
+            }
+
+            fn description(&self) -> Option<&str> {
+                Some($description)
+            }
+
+            fn driver_version(&self) -> Option<&str> {
+                Some(super::SIMULATOR_VERSION)
+            }
+
+            async fn get_property(
+                &self,
+                _name: &str,
+            ) -> crate::error::Result<Option<crate::types::Property>> {
+                Ok(None)
+            }
+
+            async fn get_properties(&self) -> crate::error::Result<Vec<crate::types::Property>> {
+                Ok(Vec::new())
+            }
+
+            async fn wait_for_property_state(
+                &self,
+                name: &str,
+                _state: crate::types::PropertyState,
+                _timeout: std::time::Duration,
+            ) -> crate::error::Result<crate::types::Property> {
+                Err(crate::error::IndigoError::NotSupported(name.to_string()))
+            }
+        }
+    };
+}
+use simulator_device;
+
+/// Motion over a distance at a constant rate, started at an instant.
+#[derive(Debug, Clone, Copy)]
+struct Motion {
+    since: tokio::time::Instant,
+    distance: f64,
+    /// Rate in units of distance per second
+    rate: f64,
+}
+
+impl Motion {
+    /// Starts a motion now.
+    fn start(distance: f64, rate: f64) -> Self {
+        Self {
+            since: tokio::time::Instant::now(),
+            distance,
+            rate,
+        }
+    }
+
+    /// Gets the distance covered so far.
+    fn covered(&self) -> f64 {
+        (self.since.elapsed().as_secs_f64() * self.rate).min(self.distance)
+    }
+
+    /// Whether the whole distance is covered.
+    fn is_done(&self) -> bool {
+        self.covered() >= self.distance
+    }
+}
@@ -0,0 +1,353 @@
+//! Simulated equatorial mount.
+
+use super::{simulator_device, Motion};
+use crate::device::traits::{
+    AxisDirection, Coordinates, Mount, MountAxis, MountType, SlewRate, TrackingMode,
+};
+use crate::device::DeviceInterface;
+use crate::error::{IndigoError, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
+
+/// Slew rate of a new mount in degrees per second.
+const SLEW_RATE: f64 = 5.0;
+/// Sidereal rate in degrees per second.
+const SIDEREAL_RATE: f64 = 360.0 / 86164.0905;
+/// Location of a new mount (latitude, longitude in degrees).
+const LOCATION: (f64, f64) = (51.48, 0.0);
+/// Unix time of the J2000 epoch.
+const J2000_UNIX: f64 = 946_728_000.0;
+
+/// Manual motion of an axis.
+#[derive(Debug, Clone, Copy)]
+struct AxisMotion {
+    /// Signed rate in degrees per second
+    rate: f64,
+    since: Instant,
+}
+
+/// Slew to coordinates.
+#[derive(Debug, Clone, Copy)]
+struct Slew {
+    from: Coordinates,
+    /// Signed angles to move in right ascension and declination, in degrees
+    angles: (f64, f64),
+    motion: Motion,
+    /// Whether the mount parks at the end of the slew
+    parking: bool,
+}
+
+impl Slew {
+    /// Gets the coordinates reached so far, each axis moving until it has
+    /// covered its angle.
+    fn current(&self) -> Coordinates {
+        let covered = self.motion.covered();
+        let moved = |angle: f64| angle.signum() * covered.min(angle.abs());
+        normalize(Coordinates::new(
+            self.from.ra + moved(self.angles.0) / 15.0,
+            self.from.dec + moved(self.angles.1),
+        ))
+    }
+}
+
+/// Simulated equatorial mount.
+///
+/// Slews move both axes at the [slew rate](Self::with_slew_rate), so a slew
+/// takes as long as the larger of the two angles needs; an aborted slew
+/// stops where it is, and parking completes once the mount arrives at the
+/// park position. Manual motion moves
+/// an axis until it is stopped, at a multiple of the sidereal rate for the
+/// guide and centering rates and at a fraction of the slew rate otherwise.
+/// While tracking, the mount stays on its coordinates.
+pub struct MountSimulator {
+    name: String,
+    connected: bool,
+    slew_rate: f64,
+    /// Coordinates, apart from the slew or manual motion in progress
+    position: Coordinates,
+    slew: Option<Slew>,
+    motion: [Option<AxisMotion>; 2],
+    tracking: TrackingMode,
+    parked: bool,
+    park_position: Coordinates,
+    location: (f64, f64),
+}
+
+impl MountSimulator {
+    /// Create a simulated mount, parked at the celestial pole
+    pub fn new(name: impl Into<String>) -> Self {
+        let park_position = Coordinates::new(0.0, 90.0);
+        Self {
+            name: name.into(),
+            connected: false,
+            slew_rate: SLEW_RATE,
+            position: park_position,
+            slew: None,
+            motion: [None, None],
+            tracking: TrackingMode::Off,
+            parked: true,
+            park_position,
+            location: LOCATION,
+        }
+    }
+
+    /// Set the slew rate in degrees per second
+    pub fn with_slew_rate(mut self, degrees_per_second: f64) -> Self {
+        self.slew_rate = degrees_per_second;
+        self
+    }
+
+    /// Whether the mount is parked, or has reached the park position.
+    fn parked(&self) -> bool {
+        self.parked
+            || self
+                .slew
+                .is_some_and(|slew| slew.parking && slew.motion.is_done())
+    }
+
+    /// Returns an error if the mount is parked.
+    fn ensure_unparked(&self) -> Result<()> {
+        if self.parked() {
+            return Err(IndigoError::InvalidState(format!(
+                "{} is parked",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Gets the rate of manual motion in degrees per second.
+    fn axis_rate(&self, rate: SlewRate) -> f64 {
+        match rate {
+            SlewRate::Guide => SIDEREAL_RATE / 2.0,
+            SlewRate::Centering => SIDEREAL_RATE * 8.0,
+            SlewRate::Find => self.slew_rate / 4.0,
+            SlewRate::Max => self.slew_rate,
+        }
+    }
+
+    /// Gets the coordinates including the slew or manual motion in progress.
+    fn current(&self) -> Coordinates {
+        if let Some(slew) = &self.slew {
+            return slew.current();
+        }
+        let moved = |axis: usize| {
+            self.motion[axis].map_or(0.0, |m| m.rate * m.since.elapsed().as_secs_f64())
+        };
+        normalize(Coordinates::new(
+            self.position.ra + moved(0) / 15.0,
+            self.position.dec + moved(1),
+        ))
+    }
+
+    /// Ends the slew in progress at the current coordinates, parking the
+    /// mount if it reached the park position.
+    fn end_slew(&mut self) {
+        self.parked = self.parked();
+        if let Some(slew) = self.slew.take() {
+            self.position = slew.current();
+        }
+    }
+
+    /// Ends the slew and the manual motion of all axes at the current
+    /// coordinates.
+    fn stop_motion(&mut self) {
+        self.end_slew();
+        self.position = self.current();
+        self.motion = [None, None];
+    }
+
+    /// Starts moving to new coordinates at the slew rate.
+    fn slew(&mut self, target: Coordinates, parking: bool) {
+        self.stop_motion();
+        let mut ra = (target.ra - self.position.ra).rem_euclid(24.0);
+        if ra > 12.0 {
+            ra -= 24.0;
+        }
+        let angles = (ra * 15.0, target.dec - self.position.dec);
+        self.slew = Some(Slew {
+            from: self.position,
+            angles,
+            motion: Motion::start(angles.0.abs().max(angles.1.abs()), self.slew_rate),
+            parking,
+        });
+    }
+
+    /// Gets the local sidereal time in hours.
+    fn local_sidereal_time(&self) -> f64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64());
+        let days = (now - J2000_UNIX) / 86400.0;
+        (18.697374558 + 24.06570982441908 * days + self.location.1 / 15.0).rem_euclid(24.0)
+    }
+}
+
+impl Default for MountSimulator {
+    fn default() -> Self {
+        Self::new("Mount Simulator")
+    }
+}
+
+simulator_device!(MountSimulator, DeviceInterface::Mount, "Mount Simulator");
+
+/// Wraps right ascension into 0..24 hours and clamps declination to the
+/// poles.
+fn normalize(coords: Coordinates) -> Coordinates {
+    Coordinates::new(coords.ra.rem_euclid(24.0), coords.dec.clamp(-90.0, 90.0))
+}
+
+#[async_trait::async_trait]
+impl Mount for MountSimulator {
+    async fn mount_type(&self) -> Result<MountType> {
+        Ok(MountType::Equatorial)
+    }
+
+    async fn slew_to(&mut self, coords: Coordinates) -> Result<()> {
+        self.ensure_unparked()?;
+        if !(-90.0..=90.0).contains(&coords.dec) {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Declination {} is out of range",
+                coords.dec
+            )));
+        }
+        self.slew(coords, false);
+        Ok(())
+    }
+
+    async fn slew_to_altaz(&mut self, alt: f64, az: f64) -> Result<()> {
+        let (latitude, alt, az) = (
+            self.location.0.to_radians(),
+            alt.to_radians(),
+            az.to_radians(),
+        );
+        let dec = (alt.sin() * latitude.sin() + alt.cos() * latitude.cos() * az.cos()).asin();
+        let hour_angle = (-az.sin() * alt.cos())
+            .atan2(alt.sin() * latitude.cos() - alt.cos() * latitude.sin() * az.cos());
+        let ra = self.local_sidereal_time() - hour_angle.to_degrees() / 15.0;
+        self.slew_to(normalize(Coordinates::new(ra, dec.to_degrees())))
+            .await
+    }
+
+    async fn sync_to(&mut self, coords: Coordinates) -> Result<()> {
+        self.stop_motion();
+        self.position = normalize(coords);
+        Ok(())
+    }
+
+    async fn abort_slew(&mut self) -> Result<()> {
+        self.stop_motion();
+        Ok(())
+    }
+
+    async fn coordinates(&self) -> Result<Coordinates> {
+        Ok(self.current())
+    }
+
+    async fn altaz(&self) -> Result<(f64, f64)> {
+        let coords = self.current();
+        let latitude = self.location.0.to_radians();
+        let hour_angle = ((self.local_sidereal_time() - coords.ra) * 15.0).to_radians();
+        let dec = coords.dec.to_radians();
+        let alt =
+            (dec.sin() * latitude.sin() + dec.cos() * latitude.cos() * hour_angle.cos()).asin();
+        let az = (-hour_angle.sin() * dec.cos())
+            .atan2(dec.sin() * latitude.cos() - dec.cos() * latitude.sin() * hour_angle.cos());
+        Ok((alt.to_degrees(), az.to_degrees().rem_euclid(360.0)))
+    }
+
+    async fn is_slewing(&self) -> Result<bool> {
+        Ok(self.slew.is_some_and(|slew| !slew.motion.is_done()))
+    }
+
+    async fn set_tracking(&mut self, mode: TrackingMode) -> Result<()> {
+        if mode != TrackingMode::Off {
+            self.ensure_unparked()?;
+        }
+        self.tracking = mode;
+        Ok(())
+    }
+
+    async fn tracking(&self) -> Result<TrackingMode> {
+        Ok(self.tracking)
+    }
+
+    async fn is_tracking(&self) -> Result<bool> {
+        Ok(self.tracking != TrackingMode::Off)
+    }
+
+    async fn park(&mut self) -> Result<()> {
+        self.tracking = TrackingMode::Off;
+        self.slew(self.park_position, true);
+        Ok(())
+    }
+
+    async fn unpark(&mut self) -> Result<()> {
+        self.stop_motion();
+        self.parked = false;
+        Ok(())
+    }
+
+    async fn is_parked(&self) -> Result<bool> {
+        Ok(self.parked())
+    }
+
+    async fn set_park_position(&mut self) -> Result<()> {
+        self.park_position = self.current();
+        Ok(())
+    }
+
+    async fn move_axis(
+        &mut self,
+        axis: MountAxis,
+        direction: AxisDirection,
+        rate: SlewRate,
+    ) -> Result<()> {
+        self.ensure_unparked()?;
+        self.end_slew();
+        self.stop_axis(axis).await?;
+        let rate = match direction {
+            AxisDirection::Forward => self.axis_rate(rate),
+            AxisDirection::Reverse => -self.axis_rate(rate),
+        };
+        // Forward moves the primary axis west, towards smaller right ascension
+        let rate = match axis {
+            MountAxis::Primary => -rate,
+            MountAxis::Secondary => rate,
+        };
+        self.motion[axis as usize] = Some(AxisMotion {
+            rate,
+            since: Instant::now(),
+        });
+        Ok(())
+    }
+
+    async fn stop_axis(&mut self, axis: MountAxis) -> Result<()> {
+        let current = self.current();
+        match axis {
+            MountAxis::Primary => self.position.ra = current.ra,
+            MountAxis::Secondary => self.position.dec = current.dec,
+        }
+        self.motion[axis as usize] = None;
+        Ok(())
+    }
+
+    async fn sidereal_time(&self) -> Result<f64> {
+        Ok(self.local_sidereal_time())
+    }
+
+    async fn set_location(&mut self, latitude: f64, longitude: f64) -> Result<()> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(IndigoError::InvalidParameter(format!(
+                "Latitude {} is out of range",
+                latitude
+            )));
+        }
+        self.location = (latitude, longitude);
+        Ok(())
+    }
+
+    async fn location(&self) -> Result<(f64, f64)> {
+        Ok(self.location)
+    }
+}
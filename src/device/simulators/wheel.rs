@@ -0,0 +1,155 @@
+//! Simulated filter wheel.
+
+use super::{simulator_device, Motion};
+use crate::device::traits::{FilterInfo, FilterWheel};
+use crate::device::DeviceInterface;
+use crate::error::{IndigoError, Result};
+use std::time::Duration;
+
+/// Filters of a new wheel.
+const FILTERS: &[&str] = &["Luminance", "Red", "Green", "Blue", "H-alpha"];
+
+/// Time to turn the wheel by one slot of a new wheel.
+const SLOT_DELAY: Duration = Duration::from_millis(500);
+
+/// Simulated filter wheel.
+///
+/// Turning the wheel takes [`slot_delay`](Self::with_slot_delay) for every
+/// slot passed, turning whichever way around is shorter.
+pub struct FilterWheelSimulator {
+    name: String,
+    connected: bool,
+    filters: Vec<FilterInfo>,
+    /// Slot, apart from the turn in progress
+    slot: u32,
+    slot_delay: Duration,
+    /// Turn in progress, forward or backward, over a number of slots
+    turning: Option<(bool, Motion)>,
+}
+
+impl FilterWheelSimulator {
+    /// Create a simulated filter wheel with five filters
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            connected: false,
+            filters: FILTERS
+                .iter()
+                .zip(1..)
+                .map(|(name, slot)| FilterInfo {
+                    slot,
+                    name: name.to_string(),
+                    offset: 0,
+                })
+                .collect(),
+            slot: 1,
+            slot_delay: SLOT_DELAY,
+            turning: None,
+        }
+    }
+
+    /// Set the names of the filters, one slot per name
+    pub fn with_filters(mut self, names: &[&str]) -> Self {
+        self.filters = names
+            .iter()
+            .zip(1..)
+            .map(|(name, slot)| FilterInfo {
+                slot,
+                name: name.to_string(),
+                offset: 0,
+            })
+            .collect();
+        self.slot = 1;
+        self
+    }
+
+    /// Set the time to turn the wheel by one slot
+    pub fn with_slot_delay(mut self, delay: Duration) -> Self {
+        self.slot_delay = delay;
+        self
+    }
+
+    /// Gets the slot including the turn in progress.
+    fn current(&self) -> u32 {
+        let Some((forward, motion)) = &self.turning else {
+            return self.slot;
+        };
+        let count = self.filters.len() as u32;
+        let passed = motion.covered().floor() as u32 % count;
+        let slot = if *forward {
+            self.slot - 1 + passed
+        } else {
+            self.slot - 1 + count - passed
+        };
+        slot % count + 1
+    }
+
+    /// Gets a filter by slot.
+    fn filter_mut(&mut self, slot: u32) -> Result<&mut FilterInfo> {
+        self.filters
+            .iter_mut()
+            .find(|f| f.slot == slot)
+            .ok_or_else(|| IndigoError::InvalidParameter(format!("No filter slot {}", slot)))
+    }
+}
+
+impl Default for FilterWheelSimulator {
+    fn default() -> Self {
+        Self::new("Filter Wheel Simulator")
+    }
+}
+
+simulator_device!(
+    FilterWheelSimulator,
+    DeviceInterface::FilterWheel,
+    "Filter Wheel Simulator"
+);
+
+#[async_trait::async_trait]
+impl FilterWheel for FilterWheelSimulator {
+    async fn slot_count(&self) -> Result<u32> {
+        Ok(self.filters.len() as u32)
+    }
+
+    async fn filters(&self) -> Result<Vec<FilterInfo>> {
+        Ok(self.filters.clone())
+    }
+
+    async fn select_filter(&mut self, slot: u32) -> Result<()> {
+        self.filter_mut(slot)?;
+        self.slot = self.current();
+        let count = self.filters.len() as u32;
+        let forward = (slot + count - self.slot) % count;
+        let slots = forward.min(count - forward);
+        let rate = 1.0 / self.slot_delay.as_secs_f64();
+        self.turning = Some((forward == slots, Motion::start(slots as f64, rate)));
+        Ok(())
+    }
+
+    async fn current_slot(&self) -> Result<u32> {
+        Ok(self.current())
+    }
+
+    async fn current_filter_name(&self) -> Result<String> {
+        Ok(self
+            .filters
+            .iter()
+            .find(|f| f.slot == self.current())
+            .map(|f| f.name.clone())
+            .unwrap_or_default())
+    }
+
+    async fn is_moving(&self) -> Result<bool> {
+        Ok(self.turning.is_some_and(|(_, motion)| !motion.is_done()))
+    }
+
+    async fn set_filter_name(&mut self, slot: u32, name: &str) -> Result<()> {
+        self.filter_mut(slot)?.name = name.to_string();
+        Ok(())
+    }
+
+    async fn set_filter_offset(&mut self, slot: u32, offset: i32) -> Result<()> {
+        self.filter_mut(slot)?.offset = offset;
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+//! Timers and background tasks of device drivers.
+//!
+//! Tasks are started through the [`DeviceContext`](super::DeviceContext) and
+//! run on the tokio runtime. When a timer fires or a background task
+//! completes, a [`TaskEvent`] is sent to the runtime hosting the driver, which
+//! calls [`DeviceDriver::on_task`](super::DeviceDriver::on_task) with the
+//! context, so the driver can update its properties. Tasks are cancelled when
+//! the device disconnects, the driver detaches or the context is dropped.
+
+use crate::error::{IndigoError, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// Sender half of the channel that task events are sent to.
+pub type TaskSender = mpsc::UnboundedSender<TaskEvent>;
+
+/// Identifies a timer or background task of a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+/// A timer of a device fired, or a background task completed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskEvent {
+    /// Device name.
+    pub device: String,
+    /// The task that fired.
+    pub task: TaskId,
+}
+
+/// A running task.
+#[derive(Debug)]
+struct Task {
+    handle: JoinHandle<()>,
+    /// Whether the task fires more than once
+    repeating: bool,
+}
+
+/// Running tasks of a device.
+#[derive(Debug, Default)]
+pub(super) struct Tasks {
+    /// Channel task events are sent to
+    sender: Option<TaskSender>,
+    /// Identifier of the next task
+    next_id: u64,
+    running: HashMap<TaskId, Task>,
+}
+
+impl Tasks {
+    /// Set the channel that task events are sent to.
+    pub(super) fn set_sender(&mut self, sender: Option<TaskSender>) {
+        self.sender = sender;
+    }
+
+    /// Fire a task once after a delay.
+    pub(super) fn set_timer(&mut self, device: &str, delay: Duration) -> Result<TaskId> {
+        self.start(device, false, move |fire| async move {
+            tokio::time::sleep(delay).await;
+            fire();
+        })
+    }
+
+    /// Fire a task every period, starting one period from now.
+    pub(super) fn set_interval(&mut self, device: &str, period: Duration) -> Result<TaskId> {
+        if period.is_zero() {
+            return Err(IndigoError::InvalidParameter(
+                "Task period must not be zero".to_string(),
+            ));
+        }
+        self.start(device, true, move |fire| async move {
+            let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                fire();
+            }
+        })
+    }
+
+    /// Run a future in the background, firing the task when it completes.
+    pub(super) fn spawn<F>(&mut self, device: &str, future: F) -> Result<TaskId>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.start(device, false, move |fire| async move {
+            future.await;
+            fire();
+        })
+    }
+
+    /// Starts a task, given a function that sends its event.
+    fn start<F, Fut>(&mut self, device: &str, repeating: bool, task: F) -> Result<TaskId>
+    where
+        F: FnOnce(Box<dyn Fn() + Send>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let no_runtime =
+            || IndigoError::InvalidState(format!("{} has no runtime to run tasks", device));
+        let sender = self.sender.clone().ok_or_else(no_runtime)?;
+        let runtime = tokio::runtime::Handle::try_current().map_err(|_| no_runtime())?;
+        let id = TaskId(self.next_id);
+        self.next_id += 1;
+
+        let event = TaskEvent {
+            device: device.to_string(),
+            task: id,
+        };
+        // The receiver is gone when the runtime hosting the driver stopped
+        let fire = Box::new(move || {
+            let _ = sender.send(event.clone());
+        });
+        let handle = runtime.spawn(task(fire));
+        self.running.insert(id, Task { handle, repeating });
+        Ok(id)
+    }
+
+    /// Check if a task has not been cancelled or fired for the last time
+    pub(super) fn is_running(&self, id: TaskId) -> bool {
+        self.running.contains_key(&id)
+    }
+
+    /// Get the number of running tasks
+    pub(super) fn count(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Accept an event of a task, returning whether it is still running.
+    ///
+    /// Tasks that fire once are done after their event.
+    pub(super) fn fired(&mut self, id: TaskId) -> bool {
+        match self.running.get(&id) {
+            Some(task) if task.repeating => true,
+            Some(_) => {
+                self.running.remove(&id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel a task, returning whether it was running.
+    pub(super) fn cancel(&mut self, id: TaskId) -> bool {
+        match self.running.remove(&id) {
+            Some(task) => {
+                task.handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel all tasks.
+    pub(super) fn cancel_all(&mut self) {
+        for (_, task) in self.running.drain() {
+            task.handle.abort();
+        }
+    }
+}
+
+impl Drop for Tasks {
+    fn drop(&mut self) {
+        self.cancel_all();
+    }
+}
@@ -0,0 +1,899 @@
+//! Standard INDIGO property sets for device drivers.
+//!
+//! Each `register_*_standard` method defines the properties INDIGO clients
+//! expect from a device interface, with the names, groups, switch rules and
+//! permissions of the INDIGO drivers. Drivers update the values with
+//! [`PropertyManager::update_property`] as the device changes.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo::device::traits::CcdInfo;
+//!
+//! ctx.property_manager().register_ccd_standard(&CcdInfo {
+//!     width: 1280,
+//!     height: 1024,
+//!     pixel_size: 5.2,
+//!     max_bin_x: 4,
+//!     max_bin_y: 4,
+//!     bits_per_pixel: 16,
+//! })?;
+//! ```
+
+use super::property_manager::PropertyManager;
+use super::traits::{CcdInfo, FilterInfo, FocuserInfo};
+use crate::error::{IndigoError, Result};
+use crate::name;
+use crate::types::{
+    Property, PropertyItem, PropertyPerm, PropertyState, PropertyType, PropertyValue, SwitchRule,
+    SwitchState,
+};
+
+/// Group of the main device properties.
+pub const MAIN_GROUP: &str = "Main";
+/// Group of the camera properties.
+pub const CCD_MAIN_GROUP: &str = "Camera";
+/// Group of the camera image properties.
+pub const CCD_IMAGE_GROUP: &str = "Image";
+/// Group of the mount properties.
+pub const MOUNT_MAIN_GROUP: &str = "Mount";
+/// Group of the focuser properties.
+pub const FOCUSER_MAIN_GROUP: &str = "Focuser";
+/// Group of the filter wheel properties.
+pub const WHEEL_MAIN_GROUP: &str = "Filter wheel";
+/// Group of the guider properties.
+pub const GUIDER_MAIN_GROUP: &str = "Guider";
+
+/// Longest exposure offered by the CCD template, in seconds.
+const MAX_EXPOSURE: f64 = 3600.0;
+/// Largest gain offered by the CCD template.
+const MAX_GAIN: f64 = 1000.0;
+/// Largest offset offered by the CCD template.
+const MAX_OFFSET: f64 = 10000.0;
+/// Largest focuser backlash offered by the focuser template, in steps.
+const MAX_BACKLASH: f64 = 1000.0;
+/// Longest guide pulse offered by the guider template, in milliseconds.
+const MAX_GUIDE_PULSE: f64 = 10000.0;
+
+impl PropertyManager {
+    /// Register the standard CCD properties: `CCD_INFO`, `CCD_EXPOSURE`,
+    /// `CCD_ABORT_EXPOSURE`, `CCD_FRAME`, `CCD_BIN`, `CCD_FRAME_TYPE` and
+    /// `CCD_IMAGE`.
+    pub fn register_ccd_standard(&mut self, info: &CcdInfo) -> Result<()> {
+        let width = info.width as f64;
+        let height = info.height as f64;
+        let bits = info.bits_per_pixel as f64;
+
+        self.define_numbers(
+            name::CCD_INFO_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Info",
+            PropertyPerm::ReadOnly,
+            vec![
+                integer(
+                    name::CCD_INFO_WIDTH_ITEM,
+                    "Horizontal resolution",
+                    width,
+                    0.0,
+                    width,
+                ),
+                integer(
+                    name::CCD_INFO_HEIGHT_ITEM,
+                    "Vertical resolution",
+                    height,
+                    0.0,
+                    height,
+                ),
+                integer(
+                    name::CCD_INFO_MAX_HORIZONTAL_BIN_ITEM,
+                    "Max horizontal binning",
+                    info.max_bin_x as f64,
+                    0.0,
+                    info.max_bin_x as f64,
+                ),
+                integer(
+                    name::CCD_INFO_MAX_VERTICAL_BIN_ITEM,
+                    "Max vertical binning",
+                    info.max_bin_y as f64,
+                    0.0,
+                    info.max_bin_y as f64,
+                ),
+                pixel_size(
+                    name::CCD_INFO_PIXEL_SIZE_ITEM,
+                    "Pixel size",
+                    info.pixel_size,
+                ),
+                pixel_size(
+                    name::CCD_INFO_PIXEL_WIDTH_ITEM,
+                    "Pixel width",
+                    info.pixel_size,
+                ),
+                pixel_size(
+                    name::CCD_INFO_PIXEL_HEIGHT_ITEM,
+                    "Pixel height",
+                    info.pixel_size,
+                ),
+                integer(
+                    name::CCD_INFO_BITS_PER_PIXEL_ITEM,
+                    "Bits/pixel",
+                    bits,
+                    0.0,
+                    bits,
+                ),
+            ],
+        )?;
+        self.define_numbers(
+            name::CCD_EXPOSURE_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Start exposure",
+            PropertyPerm::ReadWrite,
+            vec![number(
+                name::CCD_EXPOSURE_ITEM,
+                "Start exposure",
+                0.0,
+                0.0,
+                MAX_EXPOSURE,
+                0.001,
+                "%.3f",
+            )],
+        )?;
+        self.define_switches(
+            name::CCD_ABORT_EXPOSURE_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Abort exposure",
+            SwitchRule::AnyOfMany,
+            vec![switch(
+                name::CCD_ABORT_EXPOSURE_ITEM,
+                "Abort exposure",
+                false,
+            )],
+        )?;
+        self.define_numbers(
+            name::CCD_FRAME_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Frame size",
+            PropertyPerm::ReadWrite,
+            vec![
+                integer(name::CCD_FRAME_LEFT_ITEM, "Left", 0.0, 0.0, width),
+                integer(name::CCD_FRAME_TOP_ITEM, "Top", 0.0, 0.0, height),
+                integer(name::CCD_FRAME_WIDTH_ITEM, "Width", width, 0.0, width),
+                integer(name::CCD_FRAME_HEIGHT_ITEM, "Height", height, 0.0, height),
+                number(
+                    name::CCD_FRAME_BITS_PER_PIXEL_ITEM,
+                    "Bits per pixel",
+                    bits,
+                    8.0,
+                    bits.max(8.0),
+                    8.0,
+                    "%.0f",
+                ),
+            ],
+        )?;
+        self.define_numbers(
+            name::CCD_BIN_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Binning",
+            PropertyPerm::ReadWrite,
+            vec![
+                integer(
+                    name::CCD_BIN_HORIZONTAL_ITEM,
+                    "Horizontal binning",
+                    1.0,
+                    1.0,
+                    info.max_bin_x as f64,
+                ),
+                integer(
+                    name::CCD_BIN_VERTICAL_ITEM,
+                    "Vertical binning",
+                    1.0,
+                    1.0,
+                    info.max_bin_y as f64,
+                ),
+            ],
+        )?;
+        self.define_switches(
+            name::CCD_FRAME_TYPE_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Frame type",
+            SwitchRule::OneOfMany,
+            vec![
+                switch(name::CCD_FRAME_TYPE_LIGHT_ITEM, "Light", true),
+                switch(name::CCD_FRAME_TYPE_BIAS_ITEM, "Bias", false),
+                switch(name::CCD_FRAME_TYPE_DARK_ITEM, "Dark", false),
+                switch(name::CCD_FRAME_TYPE_FLAT_ITEM, "Flat", false),
+                switch(name::CCD_FRAME_TYPE_DARKFLAT_ITEM, "Dark flat", false),
+            ],
+        )?;
+        self.define_standard(
+            name::CCD_IMAGE_PROPERTY,
+            CCD_IMAGE_GROUP,
+            "Image data",
+            PropertyPerm::ReadOnly,
+            PropertyType::Blob,
+            None,
+            vec![PropertyItem::new(
+                name::CCD_IMAGE_ITEM,
+                "Image",
+                PropertyValue::blob(Vec::new(), ""),
+            )],
+        )
+    }
+
+    /// Register the CCD cooler properties: `CCD_TEMPERATURE`, `CCD_COOLER`
+    /// and `CCD_COOLER_POWER`.
+    ///
+    /// The cooler starts off.
+    pub fn register_ccd_cooler(&mut self) -> Result<()> {
+        self.define_numbers(
+            name::CCD_TEMPERATURE_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Sensor temperature",
+            PropertyPerm::ReadWrite,
+            vec![number(
+                name::CCD_TEMPERATURE_ITEM,
+                "Temperature (°C)",
+                0.0,
+                -50.0,
+                50.0,
+                0.1,
+                "%.1f",
+            )],
+        )?;
+        self.define_switches(
+            name::CCD_COOLER_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Cooler status",
+            SwitchRule::OneOfMany,
+            vec![
+                switch(name::CCD_COOLER_ON_ITEM, "On", false),
+                switch(name::CCD_COOLER_OFF_ITEM, "Off", true),
+            ],
+        )?;
+        self.define_numbers(
+            name::CCD_COOLER_POWER_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Cooler power",
+            PropertyPerm::ReadOnly,
+            vec![number(
+                name::CCD_COOLER_POWER_ITEM,
+                "Power (%)",
+                0.0,
+                0.0,
+                100.0,
+                1.0,
+                "%.1f",
+            )],
+        )
+    }
+
+    /// Register the CCD `CCD_GAIN` and `CCD_OFFSET` properties.
+    pub fn register_ccd_gain_offset(&mut self) -> Result<()> {
+        self.define_numbers(
+            name::CCD_GAIN_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Gain",
+            PropertyPerm::ReadWrite,
+            vec![integer(name::CCD_GAIN_ITEM, "Gain", 0.0, 0.0, MAX_GAIN)],
+        )?;
+        self.define_numbers(
+            name::CCD_OFFSET_PROPERTY,
+            CCD_MAIN_GROUP,
+            "Offset",
+            PropertyPerm::ReadWrite,
+            vec![integer(
+                name::CCD_OFFSET_ITEM,
+                "Offset",
+                0.0,
+                0.0,
+                MAX_OFFSET,
+            )],
+        )
+    }
+
+    /// Register the standard mount properties: `MOUNT_INFO`,
+    /// `GEOGRAPHIC_COORDINATES`, `MOUNT_LST_TIME`,
+    /// `MOUNT_EQUATORIAL_COORDINATES`, `MOUNT_ON_COORDINATES_SET`,
+    /// `MOUNT_ABORT_MOTION`, `MOUNT_PARK`, `MOUNT_PARK_SET`, `MOUNT_TRACKING`,
+    /// `MOUNT_TRACK_RATE`, `MOUNT_SLEW_RATE`, `MOUNT_MOTION_DEC` and
+    /// `MOUNT_MOTION_RA`.
+    ///
+    /// The mount starts unparked and not tracking, pointing at RA 0h, Dec 0°.
+    pub fn register_mount_standard(&mut self, vendor: &str, model: &str) -> Result<()> {
+        self.define_standard(
+            name::MOUNT_INFO_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Mount info",
+            PropertyPerm::ReadOnly,
+            PropertyType::Text,
+            None,
+            vec![
+                text(name::MOUNT_INFO_VENDOR_ITEM, "Vendor", vendor),
+                text(name::MOUNT_INFO_MODEL_ITEM, "Model", model),
+                text(name::MOUNT_INFO_FIRMWARE_ITEM, "Firmware version", ""),
+            ],
+        )?;
+        self.define_numbers(
+            name::GEOGRAPHIC_COORDINATES_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Location",
+            PropertyPerm::ReadWrite,
+            vec![
+                number(
+                    name::GEOGRAPHIC_COORDINATES_LATITUDE_ITEM,
+                    "Latitude (-90 to +90° +N)",
+                    0.0,
+                    -90.0,
+                    90.0,
+                    0.0,
+                    "%.6f",
+                ),
+                number(
+                    name::GEOGRAPHIC_COORDINATES_LONGITUDE_ITEM,
+                    "Longitude (0 to 360° +E)",
+                    0.0,
+                    -180.0,
+                    360.0,
+                    0.0,
+                    "%.6f",
+                ),
+                number(
+                    name::GEOGRAPHIC_COORDINATES_ELEVATION_ITEM,
+                    "Elevation (m)",
+                    0.0,
+                    -400.0,
+                    8000.0,
+                    1.0,
+                    "%.0f",
+                ),
+            ],
+        )?;
+        self.define_numbers(
+            name::MOUNT_LST_TIME_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "LST time",
+            PropertyPerm::ReadOnly,
+            vec![number(
+                name::MOUNT_LST_TIME_ITEM,
+                "LST time (hrs)",
+                0.0,
+                0.0,
+                24.0,
+                0.0,
+                "%.6f",
+            )],
+        )?;
+        self.define_numbers(
+            name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Equatorial EOD coordinates",
+            PropertyPerm::ReadWrite,
+            vec![
+                number(
+                    name::MOUNT_EQUATORIAL_COORDINATES_RA_ITEM,
+                    "Right ascension (0 to 24 hrs)",
+                    0.0,
+                    0.0,
+                    24.0,
+                    0.0,
+                    "%.6f",
+                ),
+                number(
+                    name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM,
+                    "Declination (-90° to +90°)",
+                    0.0,
+                    -90.0,
+                    90.0,
+                    0.0,
+                    "%.6f",
+                ),
+            ],
+        )?;
+        self.define_switches(
+            name::MOUNT_ON_COORDINATES_SET_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "On coordinates set",
+            SwitchRule::OneOfMany,
+            vec![
+                switch(
+                    name::MOUNT_ON_COORDINATES_SET_TRACK_ITEM,
+                    "Slew and track",
+                    true,
+                ),
+                switch(name::MOUNT_ON_COORDINATES_SET_SYNC_ITEM, "Sync", false),
+                switch(name::MOUNT_ON_COORDINATES_SET_SLEW_ITEM, "Slew", false),
+            ],
+        )?;
+        self.define_switches(
+            name::MOUNT_ABORT_MOTION_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Abort motion",
+            SwitchRule::AnyOfMany,
+            vec![switch(name::MOUNT_ABORT_MOTION_ITEM, "Abort motion", false)],
+        )?;
+        self.define_switches(
+            name::MOUNT_PARK_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Park",
+            SwitchRule::OneOfMany,
+            vec![
+                switch(name::MOUNT_PARK_PARKED_ITEM, "Mount parked", false),
+                switch(name::MOUNT_PARK_UNPARKED_ITEM, "Mount unparked", true),
+            ],
+        )?;
+        self.define_switches(
+            name::MOUNT_PARK_SET_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Park position",
+            SwitchRule::AtMostOne,
+            vec![
+                switch(name::MOUNT_PARK_SET_DEFAULT_ITEM, "Set default", false),
+                switch(name::MOUNT_PARK_SET_CURRENT_ITEM, "Set current", false),
+            ],
+        )?;
+        self.define_switches(
+            name::MOUNT_TRACKING_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Tracking",
+            SwitchRule::OneOfMany,
+            vec![
+                switch(name::MOUNT_TRACKING_ON_ITEM, "Tracking", false),
+                switch(name::MOUNT_TRACKING_OFF_ITEM, "Stopped", true),
+            ],
+        )?;
+        self.define_switches(
+            name::MOUNT_TRACK_RATE_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Track rate",
+            SwitchRule::OneOfMany,
+            vec![
+                switch(name::MOUNT_TRACK_RATE_SIDEREAL_ITEM, "Sidereal rate", true),
+                switch(name::MOUNT_TRACK_RATE_SOLAR_ITEM, "Solar rate", false),
+                switch(name::MOUNT_TRACK_RATE_LUNAR_ITEM, "Lunar rate", false),
+            ],
+        )?;
+        self.define_switches(
+            name::MOUNT_SLEW_RATE_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Slew rate",
+            SwitchRule::OneOfMany,
+            vec![
+                switch(name::MOUNT_SLEW_RATE_GUIDE_ITEM, "Guide rate", false),
+                switch(name::MOUNT_SLEW_RATE_CENTERING_ITEM, "Centering rate", true),
+                switch(name::MOUNT_SLEW_RATE_FIND_ITEM, "Find rate", false),
+                switch(name::MOUNT_SLEW_RATE_MAX_ITEM, "Max rate", false),
+            ],
+        )?;
+        self.define_switches(
+            name::MOUNT_MOTION_DEC_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Move N/S",
+            SwitchRule::AtMostOne,
+            vec![
+                switch(name::MOUNT_MOTION_NORTH_ITEM, "North", false),
+                switch(name::MOUNT_MOTION_SOUTH_ITEM, "South", false),
+            ],
+        )?;
+        self.define_switches(
+            name::MOUNT_MOTION_RA_PROPERTY,
+            MOUNT_MAIN_GROUP,
+            "Move W/E",
+            SwitchRule::AtMostOne,
+            vec![
+                switch(name::MOUNT_MOTION_WEST_ITEM, "West", false),
+                switch(name::MOUNT_MOTION_EAST_ITEM, "East", false),
+            ],
+        )
+    }
+
+    /// Register the standard focuser properties: `FOCUSER_SPEED`,
+    /// `FOCUSER_DIRECTION`, `FOCUSER_STEPS`, `FOCUSER_ABORT_MOTION` and
+    /// `FOCUSER_BACKLASH`, with `FOCUSER_POSITION` and `FOCUSER_LIMITS` for
+    /// absolute focusers and `FOCUSER_TEMPERATURE`, `FOCUSER_MODE` and
+    /// `FOCUSER_COMPENSATION` for focusers with temperature compensation.
+    pub fn register_focuser_standard(&mut self, info: &FocuserInfo) -> Result<()> {
+        let max_position = info.max_position as f64;
+
+        self.define_numbers(
+            name::FOCUSER_SPEED_PROPERTY,
+            FOCUSER_MAIN_GROUP,
+            "Focuser speed",
+            PropertyPerm::ReadWrite,
+            vec![integer(name::FOCUSER_SPEED_ITEM, "Speed", 1.0, 1.0, 10.0)],
+        )?;
+        self.define_switches(
+            name::FOCUSER_DIRECTION_PROPERTY,
+            FOCUSER_MAIN_GROUP,
+            "Direction",
+            SwitchRule::OneOfMany,
+            vec![
+                switch(
+                    name::FOCUSER_DIRECTION_MOVE_INWARD_ITEM,
+                    "Move inward",
+                    true,
+                ),
+                switch(
+                    name::FOCUSER_DIRECTION_MOVE_OUTWARD_ITEM,
+                    "Move outward",
+                    false,
+                ),
+            ],
+        )?;
+        self.define_numbers(
+            name::FOCUSER_STEPS_PROPERTY,
+            FOCUSER_MAIN_GROUP,
+            "Relative move",
+            PropertyPerm::ReadWrite,
+            vec![integer(
+                name::FOCUSER_STEPS_ITEM,
+                "Relative move (steps)",
+                0.0,
+                0.0,
+                max_position,
+            )],
+        )?;
+        self.define_switches(
+            name::FOCUSER_ABORT_MOTION_PROPERTY,
+            FOCUSER_MAIN_GROUP,
+            "Abort motion",
+            SwitchRule::AnyOfMany,
+            vec![switch(
+                name::FOCUSER_ABORT_MOTION_ITEM,
+                "Abort motion",
+                false,
+            )],
+        )?;
+        self.define_numbers(
+            name::FOCUSER_BACKLASH_PROPERTY,
+            FOCUSER_MAIN_GROUP,
+            "Backlash compensation",
+            PropertyPerm::ReadWrite,
+            vec![integer(
+                name::FOCUSER_BACKLASH_ITEM,
+                "Backlash (steps)",
+                0.0,
+                0.0,
+                MAX_BACKLASH,
+            )],
+        )?;
+        if info.has_absolute {
+            self.define_numbers(
+                name::FOCUSER_POSITION_PROPERTY,
+                FOCUSER_MAIN_GROUP,
+                "Absolute position",
+                PropertyPerm::ReadWrite,
+                vec![integer(
+                    name::FOCUSER_POSITION_ITEM,
+                    "Absolute position",
+                    0.0,
+                    0.0,
+                    max_position,
+                )],
+            )?;
+            self.define_numbers(
+                name::FOCUSER_LIMITS_PROPERTY,
+                FOCUSER_MAIN_GROUP,
+                "Focuser limits",
+                PropertyPerm::ReadWrite,
+                vec![
+                    integer(
+                        name::FOCUSER_LIMITS_MIN_POSITION_ITEM,
+                        "Minimum (steps)",
+                        0.0,
+                        0.0,
+                        max_position,
+                    ),
+                    integer(
+                        name::FOCUSER_LIMITS_MAX_POSITION_ITEM,
+                        "Maximum (steps)",
+                        max_position,
+                        0.0,
+                        max_position,
+                    ),
+                ],
+            )?;
+        }
+        if info.has_temperature_compensation {
+            self.define_numbers(
+                name::FOCUSER_TEMPERATURE_PROPERTY,
+                FOCUSER_MAIN_GROUP,
+                "Temperature",
+                PropertyPerm::ReadOnly,
+                vec![number(
+                    name::FOCUSER_TEMPERATURE_ITEM,
+                    "Temperature (°C)",
+                    0.0,
+                    -50.0,
+                    50.0,
+                    0.1,
+                    "%.1f",
+                )],
+            )?;
+            self.define_switches(
+                name::FOCUSER_MODE_PROPERTY,
+                FOCUSER_MAIN_GROUP,
+                "Focusing mode",
+                SwitchRule::OneOfMany,
+                vec![
+                    switch(name::FOCUSER_MODE_MANUAL_ITEM, "Manual", true),
+                    switch(name::FOCUSER_MODE_AUTOMATIC_ITEM, "Automatic", false),
+                ],
+            )?;
+            self.define_numbers(
+                name::FOCUSER_COMPENSATION_PROPERTY,
+                FOCUSER_MAIN_GROUP,
+                "Temperature compensation",
+                PropertyPerm::ReadWrite,
+                vec![integer(
+                    name::FOCUSER_COMPENSATION_ITEM,
+                    "Compensation (steps/°C)",
+                    0.0,
+                    -10000.0,
+                    10000.0,
+                )],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Register the standard filter wheel properties for the given filters:
+    /// `WHEEL_SLOT`, `WHEEL_SLOT_NAME` and `WHEEL_SLOT_OFFSET`.
+    ///
+    /// The wheel starts at slot 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::InvalidParameter`] if there are no filters.
+    pub fn register_wheel_standard(&mut self, filters: &[FilterInfo]) -> Result<()> {
+        if filters.is_empty() {
+            return Err(IndigoError::InvalidParameter(
+                "A filter wheel needs at least one filter".to_string(),
+            ));
+        }
+
+        self.define_numbers(
+            name::WHEEL_SLOT_PROPERTY,
+            WHEEL_MAIN_GROUP,
+            "Current slot",
+            PropertyPerm::ReadWrite,
+            vec![integer(
+                name::WHEEL_SLOT_ITEM,
+                "Slot number",
+                1.0,
+                1.0,
+                filters.len() as f64,
+            )],
+        )?;
+        self.define_standard(
+            name::WHEEL_SLOT_NAME_PROPERTY,
+            WHEEL_MAIN_GROUP,
+            "Slot names",
+            PropertyPerm::ReadWrite,
+            PropertyType::Text,
+            None,
+            filters
+                .iter()
+                .map(|filter| {
+                    text(
+                        &slot_item(name::WHEEL_SLOT_NAME_ITEM, filter.slot),
+                        &format!("Slot #{}", filter.slot),
+                        &filter.name,
+                    )
+                })
+                .collect(),
+        )?;
+        self.define_numbers(
+            name::WHEEL_SLOT_OFFSET_PROPERTY,
+            WHEEL_MAIN_GROUP,
+            "Slot offsets",
+            PropertyPerm::ReadWrite,
+            filters
+                .iter()
+                .map(|filter| {
+                    integer(
+                        &slot_item(name::WHEEL_SLOT_OFFSET_ITEM, filter.slot),
+                        &format!("Filter #{} offset", filter.slot),
+                        filter.offset as f64,
+                        -10000.0,
+                        10000.0,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Register the standard guider properties: `GUIDER_GUIDE_DEC`,
+    /// `GUIDER_GUIDE_RA` and `GUIDER_RATE`.
+    pub fn register_guider_standard(&mut self) -> Result<()> {
+        self.define_numbers(
+            name::GUIDER_GUIDE_DEC_PROPERTY,
+            GUIDER_MAIN_GROUP,
+            "DEC guiding",
+            PropertyPerm::ReadWrite,
+            vec![
+                integer(
+                    name::GUIDER_GUIDE_NORTH_ITEM,
+                    "North (ms)",
+                    0.0,
+                    0.0,
+                    MAX_GUIDE_PULSE,
+                ),
+                integer(
+                    name::GUIDER_GUIDE_SOUTH_ITEM,
+                    "South (ms)",
+                    0.0,
+                    0.0,
+                    MAX_GUIDE_PULSE,
+                ),
+            ],
+        )?;
+        self.define_numbers(
+            name::GUIDER_GUIDE_RA_PROPERTY,
+            GUIDER_MAIN_GROUP,
+            "RA guiding",
+            PropertyPerm::ReadWrite,
+            vec![
+                integer(
+                    name::GUIDER_GUIDE_EAST_ITEM,
+                    "East (ms)",
+                    0.0,
+                    0.0,
+                    MAX_GUIDE_PULSE,
+                ),
+                integer(
+                    name::GUIDER_GUIDE_WEST_ITEM,
+                    "West (ms)",
+                    0.0,
+                    0.0,
+                    MAX_GUIDE_PULSE,
+                ),
+            ],
+        )?;
+        self.define_numbers(
+            name::GUIDER_RATE_PROPERTY,
+            GUIDER_MAIN_GROUP,
+            "Guiding rate",
+            PropertyPerm::ReadWrite,
+            vec![
+                integer(
+                    name::GUIDER_RATE_ITEM,
+                    "RA guiding rate (% of sidereal)",
+                    50.0,
+                    10.0,
+                    90.0,
+                ),
+                integer(
+                    name::GUIDER_DEC_RATE_ITEM,
+                    "DEC guiding rate (% of sidereal)",
+                    50.0,
+                    10.0,
+                    90.0,
+                ),
+            ],
+        )
+    }
+
+    /// Define a number property.
+    fn define_numbers(
+        &mut self,
+        name: &str,
+        group: &str,
+        label: &str,
+        perm: PropertyPerm,
+        items: Vec<PropertyItem>,
+    ) -> Result<()> {
+        self.define_standard(name, group, label, perm, PropertyType::Number, None, items)
+    }
+
+    /// Define a read-write switch property.
+    pub(super) fn define_switches(
+        &mut self,
+        name: &str,
+        group: &str,
+        label: &str,
+        rule: SwitchRule,
+        items: Vec<PropertyItem>,
+    ) -> Result<()> {
+        self.define_standard(
+            name,
+            group,
+            label,
+            PropertyPerm::ReadWrite,
+            PropertyType::Switch,
+            Some(rule),
+            items,
+        )
+    }
+
+    /// Define a property of this device.
+    ///
+    /// Read-only properties start `Ok`, as they describe the device; others
+    /// start `Idle`.
+    #[allow(clippy::too_many_arguments)]
+    fn define_standard(
+        &mut self,
+        name: &str,
+        group: &str,
+        label: &str,
+        perm: PropertyPerm,
+        property_type: PropertyType,
+        switch_rule: Option<SwitchRule>,
+        items: Vec<PropertyItem>,
+    ) -> Result<()> {
+        let state = if perm == PropertyPerm::ReadOnly {
+            PropertyState::Ok
+        } else {
+            PropertyState::Idle
+        };
+        let property = Property {
+            device: self.device_name().to_string(),
+            name: name.to_string(),
+            group: group.to_string(),
+            label: label.to_string(),
+            state,
+            perm,
+            property_type,
+            switch_rule,
+            items: items
+                .into_iter()
+                .map(|item| (item.name.clone(), item))
+                .collect(),
+            timeout: None,
+            timestamp: None,
+            message: None,
+        };
+        self.define_property(property)
+    }
+}
+
+/// Creates a number item.
+fn number(
+    name: &str,
+    label: &str,
+    value: f64,
+    min: f64,
+    max: f64,
+    step: f64,
+    format: &str,
+) -> PropertyItem {
+    PropertyItem::new(
+        name,
+        label,
+        PropertyValue::Number {
+            value,
+            min,
+            max,
+            step,
+            format: format.to_string(),
+        },
+    )
+}
+
+/// Creates a number item holding whole numbers.
+fn integer(name: &str, label: &str, value: f64, min: f64, max: f64) -> PropertyItem {
+    number(name, label, value, min, max, 1.0, "%.0f")
+}
+
+/// Creates a pixel size item, in microns.
+fn pixel_size(name: &str, label: &str, value: f64) -> PropertyItem {
+    number(name, label, value, 0.0, value, 0.01, "%.2f")
+}
+
+/// Creates a switch item.
+pub(super) fn switch(name: &str, label: &str, on: bool) -> PropertyItem {
+    let state = if on {
+        SwitchState::On
+    } else {
+        SwitchState::Off
+    };
+    PropertyItem::new(name, label, PropertyValue::switch(state))
+}
+
+/// Creates a text item.
+fn text(name: &str, label: &str, value: &str) -> PropertyItem {
+    PropertyItem::new(name, label, PropertyValue::text(value))
+}
+
+/// Gets the name of a per-slot item, such as `SLOT_NAME_1`.
+pub(super) fn slot_item(pattern: &str, slot: u32) -> String {
+    pattern.replace("%d", &slot.to_string())
+}
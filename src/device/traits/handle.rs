@@ -0,0 +1,53 @@
+//! Devices opened by trait through a client.
+
+use super::{Camera, FilterWheel, Focuser, Guider, Mount};
+use crate::device::DeviceInterface;
+
+/// A device opened by a client strategy, as the trait object of one of its
+/// interfaces.
+pub enum DeviceHandle {
+    /// A camera
+    Camera(Box<dyn Camera>),
+    /// A filter wheel
+    FilterWheel(Box<dyn FilterWheel>),
+    /// A focuser
+    Focuser(Box<dyn Focuser>),
+    /// A mount
+    Mount(Box<dyn Mount>),
+    /// A guider
+    Guider(Box<dyn Guider>),
+}
+
+/// A device trait that devices can be opened as.
+///
+/// Implemented for the trait objects of the device traits, such as
+/// `dyn Camera`, so devices can be opened with
+/// [`Client::device`](crate::client::Client::device).
+pub trait TypedDevice {
+    /// The INDIGO interface a device must implement.
+    const INTERFACE: DeviceInterface;
+
+    /// Takes the device out of a handle of the same interface.
+    fn from_handle(handle: DeviceHandle) -> Option<Box<Self>>;
+}
+
+macro_rules! typed_device {
+    ($trait:ident, $variant:ident, $interface:ident) => {
+        impl TypedDevice for dyn $trait {
+            const INTERFACE: DeviceInterface = DeviceInterface::$interface;
+
+            fn from_handle(handle: DeviceHandle) -> Option<Box<Self>> {
+                match handle {
+                    DeviceHandle::$variant(device) => Some(device),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+typed_device!(Camera, Camera, Ccd);
+typed_device!(FilterWheel, FilterWheel, FilterWheel);
+typed_device!(Focuser, Focuser, Focuser);
+typed_device!(Mount, Mount, Mount);
+typed_device!(Guider, Guider, Guider);
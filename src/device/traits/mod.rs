@@ -9,6 +9,7 @@ mod camera;
 mod filter_wheel;
 mod focuser;
 mod guider;
+mod handle;
 mod mount;
 mod proxy;
 
@@ -17,5 +18,6 @@ pub use camera::{BinningMode, Camera, CcdInfo, ExposureState, FrameType};
 pub use filter_wheel::{FilterInfo, FilterWheel};
 pub use focuser::{Focuser, FocuserInfo};
 pub use guider::{GuideDirection, GuidePulse, Guider};
+pub use handle::{DeviceHandle, TypedDevice};
 pub use mount::{AxisDirection, Coordinates, Mount, MountAxis, MountType, SlewRate, TrackingMode};
 pub use proxy::DeviceProxy;
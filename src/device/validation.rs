@@ -0,0 +1,152 @@
+//! Validation of property changes requested by clients.
+//!
+//! Requests are checked against the property definition before the driver
+//! sees them: the property must be writable, the items must exist and have
+//! the right type, numbers must be within range and aligned to their step,
+//! and switches must respect the switch rule. Valid requests are merged into
+//! the full property, so drivers always receive every item.
+
+use super::property_manager::PropertyManager;
+use crate::error::{IndigoError, Result};
+use crate::types::{Property, PropertyPerm, PropertyValue, SwitchRule, SwitchState};
+
+/// Relative tolerance when checking that a number is aligned to its step.
+const STEP_TOLERANCE: f64 = 1e-6;
+
+impl PropertyManager {
+    /// Validate a requested property change and merge it into the current
+    /// property.
+    ///
+    /// Only the requested values are taken from `request`; the metadata and
+    /// other items come from the definition. Turning a switch on in a
+    /// `OneOfMany` or `AtMostOne` property turns the other switches off.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::PropertyNotFound`] if the property is not
+    /// defined, or [`IndigoError::InvalidParameter`] describing why the
+    /// request is invalid.
+    pub fn validate_change(&self, request: &Property) -> Result<Property> {
+        let definition = self
+            .get_property(&request.name)
+            .ok_or_else(|| IndigoError::PropertyNotFound(request.name.clone()))?;
+        if definition.perm == PropertyPerm::ReadOnly {
+            return Err(IndigoError::InvalidParameter(format!(
+                "{} is read-only",
+                definition.name
+            )));
+        }
+
+        let mut merged = definition.clone();
+        let mut switched_on = Vec::new();
+        for (name, item) in &request.items {
+            let current = merged.items.get_mut(name).ok_or_else(|| {
+                IndigoError::InvalidParameter(format!("{} has no item {}", definition.name, name))
+            })?;
+            current.value = match (&current.value, &item.value) {
+                (
+                    PropertyValue::Number {
+                        min,
+                        max,
+                        step,
+                        format,
+                        ..
+                    },
+                    PropertyValue::Number { value, .. },
+                ) => {
+                    check_number(&definition.name, name, *value, *min, *max, *step)?;
+                    PropertyValue::Number {
+                        value: *value,
+                        min: *min,
+                        max: *max,
+                        step: *step,
+                        format: format.clone(),
+                    }
+                }
+                (PropertyValue::Switch { .. }, PropertyValue::Switch { state }) => {
+                    if *state == SwitchState::On {
+                        switched_on.push(name.clone());
+                    }
+                    item.value.clone()
+                }
+                (PropertyValue::Text(_), PropertyValue::Text(_))
+                | (PropertyValue::Blob { .. }, PropertyValue::Blob { .. }) => item.value.clone(),
+                _ => {
+                    return Err(IndigoError::InvalidParameter(format!(
+                        "{}.{} is not a {:?} item",
+                        definition.name, name, definition.property_type
+                    )))
+                }
+            };
+        }
+
+        if let Some(rule) = definition.switch_rule {
+            apply_switch_rule(&mut merged, rule, &switched_on)?;
+        }
+        Ok(merged)
+    }
+}
+
+/// Checks that a number is within range and aligned to its step.
+fn check_number(
+    property: &str,
+    item: &str,
+    value: f64,
+    min: f64,
+    max: f64,
+    step: f64,
+) -> Result<()> {
+    if value < min || value > max {
+        return Err(IndigoError::InvalidParameter(format!(
+            "{}.{} = {} is outside {} to {}",
+            property, item, value, min, max
+        )));
+    }
+    if step > 0.0 && min > f64::MIN {
+        let steps = (value - min) / step;
+        if (steps - steps.round()).abs() > STEP_TOLERANCE * steps.abs().max(1.0) {
+            return Err(IndigoError::InvalidParameter(format!(
+                "{}.{} = {} is not a multiple of {} from {}",
+                property, item, value, step, min
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Applies a switch rule to a merged property, given the switches the
+/// request turned on.
+fn apply_switch_rule(
+    property: &mut Property,
+    rule: SwitchRule,
+    switched_on: &[String],
+) -> Result<()> {
+    if rule == SwitchRule::AnyOfMany {
+        return Ok(());
+    }
+    if switched_on.len() > 1 {
+        return Err(IndigoError::InvalidParameter(format!(
+            "Only one of {} can be on",
+            property.name
+        )));
+    }
+
+    if let Some(on) = switched_on.first() {
+        for item in property.items.values_mut() {
+            if item.name != *on {
+                item.value = PropertyValue::switch(SwitchState::Off);
+            }
+        }
+    }
+    let any_on = property
+        .items
+        .values()
+        .any(|item| item.value == PropertyValue::switch(SwitchState::On));
+    if rule == SwitchRule::OneOfMany && !any_on {
+        return Err(IndigoError::InvalidParameter(format!(
+            "One of {} must be on",
+            property.name
+        )));
+    }
+    Ok(())
+}
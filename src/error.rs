@@ -82,6 +82,10 @@ pub enum IndigoError {
     /// Driver still attached (cannot unregister).
     #[error("Driver still attached: {0}")]
     DriverStillAttached(String),
+
+    /// Device does not implement the requested interface.
+    #[error("Interface mismatch: {0}")]
+    InterfaceMismatch(String),
 }
 
 /// Error type for property builder operations.
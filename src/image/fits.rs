@@ -0,0 +1,448 @@
+//! FITS images.
+//!
+//! Reads and writes the primary HDU of FITS files, the format INDIGO cameras
+//! send images in. Extensions following the primary HDU are ignored.
+
+use super::{Header, Image, ImageData, Keyword, Pixels, Value};
+use crate::error::{IndigoError, Result};
+
+/// Size of FITS blocks, to which the header and data are padded.
+const BLOCK: usize = 2880;
+
+/// Size of a header card.
+const CARD: usize = 80;
+
+/// Keywords describing the data, which are derived from the pixels when
+/// writing.
+const STRUCTURAL: &[&str] = &[
+    "SIMPLE", "BITPIX", "NAXIS", "NAXIS1", "NAXIS2", "NAXIS3", "EXTEND", "BZERO", "BSCALE", "END",
+];
+
+/// Keywords whose cards hold text instead of a value.
+const COMMENTARY: &[&str] = &["COMMENT", "HISTORY", ""];
+
+/// Reads the primary HDU of a FITS file.
+///
+/// The header keeps all keywords, including those describing the data such
+/// as `BITPIX`. Images with two axes are monochrome, a third axis holds the
+/// color channels.
+///
+/// # Errors
+///
+/// Returns [`IndigoError::ParseError`] if the data is not a valid FITS image
+/// and [`IndigoError::NotSupported`] for 64 bit integers or more than three
+/// axes.
+pub fn read(data: &[u8]) -> Result<Image> {
+    if !data.starts_with(b"SIMPLE  =") {
+        return Err(parse_error("not a FITS file"));
+    }
+    let mut header = Header::new();
+    let mut end = None;
+    for (i, card) in data.as_chunks::<CARD>().0.iter().enumerate() {
+        if card.starts_with(b"END     ") {
+            end = Some(i);
+            break;
+        }
+        if card.iter().any(|&b| b != b' ') {
+            header.push(parse_card(card)?);
+        }
+    }
+    let end = end.ok_or_else(|| parse_error("header has no END"))?;
+    let start = ((end + 1) * CARD).div_ceil(BLOCK) * BLOCK;
+
+    let integer = |name: &str| {
+        header
+            .get(name)
+            .and_then(Value::as_i64)
+            .ok_or_else(|| parse_error(&format!("missing or invalid {}", name)))
+    };
+    let axis = |name: &str| {
+        integer(name).and_then(|size| {
+            usize::try_from(size).map_err(|_| parse_error(&format!("invalid {}", name)))
+        })
+    };
+    let bitpix = integer("BITPIX")?;
+    let (width, height, channels) = match integer("NAXIS")? {
+        2 => (axis("NAXIS1")?, axis("NAXIS2")?, 1),
+        3 => (axis("NAXIS1")?, axis("NAXIS2")?, axis("NAXIS3")?),
+        naxis => {
+            return Err(IndigoError::NotSupported(format!(
+                "FITS images with {} axes",
+                naxis
+            )))
+        }
+    };
+    let bzero = header.get("BZERO").and_then(Value::as_f64).unwrap_or(0.0);
+    let bscale = header.get("BSCALE").and_then(Value::as_f64).unwrap_or(1.0);
+
+    let size = (bitpix.unsigned_abs() / 8) as usize;
+    let stored = width
+        .checked_mul(height)
+        .and_then(|count| count.checked_mul(channels))
+        .and_then(|count| count.checked_mul(size))
+        .and_then(|length| start.checked_add(length))
+        .and_then(|stop| data.get(start..stop))
+        .ok_or_else(|| parse_error("data is truncated"))?;
+
+    let scaled = bscale != 1.0 || bzero != 0.0;
+    let scale = |value: f64| bzero + bscale * value;
+    let data = match (bitpix, scaled) {
+        (8, false) => ImageData::U8(Pixels::new(width, height, channels, stored.to_vec())?),
+        (16, _) if bscale == 1.0 && bzero == 32768.0 => ImageData::U16(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, |b| u16::from_be_bytes(b) ^ 0x8000),
+        )?),
+        (16, false) => ImageData::I16(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, i16::from_be_bytes),
+        )?),
+        (32, _) if bscale == 1.0 && bzero == 2147483648.0 => ImageData::U32(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, |b| u32::from_be_bytes(b) ^ 0x8000_0000),
+        )?),
+        (32, false) => ImageData::I32(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, i32::from_be_bytes),
+        )?),
+        (-32, false) => ImageData::F32(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, f32::from_be_bytes),
+        )?),
+        (-64, false) => ImageData::F64(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, f64::from_be_bytes),
+        )?),
+        // Physical values of scaled data need floating point
+        (8, true) => ImageData::F32(Pixels::new(
+            width,
+            height,
+            channels,
+            stored.iter().map(|&b| scale(b.into()) as f32).collect(),
+        )?),
+        (16, true) => ImageData::F32(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, |b| scale(i16::from_be_bytes(b).into()) as f32),
+        )?),
+        (-32, true) => ImageData::F32(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, |b| scale(f32::from_be_bytes(b).into()) as f32),
+        )?),
+        (32, true) => ImageData::F64(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, |b| scale(i32::from_be_bytes(b).into())),
+        )?),
+        (-64, true) => ImageData::F64(Pixels::new(
+            width,
+            height,
+            channels,
+            values(stored, |b| scale(f64::from_be_bytes(b))),
+        )?),
+        _ => {
+            return Err(IndigoError::NotSupported(format!(
+                "FITS images with BITPIX {}",
+                bitpix
+            )))
+        }
+    };
+    Ok(Image { header, data })
+}
+
+/// Writes an image as a FITS file.
+///
+/// Keywords describing the data are written for the pixels, with `BZERO`
+/// offsetting unsigned integers, followed by the other keywords of the header.
+///
+/// # Errors
+///
+/// Returns [`IndigoError::InvalidParameter`] if a keyword can't be written as
+/// a FITS card.
+pub fn write(image: &Image) -> Result<Vec<u8>> {
+    let (bitpix, bzero) = match &image.data {
+        ImageData::U8(_) => (8, None),
+        ImageData::I16(_) => (16, None),
+        ImageData::U16(_) => (16, Some(32768)),
+        ImageData::I32(_) => (32, None),
+        ImageData::U32(_) => (32, Some(2147483648)),
+        ImageData::F32(_) => (-32, None),
+        ImageData::F64(_) => (-64, None),
+    };
+    let axes = if image.channels() > 1 { 3 } else { 2 };
+    let mut structure = vec![
+        Keyword::new("SIMPLE", Value::Logical(true)),
+        Keyword::new("BITPIX", Value::Integer(bitpix)),
+        Keyword::new("NAXIS", Value::Integer(axes)),
+        Keyword::new("NAXIS1", Value::Integer(image.width() as i64)),
+        Keyword::new("NAXIS2", Value::Integer(image.height() as i64)),
+    ];
+    if axes == 3 {
+        structure.push(Keyword::new(
+            "NAXIS3",
+            Value::Integer(image.channels() as i64),
+        ));
+    }
+    if let Some(bzero) = bzero {
+        structure.push(Keyword::new("BZERO", Value::Integer(bzero)));
+        structure.push(Keyword::new("BSCALE", Value::Integer(1)));
+    }
+
+    let mut data = Vec::new();
+    let keywords = image
+        .header
+        .keywords()
+        .iter()
+        .filter(|keyword| !STRUCTURAL.contains(&keyword.name.as_str()));
+    for keyword in structure.iter().chain(keywords) {
+        data.extend_from_slice(format_card(keyword)?.as_bytes());
+    }
+    data.extend_from_slice(format!("{:<80}", "END").as_bytes());
+    data.resize(data.len().div_ceil(BLOCK) * BLOCK, b' ');
+
+    match &image.data {
+        ImageData::U8(pixels) => data.extend_from_slice(pixels.as_slice()),
+        ImageData::I16(pixels) => extend(&mut data, pixels, |v| v.to_be_bytes()),
+        ImageData::U16(pixels) => extend(&mut data, pixels, |v| (v ^ 0x8000).to_be_bytes()),
+        ImageData::I32(pixels) => extend(&mut data, pixels, |v| v.to_be_bytes()),
+        ImageData::U32(pixels) => extend(&mut data, pixels, |v| (v ^ 0x8000_0000).to_be_bytes()),
+        ImageData::F32(pixels) => extend(&mut data, pixels, |v| v.to_be_bytes()),
+        ImageData::F64(pixels) => extend(&mut data, pixels, |v| v.to_be_bytes()),
+    }
+    data.resize(data.len().div_ceil(BLOCK) * BLOCK, 0);
+    Ok(data)
+}
+
+fn parse_error(reason: &str) -> IndigoError {
+    IndigoError::ParseError(format!("Invalid FITS image: {}", reason))
+}
+
+/// Decodes big-endian values of `N` bytes.
+fn values<const N: usize, T>(data: &[u8], decode: impl Fn([u8; N]) -> T) -> Vec<T> {
+    data.as_chunks::<N>()
+        .0
+        .iter()
+        .map(|&bytes| decode(bytes))
+        .collect()
+}
+
+/// Appends pixels encoded as big-endian values.
+fn extend<T: Copy, const N: usize>(
+    data: &mut Vec<u8>,
+    pixels: &Pixels<T>,
+    encode: impl Fn(T) -> [u8; N],
+) {
+    data.reserve(pixels.as_slice().len() * N);
+    for &pixel in pixels.as_slice() {
+        data.extend_from_slice(&encode(pixel));
+    }
+}
+
+/// Parses a header card into a keyword.
+pub(super) fn parse_card(card: &[u8]) -> Result<Keyword> {
+    let card = std::str::from_utf8(card)
+        .ok()
+        .filter(|card| card.is_ascii())
+        .ok_or_else(|| parse_error("header is not ASCII"))?;
+    let name = card[..8].trim_end().to_string();
+    if &card[8..10] != "= " || COMMENTARY.contains(&name.as_str()) {
+        let text = card[8..].trim_end();
+        return Ok(Keyword {
+            name,
+            value: Value::Undefined,
+            comment: (!text.is_empty()).then(|| text.to_string()),
+        });
+    }
+    let (value, comment) = parse_field(&name, &card[10..])?;
+    Ok(Keyword {
+        name,
+        value,
+        comment,
+    })
+}
+
+/// Parses the value and comment of a keyword, as written after `= `.
+pub(super) fn parse_field(name: &str, field: &str) -> Result<(Value, Option<String>)> {
+    let field = field.trim_start();
+    let (value, comment) = match field.strip_prefix('\'') {
+        Some(quoted) => {
+            // Quotes inside strings are doubled
+            let mut text = String::new();
+            let mut chars = quoted.char_indices().peekable();
+            let rest = loop {
+                match chars.next() {
+                    Some((_, '\'')) if chars.peek().map(|&(_, c)| c) == Some('\'') => {
+                        chars.next();
+                        text.push('\'');
+                    }
+                    Some((i, '\'')) => break &quoted[i + 1..],
+                    Some((_, c)) => text.push(c),
+                    None => return Err(parse_error(&format!("unterminated string in {}", name))),
+                }
+            };
+            let comment = rest.split_once('/').map(|(_, comment)| comment);
+            (Value::Text(text.trim_end().to_string()), comment)
+        }
+        None => {
+            let (value, comment) = match field.split_once('/') {
+                Some((value, comment)) => (value, Some(comment)),
+                None => (field, None),
+            };
+            (parse_value(name, value.trim())?, comment)
+        }
+    };
+    let comment = comment
+        .map(str::trim)
+        .filter(|comment| !comment.is_empty())
+        .map(str::to_string);
+    Ok((value, comment))
+}
+
+/// Parses a value that is not a string.
+fn parse_value(name: &str, value: &str) -> Result<Value> {
+    match value {
+        "" => Ok(Value::Undefined),
+        "T" => Ok(Value::Logical(true)),
+        "F" => Ok(Value::Logical(false)),
+        _ => {
+            if let Ok(integer) = value.parse() {
+                return Ok(Value::Integer(integer));
+            }
+            // Double precision exponents may be written with a D
+            value
+                .replace(['D', 'd'], "E")
+                .parse()
+                .map(Value::Real)
+                .map_err(|_| parse_error(&format!("invalid value of {}: {}", name, value)))
+        }
+    }
+}
+
+/// Formats a keyword as a header card, with strings starting in column 11
+/// and other values ending in column 30.
+fn format_card(keyword: &Keyword) -> Result<String> {
+    let invalid = |reason: &str| {
+        IndigoError::InvalidParameter(format!("FITS keyword {}: {}", keyword.name, reason))
+    };
+    if keyword.name.len() > 8 || !keyword.name.is_ascii() {
+        return Err(invalid("name is not up to 8 ASCII characters"));
+    }
+    let comment = keyword.comment.as_deref().unwrap_or_default();
+    if !comment.is_ascii() {
+        return Err(invalid("comment is not ASCII"));
+    }
+    if COMMENTARY.contains(&keyword.name.as_str()) {
+        return Ok(format!("{:<8}{:<72.72}", keyword.name, comment));
+    }
+
+    let value = match &keyword.value {
+        Value::Logical(value) => format!("{:>20}", if *value { "T" } else { "F" }),
+        Value::Integer(value) => format!("{:>20}", value),
+        Value::Real(value) if value.is_finite() => {
+            // Always with a decimal point or exponent, which is upper case
+            format!("{:>20}", format!("{:?}", value).to_uppercase())
+        }
+        Value::Real(_) => return Err(invalid("value is not finite")),
+        Value::Text(value) => {
+            let quoted = format!("'{:<8}'", value.replace('\'', "''"));
+            if quoted.len() > 70 || !quoted.is_ascii() {
+                return Err(invalid("value is not up to 68 ASCII characters"));
+            }
+            quoted
+        }
+        Value::Undefined => String::new(),
+    };
+    let card = if comment.is_empty() {
+        format!("{:<8}= {}", keyword.name, value)
+    } else {
+        format!("{:<8}= {} / {}", keyword.name, value, comment)
+    };
+    Ok(format!("{:<80.80}", card))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(text: &str) -> Keyword {
+        parse_card(format!("{:<80}", text).as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_cards() {
+        let keyword = card("EXPTIME =                 1.50 / Exposure time [s]");
+        assert_eq!(keyword.name, "EXPTIME");
+        assert_eq!(keyword.value, Value::Real(1.5));
+        assert_eq!(keyword.comment.as_deref(), Some("Exposure time [s]"));
+
+        assert_eq!(
+            card("XBINNING=                    2").value,
+            Value::Integer(2)
+        );
+        assert_eq!(
+            card("SIMPLE  =                    T").value,
+            Value::Logical(true)
+        );
+        assert_eq!(
+            card("BSCALE  =               1.0D+0").value,
+            Value::Real(1.0)
+        );
+        assert_eq!(
+            card("OBJECT  = 'M 31 / ''Andromeda''  ' / Target").value,
+            Value::Text("M 31 / 'Andromeda'".to_string())
+        );
+        let history = card("HISTORY Dark subtracted");
+        assert_eq!(history.value, Value::Undefined);
+        assert_eq!(history.comment.as_deref(), Some("Dark subtracted"));
+
+        assert!(parse_card(format!("{:<80}", "GAIN    = high").as_bytes()).is_err());
+        assert!(parse_card(format!("{:<80}", "OBJECT  = 'M 31").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_format_cards() {
+        let keywords = [
+            Keyword::new("EXPTIME", Value::Real(1.5)),
+            Keyword::new("EXPTIME", Value::Real(2.0)),
+            Keyword::new("SCALE", Value::Real(1e-7)),
+            Keyword::new("XBINNING", Value::Integer(2)),
+            Keyword::new("OBJECT", Value::Text("M 31 'Andromeda'".to_string())),
+            Keyword {
+                name: "CCD-TEMP".to_string(),
+                value: Value::Real(-10.0),
+                comment: Some("Sensor temperature [C]".to_string()),
+            },
+            Keyword {
+                name: "HISTORY".to_string(),
+                value: Value::Undefined,
+                comment: Some("Dark subtracted".to_string()),
+            },
+        ];
+        for keyword in keywords {
+            let formatted = format_card(&keyword).unwrap();
+            assert_eq!(formatted.len(), CARD);
+            assert_eq!(parse_card(formatted.as_bytes()).unwrap(), keyword);
+        }
+        assert_eq!(
+            format_card(&Keyword::new("BITPIX", Value::Integer(16))).unwrap(),
+            format!("{:<80}", "BITPIX  =                   16")
+        );
+        assert!(format_card(&Keyword::new("EXPOSURETIME", Value::Real(1.0))).is_err());
+        assert!(format_card(&Keyword::new("EXPTIME", Value::Real(f64::NAN))).is_err());
+    }
+}
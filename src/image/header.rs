@@ -0,0 +1,175 @@
+//! Header keywords of images.
+
+/// Value of a header keyword.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Logical value, `T` or `F` in FITS.
+    Logical(bool),
+    /// Integer value.
+    Integer(i64),
+    /// Floating point value.
+    Real(f64),
+    /// Character string.
+    Text(String),
+    /// No value, as for `COMMENT` and `HISTORY` keywords.
+    Undefined,
+}
+
+impl Value {
+    /// Gets a numeric value as floating point.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(value) => Some(*value as f64),
+            Value::Real(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets an integer value, accepting floating point values without a
+    /// fraction.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Integer(value) => Some(*value),
+            Value::Real(value) if value.fract() == 0.0 => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    /// Gets a string value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets a logical value.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Logical(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// A header keyword.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyword {
+    /// Keyword name, such as `EXPTIME`.
+    pub name: String,
+    /// Keyword value.
+    pub value: Value,
+    /// Comment, or the text of commentary keywords such as `HISTORY`.
+    pub comment: Option<String>,
+}
+
+impl Keyword {
+    /// Creates a keyword without a comment.
+    pub fn new(name: impl Into<String>, value: Value) -> Self {
+        Keyword {
+            name: name.into(),
+            value,
+            comment: None,
+        }
+    }
+}
+
+/// Header keywords of an image, in the order they were stored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Header {
+    keywords: Vec<Keyword>,
+}
+
+impl Header {
+    /// Creates an empty header.
+    pub fn new() -> Self {
+        Header::default()
+    }
+
+    /// Gets all keywords.
+    pub fn keywords(&self) -> &[Keyword] {
+        &self.keywords
+    }
+
+    /// Gets the value of the first keyword with a name.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.keywords
+            .iter()
+            .find(|keyword| keyword.name == name)
+            .map(|keyword| &keyword.value)
+    }
+
+    /// Sets the value of a keyword, adding the keyword if it is missing.
+    pub fn set(&mut self, name: &str, value: Value) {
+        match self
+            .keywords
+            .iter_mut()
+            .find(|keyword| keyword.name == name)
+        {
+            Some(keyword) => keyword.value = value,
+            None => self.keywords.push(Keyword::new(name, value)),
+        }
+    }
+
+    /// Adds a keyword, keeping any keywords of the same name.
+    pub fn push(&mut self, keyword: Keyword) {
+        self.keywords.push(keyword);
+    }
+
+    /// Removes all keywords with a name.
+    pub fn remove(&mut self, name: &str) {
+        self.keywords.retain(|keyword| keyword.name != name);
+    }
+
+    /// Exposure time in seconds (`EXPTIME`).
+    pub fn exposure_time(&self) -> Option<f64> {
+        self.get("EXPTIME")?.as_f64()
+    }
+
+    /// Sensor temperature in °C (`CCD-TEMP`).
+    pub fn ccd_temperature(&self) -> Option<f64> {
+        self.get("CCD-TEMP")?.as_f64()
+    }
+
+    /// Bayer pattern of a color sensor, such as `RGGB` (`BAYERPAT`).
+    pub fn bayer_pattern(&self) -> Option<&str> {
+        self.get("BAYERPAT")?.as_str()
+    }
+
+    /// Horizontal and vertical binning (`XBINNING`, `YBINNING`).
+    pub fn binning(&self) -> Option<(u32, u32)> {
+        let x = self.get("XBINNING")?.as_i64()?;
+        let y = self.get("YBINNING")?.as_i64()?;
+        Some((u32::try_from(x).ok()?, u32::try_from(y).ok()?))
+    }
+
+    /// Pixel size in µm (`XPIXSZ`, `YPIXSZ`).
+    pub fn pixel_size(&self) -> Option<(f64, f64)> {
+        Some((self.get("XPIXSZ")?.as_f64()?, self.get("YPIXSZ")?.as_f64()?))
+    }
+
+    /// Frame type, such as `Light Frame` (`IMAGETYP`).
+    pub fn image_type(&self) -> Option<&str> {
+        self.get("IMAGETYP")?.as_str()
+    }
+
+    /// Camera gain (`GAIN`).
+    pub fn gain(&self) -> Option<f64> {
+        self.get("GAIN")?.as_f64()
+    }
+
+    /// Camera offset (`OFFSET`).
+    pub fn offset(&self) -> Option<f64> {
+        self.get("OFFSET")?.as_f64()
+    }
+
+    /// Start of the exposure, as an ISO 8601 string (`DATE-OBS`).
+    pub fn date_obs(&self) -> Option<&str> {
+        self.get("DATE-OBS")?.as_str()
+    }
+
+    /// Camera name (`INSTRUME`).
+    pub fn instrument(&self) -> Option<&str> {
+        self.get("INSTRUME")?.as_str()
+    }
+}
@@ -0,0 +1,284 @@
+//! Decoding of camera images received as BLOBs.
+//!
+//! Cameras deliver images as BLOBs in the file format selected with
+//! `CCD_IMAGE_FORMAT`, such as FITS, XISF or INDIGO raw, which
+//! [`Camera::download_image`](crate::device::traits::Camera::download_image)
+//! returns as raw bytes. This module decodes them into an [`Image`]: the
+//! header keywords with typed values, and the pixels in the type they were
+//! stored in.
+//!
+//! # Feature Flag
+//!
+//! This module is only available when the `image` feature is enabled.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use libindigo::image::{Image, ImageData};
+//!
+//! let image = Image::decode(".fits", &camera.download_image().await?)?;
+//! println!("{}x{} exposed for {:?} s", image.width(), image.height(), image.header.exposure_time());
+//! if let ImageData::U16(pixels) = &image.data {
+//!     println!("Center: {:?}", pixels.get(image.width() / 2, image.height() / 2));
+//! }
+//! ```
+
+use crate::error::{IndigoError, Result};
+use crate::types::PropertyValue;
+
+pub mod fits;
+mod header;
+pub mod raw;
+pub mod xisf;
+
+pub use header::{Header, Keyword, Value};
+
+/// A decoded camera image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    /// Header keywords, in the order they were stored.
+    pub header: Header,
+    /// Pixel data.
+    pub data: ImageData,
+}
+
+impl Image {
+    /// Decodes an image in the format of a BLOB, such as `.fits`, `.xisf`
+    /// or `.raw`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::NotSupported`] for formats without a decoder,
+    /// such as `.jpeg` and `.tiff`, and [`IndigoError::ParseError`] if the
+    /// data is not a valid image.
+    pub fn decode(format: &str, data: &[u8]) -> Result<Image> {
+        match format.to_ascii_lowercase().as_str() {
+            ".fits" | ".fit" | ".fts" => fits::read(data),
+            ".xisf" => xisf::read(data),
+            ".raw" => raw::read(data),
+            _ => Err(IndigoError::NotSupported(format!(
+                "Decoding images in format '{}'",
+                format
+            ))),
+        }
+    }
+
+    /// Decodes the image of a BLOB value, in the format of the BLOB.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::InvalidParameter`] if the value is not a BLOB,
+    /// and the errors of [`Image::decode`].
+    pub fn from_blob(value: &PropertyValue) -> Result<Image> {
+        match value {
+            PropertyValue::Blob { data, format, .. } => Image::decode(format, data),
+            other => Err(IndigoError::InvalidParameter(format!(
+                "Expected a BLOB image, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> usize {
+        self.data.width()
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> usize {
+        self.data.height()
+    }
+
+    /// Number of color channels, 1 for monochrome and Bayer images.
+    pub fn channels(&self) -> usize {
+        self.data.channels()
+    }
+}
+
+/// Pixels of an image, in the type they were stored in.
+///
+/// Data scaled with `BZERO`/`BSCALE` other than the conventions for unsigned
+/// integers is converted to floating point.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageData {
+    /// Unsigned 8 bit pixels.
+    U8(Pixels<u8>),
+    /// Signed 16 bit pixels.
+    I16(Pixels<i16>),
+    /// Unsigned 16 bit pixels.
+    U16(Pixels<u16>),
+    /// Signed 32 bit pixels.
+    I32(Pixels<i32>),
+    /// Unsigned 32 bit pixels.
+    U32(Pixels<u32>),
+    /// Single precision floating point pixels.
+    F32(Pixels<f32>),
+    /// Double precision floating point pixels.
+    F64(Pixels<f64>),
+}
+
+/// Applies an expression to the pixels of any type.
+macro_rules! with_pixels {
+    ($data:expr, $pixels:ident => $e:expr) => {
+        match $data {
+            ImageData::U8($pixels) => $e,
+            ImageData::I16($pixels) => $e,
+            ImageData::U16($pixels) => $e,
+            ImageData::I32($pixels) => $e,
+            ImageData::U32($pixels) => $e,
+            ImageData::F32($pixels) => $e,
+            ImageData::F64($pixels) => $e,
+        }
+    };
+}
+
+impl ImageData {
+    /// Width in pixels.
+    pub fn width(&self) -> usize {
+        with_pixels!(self, pixels => pixels.width())
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> usize {
+        with_pixels!(self, pixels => pixels.height())
+    }
+
+    /// Number of color channels.
+    pub fn channels(&self) -> usize {
+        with_pixels!(self, pixels => pixels.channels())
+    }
+
+    /// Gets the value of a pixel in the first channel, whatever its type.
+    pub fn value(&self, x: usize, y: usize) -> Option<f64> {
+        match self {
+            ImageData::U8(pixels) => pixels.get(x, y).map(f64::from),
+            ImageData::I16(pixels) => pixels.get(x, y).map(f64::from),
+            ImageData::U16(pixels) => pixels.get(x, y).map(f64::from),
+            ImageData::I32(pixels) => pixels.get(x, y).map(f64::from),
+            ImageData::U32(pixels) => pixels.get(x, y).map(f64::from),
+            ImageData::F32(pixels) => pixels.get(x, y).map(f64::from),
+            ImageData::F64(pixels) => pixels.get(x, y),
+        }
+    }
+}
+
+/// A 2D array of pixels with one or more channels.
+///
+/// Pixels are stored row by row, one channel after the other, with rows in
+/// the order they were stored in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pixels<T> {
+    width: usize,
+    height: usize,
+    channels: usize,
+    data: Vec<T>,
+}
+
+impl<T: Copy> Pixels<T> {
+    /// Creates pixels from data stored row by row, channel by channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::InvalidParameter`] if the length of the data
+    /// doesn't match the dimensions.
+    pub fn new(width: usize, height: usize, channels: usize, data: Vec<T>) -> Result<Self> {
+        check_size(width, height, channels, data.len())?;
+        Ok(Pixels {
+            width,
+            height,
+            channels,
+            data,
+        })
+    }
+
+    /// Creates pixels from data with the channels of each pixel together,
+    /// such as RGB triplets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndigoError::InvalidParameter`] if the length of the data
+    /// doesn't match the dimensions.
+    pub fn from_interleaved(
+        width: usize,
+        height: usize,
+        channels: usize,
+        data: Vec<T>,
+    ) -> Result<Self> {
+        check_size(width, height, channels, data.len())?;
+        let planes = (0..channels)
+            .flat_map(|channel| data.iter().skip(channel).step_by(channels).copied())
+            .collect();
+        Ok(Pixels {
+            width,
+            height,
+            channels,
+            data: planes,
+        })
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of channels.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Gets a pixel of the first channel.
+    pub fn get(&self, x: usize, y: usize) -> Option<T> {
+        self.get_channel(x, y, 0)
+    }
+
+    /// Gets a pixel of a channel.
+    pub fn get_channel(&self, x: usize, y: usize, channel: usize) -> Option<T> {
+        if x >= self.width || y >= self.height || channel >= self.channels {
+            return None;
+        }
+        Some(self.data[(channel * self.height + y) * self.width + x])
+    }
+
+    /// Gets a row of the first channel.
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y >= self.height {
+            return None;
+        }
+        self.data.get(y * self.width..(y + 1) * self.width)
+    }
+
+    /// Gets all pixels of a channel, row by row.
+    pub fn channel(&self, channel: usize) -> Option<&[T]> {
+        let size = self.width * self.height;
+        self.data.get(channel * size..(channel + 1) * size)
+    }
+
+    /// Gets all pixels.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Takes the pixels, row by row and channel by channel.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+/// Checks that the dimensions of an image match the number of pixels.
+fn check_size(width: usize, height: usize, channels: usize, count: usize) -> Result<()> {
+    let expected = width
+        .checked_mul(height)
+        .and_then(|size| size.checked_mul(channels));
+    if expected != Some(count) {
+        return Err(IndigoError::InvalidParameter(format!(
+            "{} pixels don't fill {}x{}x{}",
+            count, width, height, channels
+        )));
+    }
+    Ok(())
+}
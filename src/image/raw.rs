@@ -0,0 +1,139 @@
+//! INDIGO raw images.
+//!
+//! The raw format is the fastest for live view: a 12 byte header with the
+//! pixel type, width and height, followed by the pixels in little-endian
+//! byte order with the channels of color pixels together.
+
+use super::{fits, Header, Image, ImageData, Pixels};
+use crate::error::{IndigoError, Result};
+
+/// Size of the raw header.
+const HEADER: usize = 12;
+
+/// Size of FITS header cards following the pixels.
+const CARD: usize = 80;
+
+/// Pixel types of raw images, identified by the signature of the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawType {
+    /// 8 bit monochrome, signature `RAW1`.
+    Mono8,
+    /// 16 bit monochrome, signature `RAW2`.
+    Mono16,
+    /// 8 bit RGB, signature `RGB3`.
+    Rgb24,
+    /// 16 bit RGB, signature `RGB6`.
+    Rgb48,
+}
+
+impl RawType {
+    /// Gets the type with a signature.
+    pub fn from_signature(signature: &[u8; 4]) -> Option<Self> {
+        match signature {
+            b"RAW1" => Some(RawType::Mono8),
+            b"RAW2" => Some(RawType::Mono16),
+            b"RGB3" => Some(RawType::Rgb24),
+            b"RGB6" => Some(RawType::Rgb48),
+            _ => None,
+        }
+    }
+
+    /// Number of color channels.
+    pub fn channels(&self) -> usize {
+        match self {
+            RawType::Mono8 | RawType::Mono16 => 1,
+            RawType::Rgb24 | RawType::Rgb48 => 3,
+        }
+    }
+
+    /// Size of a sample in bytes.
+    fn sample_size(&self) -> usize {
+        match self {
+            RawType::Mono8 | RawType::Rgb24 => 1,
+            RawType::Mono16 | RawType::Rgb48 => 2,
+        }
+    }
+}
+
+/// Reads an INDIGO raw image.
+///
+/// Raw images carry no keywords, unless FITS header cards follow the pixels,
+/// which are then read into the header.
+///
+/// # Errors
+///
+/// Returns [`IndigoError::ParseError`] if the data is not a valid raw image
+/// and [`IndigoError::NotSupported`] for unknown pixel types.
+pub fn read(data: &[u8]) -> Result<Image> {
+    let (header, rest) = data
+        .split_first_chunk::<HEADER>()
+        .ok_or_else(|| parse_error("no header"))?;
+    let word = |i: usize| [header[i], header[i + 1], header[i + 2], header[i + 3]];
+    let raw_type = RawType::from_signature(&word(0)).ok_or_else(|| {
+        IndigoError::NotSupported(format!(
+            "Raw images of type {}",
+            String::from_utf8_lossy(&word(0)).escape_debug()
+        ))
+    })?;
+    let dimension = |i: usize| {
+        usize::try_from(i32::from_le_bytes(word(i))).map_err(|_| parse_error("invalid size"))
+    };
+    let (width, height) = (dimension(4)?, dimension(8)?);
+    let channels = raw_type.channels();
+
+    let length = width
+        .checked_mul(height)
+        .and_then(|count| count.checked_mul(channels * raw_type.sample_size()))
+        .filter(|&length| length <= rest.len())
+        .ok_or_else(|| parse_error("data is truncated"))?;
+    let (pixels, trailer) = rest.split_at(length);
+    let data = match raw_type.sample_size() {
+        1 => ImageData::U8(Pixels::from_interleaved(
+            width,
+            height,
+            channels,
+            pixels.to_vec(),
+        )?),
+        _ => ImageData::U16(Pixels::from_interleaved(
+            width,
+            height,
+            channels,
+            pixels
+                .as_chunks::<2>()
+                .0
+                .iter()
+                .map(|&bytes| u16::from_le_bytes(bytes))
+                .collect(),
+        )?),
+    };
+    Ok(Image {
+        header: keywords(trailer),
+        data,
+    })
+}
+
+/// Reads FITS header cards following the pixels, ignoring anything else.
+fn keywords(trailer: &[u8]) -> Header {
+    let mut header = Header::new();
+    let (cards, rest) = trailer.as_chunks::<CARD>();
+    if !rest.is_empty() {
+        return header;
+    }
+    for card in cards {
+        if card.starts_with(b"END     ") {
+            break;
+        }
+        if card.iter().all(|&b| b == b' ') {
+            continue;
+        }
+        match fits::parse_card(card) {
+            Ok(keyword) => header.push(keyword),
+            Err(_) => return Header::new(),
+        }
+    }
+    header
+}
+
+fn parse_error(reason: &str) -> IndigoError {
+    IndigoError::ParseError(format!("Invalid raw image: {}", reason))
+}
@@ -0,0 +1,275 @@
+//! XISF images.
+//!
+//! Reads monolithic XISF files: a signature, an XML header describing the
+//! image, and the pixels as an attachment. FITS keywords in the header are
+//! read into [`Header`] as in FITS files.
+
+use super::{fits, Header, Image, ImageData, Keyword, Pixels};
+use crate::error::{IndigoError, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::borrow::Cow;
+use std::io::Read;
+
+/// Signature of monolithic XISF 1.0 files.
+const SIGNATURE: &[u8] = b"XISF0100";
+
+/// Size of the signature and the header length that follows it, with 4
+/// reserved bytes.
+const PREAMBLE: usize = 16;
+
+/// Largest buffer reserved in advance for inflated data, as the announced
+/// size is not trusted.
+const MAX_PREALLOCATION: usize = 4 * 1024 * 1024; // 4 MB
+
+/// Description of the first image of an XISF header.
+#[derive(Debug, Default)]
+struct Description {
+    geometry: String,
+    sample_format: String,
+    location: String,
+    pixel_storage: Option<String>,
+    byte_order: Option<String>,
+    compression: Option<String>,
+    header: Header,
+}
+
+/// Reads the first image of a monolithic XISF file.
+///
+/// # Errors
+///
+/// Returns [`IndigoError::ParseError`] if the data is not a valid XISF image
+/// and [`IndigoError::NotSupported`] for complex or 64 bit integer samples,
+/// images of more than two dimensions, data not stored as an attachment,
+/// and compression other than zlib.
+pub fn read(data: &[u8]) -> Result<Image> {
+    if !data.starts_with(SIGNATURE) {
+        return Err(parse_error("not a monolithic XISF file"));
+    }
+    let length = data
+        .get(SIGNATURE.len()..SIGNATURE.len() + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+        .ok_or_else(|| parse_error("no header"))?;
+    let xml = data
+        .get(PREAMBLE..PREAMBLE + length)
+        .and_then(|xml| std::str::from_utf8(xml).ok())
+        .ok_or_else(|| parse_error("invalid header"))?;
+    let description = describe(xml)?;
+
+    let dimensions = description
+        .geometry
+        .split(':')
+        .map(|size| size.trim().parse::<usize>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| parse_error(&format!("invalid geometry {}", description.geometry)))?;
+    let (width, height, channels) = match dimensions[..] {
+        [width, height] => (width, height, 1),
+        [width, height, channels] => (width, height, channels),
+        _ => {
+            return Err(IndigoError::NotSupported(format!(
+                "XISF images with geometry {}",
+                description.geometry
+            )))
+        }
+    };
+    let sample_size = match description.sample_format.as_str() {
+        "UInt8" => 1,
+        "UInt16" => 2,
+        "UInt32" | "Float32" => 4,
+        "Float64" => 8,
+        format => {
+            return Err(IndigoError::NotSupported(format!(
+                "XISF images with {} samples",
+                format
+            )))
+        }
+    };
+
+    let stored = attachment(data, &description.location)?;
+    let mut samples = match &description.compression {
+        Some(compression) => Cow::Owned(decompress(stored, compression)?),
+        None => Cow::Borrowed(stored),
+    };
+    let expected = width
+        .checked_mul(height)
+        .and_then(|count| count.checked_mul(channels))
+        .and_then(|count| count.checked_mul(sample_size));
+    if expected != Some(samples.len()) {
+        return Err(parse_error("data doesn't match the geometry"));
+    }
+    if description.byte_order.as_deref() == Some("big") {
+        for sample in samples.to_mut().chunks_exact_mut(sample_size) {
+            sample.reverse();
+        }
+    }
+
+    let interleaved = description.pixel_storage.as_deref() == Some("Normal");
+    let shape = (width, height, channels, interleaved);
+    let data = match description.sample_format.as_str() {
+        "UInt8" => ImageData::U8(arrange(samples.into_owned(), shape)?),
+        "UInt16" => ImageData::U16(arrange(values(&samples, u16::from_le_bytes), shape)?),
+        "UInt32" => ImageData::U32(arrange(values(&samples, u32::from_le_bytes), shape)?),
+        "Float32" => ImageData::F32(arrange(values(&samples, f32::from_le_bytes), shape)?),
+        _ => ImageData::F64(arrange(values(&samples, f64::from_le_bytes), shape)?),
+    };
+    Ok(Image {
+        header: description.header,
+        data,
+    })
+}
+
+/// Reads the description of the first image from the XML header.
+fn describe(xml: &str) -> Result<Description> {
+    let mut reader = Reader::from_str(xml);
+    let mut description = None;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| parse_error(&format!("invalid header: {}", e)))?;
+        match (&mut description, event) {
+            (None, Event::Start(e) | Event::Empty(e)) if e.local_name().as_ref() == b"Image" => {
+                description = Some(Description {
+                    geometry: attribute(&e, "geometry")?.unwrap_or_default(),
+                    sample_format: attribute(&e, "sampleFormat")?.unwrap_or_default(),
+                    location: attribute(&e, "location")?.unwrap_or_default(),
+                    pixel_storage: attribute(&e, "pixelStorage")?,
+                    byte_order: attribute(&e, "byteOrder")?,
+                    compression: attribute(&e, "compression")?,
+                    header: Header::new(),
+                });
+            }
+            (Some(description), Event::Start(e) | Event::Empty(e))
+                if e.local_name().as_ref() == b"FITSKeyword" =>
+            {
+                let name = attribute(&e, "name")?.unwrap_or_default();
+                let value = attribute(&e, "value")?.unwrap_or_default();
+                let (value, _) = fits::parse_field(&name, &value)?;
+                let comment = attribute(&e, "comment")?.filter(|comment| !comment.is_empty());
+                description.header.push(Keyword {
+                    name,
+                    value,
+                    comment,
+                });
+            }
+            (Some(_), Event::End(e)) if e.local_name().as_ref() == b"Image" => break,
+            (_, Event::Eof) => break,
+            _ => {}
+        }
+    }
+    description.ok_or_else(|| parse_error("no image"))
+}
+
+/// Gets the data of an image stored at `attachment:position:size`.
+fn attachment<'a>(data: &'a [u8], location: &str) -> Result<&'a [u8]> {
+    let mut parts = location.split(':');
+    if parts.next() != Some("attachment") {
+        return Err(IndigoError::NotSupported(format!(
+            "XISF data stored as {}",
+            location
+        )));
+    }
+    let mut number = || {
+        parts
+            .next()
+            .and_then(|part| part.trim().parse::<usize>().ok())
+    };
+    let (position, size) = number()
+        .zip(number())
+        .ok_or_else(|| parse_error(&format!("invalid location {}", location)))?;
+    position
+        .checked_add(size)
+        .and_then(|end| data.get(position..end))
+        .ok_or_else(|| parse_error("data is truncated"))
+}
+
+/// Decompresses data compressed as `codec:size` or `codec:size:item-size`.
+fn decompress(data: &[u8], compression: &str) -> Result<Vec<u8>> {
+    let mut parts = compression.split(':');
+    let codec = parts.next().unwrap_or_default();
+    let mut number = || {
+        parts
+            .next()
+            .and_then(|part| part.trim().parse::<usize>().ok())
+    };
+    let size =
+        number().ok_or_else(|| parse_error(&format!("invalid compression {}", compression)))?;
+    let item_size = number();
+    if item_size.is_some_and(|item_size| item_size == 0 || item_size > size) {
+        return Err(parse_error(&format!("invalid compression {}", compression)));
+    }
+    let shuffled = match codec {
+        "zlib" => false,
+        "zlib+sh" => true,
+        _ => {
+            return Err(IndigoError::NotSupported(format!(
+                "XISF data compressed with {}",
+                codec
+            )))
+        }
+    };
+
+    // Reading one byte more than announced detects data that inflates beyond
+    // its size without inflating all of it
+    let mut inflated = Vec::with_capacity(size.min(MAX_PREALLOCATION));
+    flate2::read::ZlibDecoder::new(data)
+        .take(size as u64 + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|e| parse_error(&format!("invalid compressed data: {}", e)))?;
+    if inflated.len() != size {
+        return Err(parse_error("compressed data has the wrong size"));
+    }
+    match item_size {
+        Some(item_size) if shuffled && item_size > 1 => Ok(unshuffle(&inflated, item_size)),
+        _ => Ok(inflated),
+    }
+}
+
+/// Restores the order of bytes shuffled before compression, which puts the
+/// first bytes of all items first, then the second bytes, and so on.
+fn unshuffle(data: &[u8], item_size: usize) -> Vec<u8> {
+    let count = data.len() / item_size;
+    let mut items = vec![0; data.len()];
+    for (byte, column) in data[..count * item_size].chunks_exact(count).enumerate() {
+        for (item, &value) in column.iter().enumerate() {
+            items[item * item_size + byte] = value;
+        }
+    }
+    // Bytes of an incomplete last item are not shuffled
+    items[count * item_size..].copy_from_slice(&data[count * item_size..]);
+    items
+}
+
+/// Decodes little-endian values of `N` bytes.
+fn values<const N: usize, T>(data: &[u8], decode: impl Fn([u8; N]) -> T) -> Vec<T> {
+    data.as_chunks::<N>()
+        .0
+        .iter()
+        .map(|&bytes| decode(bytes))
+        .collect()
+}
+
+/// Arranges samples of planar or interleaved (`Normal`) storage into pixels.
+fn arrange<T: Copy>(
+    values: Vec<T>,
+    (width, height, channels, interleaved): (usize, usize, usize, bool),
+) -> Result<Pixels<T>> {
+    if interleaved {
+        Pixels::from_interleaved(width, height, channels, values)
+    } else {
+        Pixels::new(width, height, channels, values)
+    }
+}
+
+/// Gets the value of an attribute of an element.
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+    let invalid = |e: quick_xml::Error| parse_error(&format!("invalid attribute {}: {}", name, e));
+    let Some(attribute) = element.try_get_attribute(name).map_err(invalid)? else {
+        return Ok(None);
+    };
+    let value = attribute.unescape_value().map_err(invalid)?;
+    Ok(Some(value.into_owned()))
+}
+
+fn parse_error(reason: &str) -> IndigoError {
+    IndigoError::ParseError(format!("Invalid XISF image: {}", reason))
+}
@@ -58,8 +58,15 @@ pub mod logging;
 #[cfg(feature = "discovery")]
 pub mod discovery;
 
+/// Decoding of camera images received as BLOBs.
+#[cfg(feature = "image")]
+pub mod image;
+
 // Re-export commonly used types
-pub use client::{AvailabilityStatus, ClientStrategy, MonitoringConfig, MonitoringEvent};
+pub use client::{
+    AvailabilityStatus, ClientStrategy, ConnectionEvent, MonitoringConfig, MonitoringEvent,
+    PropertyEvent, PropertyOutcome, ReconnectPolicy,
+};
 #[cfg(feature = "discovery")]
 pub use discovery::{
     DiscoveredServer, DiscoveryConfig, DiscoveryError, DiscoveryEvent, DiscoveryMode,
@@ -77,7 +84,9 @@ pub use types::{Device, DeviceInfo};
 /// use libindigo::prelude::*;
 /// ```
 pub mod prelude {
-    pub use crate::client::{Client, ClientBuilder, ClientStrategy};
+    pub use crate::client::{
+        Client, ClientBuilder, ClientStrategy, PropertyEvent, PropertyOutcome,
+    };
     pub use crate::device::{DeviceDriver, DeviceInterface, DriverInfo};
     pub use crate::error::{IndigoError, Result};
     pub use crate::logging::{init_logging, LogConfig, LogLevel};
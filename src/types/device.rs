@@ -2,7 +2,8 @@
 //!
 //! This module provides types for representing INDIGO devices and their metadata.
 
-use std::collections::HashMap;
+use super::property::Property;
+use std::collections::{BTreeSet, HashMap};
 
 /// Represents an INDIGO device.
 #[derive(Debug, Clone, PartialEq)]
@@ -14,8 +15,7 @@ pub struct Device {
     pub info: DeviceInfo,
 
     /// Device properties (property name -> property).
-    /// TODO: This will be populated in future phases.
-    pub properties: HashMap<String, ()>,
+    pub properties: HashMap<String, Property>,
 }
 
 /// Information about a device.
@@ -55,6 +55,26 @@ impl Device {
             properties: HashMap::new(),
         }
     }
+
+    /// Gets a property by name.
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.get(name)
+    }
+
+    /// Returns the names of all property groups, sorted alphabetically.
+    pub fn groups(&self) -> Vec<String> {
+        self.properties
+            .values()
+            .map(|p| p.group.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns all properties belonging to the given group.
+    pub fn properties_in_group<'a>(&'a self, group: &'a str) -> impl Iterator<Item = &'a Property> {
+        self.properties.values().filter(move |p| p.group == group)
+    }
 }
 
 impl DeviceInfo {
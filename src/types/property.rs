@@ -3,7 +3,7 @@
 //! This module provides types for representing INDIGO properties and their items,
 //! along with builder patterns for ergonomic construction.
 
-use super::value::PropertyValue;
+use super::value::{PropertyValue, SwitchRule};
 use crate::error::PropertyBuilderError;
 use std::collections::HashMap;
 
@@ -31,6 +31,9 @@ pub struct Property {
     /// Type of the property.
     pub property_type: PropertyType,
 
+    /// Switch rule, for switch properties.
+    pub switch_rule: Option<SwitchRule>,
+
     /// Property items (name -> value mapping).
     pub items: HashMap<String, PropertyItem>,
 
@@ -208,6 +211,7 @@ pub struct PropertyBuilder {
     state: PropertyState,
     perm: PropertyPerm,
     property_type: Option<PropertyType>,
+    switch_rule: Option<SwitchRule>,
     items: HashMap<String, PropertyItem>,
     timeout: Option<f64>,
     timestamp: Option<String>,
@@ -257,6 +261,12 @@ impl PropertyBuilder {
         self
     }
 
+    /// Sets the switch rule.
+    pub fn switch_rule(mut self, rule: SwitchRule) -> Self {
+        self.switch_rule = Some(rule);
+        self
+    }
+
     /// Adds an item to the property.
     pub fn item(mut self, item: PropertyItem) -> Self {
         self.items.insert(item.name.clone(), item);
@@ -316,6 +326,7 @@ impl PropertyBuilder {
             state: self.state,
             perm: self.perm,
             property_type,
+            switch_rule: self.switch_rule,
             items: self.items,
             timeout: self.timeout,
             timestamp: self.timestamp,
@@ -107,6 +107,8 @@ pub enum PropertyValue {
         format: String,
         /// Size of the data in bytes.
         size: usize,
+        /// Size in bytes as transferred, when the BLOB was sent compressed.
+        compressed_size: Option<usize>,
     },
 }
 
@@ -207,6 +209,7 @@ impl PropertyValue {
             data,
             format: format.into(),
             size,
+            compressed_size: None,
         }
     }
 }
@@ -0,0 +1,190 @@
+//! Integration tests for streaming reception of large BLOBs.
+
+use libindigo::client::ClientStrategy;
+use libindigo::device::{DeviceContext, DeviceDriver, DeviceInterface, DriverInfo, DriverRegistry};
+use libindigo::error::{IndigoError, Result};
+use libindigo::types::{
+    BlobTransferMode, Property, PropertyItem, PropertyPerm, PropertyState, PropertyType,
+    PropertyValue,
+};
+use libindigo_rs::blob::{BlobHeader, BlobSink, BlobSinkFactory};
+use libindigo_rs::{IndigoServer, ProtocolNegotiator, RemoteDevice, RsClientStrategy};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod fixtures;
+
+use fixtures::{wait_for_property, wait_until};
+
+const DEVICE: &str = "Rust Camera";
+
+/// Larger than the 10 MB limit of the read buffer, even when decoded.
+const IMAGE_SIZE: usize = 24 * 1024 * 1024;
+
+/// Camera driver that sends an image of the requested size.
+struct RustCamera;
+
+fn image(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+#[async_trait::async_trait]
+impl DeviceDriver for RustCamera {
+    fn info(&self) -> DriverInfo {
+        DriverInfo {
+            name: DEVICE.to_string(),
+            description: "Camera written in Rust".to_string(),
+            version: "1.0.0".to_string(),
+            interfaces: DeviceInterface::Ccd as u32,
+        }
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        let manager = ctx.property_manager();
+        manager.define_property(
+            Property::builder()
+                .device(DEVICE)
+                .name("IMAGE_SIZE")
+                .group("Camera")
+                .label("Image size")
+                .state(PropertyState::Idle)
+                .perm(PropertyPerm::ReadWrite)
+                .property_type(PropertyType::Number)
+                .item(PropertyItem::new(
+                    "SIZE",
+                    "Size",
+                    PropertyValue::Number {
+                        value: 0.0,
+                        min: 0.0,
+                        max: 1e9,
+                        step: 1.0,
+                        format: "%.0f".to_string(),
+                    },
+                ))
+                .build()?,
+        )?;
+        manager.define_property(
+            Property::builder()
+                .device(DEVICE)
+                .name("CCD_IMAGE")
+                .group("Camera")
+                .label("Image")
+                .state(PropertyState::Idle)
+                .perm(PropertyPerm::ReadOnly)
+                .property_type(PropertyType::Blob)
+                .item(PropertyItem::new(
+                    "IMAGE",
+                    "Image",
+                    PropertyValue::blob(Vec::new(), ".fits"),
+                ))
+                .build()?,
+        )
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        let size = match property.items.get("SIZE").map(|i| &i.value) {
+            Some(PropertyValue::Number { value, .. }) => *value,
+            _ => return Err(IndigoError::NotSupported(property.name.clone())),
+        };
+        let manager = ctx.property_manager();
+        manager.update_property(
+            "CCD_IMAGE",
+            PropertyState::Ok,
+            vec![(
+                "IMAGE".to_string(),
+                PropertyValue::blob(image(size as usize), ".fits"),
+            )],
+        )?;
+        let values = vec![("SIZE".to_string(), property.items["SIZE"].value.clone())];
+        manager.update_property("IMAGE_SIZE", PropertyState::Ok, values)
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        ctx.property_manager().delete_all_properties();
+        Ok(())
+    }
+}
+
+/// Starts a server hosting the camera and connects an XML client to it.
+async fn connect(blob_sinks: Option<BlobSinkFactory>) -> (IndigoServer, RsClientStrategy) {
+    let mut registry = DriverRegistry::new();
+    registry.register(Box::new(RustCamera)).unwrap();
+    let server = IndigoServer::start("127.0.0.1:0", registry)
+        .await
+        .expect("Failed to start server");
+
+    // Only the XML protocol sends the content of BLOBs
+    let mut client = RsClientStrategy::with_protocol_negotiator(ProtocolNegotiator::xml_only());
+    client.set_blob_sink_factory(blob_sinks).await;
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_property(&client, DEVICE, "CCD_IMAGE").await;
+    client
+        .enable_blob(DEVICE, None, BlobTransferMode::Also)
+        .await
+        .unwrap();
+    (server, client)
+}
+
+/// Requests an image and waits for the client to receive it.
+async fn expose(client: &RsClientStrategy, size: usize) -> (Vec<u8>, usize) {
+    let mut camera = RemoteDevice::new(client, DEVICE).await.unwrap();
+    camera
+        .change_numbers("IMAGE_SIZE", &[("SIZE", size as f64)])
+        .await
+        .unwrap();
+    let image = || async {
+        match client.property(DEVICE, "CCD_IMAGE").await?.items["IMAGE"].value {
+            PropertyValue::Blob { ref data, size, .. } if size > 0 => Some((data.clone(), size)),
+            _ => None,
+        }
+    };
+    wait_until(
+        client,
+        Duration::from_secs(60),
+        "Image was not received",
+        || async { image().await.is_some() },
+    )
+    .await;
+    image().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_receive_large_blob_into_memory() {
+    let (server, mut client) = connect(None).await;
+
+    let (data, size) = expose(&client, IMAGE_SIZE).await;
+    assert_eq!(size, IMAGE_SIZE);
+    assert!(data == image(IMAGE_SIZE), "Image was corrupted");
+
+    client.disconnect().await.unwrap();
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_receive_large_blob_into_file() {
+    let path = std::env::temp_dir().join(format!("libindigo-blob-{}.fits", std::process::id()));
+    let file_path = path.clone();
+    let sinks: BlobSinkFactory = Arc::new(move |header: &BlobHeader| {
+        assert_eq!(header.device, DEVICE);
+        assert_eq!(header.property, "CCD_IMAGE");
+        assert_eq!(header.size, IMAGE_SIZE);
+        let file = std::fs::File::create(&file_path).ok()?;
+        Some(Box::new(tokio::fs::File::from_std(file)) as BlobSink)
+    });
+    let (server, mut client) = connect(Some(sinks)).await;
+
+    // The client only learns the size, the data is in the file
+    let (data, size) = expose(&client, IMAGE_SIZE).await;
+    assert_eq!(size, IMAGE_SIZE);
+    assert!(data.is_empty());
+    let written = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(written == image(IMAGE_SIZE), "Image was corrupted");
+
+    client.disconnect().await.unwrap();
+    server.shutdown().await.unwrap();
+}
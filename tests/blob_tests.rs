@@ -216,6 +216,8 @@ fn test_serialize_new_blob_vector() {
             size: data.len(),
             format: ".fits".to_string(),
             value: encoded,
+            data: None,
+            compressed_size: None,
         }],
     });
 
@@ -246,6 +248,8 @@ fn test_new_blob_vector_round_trip() {
             size: data.len(),
             format: ".fits".to_string(),
             value: encoded.clone(),
+            data: None,
+            compressed_size: None,
         }],
     });
 
@@ -318,10 +322,12 @@ fn test_property_value_blob_creation() {
             data: d,
             format,
             size,
+            compressed_size,
         } => {
             assert_eq!(d, data);
             assert_eq!(format, ".fits");
             assert_eq!(size, data.len());
+            assert_eq!(compressed_size, None);
         }
         _ => panic!("Expected Blob variant"),
     }
@@ -353,10 +359,12 @@ fn test_blob_property_builder() {
             data: d,
             format,
             size,
+            compressed_size,
         } => {
             assert_eq!(d, data);
             assert_eq!(format, ".fits");
             assert_eq!(*size, data.len());
+            assert_eq!(*compressed_size, None);
         }
         _ => panic!("Expected Blob value"),
     }
@@ -0,0 +1,186 @@
+//! Integration tests for BLOBs sent by URL over the JSON protocol.
+
+use libindigo::client::ClientStrategy;
+use libindigo::types::PropertyValue;
+use libindigo_rs::blob::compress;
+use libindigo_rs::protocol::{
+    DefBLOB, DefBLOBVector, OneBLOB, PropertyPerm, PropertyState, ProtocolMessage, SetBLOBVector,
+    SetVectorAttributes, VectorAttributes,
+};
+use libindigo_rs::protocol_json::JsonProtocolSerializer;
+use libindigo_rs::RsClientStrategy;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+mod fixtures;
+
+use fixtures::wait_until;
+
+const DEVICE: &str = "CCD Imager";
+
+fn image(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i * 13 % 251) as u8).collect()
+}
+
+/// Stand-in for an INDIGO server that sends an image by URL, and serves it
+/// over HTTP on the same port as INDIGO servers do.
+async fn start_server(url: &'static str, format: &'static str, image: Vec<u8>) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let image = Arc::new(image);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream, url, format, image.clone()));
+        }
+    });
+    addr
+}
+
+async fn serve(mut stream: TcpStream, url: &str, format: &str, image: Arc<Vec<u8>>) {
+    let mut request = vec![0u8; 4096];
+    let Ok(count) = stream.read(&mut request).await else {
+        return;
+    };
+
+    if request.starts_with(b"GET ") {
+        let request = String::from_utf8_lossy(&request[..count]);
+        let path = request.split_whitespace().nth(1).unwrap_or_default();
+        if path.starts_with("/blob/0x10381d798.fits") {
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: image/fits\r\nContent-Length: {}\r\n\r\n",
+                image.len()
+            );
+            let _ = stream.write_all(head.as_bytes()).await;
+            let _ = stream.write_all(&image).await;
+        } else {
+            let _ = stream
+                .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                .await;
+        }
+        return;
+    }
+
+    // Answer getProperties with the image property and an image
+    let definition = ProtocolMessage::DefBLOBVector(DefBLOBVector {
+        attrs: VectorAttributes {
+            device: DEVICE.to_string(),
+            name: "CCD_IMAGE".to_string(),
+            label: "Image".to_string(),
+            group: "Image".to_string(),
+            state: PropertyState::Idle,
+            timeout: None,
+            timestamp: None,
+            message: None,
+        },
+        perm: PropertyPerm::ReadOnly,
+        elements: vec![DefBLOB {
+            name: "IMAGE".to_string(),
+            label: "Image".to_string(),
+        }],
+    });
+    let update = ProtocolMessage::SetBLOBVector(SetBLOBVector {
+        attrs: SetVectorAttributes {
+            device: DEVICE.to_string(),
+            name: "CCD_IMAGE".to_string(),
+            state: Some(PropertyState::Ok),
+            timeout: None,
+            timestamp: None,
+            message: None,
+        },
+        elements: vec![OneBLOB {
+            name: "IMAGE".to_string(),
+            size: image.len(),
+            format: format.to_string(),
+            value: url.to_string(),
+            data: None,
+            compressed_size: None,
+        }],
+    });
+    for message in [definition, update] {
+        let json = JsonProtocolSerializer::serialize(&message).unwrap() + "\n";
+        if stream.write_all(json.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+
+    // Keep the connection open until the client leaves
+    while matches!(stream.read(&mut request).await, Ok(count) if count > 0) {}
+}
+
+/// Waits until the client knows the state of the image property.
+async fn wait_for_image(client: &RsClientStrategy, state: PropertyState) -> PropertyValue {
+    let image = || async {
+        let property = client.property(DEVICE, "CCD_IMAGE").await?;
+        (property.state == state).then(|| property.items["IMAGE"].value.clone())
+    };
+    wait_until(
+        client,
+        Duration::from_secs(10),
+        "Image was not received",
+        || async { image().await.is_some() },
+    )
+    .await;
+    image().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_fetch_blob_from_server() {
+    let expected = image(3 * 1024 * 1024);
+    let addr = start_server("/blob/0x10381d798.fits", ".fits", expected.clone()).await;
+    let mut client = RsClientStrategy::new();
+    client.connect(&addr.to_string()).await.unwrap();
+
+    let PropertyValue::Blob { data, size, .. } = wait_for_image(&client, PropertyState::Ok).await
+    else {
+        panic!("Expected BLOB value");
+    };
+    assert_eq!(size, expected.len());
+    assert!(data == expected, "Image was corrupted");
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_fetch_compressed_blob() {
+    let expected = image(3 * 1024 * 1024);
+    let compressed = compress(&expected).unwrap();
+    let addr = start_server("/blob/0x10381d798.fits.z", ".fits.z", compressed.clone()).await;
+    let mut client = RsClientStrategy::new();
+    client.connect(&addr.to_string()).await.unwrap();
+
+    // The client gets the image as if it was sent uncompressed
+    let value = wait_for_image(&client, PropertyState::Ok).await;
+    let PropertyValue::Blob {
+        data,
+        format,
+        size,
+        compressed_size,
+    } = value
+    else {
+        panic!("Expected BLOB value");
+    };
+    assert_eq!(format, ".fits");
+    assert_eq!(size, expected.len());
+    assert_eq!(compressed_size, Some(compressed.len()));
+    assert!(data == expected, "Image was corrupted");
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_missing_blob_is_not_delivered() {
+    let addr = start_server("/blob/0x0.fits", ".fits", image(16)).await;
+    let mut client = RsClientStrategy::new();
+    client.connect(&addr.to_string()).await.unwrap();
+
+    // The update fails, leaving the definition as it was
+    wait_for_image(&client, PropertyState::Idle).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let property = client.property(DEVICE, "CCD_IMAGE").await.unwrap();
+    assert_eq!(property.state, PropertyState::Idle);
+
+    client.disconnect().await.unwrap();
+}
@@ -0,0 +1,934 @@
+//! Tests for the adapters that host high-level device traits as drivers.
+
+use libindigo::client::ClientStrategy;
+use libindigo::device::traits::*;
+use libindigo::device::{
+    CameraDriver, DeviceInterface, DriverRegistry, FilterWheelDriver, FocuserDriver, MountDriver,
+    MountSimulator, TaskEvent, CONFIG_DEFAULT_ITEM,
+};
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
+use libindigo::types::{Property, PropertyState, PropertyType, PropertyValue, SwitchState};
+use libindigo_rs::{IndigoServer, RemoteDevice, RsClientStrategy};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+mod fixtures;
+
+use fixtures::{request, wait_for_property, wait_until, TIMEOUT};
+
+/// Implements [`Device`] for a fake with `name` and `connected` fields.
+macro_rules! fake_device {
+    ($fake:ty, $interface:expr) => {
+        #[async_trait::async_trait]
+        impl Device for $fake {
+            fn name(&self) -> &str {
+                self.name
+            }
+
+            fn device_type(&self) -> DeviceInterface {
+                $interface
+            }
+
+            async fn connect(&mut self) -> Result<()> {
+                self.connected = true;
+                Ok(())
+            }
+
+            async fn disconnect(&mut self) -> Result<()> {
+                self.connected = false;
+                Ok(())
+            }
+
+            fn is_connected(&self) -> bool {
+                self.connected
+            }
+
+            fn description(&self) -> Option<&str> {
+                Some("Fake device")
+            }
+
+            fn driver_version(&self) -> Option<&str> {
+                Some("0.1.0")
+            }
+
+            async fn get_property(&self, _name: &str) -> Result<Option<Property>> {
+                Ok(None)
+            }
+
+            async fn get_properties(&self) -> Result<Vec<Property>> {
+                Ok(Vec::new())
+            }
+
+            async fn wait_for_property_state(
+                &self,
+                name: &str,
+                _state: PropertyState,
+                _timeout: Duration,
+            ) -> Result<Property> {
+                Err(IndigoError::NotSupported(name.to_string()))
+            }
+        }
+    };
+}
+
+// ============================================================================
+// Focuser
+// ============================================================================
+
+#[derive(Debug, Default)]
+struct FocuserState {
+    position: u32,
+    backlash: u32,
+    compensation: bool,
+    speed: f64,
+    temperature: f64,
+    /// Whether moves stay in progress until they are aborted
+    slow: bool,
+    /// Target of the move in progress
+    target: Option<u32>,
+}
+
+struct FakeFocuser {
+    name: &'static str,
+    connected: bool,
+    state: Arc<Mutex<FocuserState>>,
+}
+
+fake_device!(FakeFocuser, DeviceInterface::Focuser);
+
+#[async_trait::async_trait]
+impl Focuser for FakeFocuser {
+    async fn focuser_info(&self) -> Result<FocuserInfo> {
+        Ok(FocuserInfo {
+            max_position: 1000,
+            has_absolute: true,
+            has_temperature_compensation: true,
+        })
+    }
+
+    async fn move_to(&mut self, position: u32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.slow {
+            state.target = Some(position);
+        } else {
+            state.position = position;
+        }
+        Ok(())
+    }
+
+    async fn move_relative(&mut self, steps: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.position = state.position.saturating_add_signed(steps);
+        Ok(())
+    }
+
+    async fn abort_move(&mut self) -> Result<()> {
+        self.state.lock().unwrap().target = None;
+        Ok(())
+    }
+
+    async fn position(&self) -> Result<u32> {
+        Ok(self.state.lock().unwrap().position)
+    }
+
+    async fn is_moving(&self) -> Result<bool> {
+        Ok(self.state.lock().unwrap().target.is_some())
+    }
+
+    async fn temperature(&self) -> Result<f64> {
+        Ok(self.state.lock().unwrap().temperature)
+    }
+
+    async fn set_temperature_compensation(&mut self, enabled: bool) -> Result<()> {
+        self.state.lock().unwrap().compensation = enabled;
+        Ok(())
+    }
+
+    async fn temperature_compensation(&self) -> Result<bool> {
+        Ok(self.state.lock().unwrap().compensation)
+    }
+
+    async fn set_backlash(&mut self, steps: u32) -> Result<()> {
+        self.state.lock().unwrap().backlash = steps;
+        Ok(())
+    }
+
+    async fn backlash(&self) -> Result<u32> {
+        Ok(self.state.lock().unwrap().backlash)
+    }
+
+    async fn set_speed(&mut self, speed: f64) -> Result<()> {
+        self.state.lock().unwrap().speed = speed;
+        Ok(())
+    }
+
+    async fn speed(&self) -> Result<f64> {
+        Ok(self.state.lock().unwrap().speed)
+    }
+}
+
+#[tokio::test]
+async fn test_focuser_driver_over_server() {
+    let state = Arc::new(Mutex::new(FocuserState {
+        position: 100,
+        temperature: 12.5,
+        ..Default::default()
+    }));
+    let mut registry = DriverRegistry::new();
+    registry
+        .register(Box::new(FocuserDriver::new(FakeFocuser {
+            name: "Fake Focuser",
+            connected: false,
+            state: state.clone(),
+        })))
+        .unwrap();
+    let server = IndigoServer::start("127.0.0.1:0", registry).await.unwrap();
+    let mut client = RsClientStrategy::new();
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_property(&client, "Fake Focuser", name::FOCUSER_COMPENSATION_PROPERTY).await;
+
+    let mut focuser = client.focuser("Fake Focuser").await.unwrap();
+    assert_eq!(focuser.name(), "Fake Focuser");
+    focuser.connect().await.unwrap();
+    assert_eq!(focuser.position().await.unwrap(), 100);
+    assert_eq!(focuser.temperature().await.unwrap(), 12.5);
+
+    focuser.move_to(500).await.unwrap();
+    assert_eq!(focuser.position().await.unwrap(), 500);
+    focuser.move_relative(-120).await.unwrap();
+    assert_eq!(focuser.position().await.unwrap(), 380);
+    assert_eq!(state.lock().unwrap().position, 380);
+
+    focuser.set_backlash(25).await.unwrap();
+    assert_eq!(focuser.backlash().await.unwrap(), 25);
+    focuser.set_temperature_compensation(true).await.unwrap();
+    assert!(focuser.temperature_compensation().await.unwrap());
+    focuser.set_speed(1.0).await.unwrap();
+    {
+        let state = state.lock().unwrap();
+        assert_eq!(state.backlash, 25);
+        assert!(state.compensation);
+        assert_eq!(state.speed, 1.0);
+    }
+
+    server.shutdown().await.unwrap();
+}
+
+/// Waits until a number item of a remote device has a value.
+async fn wait_for_number(
+    client: &RsClientStrategy,
+    device: &RemoteDevice,
+    property: &str,
+    item: &str,
+    value: f64,
+) {
+    let what = format!("{}.{} did not reach {}", property, item, value);
+    wait_until(client, TIMEOUT, &what, || async {
+        device.number(property, item).await.ok() == Some(value)
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_focuser_driver_timers_over_server() {
+    let state = Arc::new(Mutex::new(FocuserState {
+        position: 100,
+        temperature: 12.5,
+        slow: true,
+        ..Default::default()
+    }));
+    let mut registry = DriverRegistry::new();
+    registry
+        .register(Box::new(FocuserDriver::new(FakeFocuser {
+            name: "Fake Focuser",
+            connected: false,
+            state: state.clone(),
+        })))
+        .unwrap();
+    let server = IndigoServer::start("127.0.0.1:0", registry).await.unwrap();
+    let mut client = RsClientStrategy::new();
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_property(&client, "Fake Focuser", name::FOCUSER_POSITION_PROPERTY).await;
+    let mut focuser = RemoteDevice::new(&client, "Fake Focuser").await.unwrap();
+    focuser.connect().await.unwrap();
+
+    // Readings are published while connected
+    state.lock().unwrap().temperature = 15.0;
+    wait_for_number(
+        &client,
+        &focuser,
+        name::FOCUSER_TEMPERATURE_PROPERTY,
+        name::FOCUSER_TEMPERATURE_ITEM,
+        15.0,
+    )
+    .await;
+
+    // A move stays busy, without blocking other changes, until it is aborted
+    focuser
+        .send_numbers(
+            name::FOCUSER_POSITION_PROPERTY,
+            &[(name::FOCUSER_POSITION_ITEM, 500.0)],
+        )
+        .await
+        .unwrap();
+    focuser
+        .wait_for_state(
+            name::FOCUSER_POSITION_PROPERTY,
+            PropertyState::Busy,
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+    state.lock().unwrap().position = 300;
+    focuser
+        .change_switches(
+            name::FOCUSER_ABORT_MOTION_PROPERTY,
+            &[(name::FOCUSER_ABORT_MOTION_ITEM, true)],
+        )
+        .await
+        .unwrap();
+    focuser
+        .wait_for_state(
+            name::FOCUSER_POSITION_PROPERTY,
+            PropertyState::Alert,
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        focuser
+            .number(name::FOCUSER_POSITION_PROPERTY, name::FOCUSER_POSITION_ITEM)
+            .await
+            .unwrap(),
+        300.0
+    );
+    assert!(state.lock().unwrap().target.is_none());
+
+    // Readings stop once disconnected
+    focuser.disconnect().await.unwrap();
+    state.lock().unwrap().temperature = 20.0;
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(
+        focuser
+            .number(
+                name::FOCUSER_TEMPERATURE_PROPERTY,
+                name::FOCUSER_TEMPERATURE_ITEM
+            )
+            .await
+            .unwrap(),
+        15.0
+    );
+
+    server.shutdown().await.unwrap();
+}
+
+// ============================================================================
+// Mount
+// ============================================================================
+
+#[tokio::test]
+async fn test_mount_driver_round_trips_with_remote_mount() {
+    let mut registry = DriverRegistry::new();
+    registry
+        .register(Box::new(MountDriver::new(
+            MountSimulator::default().with_slew_rate(100.0),
+        )))
+        .unwrap();
+    let server = IndigoServer::start("127.0.0.1:0", registry).await.unwrap();
+    let mut client = RsClientStrategy::new();
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_property(
+        &client,
+        "Mount Simulator",
+        name::GEOGRAPHIC_COORDINATES_PROPERTY,
+    )
+    .await;
+
+    let mut mount = client.mount("Mount Simulator").await.unwrap();
+    mount.connect().await.unwrap();
+    mount.set_location(48.2, 16.4).await.unwrap();
+    assert_eq!(mount.location().await.unwrap(), (48.2, 16.4));
+
+    mount.unpark().await.unwrap();
+    assert!(!mount.is_parked().await.unwrap());
+    mount.sync_to(Coordinates::new(2.0, 10.0)).await.unwrap();
+    let coordinates = mount.coordinates().await.unwrap();
+    assert_eq!((coordinates.ra, coordinates.dec), (2.0, 10.0));
+
+    mount.slew_to(Coordinates::new(2.0, 30.0)).await.unwrap();
+    let coordinates = mount.coordinates().await.unwrap();
+    assert!(
+        (coordinates.dec - 30.0).abs() < 1e-6,
+        "dec {}",
+        coordinates.dec
+    );
+    assert!(!mount.is_slewing().await.unwrap());
+
+    mount.set_tracking(TrackingMode::Lunar).await.unwrap();
+    assert_eq!(mount.tracking().await.unwrap(), TrackingMode::Lunar);
+    mount.park().await.unwrap();
+    assert!(mount.is_parked().await.unwrap());
+
+    server.shutdown().await.unwrap();
+}
+
+// ============================================================================
+// Camera
+// ============================================================================
+
+struct FakeCamera {
+    name: &'static str,
+    connected: bool,
+    exposure: Option<f64>,
+    binning: Arc<Mutex<BinningMode>>,
+    gain: Arc<Mutex<f64>>,
+}
+
+fake_device!(FakeCamera, DeviceInterface::Ccd);
+
+#[async_trait::async_trait]
+impl Camera for FakeCamera {
+    async fn ccd_info(&self) -> Result<CcdInfo> {
+        Ok(CcdInfo {
+            width: 64,
+            height: 48,
+            pixel_size: 3.8,
+            max_bin_x: 2,
+            max_bin_y: 2,
+            bits_per_pixel: 8,
+        })
+    }
+
+    async fn start_exposure(&mut self, duration: f64) -> Result<()> {
+        self.exposure = Some(duration);
+        Ok(())
+    }
+
+    async fn abort_exposure(&mut self) -> Result<()> {
+        self.exposure = None;
+        Ok(())
+    }
+
+    async fn exposure_state(&self) -> Result<ExposureState> {
+        Ok(ExposureState::Complete)
+    }
+
+    async fn download_image(&self) -> Result<Vec<u8>> {
+        let level = (self.exposure.unwrap_or_default() * 10.0) as u8;
+        Ok(vec![level; 64 * 48])
+    }
+
+    async fn set_frame_type(&mut self, _frame_type: FrameType) -> Result<()> {
+        Ok(())
+    }
+
+    async fn frame_type(&self) -> Result<FrameType> {
+        Ok(FrameType::Light)
+    }
+
+    async fn set_binning(&mut self, binning: BinningMode) -> Result<()> {
+        *self.binning.lock().unwrap() = binning;
+        Ok(())
+    }
+
+    async fn binning(&self) -> Result<BinningMode> {
+        Ok(*self.binning.lock().unwrap())
+    }
+
+    async fn set_frame(&mut self, _x: u32, _y: u32, _width: u32, _height: u32) -> Result<()> {
+        Ok(())
+    }
+
+    async fn frame(&self) -> Result<(u32, u32, u32, u32)> {
+        Ok((0, 0, 64, 48))
+    }
+
+    async fn set_temperature(&mut self, _target: f64) -> Result<()> {
+        Err(IndigoError::NotSupported("No cooler".to_string()))
+    }
+
+    async fn temperature(&self) -> Result<f64> {
+        Ok(20.0)
+    }
+
+    async fn set_cooler(&mut self, _enabled: bool) -> Result<()> {
+        Err(IndigoError::NotSupported("No cooler".to_string()))
+    }
+
+    async fn cooler_enabled(&self) -> Result<bool> {
+        Err(IndigoError::NotSupported("No cooler".to_string()))
+    }
+
+    async fn cooler_power(&self) -> Result<f64> {
+        Err(IndigoError::NotSupported("No cooler".to_string()))
+    }
+
+    async fn set_gain(&mut self, gain: f64) -> Result<()> {
+        *self.gain.lock().unwrap() = gain;
+        Ok(())
+    }
+
+    async fn gain(&self) -> Result<f64> {
+        Ok(*self.gain.lock().unwrap())
+    }
+
+    async fn set_offset(&mut self, _offset: f64) -> Result<()> {
+        Ok(())
+    }
+
+    async fn offset(&self) -> Result<f64> {
+        Ok(0.0)
+    }
+}
+
+fn connect_request() -> Property {
+    request(
+        "Fake",
+        name::CONNECTION_PROPERTY,
+        PropertyType::Switch,
+        vec![(
+            name::CONNECTION_CONNECTED_ITEM,
+            PropertyValue::switch(SwitchState::On),
+        )],
+    )
+}
+
+/// Creates a registry that sends the timer events of its drivers to the
+/// returned receiver.
+fn task_registry() -> (DriverRegistry, mpsc::UnboundedReceiver<TaskEvent>) {
+    let (sender, events) = mpsc::unbounded_channel();
+    let mut registry = DriverRegistry::new();
+    registry.set_task_sender(Some(sender));
+    (registry, events)
+}
+
+fn number_value(registry: &DriverRegistry, device: &str, property: &str, item: &str) -> f64 {
+    let property = registry
+        .context(device)
+        .unwrap()
+        .properties()
+        .get_property(property)
+        .unwrap();
+    match &property.items[item].value {
+        PropertyValue::Number { value, .. } => *value,
+        other => panic!("Expected number, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_camera_driver_exposes_image() {
+    let binning = Arc::new(Mutex::new(BinningMode::default()));
+    let gain = Arc::new(Mutex::new(50.0));
+    let (mut registry, _events) = task_registry();
+    registry
+        .register(Box::new(
+            CameraDriver::new(FakeCamera {
+                name: "Fake Camera",
+                connected: false,
+                exposure: None,
+                binning: binning.clone(),
+                gain: gain.clone(),
+            })
+            .with_image_format(".raw"),
+        ))
+        .unwrap();
+    registry.attach("Fake Camera").await.unwrap();
+    let info = registry
+        .context("Fake Camera")
+        .unwrap()
+        .properties()
+        .get_property("INFO")
+        .unwrap()
+        .clone();
+    assert_eq!(
+        info.items["DEVICE_INTERFACE"].value,
+        PropertyValue::text((DeviceInterface::Ccd as u32).to_string())
+    );
+
+    let exposure = request(
+        "Fake",
+        name::CCD_EXPOSURE_PROPERTY,
+        PropertyType::Number,
+        vec![(name::CCD_EXPOSURE_ITEM, PropertyValue::number(2.0))],
+    );
+    let err = registry
+        .handle_property_change("Fake Camera", &exposure)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, IndigoError::InvalidState(_)));
+
+    registry
+        .handle_property_change("Fake Camera", &connect_request())
+        .await
+        .unwrap();
+    registry
+        .handle_property_change("Fake Camera", &exposure)
+        .await
+        .unwrap();
+    let context = registry.context("Fake Camera").unwrap();
+    let image = context
+        .properties()
+        .get_property(name::CCD_IMAGE_PROPERTY)
+        .unwrap();
+    assert_eq!(image.state, PropertyState::Ok);
+    match &image.items[name::CCD_IMAGE_ITEM].value {
+        PropertyValue::Blob { data, format, .. } => {
+            assert_eq!(format, ".raw");
+            assert_eq!(data.len(), 64 * 48);
+            assert!(data.iter().all(|&pixel| pixel == 20));
+        }
+        other => panic!("Expected BLOB, got {:?}", other),
+    }
+    assert_eq!(
+        number_value(
+            &registry,
+            "Fake Camera",
+            name::CCD_EXPOSURE_PROPERTY,
+            name::CCD_EXPOSURE_ITEM
+        ),
+        0.0
+    );
+
+    registry
+        .handle_property_change(
+            "Fake Camera",
+            &request(
+                "Fake",
+                name::CCD_BIN_PROPERTY,
+                PropertyType::Number,
+                vec![(name::CCD_BIN_HORIZONTAL_ITEM, PropertyValue::number(2.0))],
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(*binning.lock().unwrap(), BinningMode::new(2, 1));
+
+    // Only the features the camera supports are defined
+    let properties = registry.context("Fake Camera").unwrap().properties();
+    assert!(!properties.has_property(name::CCD_TEMPERATURE_PROPERTY));
+    assert!(!properties.has_property(name::CCD_COOLER_PROPERTY));
+    assert!(properties.has_property(name::CCD_OFFSET_PROPERTY));
+    assert_eq!(
+        number_value(
+            &registry,
+            "Fake Camera",
+            name::CCD_GAIN_PROPERTY,
+            name::CCD_GAIN_ITEM
+        ),
+        50.0
+    );
+    registry
+        .handle_property_change(
+            "Fake Camera",
+            &request(
+                "Fake",
+                name::CCD_GAIN_PROPERTY,
+                PropertyType::Number,
+                vec![(name::CCD_GAIN_ITEM, PropertyValue::number(120.0))],
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(*gain.lock().unwrap(), 120.0);
+
+    registry
+        .handle_property_change(
+            "Fake Camera",
+            &request(
+                "Fake",
+                name::CONNECTION_PROPERTY,
+                PropertyType::Switch,
+                vec![(
+                    name::CONNECTION_DISCONNECTED_ITEM,
+                    PropertyValue::switch(SwitchState::On),
+                )],
+            ),
+        )
+        .await
+        .unwrap();
+    let properties = registry.context("Fake Camera").unwrap().properties();
+    assert!(!properties.has_property(name::CCD_GAIN_PROPERTY));
+    assert!(!properties.has_property(name::CCD_OFFSET_PROPERTY));
+}
+
+// ============================================================================
+// Filter wheel
+// ============================================================================
+
+struct FakeWheel {
+    name: &'static str,
+    connected: bool,
+    slot: u32,
+    filters: Arc<Mutex<Vec<FilterInfo>>>,
+}
+
+fake_device!(FakeWheel, DeviceInterface::FilterWheel);
+
+#[async_trait::async_trait]
+impl FilterWheel for FakeWheel {
+    async fn slot_count(&self) -> Result<u32> {
+        Ok(self.filters.lock().unwrap().len() as u32)
+    }
+
+    async fn filters(&self) -> Result<Vec<FilterInfo>> {
+        Ok(self.filters.lock().unwrap().clone())
+    }
+
+    async fn select_filter(&mut self, slot: u32) -> Result<()> {
+        self.slot = slot;
+        Ok(())
+    }
+
+    async fn current_slot(&self) -> Result<u32> {
+        Ok(self.slot)
+    }
+
+    async fn current_filter_name(&self) -> Result<String> {
+        Ok(self.filters.lock().unwrap()[self.slot as usize - 1]
+            .name
+            .clone())
+    }
+
+    async fn is_moving(&self) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn set_filter_name(&mut self, slot: u32, name: &str) -> Result<()> {
+        self.filters.lock().unwrap()[slot as usize - 1].name = name.to_string();
+        Ok(())
+    }
+
+    async fn set_filter_offset(&mut self, slot: u32, offset: i32) -> Result<()> {
+        self.filters.lock().unwrap()[slot as usize - 1].offset = offset;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_filter_wheel_driver_changes_filters() {
+    let filters = wheel_filters();
+    let (mut registry, _events) = task_registry();
+    registry
+        .register(Box::new(FilterWheelDriver::new(FakeWheel {
+            name: "Fake Wheel",
+            connected: false,
+            slot: 2,
+            filters: filters.clone(),
+        })))
+        .unwrap();
+    registry.attach("Fake Wheel").await.unwrap();
+    registry
+        .handle_property_change("Fake Wheel", &connect_request())
+        .await
+        .unwrap();
+    assert_eq!(
+        number_value(
+            &registry,
+            "Fake Wheel",
+            name::WHEEL_SLOT_PROPERTY,
+            name::WHEEL_SLOT_ITEM
+        ),
+        2.0
+    );
+
+    registry
+        .handle_property_change(
+            "Fake Wheel",
+            &request(
+                "Fake",
+                name::WHEEL_SLOT_PROPERTY,
+                PropertyType::Number,
+                vec![(name::WHEEL_SLOT_ITEM, PropertyValue::number(4.0))],
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        number_value(
+            &registry,
+            "Fake Wheel",
+            name::WHEEL_SLOT_PROPERTY,
+            name::WHEEL_SLOT_ITEM
+        ),
+        4.0
+    );
+
+    registry
+        .handle_property_change(
+            "Fake Wheel",
+            &request(
+                "Fake",
+                name::WHEEL_SLOT_NAME_PROPERTY,
+                PropertyType::Text,
+                vec![("SLOT_NAME_3", PropertyValue::text("Ha"))],
+            ),
+        )
+        .await
+        .unwrap();
+    registry
+        .handle_property_change(
+            "Fake Wheel",
+            &request(
+                "Fake",
+                name::WHEEL_SLOT_OFFSET_PROPERTY,
+                PropertyType::Number,
+                vec![("SLOT_OFFSET_3", PropertyValue::number(-40.0))],
+            ),
+        )
+        .await
+        .unwrap();
+    {
+        let filters = filters.lock().unwrap();
+        assert_eq!(filters[2].name, "Ha");
+        assert_eq!(filters[2].offset, -40);
+        assert_eq!(filters[1].name, "R");
+    }
+
+    registry.detach("Fake Wheel").await.unwrap();
+    assert_eq!(
+        registry
+            .context("Fake Wheel")
+            .unwrap()
+            .properties()
+            .property_count(),
+        0
+    );
+}
+
+fn wheel_filters() -> Arc<Mutex<Vec<FilterInfo>>> {
+    Arc::new(Mutex::new(
+        ["L", "R", "G", "B"]
+            .into_iter()
+            .zip(1..)
+            .map(|(name, slot)| FilterInfo {
+                slot,
+                name: name.to_string(),
+                offset: 0,
+            })
+            .collect(),
+    ))
+}
+
+fn wheel_registry(filters: &Arc<Mutex<Vec<FilterInfo>>>, dir: &std::path::Path) -> DriverRegistry {
+    let mut registry = DriverRegistry::new();
+    registry.set_config_dir(Some(dir.to_path_buf()));
+    registry
+        .register(Box::new(FilterWheelDriver::new(FakeWheel {
+            name: "Fake Wheel",
+            connected: false,
+            slot: 1,
+            filters: filters.clone(),
+        })))
+        .unwrap();
+    registry
+}
+
+fn config_request(item: &str) -> Property {
+    request(
+        "Fake",
+        name::CONFIG_PROPERTY,
+        PropertyType::Switch,
+        vec![(item, PropertyValue::switch(SwitchState::On))],
+    )
+}
+
+#[tokio::test]
+async fn test_filter_wheel_config_persistence() {
+    let dir = std::env::temp_dir().join(format!("libindigo-config-{}", std::process::id()));
+
+    let filters = wheel_filters();
+    let mut registry = wheel_registry(&filters, &dir);
+    registry.attach("Fake Wheel").await.unwrap();
+    registry
+        .handle_property_change(
+            "Fake Wheel",
+            &request(
+                "Fake",
+                name::WHEEL_SLOT_NAME_PROPERTY,
+                PropertyType::Text,
+                vec![("SLOT_NAME_3", PropertyValue::text("H alpha"))],
+            ),
+        )
+        .await
+        .unwrap();
+    // Settings changed while disconnected are only applied on connect
+    assert_eq!(filters.lock().unwrap()[2].name, "G");
+    registry
+        .handle_property_change("Fake Wheel", &connect_request())
+        .await
+        .unwrap();
+    assert_eq!(filters.lock().unwrap()[2].name, "H alpha");
+    registry
+        .handle_property_change(
+            "Fake Wheel",
+            &request(
+                "Fake",
+                name::WHEEL_SLOT_OFFSET_PROPERTY,
+                PropertyType::Number,
+                vec![("SLOT_OFFSET_3", PropertyValue::number(-40.0))],
+            ),
+        )
+        .await
+        .unwrap();
+    registry
+        .handle_property_change("Fake Wheel", &config_request(name::CONFIG_SAVE_ITEM))
+        .await
+        .unwrap();
+    let path = registry
+        .context("Fake Wheel")
+        .unwrap()
+        .properties()
+        .config_path()
+        .unwrap()
+        .to_path_buf();
+    assert!(path.exists());
+
+    // A new instance restores the saved settings on attach and connect
+    let filters = wheel_filters();
+    let mut registry = wheel_registry(&filters, &dir);
+    registry.attach("Fake Wheel").await.unwrap();
+    assert_eq!(
+        number_value(
+            &registry,
+            "Fake Wheel",
+            name::WHEEL_SLOT_OFFSET_PROPERTY,
+            "SLOT_OFFSET_3"
+        ),
+        -40.0
+    );
+    registry
+        .handle_property_change("Fake Wheel", &connect_request())
+        .await
+        .unwrap();
+    {
+        let filters = filters.lock().unwrap();
+        assert_eq!(filters[2].name, "H alpha");
+        assert_eq!(filters[2].offset, -40);
+    }
+
+    registry
+        .handle_property_change("Fake Wheel", &config_request(CONFIG_DEFAULT_ITEM))
+        .await
+        .unwrap();
+    {
+        let filters = filters.lock().unwrap();
+        assert_eq!(filters[2].name, "G");
+        assert_eq!(filters[2].offset, 0);
+    }
+    let config = registry
+        .context("Fake Wheel")
+        .unwrap()
+        .properties()
+        .get_property(name::CONFIG_PROPERTY)
+        .unwrap();
+    assert_eq!(config.state, PropertyState::Ok);
+
+    registry
+        .handle_property_change("Fake Wheel", &config_request(name::CONFIG_REMOVE_ITEM))
+        .await
+        .unwrap();
+    assert!(!path.exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
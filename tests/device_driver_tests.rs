@@ -1,12 +1,22 @@
 //! Tests for the Device Driver API
 
+use libindigo::device::traits::{CcdInfo, FilterInfo, FocuserInfo};
 use libindigo::device::{
-    DeviceContext, DeviceDriver, DeviceInterface, DriverInfo, DriverRegistry, PropertyManager,
+    DeviceContext, DeviceDriver, DeviceInterface, DeviceNotification, DriverInfo, DriverRegistry,
+    PropertyManager, TaskEvent, TaskId, CCD_IMAGE_GROUP, CCD_MAIN_GROUP, WHEEL_MAIN_GROUP,
 };
-use libindigo::error::Result;
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
 use libindigo::types::{
-    Property, PropertyItem, PropertyPerm, PropertyState, PropertyType, PropertyValue, SwitchState,
+    Property, PropertyItem, PropertyPerm, PropertyState, PropertyType, PropertyValue, SwitchRule,
+    SwitchState,
 };
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+mod fixtures;
+
+use fixtures::request;
 
 /// Mock device driver for testing
 struct MockCamera {
@@ -89,6 +99,22 @@ async fn test_device_interface_combine() {
     assert_eq!(combined & (DeviceInterface::Mount as u32), 0);
 }
 
+#[tokio::test]
+async fn test_device_interface_decode() {
+    let mask = DeviceInterface::combine(&[DeviceInterface::Mount, DeviceInterface::Guider]);
+
+    assert_eq!(
+        DeviceInterface::decode(mask),
+        vec![DeviceInterface::Mount, DeviceInterface::Guider]
+    );
+    assert!(DeviceInterface::Guider.is_in(mask));
+    assert!(!DeviceInterface::Ccd.is_in(mask));
+    assert!(DeviceInterface::General.is_in(mask));
+
+    // Unknown bits are ignored
+    assert_eq!(DeviceInterface::decode(1 << 31), vec![]);
+}
+
 #[tokio::test]
 async fn test_property_manager_new() {
     let pm = PropertyManager::new("Test Device");
@@ -115,6 +141,7 @@ async fn test_property_manager_define_property() {
         state: PropertyState::Idle,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Number,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -146,6 +173,7 @@ async fn test_property_manager_duplicate_property() {
         state: PropertyState::Idle,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Number,
+        switch_rule: None,
         items: items.clone(),
         timeout: None,
         timestamp: None,
@@ -210,6 +238,7 @@ async fn test_property_manager_update_property() {
         state: PropertyState::Idle,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Number,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -260,6 +289,374 @@ async fn test_property_manager_delete_all() {
     assert_eq!(pm.property_count(), 0);
 }
 
+#[tokio::test]
+async fn test_property_manager_notifications() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut ctx = DeviceContext::new("Test Device");
+    ctx.set_notifier(Some(tx));
+
+    let pm = ctx.property_manager();
+    pm.register_standard_connection().unwrap();
+    match rx.try_recv().unwrap() {
+        DeviceNotification::Defined(property) => assert_eq!(property.name, "CONNECTION"),
+        other => panic!("Expected definition, got {:?}", other),
+    }
+
+    pm.update_property_with_message(
+        "CONNECTION",
+        PropertyState::Ok,
+        vec![(
+            "CONNECTED".to_string(),
+            PropertyValue::switch(SwitchState::On),
+        )],
+        Some("Connected".to_string()),
+    )
+    .unwrap();
+    match rx.try_recv().unwrap() {
+        DeviceNotification::Updated(property) => {
+            assert_eq!(property.state, PropertyState::Ok);
+            assert_eq!(property.message.as_deref(), Some("Connected"));
+            // Only the updated items are sent
+            assert_eq!(property.items.len(), 1);
+            assert!(property.items.contains_key("CONNECTED"));
+        }
+        other => panic!("Expected update, got {:?}", other),
+    }
+    // Updates are sent rather than queued
+    assert!(pm.drain_pending_updates().is_empty());
+
+    pm.delete_property("CONNECTION").unwrap();
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        DeviceNotification::Deleted {
+            device: "Test Device".to_string(),
+            name: Some("CONNECTION".to_string()),
+        }
+    );
+
+    ctx.send_message("Hello");
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        DeviceNotification::Message {
+            device: "Test Device".to_string(),
+            message: "Hello".to_string(),
+        }
+    );
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_property_manager_ccd_standard() {
+    let mut pm = PropertyManager::new("Test Camera");
+    pm.register_ccd_standard(&CcdInfo {
+        width: 1280,
+        height: 1024,
+        pixel_size: 5.2,
+        max_bin_x: 4,
+        max_bin_y: 2,
+        bits_per_pixel: 16,
+    })
+    .unwrap();
+
+    for property in [
+        name::CCD_INFO_PROPERTY,
+        name::CCD_EXPOSURE_PROPERTY,
+        name::CCD_ABORT_EXPOSURE_PROPERTY,
+        name::CCD_FRAME_PROPERTY,
+        name::CCD_BIN_PROPERTY,
+        name::CCD_FRAME_TYPE_PROPERTY,
+        name::CCD_IMAGE_PROPERTY,
+    ] {
+        assert!(pm.has_property(property), "{} is missing", property);
+    }
+    assert_eq!(pm.property_count(), 7);
+
+    let info = pm.get_property(name::CCD_INFO_PROPERTY).unwrap();
+    assert_eq!(info.group, CCD_MAIN_GROUP);
+    assert_eq!(info.perm, PropertyPerm::ReadOnly);
+    assert_eq!(
+        info.items[name::CCD_INFO_WIDTH_ITEM].value,
+        PropertyValue::Number {
+            value: 1280.0,
+            min: 0.0,
+            max: 1280.0,
+            step: 1.0,
+            format: "%.0f".to_string(),
+        }
+    );
+
+    let bin = pm.get_property(name::CCD_BIN_PROPERTY).unwrap();
+    match &bin.items[name::CCD_BIN_VERTICAL_ITEM].value {
+        PropertyValue::Number { value, max, .. } => assert_eq!((*value, *max), (1.0, 2.0)),
+        other => panic!("Expected number, got {:?}", other),
+    }
+
+    let frame_type = pm.get_property(name::CCD_FRAME_TYPE_PROPERTY).unwrap();
+    assert_eq!(frame_type.switch_rule, Some(SwitchRule::OneOfMany));
+    assert_eq!(
+        frame_type.items[name::CCD_FRAME_TYPE_LIGHT_ITEM].value,
+        PropertyValue::switch(SwitchState::On)
+    );
+
+    let image = pm.get_property(name::CCD_IMAGE_PROPERTY).unwrap();
+    assert_eq!(image.group, CCD_IMAGE_GROUP);
+    assert_eq!(image.property_type, PropertyType::Blob);
+
+    pm.register_ccd_cooler().unwrap();
+    pm.register_ccd_gain_offset().unwrap();
+    assert_eq!(pm.property_count(), 12);
+    let cooler = pm.get_property(name::CCD_COOLER_PROPERTY).unwrap();
+    assert_eq!(
+        cooler.items[name::CCD_COOLER_OFF_ITEM].value,
+        PropertyValue::switch(SwitchState::On)
+    );
+    let power = pm.get_property(name::CCD_COOLER_POWER_PROPERTY).unwrap();
+    assert_eq!(power.perm, PropertyPerm::ReadOnly);
+    assert!(pm.has_property(name::CCD_TEMPERATURE_PROPERTY));
+    assert!(pm.has_property(name::CCD_GAIN_PROPERTY));
+    assert!(pm.has_property(name::CCD_OFFSET_PROPERTY));
+}
+
+#[tokio::test]
+async fn test_property_manager_mount_and_guider_standard() {
+    let mut pm = PropertyManager::new("Test Mount");
+    pm.register_mount_standard("Acme", "EQ-1").unwrap();
+    pm.register_guider_standard().unwrap();
+
+    let info = pm.get_property(name::MOUNT_INFO_PROPERTY).unwrap();
+    assert_eq!(
+        info.items[name::MOUNT_INFO_MODEL_ITEM].value,
+        PropertyValue::text("EQ-1")
+    );
+    let motion = pm.get_property(name::MOUNT_MOTION_DEC_PROPERTY).unwrap();
+    assert_eq!(motion.switch_rule, Some(SwitchRule::AtMostOne));
+    let abort = pm.get_property(name::MOUNT_ABORT_MOTION_PROPERTY).unwrap();
+    assert_eq!(abort.switch_rule, Some(SwitchRule::AnyOfMany));
+    assert_eq!(abort.perm, PropertyPerm::ReadWrite);
+    let track_rate = pm.get_property(name::MOUNT_TRACK_RATE_PROPERTY).unwrap();
+    assert_eq!(
+        track_rate.items[name::MOUNT_TRACK_RATE_SIDEREAL_ITEM].value,
+        PropertyValue::switch(SwitchState::On)
+    );
+    let park_set = pm.get_property(name::MOUNT_PARK_SET_PROPERTY).unwrap();
+    assert_eq!(park_set.switch_rule, Some(SwitchRule::AtMostOne));
+    let lst = pm.get_property(name::MOUNT_LST_TIME_PROPERTY).unwrap();
+    assert_eq!(lst.perm, PropertyPerm::ReadOnly);
+
+    assert!(pm.has_property(name::GUIDER_GUIDE_DEC_PROPERTY));
+    assert!(pm.has_property(name::GUIDER_GUIDE_RA_PROPERTY));
+    assert!(pm.has_property(name::GUIDER_RATE_PROPERTY));
+    assert_eq!(pm.property_count(), 16);
+}
+
+#[tokio::test]
+async fn test_property_manager_focuser_standard() {
+    let mut relative = PropertyManager::new("Relative Focuser");
+    relative
+        .register_focuser_standard(&FocuserInfo {
+            max_position: 10000,
+            has_absolute: false,
+            has_temperature_compensation: false,
+        })
+        .unwrap();
+    assert!(relative.has_property(name::FOCUSER_STEPS_PROPERTY));
+    assert!(relative.has_property(name::FOCUSER_BACKLASH_PROPERTY));
+    assert!(!relative.has_property(name::FOCUSER_POSITION_PROPERTY));
+    assert!(!relative.has_property(name::FOCUSER_LIMITS_PROPERTY));
+    assert!(!relative.has_property(name::FOCUSER_MODE_PROPERTY));
+    assert!(!relative.has_property(name::FOCUSER_TEMPERATURE_PROPERTY));
+
+    let mut absolute = PropertyManager::new("Absolute Focuser");
+    absolute
+        .register_focuser_standard(&FocuserInfo {
+            max_position: 10000,
+            has_absolute: true,
+            has_temperature_compensation: true,
+        })
+        .unwrap();
+    let position = absolute
+        .get_property(name::FOCUSER_POSITION_PROPERTY)
+        .unwrap();
+    match &position.items[name::FOCUSER_POSITION_ITEM].value {
+        PropertyValue::Number { max, .. } => assert_eq!(*max, 10000.0),
+        other => panic!("Expected number, got {:?}", other),
+    }
+    let limits = absolute
+        .get_property(name::FOCUSER_LIMITS_PROPERTY)
+        .unwrap();
+    assert_eq!(
+        limits.items[name::FOCUSER_LIMITS_MAX_POSITION_ITEM].value,
+        PropertyValue::Number {
+            value: 10000.0,
+            min: 0.0,
+            max: 10000.0,
+            step: 1.0,
+            format: "%.0f".to_string(),
+        }
+    );
+    let temperature = absolute
+        .get_property(name::FOCUSER_TEMPERATURE_PROPERTY)
+        .unwrap();
+    assert_eq!(temperature.perm, PropertyPerm::ReadOnly);
+    assert!(absolute.has_property(name::FOCUSER_MODE_PROPERTY));
+    assert!(absolute.has_property(name::FOCUSER_COMPENSATION_PROPERTY));
+}
+
+#[tokio::test]
+async fn test_property_manager_wheel_standard() {
+    let filters = vec![
+        FilterInfo {
+            slot: 1,
+            name: "Red".to_string(),
+            offset: 0,
+        },
+        FilterInfo {
+            slot: 2,
+            name: "Green".to_string(),
+            offset: 25,
+        },
+    ];
+    let mut pm = PropertyManager::new("Test Wheel");
+    pm.register_wheel_standard(&filters).unwrap();
+
+    let slot = pm.get_property(name::WHEEL_SLOT_PROPERTY).unwrap();
+    assert_eq!(slot.group, WHEEL_MAIN_GROUP);
+    match &slot.items[name::WHEEL_SLOT_ITEM].value {
+        PropertyValue::Number { value, max, .. } => assert_eq!((*value, *max), (1.0, 2.0)),
+        other => panic!("Expected number, got {:?}", other),
+    }
+    let names = pm.get_property(name::WHEEL_SLOT_NAME_PROPERTY).unwrap();
+    assert_eq!(
+        names.items[name::WHEEL_SLOT_NAME_2_ITEM].value,
+        PropertyValue::text("Green")
+    );
+    let offsets = pm.get_property(name::WHEEL_SLOT_OFFSET_PROPERTY).unwrap();
+    match &offsets.items[name::WHEEL_SLOT_OFFSET_2_ITEM].value {
+        PropertyValue::Number { value, .. } => assert_eq!(*value, 25.0),
+        other => panic!("Expected number, got {:?}", other),
+    }
+
+    let mut empty = PropertyManager::new("Empty Wheel");
+    assert!(empty.register_wheel_standard(&[]).is_err());
+}
+
+#[tokio::test]
+async fn test_property_manager_validate_change() {
+    let mut pm = PropertyManager::new("Test Device");
+    pm.register_standard_connection().unwrap();
+    pm.register_device_info("Test", "1.0", 0).unwrap();
+    pm.register_ccd_standard(&CcdInfo {
+        width: 1280,
+        height: 1024,
+        pixel_size: 5.2,
+        max_bin_x: 4,
+        max_bin_y: 4,
+        bits_per_pixel: 16,
+    })
+    .unwrap();
+    let on = PropertyValue::switch(SwitchState::On);
+    let off = PropertyValue::switch(SwitchState::Off);
+
+    // Partial updates are merged into the full property
+    let merged = pm
+        .validate_change(&request(
+            "Test Device",
+            name::CCD_BIN_PROPERTY,
+            PropertyType::Number,
+            vec![(name::CCD_BIN_HORIZONTAL_ITEM, PropertyValue::number(2.0))],
+        ))
+        .unwrap();
+    assert_eq!(merged.items.len(), 2);
+    match &merged.items[name::CCD_BIN_HORIZONTAL_ITEM].value {
+        PropertyValue::Number { value, max, .. } => assert_eq!((*value, *max), (2.0, 4.0)),
+        other => panic!("Expected number, got {:?}", other),
+    }
+
+    // Turning a switch on turns the others off
+    let merged = pm
+        .validate_change(&request(
+            "Test Device",
+            "CONNECTION",
+            PropertyType::Switch,
+            vec![("CONNECTED", on.clone())],
+        ))
+        .unwrap();
+    assert_eq!(merged.items["CONNECTED"].value, on);
+    assert_eq!(merged.items["DISCONNECTED"].value, off);
+
+    let invalid = [
+        // Read-only property
+        request(
+            "Test Device",
+            "INFO",
+            PropertyType::Text,
+            vec![("DEVICE_DESCRIPTION", PropertyValue::text("Other"))],
+        ),
+        // Unknown item
+        request(
+            "Test Device",
+            name::CCD_BIN_PROPERTY,
+            PropertyType::Number,
+            vec![("DIAGONAL", PropertyValue::number(2.0))],
+        ),
+        // Wrong item type
+        request(
+            "Test Device",
+            name::CCD_BIN_PROPERTY,
+            PropertyType::Number,
+            vec![(name::CCD_BIN_VERTICAL_ITEM, PropertyValue::text("2"))],
+        ),
+        // Out of range
+        request(
+            "Test Device",
+            name::CCD_BIN_PROPERTY,
+            PropertyType::Number,
+            vec![(name::CCD_BIN_VERTICAL_ITEM, PropertyValue::number(8.0))],
+        ),
+        // Not aligned to the step
+        request(
+            "Test Device",
+            name::CCD_BIN_PROPERTY,
+            PropertyType::Number,
+            vec![(name::CCD_BIN_VERTICAL_ITEM, PropertyValue::number(1.5))],
+        ),
+        // Two switches on in a OneOfMany property
+        request(
+            "Test Device",
+            "CONNECTION",
+            PropertyType::Switch,
+            vec![("CONNECTED", on.clone()), ("DISCONNECTED", on.clone())],
+        ),
+        // No switch on in a OneOfMany property
+        request(
+            "Test Device",
+            "CONNECTION",
+            PropertyType::Switch,
+            vec![("DISCONNECTED", off.clone())],
+        ),
+    ];
+    for request in &invalid {
+        assert!(
+            matches!(
+                pm.validate_change(request),
+                Err(IndigoError::InvalidParameter(_))
+            ),
+            "{:?} should be rejected",
+            request
+        );
+    }
+
+    // Steps are counted from the minimum
+    assert!(pm
+        .validate_change(&request(
+            "Test Device",
+            name::CCD_EXPOSURE_PROPERTY,
+            PropertyType::Number,
+            vec![(name::CCD_EXPOSURE_ITEM, PropertyValue::number(0.125))],
+        ))
+        .is_ok());
+}
+
 #[tokio::test]
 async fn test_device_context_new() {
     let ctx = DeviceContext::new("Test Device");
@@ -450,6 +847,38 @@ async fn test_driver_registry_unregister_attached() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_driver_registry_split_off() {
+    let (notifier, mut notifications) = mpsc::unbounded_channel();
+    let mut registry = DriverRegistry::new();
+    registry.set_notifier(Some(notifier));
+    registry
+        .register(Box::new(MockCamera::new("Camera 1")))
+        .unwrap();
+    registry
+        .register(Box::new(MockCamera::new("Camera 2")))
+        .unwrap();
+    registry.attach("Camera 1").await.unwrap();
+
+    let mut split = registry.split_off("Camera 1").unwrap();
+    assert!(!registry.is_registered("Camera 1"));
+    assert!(registry.is_registered("Camera 2"));
+    assert_eq!(split.count(), 1);
+    assert!(split.is_attached("Camera 1"));
+    assert!(matches!(
+        registry.split_off("Camera 1"),
+        Err(IndigoError::DriverNotFound(_))
+    ));
+
+    // The split registry keeps sending to the same notifier
+    while notifications.try_recv().is_ok() {}
+    split
+        .context_mut("Camera 1")
+        .unwrap()
+        .send_message("Still here");
+    assert!(notifications.try_recv().is_ok());
+}
+
 #[tokio::test]
 async fn test_driver_registry_handle_property_change() {
     let mut registry = DriverRegistry::new();
@@ -476,6 +905,7 @@ async fn test_driver_registry_handle_property_change() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Switch,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -489,6 +919,35 @@ async fn test_driver_registry_handle_property_change() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_driver_registry_rejects_invalid_change() {
+    let mut registry = DriverRegistry::new();
+    registry
+        .register(Box::new(MockCamera::new("Test Camera")))
+        .unwrap();
+    registry.attach("Test Camera").await.unwrap();
+
+    let property = request(
+        "Test Device",
+        "CONNECTION",
+        PropertyType::Switch,
+        vec![("UNKNOWN", PropertyValue::switch(SwitchState::On))],
+    );
+    let result = registry
+        .handle_property_change("Test Camera", &property)
+        .await;
+    assert!(matches!(result, Err(IndigoError::InvalidParameter(_))));
+
+    let connection = registry
+        .context("Test Camera")
+        .unwrap()
+        .properties()
+        .get_property("CONNECTION")
+        .unwrap();
+    assert_eq!(connection.state, PropertyState::Alert);
+    assert!(connection.message.as_deref().unwrap().contains("UNKNOWN"));
+}
+
 #[tokio::test]
 async fn test_driver_registry_handle_property_change_not_attached() {
     let mut registry = DriverRegistry::new();
@@ -515,6 +974,7 @@ async fn test_driver_registry_handle_property_change_not_attached() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Switch,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -527,3 +987,333 @@ async fn test_driver_registry_handle_property_change_not_attached() {
         .await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_property_manager_config_round_trip() {
+    let dir = std::env::temp_dir().join(format!("libindigo-pm-config-{}", std::process::id()));
+    let mut pm = PropertyManager::new("Test Focuser #1");
+    pm.register_config().unwrap();
+    pm.register_focuser_standard(&FocuserInfo {
+        max_position: 10000,
+        has_absolute: true,
+        has_temperature_compensation: true,
+    })
+    .unwrap();
+    pm.define_property(
+        Property::builder()
+            .device("Test Focuser #1")
+            .name("NOTES")
+            .property_type(PropertyType::Text)
+            .perm(PropertyPerm::ReadWrite)
+            .item(PropertyItem::new("TEXT", "Text", PropertyValue::text("")))
+            .build()
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert!(matches!(
+        pm.set_persistent(name::FOCUSER_TEMPERATURE_PROPERTY),
+        Err(IndigoError::InvalidParameter(_))
+    ));
+    assert!(matches!(
+        pm.set_persistent("UNKNOWN"),
+        Err(IndigoError::PropertyNotFound(_))
+    ));
+    assert!(matches!(
+        pm.save_config(),
+        Err(IndigoError::InvalidState(_))
+    ));
+    assert!(pm.load_config().unwrap().is_empty());
+
+    pm.set_persistent(name::FOCUSER_BACKLASH_PROPERTY).unwrap();
+    pm.set_persistent(name::FOCUSER_MODE_PROPERTY).unwrap();
+    pm.set_persistent("NOTES").unwrap();
+    assert!(pm.is_persistent("NOTES"));
+    assert!(!pm.is_persistent(name::FOCUSER_POSITION_PROPERTY));
+
+    pm.set_config_dir(Some(&dir));
+    let path = pm.config_path().unwrap().to_path_buf();
+    assert_eq!(path.file_name().unwrap(), "Test_Focuser__1.config");
+    assert!(pm.load_config().unwrap().is_empty());
+
+    pm.update_property(
+        name::FOCUSER_BACKLASH_PROPERTY,
+        PropertyState::Ok,
+        vec![(
+            name::FOCUSER_BACKLASH_ITEM.to_string(),
+            PropertyValue::number(25.0),
+        )],
+    )
+    .unwrap();
+    pm.update_property(
+        name::FOCUSER_MODE_PROPERTY,
+        PropertyState::Ok,
+        vec![
+            (
+                name::FOCUSER_MODE_MANUAL_ITEM.to_string(),
+                PropertyValue::switch(SwitchState::Off),
+            ),
+            (
+                name::FOCUSER_MODE_AUTOMATIC_ITEM.to_string(),
+                PropertyValue::switch(SwitchState::On),
+            ),
+        ],
+    )
+    .unwrap();
+    pm.update_property(
+        "NOTES",
+        PropertyState::Ok,
+        vec![(
+            "TEXT".to_string(),
+            PropertyValue::text("line one\nback\\slash "),
+        )],
+    )
+    .unwrap();
+    pm.save_config().unwrap();
+
+    let requests = pm.load_config().unwrap();
+    assert_eq!(requests.len(), 3);
+    let find = |name: &str| requests.iter().find(|r| r.name == name).unwrap();
+    match &find(name::FOCUSER_BACKLASH_PROPERTY).items[name::FOCUSER_BACKLASH_ITEM].value {
+        PropertyValue::Number { value, .. } => assert_eq!(*value, 25.0),
+        other => panic!("Expected number, got {:?}", other),
+    }
+    assert_eq!(
+        find(name::FOCUSER_MODE_PROPERTY).items[name::FOCUSER_MODE_AUTOMATIC_ITEM].value,
+        PropertyValue::switch(SwitchState::On)
+    );
+    assert_eq!(
+        find("NOTES").items["TEXT"].value,
+        PropertyValue::text("line one\nback\\slash ")
+    );
+
+    let defaults = pm.default_config();
+    let backlash = defaults
+        .iter()
+        .find(|r| r.name == name::FOCUSER_BACKLASH_PROPERTY)
+        .unwrap();
+    match &backlash.items[name::FOCUSER_BACKLASH_ITEM].value {
+        PropertyValue::Number { value, .. } => assert_eq!(*value, 0.0),
+        other => panic!("Expected number, got {:?}", other),
+    }
+
+    std::fs::write(&path, "FOCUSER_BACKLASH.BACKLASH=many\n").unwrap();
+    assert!(matches!(pm.load_config(), Err(IndigoError::ParseError(_))));
+
+    pm.remove_config().unwrap();
+    assert!(!path.exists());
+    pm.remove_config().unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Driver that counts the ticks of a timer in a property
+struct TickingDriver {
+    ticks: f64,
+    interval: Option<TaskId>,
+}
+
+#[async_trait::async_trait]
+impl DeviceDriver for TickingDriver {
+    fn info(&self) -> DriverInfo {
+        DriverInfo {
+            name: "Ticker".to_string(),
+            description: "Ticking Driver".to_string(),
+            version: "1.0.0".to_string(),
+            interfaces: DeviceInterface::Aux as u32,
+        }
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        ctx.property_manager().register_standard_connection()?;
+        ctx.property_manager().define_property(
+            Property::builder()
+                .device("Ticker")
+                .name("TICKS")
+                .property_type(PropertyType::Number)
+                .perm(PropertyPerm::ReadOnly)
+                .item(PropertyItem::new(
+                    "COUNT",
+                    "Count",
+                    PropertyValue::number(0.0),
+                ))
+                .build()
+                .unwrap(),
+        )
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        if property.name == name::CONNECTION_PROPERTY {
+            let connected = property.items[name::CONNECTION_CONNECTED_ITEM].value
+                == PropertyValue::switch(SwitchState::On);
+            ctx.set_connected(connected);
+            if connected {
+                self.interval = Some(ctx.set_interval(Duration::from_millis(10))?);
+            }
+        }
+        Ok(())
+    }
+
+    async fn on_task(&mut self, ctx: &mut DeviceContext, task: TaskId) -> Result<()> {
+        assert_eq!(Some(task), self.interval);
+        self.ticks += 1.0;
+        ctx.property_manager().update_property(
+            "TICKS",
+            PropertyState::Ok,
+            vec![("COUNT".to_string(), PropertyValue::number(self.ticks))],
+        )
+    }
+
+    async fn detach(&mut self, _ctx: &mut DeviceContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn connection_request(connected: bool) -> Property {
+    let (on, off) = if connected {
+        (SwitchState::On, SwitchState::Off)
+    } else {
+        (SwitchState::Off, SwitchState::On)
+    };
+    Property::builder()
+        .device("Ticker")
+        .name(name::CONNECTION_PROPERTY)
+        .property_type(PropertyType::Switch)
+        .item(PropertyItem::new(
+            name::CONNECTION_CONNECTED_ITEM,
+            "Connected",
+            PropertyValue::switch(on),
+        ))
+        .item(PropertyItem::new(
+            name::CONNECTION_DISCONNECTED_ITEM,
+            "Disconnected",
+            PropertyValue::switch(off),
+        ))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_device_context_tasks_require_runtime() {
+    let mut ctx = DeviceContext::new("Test Device");
+    assert!(matches!(
+        ctx.set_timer(Duration::from_millis(1)),
+        Err(IndigoError::InvalidState(_))
+    ));
+
+    let (sender, _events) = mpsc::unbounded_channel();
+    ctx.set_task_sender(Some(sender));
+    assert!(matches!(
+        ctx.set_interval(Duration::ZERO),
+        Err(IndigoError::InvalidParameter(_))
+    ));
+    assert_eq!(ctx.task_count(), 0);
+}
+
+#[test]
+fn test_device_context_tasks_outside_runtime() {
+    let mut ctx = DeviceContext::new("Test Device");
+    let (sender, _events) = mpsc::unbounded_channel();
+    ctx.set_task_sender(Some(sender));
+    assert!(matches!(
+        ctx.set_timer(Duration::from_millis(1)),
+        Err(IndigoError::InvalidState(_))
+    ));
+    assert_eq!(ctx.task_count(), 0);
+}
+
+#[tokio::test]
+async fn test_device_context_timers_and_tasks() {
+    let (sender, mut events) = mpsc::unbounded_channel();
+    let mut ctx = DeviceContext::new("Test Device");
+    ctx.set_task_sender(Some(sender));
+
+    let timer = ctx.set_timer(Duration::from_millis(5)).unwrap();
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
+    let task = ctx
+        .spawn(async move {
+            let _ = done_rx.await;
+        })
+        .unwrap();
+    let cancelled = ctx.set_timer(Duration::from_millis(5)).unwrap();
+    assert_ne!(timer, task);
+    assert_eq!(ctx.task_count(), 3);
+
+    assert!(ctx.cancel_task(cancelled));
+    assert!(!ctx.cancel_task(cancelled));
+    let event = events.recv().await.unwrap();
+    assert_eq!(
+        event,
+        TaskEvent {
+            device: "Test Device".to_string(),
+            task: timer,
+        }
+    );
+
+    done_tx.send(()).unwrap();
+    let event = events.recv().await.unwrap();
+    assert_eq!(event.task, task);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), events.recv())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_registry_runs_driver_tasks() {
+    let (sender, mut events) = mpsc::unbounded_channel();
+    let mut registry = DriverRegistry::new();
+    registry.set_task_sender(Some(sender));
+    registry
+        .register(Box::new(TickingDriver {
+            ticks: 0.0,
+            interval: None,
+        }))
+        .unwrap();
+    registry.attach("Ticker").await.unwrap();
+    registry
+        .handle_property_change("Ticker", &connection_request(true))
+        .await
+        .unwrap();
+    assert_eq!(registry.context("Ticker").unwrap().task_count(), 1);
+
+    for _ in 0..3 {
+        let event = events.recv().await.unwrap();
+        registry.handle_task(&event).await.unwrap();
+    }
+    let ticks = registry
+        .context("Ticker")
+        .unwrap()
+        .properties()
+        .get_property("TICKS")
+        .unwrap();
+    assert_eq!(ticks.items["COUNT"].value, PropertyValue::number(3.0));
+
+    // Events of cancelled tasks are ignored
+    let event = events.recv().await.unwrap();
+    registry
+        .handle_property_change("Ticker", &connection_request(false))
+        .await
+        .unwrap();
+    assert_eq!(registry.context("Ticker").unwrap().task_count(), 0);
+    registry.handle_task(&event).await.unwrap();
+    let ticks = registry
+        .context("Ticker")
+        .unwrap()
+        .properties()
+        .get_property("TICKS")
+        .unwrap();
+    assert_eq!(ticks.items["COUNT"].value, PropertyValue::number(3.0));
+
+    registry
+        .handle_property_change("Ticker", &connection_request(true))
+        .await
+        .unwrap();
+    assert_eq!(registry.context("Ticker").unwrap().task_count(), 1);
+    registry.detach("Ticker").await.unwrap();
+    assert_eq!(registry.context("Ticker").unwrap().task_count(), 0);
+}
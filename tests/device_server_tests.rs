@@ -0,0 +1,532 @@
+//! Integration tests for hosting device drivers with the pure Rust INDIGO server.
+
+use libindigo::client::{ClientStrategy, PropertyEvent};
+use libindigo::device::traits::FilterInfo;
+use libindigo::device::{DeviceContext, DeviceDriver, DeviceInterface, DriverInfo, DriverRegistry};
+use libindigo::error::{IndigoError, Result};
+use libindigo::name;
+use libindigo::types::{
+    BlobTransferMode, Property, PropertyItem, PropertyPerm, PropertyState, PropertyType,
+    PropertyValue, SwitchState,
+};
+use libindigo_rs::{IndigoServer, ProtocolNegotiator, RemoteDevice, RsClientStrategy};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+mod fixtures;
+
+use fixtures::{wait_for_deletion, wait_for_property};
+
+const DEVICE: &str = "Rust Focuser";
+
+/// Focuser driver that defines its temperature property once connected.
+struct RustFocuser;
+
+fn number(name: &str, value: f64, min: f64, max: f64) -> PropertyItem {
+    PropertyItem::new(
+        name,
+        name,
+        PropertyValue::Number {
+            value,
+            min,
+            max,
+            step: 1.0,
+            format: "%.0f".to_string(),
+        },
+    )
+}
+
+fn number_property(name: &str, perm: PropertyPerm, item: PropertyItem) -> Property {
+    Property::builder()
+        .device(DEVICE)
+        .name(name)
+        .group("Focuser")
+        .label(name)
+        .state(PropertyState::Ok)
+        .perm(perm)
+        .property_type(PropertyType::Number)
+        .item(item)
+        .build()
+        .unwrap()
+}
+
+#[async_trait::async_trait]
+impl DeviceDriver for RustFocuser {
+    fn info(&self) -> DriverInfo {
+        DriverInfo {
+            name: DEVICE.to_string(),
+            description: "Focuser written in Rust".to_string(),
+            version: "1.0.0".to_string(),
+            interfaces: DeviceInterface::Focuser as u32,
+        }
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        let manager = ctx.property_manager();
+        manager.register_standard_connection()?;
+        manager.register_device_info("Focuser written in Rust", "1.0.0", 0x04)?;
+        manager.define_property(number_property(
+            "FOCUSER_POSITION",
+            PropertyPerm::ReadWrite,
+            number("POSITION", 0.0, 0.0, 1000.0),
+        ))
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        match property.name.as_str() {
+            "CONNECTION" => {
+                let connected = matches!(
+                    property.items.get("CONNECTED").map(|i| &i.value),
+                    Some(PropertyValue::Switch {
+                        state: SwitchState::On
+                    })
+                );
+                let switch = |on: bool| PropertyValue::Switch {
+                    state: if on {
+                        SwitchState::On
+                    } else {
+                        SwitchState::Off
+                    },
+                };
+                let manager = ctx.property_manager();
+                if connected && !manager.has_property("FOCUSER_TEMPERATURE") {
+                    manager.define_property(number_property(
+                        "FOCUSER_TEMPERATURE",
+                        PropertyPerm::ReadOnly,
+                        number("TEMPERATURE", 15.0, -50.0, 50.0),
+                    ))?;
+                } else if !connected && manager.has_property("FOCUSER_TEMPERATURE") {
+                    manager.delete_property("FOCUSER_TEMPERATURE")?;
+                }
+                manager.update_property(
+                    "CONNECTION",
+                    PropertyState::Ok,
+                    vec![
+                        ("CONNECTED".to_string(), switch(connected)),
+                        ("DISCONNECTED".to_string(), switch(!connected)),
+                    ],
+                )?;
+                ctx.set_connected(connected);
+                if connected {
+                    ctx.send_message("Focuser connected");
+                }
+                Ok(())
+            }
+            "FOCUSER_POSITION" => {
+                let position = property.items["POSITION"].value.clone();
+                if matches!(position, PropertyValue::Number { value, .. } if value == 13.0) {
+                    return Err(IndigoError::InvalidParameter(
+                        "Position 13 is unlucky".to_string(),
+                    ));
+                }
+                ctx.property_manager().update_property(
+                    "FOCUSER_POSITION",
+                    PropertyState::Ok,
+                    vec![("POSITION".to_string(), position)],
+                )
+            }
+            _ => Err(IndigoError::NotSupported(property.name.clone())),
+        }
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        ctx.property_manager().delete_all_properties();
+        Ok(())
+    }
+}
+
+/// Filter wheel driver built from the standard property templates.
+struct RustWheel;
+
+#[async_trait::async_trait]
+impl DeviceDriver for RustWheel {
+    fn info(&self) -> DriverInfo {
+        DriverInfo {
+            name: "Rust Wheel".to_string(),
+            description: "Filter wheel written in Rust".to_string(),
+            version: "1.0.0".to_string(),
+            interfaces: DeviceInterface::FilterWheel as u32,
+        }
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        let filters: Vec<_> = ["Red", "Green", "Blue"]
+            .iter()
+            .zip(1..)
+            .map(|(name, slot)| FilterInfo {
+                slot,
+                name: name.to_string(),
+                offset: 0,
+            })
+            .collect();
+        let manager = ctx.property_manager();
+        manager.register_standard_connection()?;
+        manager.register_device_info(
+            "Filter wheel written in Rust",
+            "1.0.0",
+            DeviceInterface::FilterWheel as u32,
+        )?;
+        manager.register_wheel_standard(&filters)
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        let values = property
+            .items
+            .values()
+            .map(|i| (i.name.clone(), i.value.clone()))
+            .collect();
+        ctx.property_manager()
+            .update_property(&property.name, PropertyState::Ok, values)
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        ctx.property_manager().delete_all_properties();
+        Ok(())
+    }
+}
+
+/// Driver whose property changes wait until they are released.
+struct GatedDriver {
+    /// Notified when a change reaches the driver.
+    entered: Arc<Notify>,
+    /// Notified to let the change complete.
+    release: Arc<Notify>,
+}
+
+#[async_trait::async_trait]
+impl DeviceDriver for GatedDriver {
+    fn info(&self) -> DriverInfo {
+        DriverInfo {
+            name: "Gated".to_string(),
+            description: "Driver that waits to be released".to_string(),
+            version: "1.0.0".to_string(),
+            interfaces: 0,
+        }
+    }
+
+    async fn attach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        ctx.property_manager().register_standard_connection()
+    }
+
+    async fn change_property(
+        &mut self,
+        ctx: &mut DeviceContext,
+        property: &Property,
+    ) -> Result<()> {
+        self.entered.notify_one();
+        self.release.notified().await;
+        let values = property
+            .items
+            .values()
+            .map(|i| (i.name.clone(), i.value.clone()))
+            .collect();
+        ctx.property_manager()
+            .update_property(&property.name, PropertyState::Ok, values)
+    }
+
+    async fn detach(&mut self, ctx: &mut DeviceContext) -> Result<()> {
+        ctx.property_manager().delete_all_properties();
+        Ok(())
+    }
+}
+
+/// Starts a server hosting the focuser driver on a free port.
+async fn start_server() -> IndigoServer {
+    let mut registry = DriverRegistry::new();
+    registry.register(Box::new(RustFocuser)).unwrap();
+    IndigoServer::start("127.0.0.1:0", registry)
+        .await
+        .expect("Failed to start server")
+}
+
+/// Connects a client and waits for the focuser to be defined.
+async fn connect(server: &IndigoServer, mut client: RsClientStrategy) -> RsClientStrategy {
+    client
+        .connect(&server.addr().to_string())
+        .await
+        .expect("Failed to connect");
+    wait_for_property(&client, DEVICE, "FOCUSER_POSITION").await;
+    wait_for_property(&client, DEVICE, "INFO").await;
+    client
+}
+
+#[tokio::test]
+async fn test_server_defines_driver_properties() {
+    let server = start_server().await;
+    let client = connect(&server, RsClientStrategy::new()).await;
+
+    let device = client.device(DEVICE).await.unwrap();
+    assert!(DeviceInterface::Focuser.is_in(device.info.interface));
+    assert!(device.property("CONNECTION").is_some());
+
+    let position = client.property(DEVICE, "FOCUSER_POSITION").await.unwrap();
+    assert_eq!(position.group, "Focuser");
+    match &position.items["POSITION"].value {
+        PropertyValue::Number { value, max, .. } => assert_eq!((*value, *max), (0.0, 1000.0)),
+        other => panic!("Expected number, got {:?}", other),
+    }
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_server_routes_changes_to_driver() {
+    let server = start_server().await;
+    let client = connect(&server, RsClientStrategy::new()).await;
+    let observer = connect(&server, RsClientStrategy::new()).await;
+    let mut focuser = RemoteDevice::new(&client, DEVICE).await.unwrap();
+    let mut events = observer.subscribe_events().await.unwrap();
+
+    // Properties defined while connecting are sent to all clients
+    focuser.connect().await.unwrap();
+    assert!(focuser.proxy().is_connected());
+    assert!(focuser.find_property("FOCUSER_TEMPERATURE").await.is_some());
+    wait_for_property(&observer, DEVICE, "FOCUSER_TEMPERATURE").await;
+
+    // So are messages from the driver
+    let message = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(PropertyEvent::Message {
+                device, message, ..
+            }) = events.recv().await
+            {
+                return (device, message);
+            }
+        }
+    })
+    .await
+    .expect("No message from the driver");
+    assert_eq!(
+        message,
+        (Some(DEVICE.to_string()), "Focuser connected".to_string())
+    );
+
+    focuser
+        .change_numbers("FOCUSER_POSITION", &[("POSITION", 500.0)])
+        .await
+        .unwrap();
+    assert_eq!(
+        focuser
+            .number("FOCUSER_POSITION", "POSITION")
+            .await
+            .unwrap(),
+        500.0
+    );
+
+    // Driver errors are reported as alerts
+    let result = focuser
+        .change_numbers("FOCUSER_POSITION", &[("POSITION", 13.0)])
+        .await;
+    match result {
+        Err(IndigoError::InvalidState(message)) => assert!(message.contains("unlucky")),
+        other => panic!("Expected an alert, got {:?}", other),
+    }
+    assert_eq!(
+        focuser.state("FOCUSER_POSITION").await.unwrap(),
+        PropertyState::Alert
+    );
+
+    // Invalid requests are rejected before they reach the driver
+    let result = focuser
+        .change_numbers("FOCUSER_POSITION", &[("POSITION", 500.5)])
+        .await;
+    match result {
+        Err(IndigoError::InvalidState(message)) => assert!(message.contains("multiple")),
+        other => panic!("Expected an alert, got {:?}", other),
+    }
+    assert_eq!(
+        focuser
+            .number("FOCUSER_POSITION", "POSITION")
+            .await
+            .unwrap(),
+        500.0
+    );
+
+    focuser.disconnect().await.unwrap();
+    wait_for_deletion(&client, DEVICE, "FOCUSER_TEMPERATURE").await;
+    wait_for_deletion(&observer, DEVICE, "FOCUSER_TEMPERATURE").await;
+
+    server.shutdown().await.unwrap();
+}
+
+/// Builds a request moving the focuser.
+async fn position_request(client: &RsClientStrategy, position: f64) -> Property {
+    let mut request = client.property(DEVICE, "FOCUSER_POSITION").await.unwrap();
+    request.items.get_mut("POSITION").unwrap().value = PropertyValue::number(position);
+    request
+}
+
+/// Waits for an update of the focuser position, returning the positions seen.
+async fn wait_for_position(
+    events: &mut tokio::sync::mpsc::UnboundedReceiver<PropertyEvent>,
+    position: f64,
+) -> Vec<f64> {
+    let mut seen = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while seen.last() != Some(&position) {
+            if let Some(PropertyEvent::Updated(property)) = events.recv().await {
+                if let Some(PropertyValue::Number { value, .. }) = property
+                    .items
+                    .get("POSITION")
+                    .map(|item| &item.value)
+                    .filter(|_| property.name == "FOCUSER_POSITION")
+                {
+                    seen.push(*value);
+                }
+            }
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("Position {} was not reported", position));
+    seen
+}
+
+#[tokio::test]
+async fn test_server_sends_only_blobs_when_asked() {
+    let server = start_server().await;
+    let mut client = connect(&server, RsClientStrategy::new()).await;
+    let observer = connect(&server, RsClientStrategy::new()).await;
+    let mut events = client.subscribe_events().await.unwrap();
+    let mut observed = observer.subscribe_events().await.unwrap();
+
+    // Requests are handled in order, so the mode applies to the change
+    client
+        .enable_blob(DEVICE, None, BlobTransferMode::Only)
+        .await
+        .unwrap();
+    let request = position_request(&client, 500.0).await;
+    client.send_property(request).await.unwrap();
+    wait_for_position(&mut observed, 500.0).await;
+
+    client
+        .enable_blob(DEVICE, None, BlobTransferMode::Also)
+        .await
+        .unwrap();
+    let request = position_request(&client, 600.0).await;
+    client.send_property(request).await.unwrap();
+    assert_eq!(wait_for_position(&mut events, 600.0).await, vec![600.0]);
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_server_speaks_xml() {
+    let server = start_server().await;
+    let client = connect(
+        &server,
+        RsClientStrategy::with_protocol_negotiator(ProtocolNegotiator::xml_only()),
+    )
+    .await;
+    let mut focuser = RemoteDevice::new(&client, DEVICE).await.unwrap();
+
+    focuser
+        .change_numbers("FOCUSER_POSITION", &[("POSITION", 250.0)])
+        .await
+        .unwrap();
+    assert_eq!(
+        focuser
+            .number("FOCUSER_POSITION", "POSITION")
+            .await
+            .unwrap(),
+        250.0
+    );
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_server_adds_and_removes_drivers() {
+    let server = IndigoServer::start("127.0.0.1:0", DriverRegistry::new())
+        .await
+        .unwrap();
+    let mut client = RsClientStrategy::new();
+    client.connect(&server.addr().to_string()).await.unwrap();
+
+    server.add_driver(Box::new(RustFocuser)).await.unwrap();
+    wait_for_property(&client, DEVICE, "FOCUSER_POSITION").await;
+
+    let duplicate = server.add_driver(Box::new(RustFocuser)).await;
+    assert!(matches!(
+        duplicate,
+        Err(IndigoError::DriverAlreadyRegistered(_))
+    ));
+
+    server.remove_driver(DEVICE).await.unwrap();
+    wait_for_deletion(&client, DEVICE, "FOCUSER_POSITION").await;
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_server_hosts_standard_templates() {
+    let mut registry = DriverRegistry::new();
+    registry.register(Box::new(RustWheel)).unwrap();
+    let server = IndigoServer::start("127.0.0.1:0", registry).await.unwrap();
+    let mut client = RsClientStrategy::new();
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_property(&client, "Rust Wheel", name::WHEEL_SLOT_OFFSET_PROPERTY).await;
+
+    let mut wheel = client.filter_wheel("Rust Wheel").await.unwrap();
+    assert_eq!(wheel.slot_count().await.unwrap(), 3);
+    let names: Vec<_> = wheel
+        .filters()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|f| f.name)
+        .collect();
+    assert_eq!(names, ["Red", "Green", "Blue"]);
+
+    wheel.select_filter(2).await.unwrap();
+    assert_eq!(wheel.current_filter_name().await.unwrap(), "Green");
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_server_slow_driver_does_not_block_others() {
+    let entered = Arc::new(Notify::new());
+    let release = Arc::new(Notify::new());
+    let mut registry = DriverRegistry::new();
+    registry.register(Box::new(RustFocuser)).unwrap();
+    registry
+        .register(Box::new(GatedDriver {
+            entered: Arc::clone(&entered),
+            release: Arc::clone(&release),
+        }))
+        .unwrap();
+    let server = IndigoServer::start("127.0.0.1:0", registry).await.unwrap();
+    let gated_client = connect(&server, RsClientStrategy::new()).await;
+    let client = connect(&server, RsClientStrategy::new()).await;
+
+    // Keep the gated driver busy with a change
+    let stuck = tokio::spawn(async move {
+        let mut gated = RemoteDevice::new(&gated_client, "Gated").await.unwrap();
+        gated.connect().await.unwrap();
+    });
+    tokio::time::timeout(Duration::from_secs(5), entered.notified())
+        .await
+        .expect("Change did not reach the gated driver");
+
+    let mut focuser = RemoteDevice::new(&client, DEVICE).await.unwrap();
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        focuser.change_numbers("FOCUSER_POSITION", &[("POSITION", 200.0)]),
+    )
+    .await
+    .expect("Focuser was blocked by the gated driver")
+    .unwrap();
+
+    release.notify_one();
+    stuck.await.unwrap();
+    server.shutdown().await.unwrap();
+}
@@ -0,0 +1,506 @@
+//! Tests for the simulated devices, driven in-process through the registry.
+
+use libindigo::device::traits::*;
+use libindigo::device::{
+    CameraDriver, CcdSimulator, DriverRegistry, FilterWheelDriver, FilterWheelSimulator,
+    FocuserDriver, FocuserSimulator, MountDriver, MountSimulator, TaskEvent,
+};
+use libindigo::name;
+use libindigo::types::{Property, PropertyState, PropertyType, PropertyValue, SwitchState};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+mod fixtures;
+
+use fixtures::request;
+
+type Events = mpsc::UnboundedReceiver<TaskEvent>;
+
+fn switch_request(name: &str, item: &str) -> Property {
+    request(
+        "Simulator",
+        name,
+        PropertyType::Switch,
+        vec![(item, PropertyValue::switch(SwitchState::On))],
+    )
+}
+
+fn number_request(name: &str, items: Vec<(&str, f64)>) -> Property {
+    request(
+        "Simulator",
+        name,
+        PropertyType::Number,
+        items
+            .into_iter()
+            .map(|(item, value)| (item, PropertyValue::number(value)))
+            .collect(),
+    )
+}
+
+/// Creates a registry that sends the timer events of its drivers to the
+/// returned receiver.
+fn task_registry() -> (DriverRegistry, Events) {
+    let (sender, events) = mpsc::unbounded_channel();
+    let mut registry = DriverRegistry::new();
+    registry.set_task_sender(Some(sender));
+    (registry, events)
+}
+
+/// Handles timer events until a property of a device is no longer busy.
+async fn wait_until_done(
+    registry: &mut DriverRegistry,
+    events: &mut Events,
+    device: &str,
+    property: &str,
+) -> PropertyState {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let state = registry
+                .context(device)
+                .unwrap()
+                .properties()
+                .get_property(property)
+                .unwrap()
+                .state;
+            if state != PropertyState::Busy {
+                return state;
+            }
+            let event = events.recv().await.unwrap();
+            registry.handle_task(&event).await.unwrap();
+        }
+    })
+    .await
+    .expect("Property stayed busy")
+}
+
+async fn connect(registry: &mut DriverRegistry, device: &str) {
+    registry.attach(device).await.unwrap();
+    registry
+        .handle_property_change(
+            device,
+            &switch_request(name::CONNECTION_PROPERTY, name::CONNECTION_CONNECTED_ITEM),
+        )
+        .await
+        .unwrap();
+}
+
+fn number_value(registry: &DriverRegistry, device: &str, property: &str, item: &str) -> f64 {
+    let property = registry
+        .context(device)
+        .unwrap()
+        .properties()
+        .get_property(property)
+        .unwrap();
+    match &property.items[item].value {
+        PropertyValue::Number { value, .. } => *value,
+        other => panic!("Expected number, got {:?}", other),
+    }
+}
+
+/// Reads the size and pixels of a 16 bit FITS image.
+fn read_fits(data: &[u8]) -> (usize, usize, Vec<u16>) {
+    let header_end = data
+        .chunks(80)
+        .position(|card| card.starts_with(b"END "))
+        .unwrap();
+    let value = |keyword: &str| -> usize {
+        let card = data
+            .chunks(80)
+            .find(|card| card.starts_with(format!("{:<8}=", keyword).as_bytes()))
+            .unwrap();
+        std::str::from_utf8(&card[10..30])
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap()
+    };
+    let (columns, rows) = (value("NAXIS1"), value("NAXIS2"));
+    let start = ((header_end * 80) / 2880 + 1) * 2880;
+    let pixels = data[start..start + columns * rows * 2]
+        .chunks(2)
+        .map(|b| (i16::from_be_bytes([b[0], b[1]]) as i32 + 32768) as u16)
+        .collect();
+    (columns, rows, pixels)
+}
+
+async fn expose(
+    registry: &mut DriverRegistry,
+    events: &mut Events,
+    duration: f64,
+) -> (usize, usize, Vec<u16>) {
+    registry
+        .handle_property_change(
+            "CCD Simulator",
+            &number_request(
+                name::CCD_EXPOSURE_PROPERTY,
+                vec![(name::CCD_EXPOSURE_ITEM, duration)],
+            ),
+        )
+        .await
+        .unwrap();
+    let state = wait_until_done(
+        registry,
+        events,
+        "CCD Simulator",
+        name::CCD_EXPOSURE_PROPERTY,
+    )
+    .await;
+    assert_eq!(state, PropertyState::Ok);
+    let image = registry
+        .context("CCD Simulator")
+        .unwrap()
+        .properties()
+        .get_property(name::CCD_IMAGE_PROPERTY)
+        .unwrap();
+    match &image.items[name::CCD_IMAGE_ITEM].value {
+        PropertyValue::Blob { data, format, .. } => {
+            assert_eq!(format, ".fits");
+            assert_eq!(data.len() % 2880, 0);
+            read_fits(data)
+        }
+        other => panic!("Expected BLOB, got {:?}", other),
+    }
+}
+
+fn mean(pixels: &[u16]) -> f64 {
+    pixels.iter().map(|&p| p as f64).sum::<f64>() / pixels.len() as f64
+}
+
+#[tokio::test]
+async fn test_ccd_simulator_signal_scales_with_exposure() {
+    let (mut registry, mut events) = task_registry();
+    registry
+        .register(Box::new(CameraDriver::new(
+            CcdSimulator::default().with_size(200, 150),
+        )))
+        .unwrap();
+    connect(&mut registry, "CCD Simulator").await;
+
+    let (columns, rows, short) = expose(&mut registry, &mut events, 0.05).await;
+    assert_eq!((columns, rows), (200, 150));
+    let (_, _, long) = expose(&mut registry, &mut events, 0.2).await;
+    // Offset of 100 ADU at a gain of 1 ADU per electron
+    let ratio = (mean(&long) - 100.0) / (mean(&short) - 100.0);
+    assert!((3.0..5.0).contains(&ratio), "ratio {}", ratio);
+    // Stars stand out from the sky
+    assert!(*long.iter().max().unwrap() as f64 - 100.0 > (mean(&long) - 100.0) * 10.0);
+
+    registry
+        .handle_property_change(
+            "CCD Simulator",
+            &number_request(
+                name::CCD_BIN_PROPERTY,
+                vec![
+                    (name::CCD_BIN_HORIZONTAL_ITEM, 2.0),
+                    (name::CCD_BIN_VERTICAL_ITEM, 2.0),
+                ],
+            ),
+        )
+        .await
+        .unwrap();
+    registry
+        .handle_property_change(
+            "CCD Simulator",
+            &switch_request(
+                name::CCD_FRAME_TYPE_PROPERTY,
+                name::CCD_FRAME_TYPE_BIAS_ITEM,
+            ),
+        )
+        .await
+        .unwrap();
+    let (columns, rows, bias) = expose(&mut registry, &mut events, 0.05).await;
+    assert_eq!((columns, rows), (100, 75));
+    assert!((mean(&bias) - 100.0).abs() < 2.0, "bias {}", mean(&bias));
+}
+
+#[tokio::test]
+async fn test_mount_simulator_slews_at_finite_rate() {
+    let (mut registry, mut events) = task_registry();
+    registry
+        .register(Box::new(MountDriver::new(
+            MountSimulator::default().with_slew_rate(100.0),
+        )))
+        .unwrap();
+    connect(&mut registry, "Mount Simulator").await;
+
+    let goto = number_request(
+        name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY,
+        vec![
+            (name::MOUNT_EQUATORIAL_COORDINATES_RA_ITEM, 0.0),
+            (name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM, 70.0),
+        ],
+    );
+    // A new mount is parked
+    assert!(registry
+        .handle_property_change("Mount Simulator", &goto)
+        .await
+        .is_err());
+    registry
+        .handle_property_change(
+            "Mount Simulator",
+            &switch_request(name::MOUNT_PARK_PROPERTY, name::MOUNT_PARK_UNPARKED_ITEM),
+        )
+        .await
+        .unwrap();
+
+    // 20 degrees in declination at 100 degrees per second
+    let started = Instant::now();
+    registry
+        .handle_property_change("Mount Simulator", &goto)
+        .await
+        .unwrap();
+    let state = wait_until_done(
+        &mut registry,
+        &mut events,
+        "Mount Simulator",
+        name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY,
+    )
+    .await;
+    assert_eq!(state, PropertyState::Ok);
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert_eq!(
+        number_value(
+            &registry,
+            "Mount Simulator",
+            name::MOUNT_EQUATORIAL_COORDINATES_PROPERTY,
+            name::MOUNT_EQUATORIAL_COORDINATES_DEC_ITEM
+        ),
+        70.0
+    );
+}
+
+#[tokio::test]
+async fn test_mount_simulator_abort_and_park() {
+    let mut mount = MountSimulator::default().with_slew_rate(100.0);
+    mount.unpark().await.unwrap();
+    mount.sync_to(Coordinates::new(0.0, 0.0)).await.unwrap();
+
+    // 60 degrees in declination at 100 degrees per second
+    mount.slew_to(Coordinates::new(0.0, 60.0)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(mount.is_slewing().await.unwrap());
+    mount.abort_slew().await.unwrap();
+    assert!(!mount.is_slewing().await.unwrap());
+    let dec = mount.coordinates().await.unwrap().dec;
+    assert!((20.0..50.0).contains(&dec), "dec {}", dec);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(mount.coordinates().await.unwrap().dec, dec);
+
+    // The mount parks once it arrives at the park position, at the pole
+    mount.park().await.unwrap();
+    assert!(mount.is_slewing().await.unwrap());
+    assert!(!mount.is_parked().await.unwrap());
+    tokio::time::sleep(Duration::from_millis(800)).await;
+    assert!(!mount.is_slewing().await.unwrap());
+    assert!(mount.is_parked().await.unwrap());
+    assert_eq!(mount.coordinates().await.unwrap().dec, 90.0);
+}
+
+#[tokio::test]
+async fn test_mount_simulator_manual_motion() {
+    let mut mount = MountSimulator::default().with_slew_rate(100.0);
+    mount.unpark().await.unwrap();
+    mount.sync_to(Coordinates::new(6.0, 0.0)).await.unwrap();
+    mount
+        .move_axis(MountAxis::Secondary, AxisDirection::Forward, SlewRate::Max)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    mount.stop_axis(MountAxis::Secondary).await.unwrap();
+    let dec = mount.coordinates().await.unwrap().dec;
+    assert!((10.0..30.0).contains(&dec), "dec {}", dec);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(mount.coordinates().await.unwrap().dec, dec);
+    assert_eq!(mount.coordinates().await.unwrap().ra, 6.0);
+}
+
+/// Moves a simulated focuser and waits until it stops.
+async fn move_by(focuser: &mut FocuserSimulator, steps: i32) {
+    focuser.move_relative(steps).await.unwrap();
+    while focuser.is_moving().await.unwrap() {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_focuser_simulator_backlash() {
+    let mut focuser = FocuserSimulator::default().with_play(40);
+    let start = focuser.drawtube_position();
+    move_by(&mut focuser, 100).await;
+    let out = focuser.drawtube_position();
+    // Reversing without compensation loses the play
+    move_by(&mut focuser, -100).await;
+    assert_eq!(focuser.position().await.unwrap(), 5000);
+    assert_eq!(out - focuser.drawtube_position(), 60.0);
+    assert!(out > start);
+
+    focuser.set_backlash(40).await.unwrap();
+    let before = focuser.drawtube_position();
+    move_by(&mut focuser, 100).await;
+    assert_eq!(focuser.drawtube_position() - before, 100.0);
+    move_by(&mut focuser, -50).await;
+    assert_eq!(focuser.drawtube_position() - before, 50.0);
+    assert_eq!(focuser.position().await.unwrap(), 5050);
+}
+
+#[tokio::test]
+async fn test_focuser_simulator_abort_stops_move() {
+    let mut focuser = FocuserSimulator::default();
+    // 1000 steps at 100 steps per second
+    focuser.set_speed(0.0).await.unwrap();
+    focuser.move_to(6000).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(focuser.is_moving().await.unwrap());
+    focuser.abort_move().await.unwrap();
+    assert!(!focuser.is_moving().await.unwrap());
+    let position = focuser.position().await.unwrap();
+    assert!((5010..5100).contains(&position), "position {}", position);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(focuser.position().await.unwrap(), position);
+}
+
+#[tokio::test]
+async fn test_focuser_simulator_moves_through_driver() {
+    let (mut registry, mut events) = task_registry();
+    registry
+        .register(Box::new(FocuserDriver::new(FocuserSimulator::default())))
+        .unwrap();
+    connect(&mut registry, "Focuser Simulator").await;
+    registry
+        .handle_property_change(
+            "Focuser Simulator",
+            &number_request(
+                name::FOCUSER_POSITION_PROPERTY,
+                vec![(name::FOCUSER_POSITION_ITEM, 5500.0)],
+            ),
+        )
+        .await
+        .unwrap();
+    let state = wait_until_done(
+        &mut registry,
+        &mut events,
+        "Focuser Simulator",
+        name::FOCUSER_POSITION_PROPERTY,
+    )
+    .await;
+    assert_eq!(state, PropertyState::Ok);
+    assert_eq!(
+        number_value(
+            &registry,
+            "Focuser Simulator",
+            name::FOCUSER_POSITION_PROPERTY,
+            name::FOCUSER_POSITION_ITEM
+        ),
+        5500.0
+    );
+
+    // Moves beyond the limits are rejected
+    registry
+        .handle_property_change(
+            "Focuser Simulator",
+            &number_request(
+                name::FOCUSER_LIMITS_PROPERTY,
+                vec![(name::FOCUSER_LIMITS_MAX_POSITION_ITEM, 6000.0)],
+            ),
+        )
+        .await
+        .unwrap();
+    assert!(registry
+        .handle_property_change(
+            "Focuser Simulator",
+            &number_request(
+                name::FOCUSER_POSITION_PROPERTY,
+                vec![(name::FOCUSER_POSITION_ITEM, 6500.0)],
+            ),
+        )
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_filter_wheel_simulator_motion_delay() {
+    let (mut registry, mut events) = task_registry();
+    registry
+        .register(Box::new(FilterWheelDriver::new(
+            FilterWheelSimulator::default().with_slot_delay(Duration::from_millis(50)),
+        )))
+        .unwrap();
+    connect(&mut registry, "Filter Wheel Simulator").await;
+
+    let elapsed = select_slot(&mut registry, &mut events, 3.0).await;
+    assert!(elapsed >= Duration::from_millis(100));
+    assert_eq!(
+        number_value(
+            &registry,
+            "Filter Wheel Simulator",
+            name::WHEEL_SLOT_PROPERTY,
+            name::WHEEL_SLOT_ITEM
+        ),
+        3.0
+    );
+
+    // The wheel turns the shorter way around, from slot 5 to slot 1
+    let mut wheel = FilterWheelSimulator::default().with_slot_delay(Duration::from_millis(50));
+    wheel.select_filter(5).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(wheel.current_slot().await.unwrap(), 5);
+    wheel.select_filter(1).await.unwrap();
+    assert!(wheel.is_moving().await.unwrap());
+    assert_eq!(wheel.current_slot().await.unwrap(), 5);
+    tokio::time::sleep(Duration::from_millis(75)).await;
+    assert!(!wheel.is_moving().await.unwrap());
+    assert_eq!(wheel.current_slot().await.unwrap(), 1);
+}
+
+/// Selects a slot of the simulated filter wheel, returning how long it took.
+async fn select_slot(registry: &mut DriverRegistry, events: &mut Events, slot: f64) -> Duration {
+    let started = Instant::now();
+    registry
+        .handle_property_change(
+            "Filter Wheel Simulator",
+            &number_request(
+                name::WHEEL_SLOT_PROPERTY,
+                vec![(name::WHEEL_SLOT_ITEM, slot)],
+            ),
+        )
+        .await
+        .unwrap();
+    let state = wait_until_done(
+        registry,
+        events,
+        "Filter Wheel Simulator",
+        name::WHEEL_SLOT_PROPERTY,
+    )
+    .await;
+    assert_eq!(state, PropertyState::Ok);
+    started.elapsed()
+}
+
+#[tokio::test]
+async fn test_simulators_attach_together() {
+    let mut registry = DriverRegistry::new();
+    registry
+        .register(Box::new(CameraDriver::new(CcdSimulator::default())))
+        .unwrap();
+    registry
+        .register(Box::new(MountDriver::new(MountSimulator::default())))
+        .unwrap();
+    registry
+        .register(Box::new(FocuserDriver::new(FocuserSimulator::default())))
+        .unwrap();
+    registry
+        .register(Box::new(FilterWheelDriver::new(
+            FilterWheelSimulator::default(),
+        )))
+        .unwrap();
+    registry.attach_all().await.unwrap();
+    assert_eq!(registry.count(), 4);
+    for (info, attached) in registry.list_drivers() {
+        assert!(attached, "{} not attached", info.name);
+        assert!(registry
+            .context(&info.name)
+            .unwrap()
+            .properties()
+            .has_property(name::CONNECTION_PROPERTY));
+    }
+}
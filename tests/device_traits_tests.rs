@@ -236,6 +236,7 @@ fn test_device_proxy_property_caching() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Text,
+        switch_rule: None,
         items: HashMap::new(),
         timeout: None,
         timestamp: None,
@@ -282,6 +283,7 @@ fn test_device_proxy_get_number() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Number,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -318,6 +320,7 @@ fn test_device_proxy_get_text() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Text,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -359,6 +362,7 @@ fn test_device_proxy_get_switch() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Switch,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -404,6 +408,7 @@ fn test_device_proxy_get_blob() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Blob,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -454,6 +459,7 @@ fn test_device_proxy_type_mismatch() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Text,
+        switch_rule: None,
         items,
         timeout: None,
         timestamp: None,
@@ -486,6 +492,7 @@ fn test_device_proxy_clear_cache() {
         state: PropertyState::Ok,
         perm: PropertyPerm::ReadWrite,
         property_type: PropertyType::Text,
+        switch_rule: None,
         items: HashMap::new(),
         timeout: None,
         timestamp: None,
@@ -575,6 +582,7 @@ impl Device for MockCamera {
             state: PropertyState::Ok,
             perm: PropertyPerm::ReadWrite,
             property_type: PropertyType::Text,
+            switch_rule: None,
             items: HashMap::new(),
             timeout: None,
             timestamp: None,
@@ -95,6 +95,7 @@ mod conversion_tests_no_sys {
             state: PropertyState::Idle,
             perm: libindigo::types::PropertyPerm::ReadWrite,
             property_type: PropertyType::Text,
+            switch_rule: None,
             items: HashMap::new(),
             timeout: None,
             timestamp: None,
@@ -244,6 +245,7 @@ mod ffi_strategy_tests {
             state: PropertyState::Idle,
             perm: libindigo::types::PropertyPerm::ReadWrite,
             property_type: PropertyType::Text,
+            switch_rule: None,
             items: HashMap::new(),
             timeout: None,
             timestamp: None,
@@ -350,6 +352,7 @@ mod async_ffi_strategy_tests {
             state: PropertyState::Idle,
             perm: libindigo::types::PropertyPerm::ReadWrite,
             property_type: PropertyType::Text,
+            switch_rule: None,
             items: HashMap::new(),
             timeout: None,
             timestamp: None,
@@ -0,0 +1,85 @@
+//! Fixtures shared by the tests of devices and the pure Rust client.
+//!
+//! Not every test file uses every fixture.
+
+#![allow(dead_code)]
+
+use libindigo::client::ClientStrategy;
+use libindigo::types::{Property, PropertyItem, PropertyType, PropertyValue};
+use libindigo_rs::RsClientStrategy;
+use std::future::Future;
+use std::time::Duration;
+
+/// How long to wait for the state of a client by default.
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Builds a change request for a property of a device.
+pub fn request(
+    device: &str,
+    name: &str,
+    property_type: PropertyType,
+    items: Vec<(&str, PropertyValue)>,
+) -> Property {
+    let mut builder = Property::builder()
+        .device(device)
+        .name(name)
+        .property_type(property_type);
+    for (item, value) in items {
+        builder = builder.item(PropertyItem::new(item, item, value));
+    }
+    builder.build().unwrap()
+}
+
+/// Waits until a condition on the state of a client holds.
+///
+/// The condition is checked again whenever the client receives a property
+/// event, and panics with `what` if it does not hold within `timeout`.
+pub async fn wait_until<F, Fut>(
+    client: &RsClientStrategy,
+    timeout: Duration,
+    what: &str,
+    mut condition: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    tokio::time::timeout(timeout, async {
+        loop {
+            // Subscribe before checking so no event is missed in between
+            let mut events = client.subscribe_events().await.unwrap();
+            if condition().await {
+                return;
+            }
+            while events.recv().await.is_some() {
+                if condition().await {
+                    return;
+                }
+            }
+            // Subscribers are dropped on disconnect, so subscribe again
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("{}", what));
+}
+
+/// Waits until the client knows a property.
+pub async fn wait_for_property(client: &RsClientStrategy, device: &str, name: &str) {
+    wait_until(
+        client,
+        TIMEOUT,
+        &format!("{} was not defined", name),
+        || async move { client.property(device, name).await.is_some() },
+    )
+    .await;
+}
+
+/// Waits until a property is deleted from the client.
+pub async fn wait_for_deletion(client: &RsClientStrategy, device: &str, name: &str) {
+    wait_until(
+        client,
+        TIMEOUT,
+        &format!("{} was not deleted", name),
+        || async move { client.property(device, name).await.is_none() },
+    )
+    .await;
+}
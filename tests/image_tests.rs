@@ -0,0 +1,435 @@
+//! Tests for decoding and encoding camera images.
+
+#![cfg(feature = "image")]
+
+use libindigo::device::traits::{BinningMode, Camera};
+use libindigo::device::CcdSimulator;
+use libindigo::error::IndigoError;
+use libindigo::image::{fits, raw, xisf, Header, Image, ImageData, Keyword, Pixels, Value};
+use libindigo::types::PropertyValue;
+use std::io::Write;
+
+/// Builds a FITS file from header cards and big-endian pixel data.
+fn fits_file(cards: &[&str], pixels: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for card in cards.iter().chain(&["END"]) {
+        data.extend_from_slice(format!("{:<80}", card).as_bytes());
+    }
+    data.resize(data.len().div_ceil(2880) * 2880, b' ');
+    data.extend_from_slice(pixels);
+    data.resize(data.len().div_ceil(2880) * 2880, 0);
+    data
+}
+
+/// Builds an INDIGO raw file from a signature and little-endian pixel data.
+fn raw_file(signature: &[u8; 4], width: i32, height: i32, pixels: &[u8]) -> Vec<u8> {
+    [
+        &signature[..],
+        &width.to_le_bytes(),
+        &height.to_le_bytes(),
+        pixels,
+    ]
+    .concat()
+}
+
+/// Builds a monolithic XISF file from the attributes of an image, the
+/// elements inside it, and the attached pixel data.
+fn xisf_file(attributes: &str, elements: &str, pixels: &[u8]) -> Vec<u8> {
+    // The attachment follows the header, whose length depends on its position
+    let xml = |position: usize| {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <xisf version=\"1.0\" xmlns=\"http://www.pixinsight.com/xisf\">\
+             <Image {} location=\"attachment:{:08}:{}\">{}</Image></xisf>",
+            attributes,
+            position,
+            pixels.len(),
+            elements
+        )
+    };
+    let length = xml(0).len();
+    let mut data = b"XISF0100".to_vec();
+    data.extend_from_slice(&(length as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(xml(16 + length).as_bytes());
+    data.extend_from_slice(pixels);
+    data
+}
+
+fn image(data: ImageData) -> Image {
+    let mut header = Header::new();
+    header.set("EXPTIME", Value::Real(30.0));
+    header.set("BAYERPAT", Value::Text("RGGB".to_string()));
+    Image { header, data }
+}
+
+#[tokio::test]
+async fn test_decode_simulator_image() {
+    let mut camera = CcdSimulator::new("CCD Simulator").with_size(160, 120);
+    camera
+        .set_binning(BinningMode { x: 2, y: 2 })
+        .await
+        .unwrap();
+    camera.start_exposure(0.0).await.unwrap();
+    let image = Image::decode(".fits", &camera.download_image().await.unwrap()).unwrap();
+
+    assert_eq!(
+        (image.width(), image.height(), image.channels()),
+        (80, 60, 1)
+    );
+    assert_eq!(image.header.exposure_time(), Some(0.0));
+    assert_eq!(image.header.binning(), Some((2, 2)));
+    assert_eq!(image.header.image_type(), Some("Light Frame"));
+    assert_eq!(image.header.instrument(), Some("CCD Simulator"));
+    assert!(image.header.ccd_temperature().is_some());
+    let ImageData::U16(pixels) = &image.data else {
+        panic!("Expected 16 bit pixels, got {:?}", image.data);
+    };
+    // Bias level of the simulator
+    let mean = pixels.as_slice().iter().map(|&p| p as f64).sum::<f64>() / (80.0 * 60.0);
+    assert!((90.0..110.0).contains(&mean), "Mean {}", mean);
+}
+
+#[test]
+fn test_read_pixel_types() {
+    let cases: [(&str, &[&str], Vec<u8>, ImageData); 5] = [
+        (
+            "8 bit",
+            &[],
+            vec![0, 255],
+            ImageData::U8(Pixels::new(2, 1, 1, vec![0, 255]).unwrap()),
+        ),
+        (
+            "signed 16 bit",
+            &[],
+            [(-2i16).to_be_bytes(), 300i16.to_be_bytes()].concat(),
+            ImageData::I16(Pixels::new(2, 1, 1, vec![-2, 300]).unwrap()),
+        ),
+        (
+            "unsigned 32 bit",
+            &[
+                "BZERO   =           2147483648",
+                "BSCALE  =                    1",
+            ],
+            [(-2147483648i32).to_be_bytes(), 5i32.to_be_bytes()].concat(),
+            ImageData::U32(Pixels::new(2, 1, 1, vec![0, 2147483653]).unwrap()),
+        ),
+        (
+            "scaled 16 bit",
+            &[
+                "BZERO   =                 10.0",
+                "BSCALE  =                  0.5",
+            ],
+            [4i16.to_be_bytes(), (-4i16).to_be_bytes()].concat(),
+            ImageData::F32(Pixels::new(2, 1, 1, vec![12.0, 8.0]).unwrap()),
+        ),
+        (
+            "64 bit floating point",
+            &[],
+            [1.5f64.to_be_bytes(), (-0.25f64).to_be_bytes()].concat(),
+            ImageData::F64(Pixels::new(2, 1, 1, vec![1.5, -0.25]).unwrap()),
+        ),
+    ];
+    for (name, scaling, pixels, expected) in cases {
+        let bitpix = match &expected {
+            ImageData::U8(_) => 8,
+            ImageData::I16(_) => 16,
+            ImageData::F32(_) => 16,
+            ImageData::U32(_) => 32,
+            _ => -64,
+        };
+        let bitpix = format!("BITPIX  = {:>20}", bitpix);
+        let mut cards = vec![
+            "SIMPLE  =                    T",
+            bitpix.as_str(),
+            "NAXIS   =                    2",
+            "NAXIS1  =                    2",
+            "NAXIS2  =                    1",
+        ];
+        cards.extend_from_slice(scaling);
+        let image = fits::read(&fits_file(&cards, &pixels)).unwrap();
+        assert_eq!(image.data, expected, "{}", name);
+    }
+}
+
+#[test]
+fn test_write_and_read_back() {
+    let data = [
+        ImageData::U8(Pixels::new(3, 2, 1, vec![1, 2, 3, 4, 5, 6]).unwrap()),
+        ImageData::U16(Pixels::new(2, 2, 1, vec![0, 1, 32768, 65535]).unwrap()),
+        ImageData::I32(Pixels::new(2, 1, 1, vec![i32::MIN, i32::MAX]).unwrap()),
+        ImageData::F32(Pixels::new(1, 2, 1, vec![0.5, -1e-3]).unwrap()),
+        // Color images have a third axis
+        ImageData::U16(Pixels::new(2, 1, 3, vec![1, 2, 3, 4, 5, 6]).unwrap()),
+    ];
+    for data in data {
+        let written = fits::write(&image(data.clone())).unwrap();
+        assert_eq!(written.len() % 2880, 0);
+        let read = fits::read(&written).unwrap();
+        assert_eq!(read.data, data);
+        assert_eq!(read.header.exposure_time(), Some(30.0));
+        assert_eq!(read.header.bayer_pattern(), Some("RGGB"));
+    }
+}
+
+#[test]
+fn test_write_keeps_keywords_and_replaces_structure() {
+    let mut image = image(ImageData::U16(Pixels::new(1, 1, 1, vec![7]).unwrap()));
+    // Stale description of the data is replaced
+    image.header.set("BITPIX", Value::Integer(-32));
+    image.header.push(Keyword {
+        name: "HISTORY".to_string(),
+        value: Value::Undefined,
+        comment: Some("Captured by libindigo".to_string()),
+    });
+
+    let read = fits::read(&fits::write(&image).unwrap()).unwrap();
+    assert_eq!(read.header.get("BITPIX"), Some(&Value::Integer(16)));
+    assert_eq!(read.header.get("BZERO"), Some(&Value::Integer(32768)));
+    let history = read.header.keywords().last().unwrap();
+    assert_eq!(history.name, "HISTORY");
+    assert_eq!(history.comment.as_deref(), Some("Captured by libindigo"));
+    assert_eq!(read.data.value(0, 0), Some(7.0));
+}
+
+#[test]
+fn test_invalid_images() {
+    let header = [
+        "SIMPLE  =                    T",
+        "BITPIX  =                   16",
+        "NAXIS   =                    2",
+        "NAXIS1  =                  100",
+        "NAXIS2  =                  100",
+    ];
+    // Data ends before the 100x100 pixels
+    let truncated = fits_file(&header, &[0; 100]);
+    assert!(matches!(
+        fits::read(&truncated),
+        Err(IndigoError::ParseError(_))
+    ));
+    assert!(matches!(
+        fits::read(b"GIF89a"),
+        Err(IndigoError::ParseError(_))
+    ));
+    let no_end = format!("{:<2880}", "SIMPLE  =                    T");
+    assert!(matches!(
+        fits::read(no_end.as_bytes()),
+        Err(IndigoError::ParseError(_))
+    ));
+    let cube = fits_file(
+        &[
+            "SIMPLE  =                    T",
+            "BITPIX  =                    8",
+            "NAXIS   =                    4",
+        ],
+        &[],
+    );
+    assert!(matches!(
+        fits::read(&cube),
+        Err(IndigoError::NotSupported(_))
+    ));
+    assert!(matches!(
+        Image::decode(".jpeg", &[]),
+        Err(IndigoError::NotSupported(_))
+    ));
+}
+
+#[test]
+fn test_read_raw_images() {
+    let mono = raw::read(&raw_file(b"RAW2", 2, 1, &[1, 0, 0, 1])).unwrap();
+    assert_eq!(
+        mono.data,
+        ImageData::U16(Pixels::new(2, 1, 1, vec![1, 256]).unwrap())
+    );
+    assert!(mono.header.keywords().is_empty());
+
+    // Channels of each pixel are stored together
+    let color = Image::decode(".raw", &raw_file(b"RGB3", 2, 1, &[1, 2, 3, 4, 5, 6])).unwrap();
+    let ImageData::U8(pixels) = &color.data else {
+        panic!("Expected 8 bit pixels, got {:?}", color.data);
+    };
+    assert_eq!(pixels.channel(0), Some(&[1, 4][..]));
+    assert_eq!(pixels.channel(2), Some(&[3, 6][..]));
+    assert_eq!(pixels.get_channel(1, 0, 1), Some(5));
+}
+
+#[test]
+fn test_read_raw_image_with_keywords() {
+    let mut data = raw_file(b"RAW1", 1, 1, &[42]);
+    for card in [
+        "EXPTIME =                  2.5",
+        "BAYERPAT= 'GRBG    '",
+        "END",
+    ] {
+        data.extend_from_slice(format!("{:<80}", card).as_bytes());
+    }
+    let image = raw::read(&data).unwrap();
+    assert_eq!(image.data.value(0, 0), Some(42.0));
+    assert_eq!(image.header.exposure_time(), Some(2.5));
+    assert_eq!(image.header.bayer_pattern(), Some("GRBG"));
+}
+
+#[test]
+fn test_read_xisf_images() {
+    let pixels = [1u16, 2, 3, 4, 5, 6];
+    let little: Vec<u8> = pixels.iter().flat_map(|p| p.to_le_bytes()).collect();
+    let big: Vec<u8> = pixels.iter().flat_map(|p| p.to_be_bytes()).collect();
+    let planar = ImageData::U16(Pixels::new(2, 1, 3, pixels.to_vec()).unwrap());
+
+    let image = xisf::read(&xisf_file(
+        "geometry=\"2:1:3\" sampleFormat=\"UInt16\"",
+        "",
+        &little,
+    ))
+    .unwrap();
+    assert_eq!(image.data, planar);
+
+    let image = xisf::read(&xisf_file(
+        "geometry=\"2:1:3\" sampleFormat=\"UInt16\" byteOrder=\"big\"",
+        "",
+        &big,
+    ))
+    .unwrap();
+    assert_eq!(image.data, planar);
+
+    // Channels of each pixel are stored together
+    let image = Image::decode(
+        ".xisf",
+        &xisf_file(
+            "geometry=\"2:1:3\" sampleFormat=\"UInt16\" pixelStorage=\"Normal\"",
+            "",
+            &little,
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        image.data,
+        ImageData::U16(Pixels::from_interleaved(2, 1, 3, pixels.to_vec()).unwrap())
+    );
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn test_read_compressed_xisf_images() {
+    let pixels = [0.5f32, -1.0, 2.0, 1e6];
+    let bytes: Vec<u8> = pixels.iter().flat_map(|p| p.to_le_bytes()).collect();
+    // Shuffling puts the first byte of each sample first, then the second...
+    let shuffled: Vec<u8> = (0..4)
+        .flat_map(|byte| bytes.iter().skip(byte).step_by(4).copied())
+        .collect();
+    let expected = ImageData::F32(Pixels::new(2, 2, 1, pixels.to_vec()).unwrap());
+
+    for (compression, stored) in [("zlib:16", zlib(&bytes)), ("zlib+sh:16:4", zlib(&shuffled))] {
+        let attributes = format!(
+            "geometry=\"2:2:1\" sampleFormat=\"Float32\" compression=\"{}\"",
+            compression
+        );
+        let image = xisf::read(&xisf_file(&attributes, "", &stored)).unwrap();
+        assert_eq!(image.data, expected, "{}", compression);
+    }
+
+    // Data inflating beyond the announced size is rejected
+    let attributes = "geometry=\"2:2:1\" sampleFormat=\"Float32\" compression=\"zlib:16\"";
+    for stored in [zlib(&[0; 17]), zlib(&vec![0; 16 * 1024 * 1024])] {
+        assert!(matches!(
+            xisf::read(&xisf_file(attributes, "", &stored)),
+            Err(IndigoError::ParseError(_))
+        ));
+    }
+
+    let attributes = "geometry=\"2:2:1\" sampleFormat=\"Float32\" compression=\"lz4:16\"";
+    assert!(matches!(
+        xisf::read(&xisf_file(attributes, "", &bytes)),
+        Err(IndigoError::NotSupported(_))
+    ));
+}
+
+#[test]
+fn test_read_xisf_keywords() {
+    let keywords = "<FITSKeyword name=\"EXPTIME\" value=\"30.\" comment=\"Exposure time\"/>\
+                    <FITSKeyword name=\"BAYERPAT\" value=\"&apos;RGGB&apos;\" comment=\"\"/>\
+                    <FITSKeyword name=\"XBINNING\" value=\"2\" comment=\"\"/>\
+                    <FITSKeyword name=\"YBINNING\" value=\"2\" comment=\"\"/>\
+                    <FITSKeyword name=\"HISTORY\" value=\"\" comment=\"Calibrated\"/>";
+    let image = xisf::read(&xisf_file(
+        "geometry=\"1:1:1\" sampleFormat=\"UInt8\"",
+        keywords,
+        &[7],
+    ))
+    .unwrap();
+    assert_eq!(image.header.exposure_time(), Some(30.0));
+    assert_eq!(image.header.bayer_pattern(), Some("RGGB"));
+    assert_eq!(image.header.binning(), Some((2, 2)));
+    let exposure = &image.header.keywords()[0];
+    assert_eq!(exposure.comment.as_deref(), Some("Exposure time"));
+    let history = image.header.keywords().last().unwrap();
+    assert_eq!(history.value, Value::Undefined);
+    assert_eq!(history.comment.as_deref(), Some("Calibrated"));
+}
+
+#[test]
+fn test_invalid_raw_and_xisf_images() {
+    assert!(matches!(
+        raw::read(&raw_file(b"RAW2", 100, 100, &[0; 10])),
+        Err(IndigoError::ParseError(_))
+    ));
+    assert!(matches!(
+        raw::read(&raw_file(b"RAW4", 1, 1, &[0; 4])),
+        Err(IndigoError::NotSupported(_))
+    ));
+    assert!(matches!(
+        xisf::read(b"SIMPLE  ="),
+        Err(IndigoError::ParseError(_))
+    ));
+    // Data doesn't fill the geometry
+    assert!(matches!(
+        xisf::read(&xisf_file(
+            "geometry=\"4:4:1\" sampleFormat=\"UInt8\"",
+            "",
+            &[0; 4]
+        )),
+        Err(IndigoError::ParseError(_))
+    ));
+    assert!(matches!(
+        xisf::read(&xisf_file(
+            "geometry=\"1:1:1\" sampleFormat=\"Complex32\"",
+            "",
+            &[0; 8]
+        )),
+        Err(IndigoError::NotSupported(_))
+    ));
+    // Item sizes that don't fit the data
+    for compression in ["zlib+sh:1:4", "zlib+sh:1:0"] {
+        let attributes = format!(
+            "geometry=\"1:1:1\" sampleFormat=\"UInt8\" compression=\"{}\"",
+            compression
+        );
+        assert!(matches!(
+            xisf::read(&xisf_file(&attributes, "", &zlib(&[7]))),
+            Err(IndigoError::ParseError(_))
+        ));
+    }
+}
+
+#[test]
+fn test_decode_blob_value() {
+    let value = PropertyValue::Blob {
+        data: raw_file(b"RAW1", 1, 1, &[9]),
+        format: ".raw".to_string(),
+        size: 13,
+        compressed_size: None,
+    };
+    assert_eq!(
+        Image::from_blob(&value).unwrap().data.value(0, 0),
+        Some(9.0)
+    );
+    assert!(matches!(
+        Image::from_blob(&PropertyValue::Text("image".to_string())),
+        Err(IndigoError::InvalidParameter(_))
+    ));
+}
@@ -1,7 +0,0 @@
-//! Preset device configurations for common INDIGO devices.
-
-mod ccd;
-mod mount;
-
-pub use ccd::ccd_simulator;
-pub use mount::mount_simulator;
@@ -0,0 +1,232 @@
+//! Tests of client robustness against faults injected by the mock server.
+
+use libindigo::client::{ClientStrategy, ConnectionEvent, ReconnectPolicy};
+use libindigo::device::traits::Focuser;
+use libindigo::error::IndigoError;
+use libindigo::types::PropertyValue;
+use libindigo_rs::mock_server::{
+    presets, Faults, Malformation, MockIndigoServer, MockServerBuilder, NumberValue, PropertyUpdate,
+};
+use libindigo_rs::{ProtocolNegotiator, RemoteFocuser, RsClientStrategy};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+mod fixtures;
+
+use fixtures::{wait_until, TIMEOUT};
+
+const FOCUSER: &str = "Focuser Simulator";
+
+async fn focuser_server(faults: Faults) -> MockIndigoServer {
+    MockServerBuilder::new()
+        .with_focuser_simulator()
+        .with_faults(faults)
+        .build()
+        .await
+        .expect("Failed to start mock server")
+}
+
+/// Waits until the client has received all definitions of the focuser.
+async fn wait_for_focuser(client: &RsClientStrategy) {
+    let count = presets::focuser_simulator().properties.len();
+    wait_until(
+        client,
+        Duration::from_secs(10),
+        "Focuser was not defined",
+        || async {
+            client
+                .device(FOCUSER)
+                .await
+                .is_some_and(|d| d.properties.len() >= count)
+        },
+    )
+    .await;
+}
+
+/// Gets the position of the focuser known to the client.
+async fn position(client: &RsClientStrategy) -> Option<f64> {
+    let property = client.property(FOCUSER, "FOCUSER_POSITION").await?;
+    match property.items.get("POSITION")?.value {
+        PropertyValue::Number { value, .. } => Some(value),
+        _ => None,
+    }
+}
+
+/// Moves the focuser on the server and waits for the client to see it.
+async fn expect_position_update(server: &MockIndigoServer, client: &RsClientStrategy) {
+    let update = PropertyUpdate {
+        state: None,
+        items: vec![(
+            "POSITION".to_string(),
+            libindigo_rs::mock_server::PropertyValue::Number(NumberValue {
+                value: 1234.0,
+                format: "%.0f".to_string(),
+                min: 0.0,
+                max: 65535.0,
+                step: 1.0,
+            }),
+        )],
+        message: None,
+    };
+    server
+        .update_property(FOCUSER, "FOCUSER_POSITION", update)
+        .await
+        .unwrap();
+    wait_until(client, TIMEOUT, "Update was not received", || async {
+        position(client).await == Some(1234.0)
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_client_recovers_from_malformed_messages() {
+    for negotiator in [
+        ProtocolNegotiator::json_only(),
+        ProtocolNegotiator::xml_only(),
+    ] {
+        let server = focuser_server(Faults {
+            malformed: HashMap::from([
+                (2, Malformation::Truncated),
+                (4, Malformation::InvalidJson),
+                (6, Malformation::InvalidXml),
+                (8, Malformation::Truncated),
+            ]),
+            ..Faults::default()
+        })
+        .await;
+        let mut client = RsClientStrategy::with_protocol_negotiator(negotiator);
+        client.connect(&server.addr().to_string()).await.unwrap();
+
+        // The definitions are sent again for the enumeration after connecting
+        wait_for_focuser(&client).await;
+        expect_position_update(&server, &client).await;
+
+        client.disconnect().await.unwrap();
+        server.shutdown().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_client_reassembles_split_messages() {
+    for negotiator in [
+        ProtocolNegotiator::json_only(),
+        ProtocolNegotiator::xml_only(),
+    ] {
+        for chunk in [1, 7, 100] {
+            let server = focuser_server(Faults {
+                split_writes: Some(chunk),
+                ..Faults::default()
+            })
+            .await;
+            let mut client = RsClientStrategy::with_protocol_negotiator(negotiator.clone());
+            client.connect(&server.addr().to_string()).await.unwrap();
+
+            wait_for_focuser(&client).await;
+            expect_position_update(&server, &client).await;
+
+            client.disconnect().await.unwrap();
+            server.shutdown().await.unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_client_reconnects_after_dropped_connection() {
+    let server = MockServerBuilder::new()
+        .with_focuser_simulator()
+        .with_connection_faults(
+            1,
+            Faults {
+                disconnect_after: Some(3),
+                ..Faults::default()
+            },
+        )
+        .build()
+        .await
+        .expect("Failed to start mock server");
+
+    let mut client = RsClientStrategy::new();
+    client
+        .set_reconnect_policy(
+            ReconnectPolicy::default()
+                .with_initial_delay(Duration::from_millis(10))
+                .with_jitter(0.0)
+                .with_max_attempts(5),
+        )
+        .unwrap();
+    let mut events = client.subscribe_connection_events().await.unwrap();
+    client.connect(&server.addr().to_string()).await.unwrap();
+
+    async fn next(events: &mut mpsc::UnboundedReceiver<ConnectionEvent>) -> ConnectionEvent {
+        tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+    assert_eq!(next(&mut events).await, ConnectionEvent::Connected);
+    assert!(matches!(
+        next(&mut events).await,
+        ConnectionEvent::Disconnected { .. }
+    ));
+    assert!(matches!(
+        next(&mut events).await,
+        ConnectionEvent::Reconnecting { attempt: 1, .. }
+    ));
+    assert_eq!(next(&mut events).await, ConnectionEvent::Reconnected);
+
+    // The second connection has no faults
+    wait_for_focuser(&client).await;
+    assert_eq!(server.stats().await.total_connections, 2);
+
+    client.disconnect().await.unwrap();
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_slow_responses() {
+    let delay = Duration::from_millis(20);
+    let server = focuser_server(Faults {
+        response_delay: Some(delay),
+        ..Faults::default()
+    })
+    .await;
+    let started = Instant::now();
+    let mut client = RsClientStrategy::new();
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_focuser(&client).await;
+
+    // Every definition is delayed
+    let count = presets::focuser_simulator().properties.len() as u32;
+    assert!(started.elapsed() >= delay * count);
+
+    client.disconnect().await.unwrap();
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_changes_answered_with_alert() {
+    let server = focuser_server(Faults {
+        alert_changes: true,
+        ..Faults::default()
+    })
+    .await;
+    let mut client = RsClientStrategy::new();
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_focuser(&client).await;
+
+    let mut focuser = RemoteFocuser::new(&client, FOCUSER).await.unwrap();
+    let before = focuser.position().await.unwrap();
+    let result = focuser.move_to(before + 100).await;
+    assert!(matches!(result, Err(IndigoError::InvalidState(_))));
+    assert_eq!(focuser.position().await.unwrap(), before);
+
+    let property = server
+        .get_property(FOCUSER, "FOCUSER_POSITION")
+        .await
+        .unwrap();
+    assert_eq!(property.message.as_deref(), Some("Simulated failure"));
+
+    client.disconnect().await.unwrap();
+    server.shutdown().await.unwrap();
+}
@@ -1,8 +1,6 @@
 //! Integration tests for the mock INDIGO server.
 
-mod mock_server;
-
-use mock_server::MockServerBuilder;
+use libindigo_rs::mock_server::MockServerBuilder;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
 
@@ -111,7 +109,7 @@ async fn test_mock_server_connection() {
 
 #[tokio::test]
 async fn test_mock_server_property_update() {
-    use mock_server::{NumberValue, PropertyUpdate, PropertyValue};
+    use libindigo_rs::mock_server::{NumberValue, PropertyUpdate, PropertyValue};
 
     // Start mock server
     let server = MockServerBuilder::new()
@@ -132,7 +130,7 @@ async fn test_mock_server_property_update() {
     let update = PropertyUpdate {
         state: Some(libindigo_rs::protocol::PropertyState::Ok),
         items: vec![(
-            "CCD_TEMPERATURE_VALUE".to_string(),
+            "TEMPERATURE".to_string(),
             PropertyValue::Number(NumberValue {
                 value: -10.5,
                 format: "%.2f".to_string(),
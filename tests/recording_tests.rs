@@ -0,0 +1,162 @@
+//! Tests for recording sessions and replaying them to clients.
+
+use libindigo::client::ClientStrategy;
+use libindigo::device::traits::Focuser;
+use libindigo_rs::mock_server::{MockServerBuilder, ReplayServer};
+use libindigo_rs::protocol::ProtocolMessage;
+use libindigo_rs::recording::{Direction, Recording, SessionRecorder};
+use libindigo_rs::{RemoteFocuser, RsClientStrategy};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+mod fixtures;
+
+use fixtures::{wait_for_property, wait_until, TIMEOUT};
+
+const FOCUSER: &str = "Focuser Simulator";
+
+fn recording_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("libindigo-{}-{}.jsonl", name, std::process::id()))
+}
+
+/// Records a session with the focuser simulator that moves the focuser.
+async fn record_session(path: &PathBuf) {
+    let server = MockServerBuilder::new()
+        .with_focuser_simulator()
+        .build()
+        .await
+        .unwrap();
+    let recorder = SessionRecorder::create(path).unwrap();
+    let mut client = RsClientStrategy::new();
+    client.set_recorder(Some(recorder.clone())).await;
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_property(&client, FOCUSER, "FOCUSER_POSITION").await;
+
+    let mut focuser = RemoteFocuser::new(&client, FOCUSER).await.unwrap();
+    focuser.move_to(6000).await.unwrap();
+
+    client.disconnect().await.unwrap();
+    server.shutdown().await.unwrap();
+    recorder.flush().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_record_session() {
+    let path = recording_path("record");
+    record_session(&path).await;
+    let recording = Recording::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let first = &recording.messages[0];
+    assert_eq!(first.direction, Direction::Sent);
+    assert!(matches!(first.message, ProtocolMessage::GetProperties(_)));
+    assert!(recording
+        .messages
+        .windows(2)
+        .all(|pair| pair[0].time <= pair[1].time));
+
+    // The definitions and the change of position were received
+    assert!(recording.received().any(|m| matches!(
+        &m.message,
+        ProtocolMessage::DefNumberVector(v) if v.attrs.name == "FOCUSER_POSITION"
+    )));
+    assert!(recording.sent().any(|m| matches!(
+        &m.message,
+        ProtocolMessage::NewNumberVector(v) if v.attrs.name == "FOCUSER_POSITION"
+    )));
+    assert!(recording.received().any(|m| matches!(
+        &m.message,
+        ProtocolMessage::SetNumberVector(v)
+            if v.attrs.name == "FOCUSER_POSITION" && v.elements[0].value == 6000.0
+    )));
+}
+
+#[tokio::test]
+async fn test_replay_recorded_session() {
+    let path = recording_path("replay");
+    record_session(&path).await;
+    let recording = Recording::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let server = ReplayServer::start(recording, f64::INFINITY).await.unwrap();
+    let mut client = RsClientStrategy::new();
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_property(&client, FOCUSER, "FOCUSER_POSITION").await;
+
+    // The replayed session ends with the focuser at its new position
+    let focuser = RemoteFocuser::new(&client, FOCUSER).await.unwrap();
+    wait_until(&client, TIMEOUT, "Position was not replayed", || async {
+        focuser.position().await.unwrap() == 6000
+    })
+    .await;
+
+    let messages = server.client_messages().await;
+    assert!(matches!(messages[0], ProtocolMessage::GetProperties(_)));
+
+    client.disconnect().await.unwrap();
+    server.shutdown().await.unwrap();
+}
+
+/// A recording with two messages received 0.2 and 0.4 seconds after the
+/// client started the session.
+fn timed_recording() -> Recording {
+    let define = |time: f64, name: &str| {
+        format!(
+            r#"{{"time":{},"direction":"received","message":{{"defTextVector":{{"device":"Replay","name":"{}","state":"Idle","perm":"ro","items":[{{"name":"VALUE","value":"x"}}]}}}}}}"#,
+            time, name
+        )
+    };
+    let lines = [
+        r#"{"time":1.0,"direction":"sent","message":{"getProperties":{"version":512}}}"#
+            .to_string(),
+        define(1.2, "FIRST"),
+        define(1.4, "SECOND"),
+    ];
+    Recording::from_reader(lines.join("\n").as_bytes()).unwrap()
+}
+
+/// Replays a recording and measures how long the client waits for the last
+/// message.
+async fn replay_time(recording: Recording, speed: f64) -> Duration {
+    let server = ReplayServer::start(recording, speed).await.unwrap();
+    let mut client = RsClientStrategy::new();
+    let started = Instant::now();
+    client.connect(&server.addr().to_string()).await.unwrap();
+    wait_for_property(&client, "Replay", "SECOND").await;
+    let elapsed = started.elapsed();
+    assert!(client.property("Replay", "FIRST").await.is_some());
+
+    client.disconnect().await.unwrap();
+    server.shutdown().await.unwrap();
+    elapsed
+}
+
+#[tokio::test]
+async fn test_replay_timing() {
+    let original = replay_time(timed_recording(), 1.0).await;
+    assert!(original >= Duration::from_millis(400), "{:?}", original);
+
+    let accelerated = replay_time(timed_recording(), 4.0).await;
+    assert!(
+        accelerated >= Duration::from_millis(100),
+        "{:?}",
+        accelerated
+    );
+    assert!(
+        accelerated < Duration::from_millis(400),
+        "{:?}",
+        accelerated
+    );
+
+    assert!(ReplayServer::start(timed_recording(), 0.0).await.is_err());
+}
+
+#[test]
+fn test_invalid_recording() {
+    let result = Recording::from_reader(&b"{\"time\":0,\"direction\":\"sent\"}"[..]);
+    assert!(result.is_err());
+    let result = Recording::from_reader(
+        &br#"{"time":0,"direction":"up","message":{"getProperties":{}}}"#[..],
+    );
+    assert!(result.is_err());
+}
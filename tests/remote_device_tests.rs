@@ -0,0 +1,675 @@
+//! Integration tests for the remote device implementations against the mock server.
+
+use libindigo::client::{ClientBuilder, ClientStrategy, PropertyEvent};
+use libindigo::device::traits::{
+    AxisDirection, BinningMode, Camera, Coordinates, Device, ExposureState, FilterWheel, Focuser,
+    FrameType, GuideDirection, GuidePulse, Guider, Mount, MountAxis, MountType, SlewRate,
+    TrackingMode,
+};
+use libindigo::device::DeviceInterface;
+use libindigo::error::IndigoError;
+use libindigo::types::PropertyState;
+use libindigo_rs::mock_server::{
+    presets, BlobValue, Faults, MockIndigoServer, MockServerBuilder, PropertyUpdate, PropertyValue,
+};
+use libindigo_rs::protocol::encode_blob;
+use libindigo_rs::{
+    RemoteCamera, RemoteFilterWheel, RemoteFocuser, RemoteGuider, RemoteMount, RsClientStrategy,
+};
+use std::collections::HashSet;
+use std::time::Duration;
+
+mod fixtures;
+
+use fixtures::wait_for_property;
+
+/// Connects a client to the mock server and waits for the device to be defined.
+async fn connect(server: &MockIndigoServer, device: &str) -> RsClientStrategy {
+    let mut client = RsClientStrategy::new();
+    client
+        .connect(&server.addr().to_string())
+        .await
+        .expect("Failed to connect");
+    wait_for_device(&client, device).await;
+    client
+}
+
+/// Waits for the definitions of a device to arrive.
+async fn wait_for_device(client: &RsClientStrategy, device: &str) {
+    wait_for_property(client, device, "CONNECTION").await;
+    wait_for_property(client, device, "INFO").await;
+}
+
+#[tokio::test]
+async fn test_typed_device_accessors() {
+    let server = MockServerBuilder::new()
+        .with_ccd_simulator()
+        .with_mount_simulator()
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "CCD Simulator").await;
+    wait_for_device(&client, "Mount Simulator").await;
+
+    let camera = client.camera("CCD Simulator").await.unwrap();
+    assert_eq!(camera.device_type(), DeviceInterface::Ccd);
+    assert_eq!(camera.ccd_info().await.unwrap().width, 1280);
+
+    let mount = client
+        .device_as::<dyn Mount>("Mount Simulator")
+        .await
+        .unwrap();
+    assert_eq!(mount.mount_type().await.unwrap(), MountType::Equatorial);
+
+    let cameras = client.cameras().await.unwrap();
+    assert_eq!(cameras.len(), 1);
+    assert_eq!(cameras[0].name(), "CCD Simulator");
+    assert!(client.focusers().await.unwrap().is_empty());
+
+    // A device that doesn't implement the interface is rejected
+    let result = client.camera("Mount Simulator").await;
+    assert!(matches!(result, Err(IndigoError::InterfaceMismatch(_))));
+    let result = client.guider("No Such Guider").await;
+    assert!(matches!(result, Err(IndigoError::DeviceNotFound(_))));
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_typed_devices_through_client() {
+    let server = MockServerBuilder::new()
+        .with_ccd_simulator()
+        .with_mount_simulator()
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let mut client = ClientBuilder::new()
+        .with_strategy(Box::new(RsClientStrategy::new()))
+        .build()
+        .unwrap();
+    let mut events = client.subscribe_events().await.unwrap();
+    client
+        .strategy_mut()
+        .connect(&server.addr().to_string())
+        .await
+        .unwrap();
+    let mut count =
+        presets::ccd_simulator().properties.len() + presets::mount_simulator().properties.len();
+    while count > 0 {
+        if let Some(PropertyEvent::Defined(_)) = events.recv().await {
+            count -= 1;
+        }
+    }
+
+    let camera = client.device::<dyn Camera>("CCD Simulator").await.unwrap();
+    assert_eq!(camera.ccd_info().await.unwrap().width, 1280);
+    let mounts = client.mounts().await.unwrap();
+    assert_eq!(mounts.len(), 1);
+    assert_eq!(mounts[0].name(), "Mount Simulator");
+    assert!(client.guiders().await.unwrap().is_empty());
+    let result = client.focuser("Mount Simulator").await;
+    assert!(matches!(result, Err(IndigoError::InterfaceMismatch(_))));
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_camera_reads_ccd_properties() {
+    let server = MockServerBuilder::new()
+        .with_ccd_simulator()
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "CCD Simulator").await;
+
+    let camera = RemoteCamera::new(&client, "CCD Simulator").await.unwrap();
+    assert_eq!(camera.name(), "CCD Simulator");
+    assert_eq!(camera.device_type(), DeviceInterface::Ccd);
+    assert_eq!(camera.description(), Some("CCD Simulator"));
+    assert!(!camera.is_connected());
+
+    let info = camera.ccd_info().await.unwrap();
+    assert_eq!((info.width, info.height), (1280, 1024));
+    assert_eq!((info.max_bin_x, info.max_bin_y), (4, 4));
+    assert_eq!(info.bits_per_pixel, 16);
+
+    assert_eq!(camera.frame_type().await.unwrap(), FrameType::Light);
+    assert_eq!(camera.binning().await.unwrap(), BinningMode::symmetric(1));
+    assert_eq!(camera.frame().await.unwrap(), (0, 0, 1280, 1024));
+    assert!(!camera.cooler_enabled().await.unwrap());
+    assert_eq!(camera.exposure_state().await.unwrap(), ExposureState::Idle);
+
+    let missing = RemoteCamera::new(&client, "No Such Camera").await;
+    assert!(matches!(missing, Err(IndigoError::DeviceNotFound(_))));
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_camera_changes_settings() {
+    let server = MockServerBuilder::new()
+        .with_ccd_simulator()
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "CCD Simulator").await;
+    let mut camera = RemoteCamera::new(&client, "CCD Simulator").await.unwrap();
+
+    camera.connect().await.unwrap();
+    assert!(camera.is_connected());
+
+    camera.set_frame_type(FrameType::Dark).await.unwrap();
+    assert_eq!(camera.frame_type().await.unwrap(), FrameType::Dark);
+
+    camera.set_binning(BinningMode::symmetric(2)).await.unwrap();
+    assert_eq!(camera.binning().await.unwrap(), BinningMode::symmetric(2));
+
+    camera.set_cooler(true).await.unwrap();
+    assert!(camera.cooler_enabled().await.unwrap());
+
+    camera.set_gain(200.0).await.unwrap();
+    assert_eq!(camera.gain().await.unwrap(), 200.0);
+
+    // Values outside the advertised range are rejected locally
+    let result = camera.set_binning(BinningMode::symmetric(8)).await;
+    assert!(matches!(result, Err(IndigoError::InvalidParameter(_))));
+
+    camera.disconnect().await.unwrap();
+    assert!(!camera.is_connected());
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_device_follows_connection_state() {
+    let server = MockServerBuilder::new()
+        .with_ccd_simulator()
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "CCD Simulator").await;
+    let mut camera = RemoteCamera::new(&client, "CCD Simulator").await.unwrap();
+    camera.connect().await.unwrap();
+    assert!(camera.is_connected());
+
+    // The server disconnects the device, as for another client
+    let mut events = client.subscribe_events().await.unwrap();
+    let update = PropertyUpdate {
+        state: None,
+        items: vec![
+            ("CONNECTED".to_string(), PropertyValue::Switch(false)),
+            ("DISCONNECTED".to_string(), PropertyValue::Switch(true)),
+        ],
+        message: None,
+    };
+    server
+        .update_property("CCD Simulator", "CONNECTION", update)
+        .await
+        .unwrap();
+    while !matches!(
+        events.recv().await,
+        Some(PropertyEvent::Updated(property)) if property.name == "CONNECTION"
+    ) {}
+    assert!(!camera.is_connected());
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_camera_downloads_image() {
+    let server = MockServerBuilder::new()
+        .with_ccd_simulator()
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "CCD Simulator").await;
+    let mut camera = RemoteCamera::new(&client, "CCD Simulator").await.unwrap();
+
+    camera.start_exposure(0.5).await.unwrap();
+
+    let image = vec![0x53, 0x49, 0x4d, 0x50, 0x4c, 0x45];
+    server
+        .update_property(
+            "CCD Simulator",
+            "CCD_IMAGE",
+            PropertyUpdate {
+                state: Some(libindigo_rs::protocol::PropertyState::Ok),
+                items: vec![(
+                    "IMAGE".to_string(),
+                    PropertyValue::Blob(BlobValue {
+                        url: encode_blob(&image),
+                        format: ".fits".to_string(),
+                        size: image.len(),
+                    }),
+                )],
+                message: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let downloaded = tokio::time::timeout(Duration::from_secs(5), camera.download_image())
+        .await
+        .expect("Timed out waiting for the image")
+        .unwrap();
+    assert_eq!(downloaded, image);
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_mount_slews_and_parks() {
+    // Slews are reported as busy, as by real mounts
+    let server = MockServerBuilder::new()
+        .with_mount_simulator()
+        .with_busy_delay(Duration::from_millis(20))
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "Mount Simulator").await;
+    let mut mount = RemoteMount::new(&client, "Mount Simulator").await.unwrap();
+
+    assert_eq!(mount.device_type(), DeviceInterface::Mount);
+    assert_eq!(mount.mount_type().await.unwrap(), MountType::Equatorial);
+    assert!(mount.is_parked().await.unwrap());
+
+    mount.unpark().await.unwrap();
+    assert!(!mount.is_parked().await.unwrap());
+
+    mount.set_tracking(TrackingMode::Lunar).await.unwrap();
+    assert!(mount.is_tracking().await.unwrap());
+    assert_eq!(mount.tracking().await.unwrap(), TrackingMode::Lunar);
+
+    mount.slew_to(Coordinates::new(5.5, -5.4)).await.unwrap();
+    assert_eq!(
+        mount.coordinates().await.unwrap(),
+        Coordinates::new(5.5, -5.4)
+    );
+    assert!(!mount.is_slewing().await.unwrap());
+    assert!(mount
+        .device()
+        .switch("MOUNT_ON_COORDINATES_SET", "TRACK")
+        .await
+        .unwrap());
+
+    mount.sync_to(Coordinates::new(6.0, -5.0)).await.unwrap();
+    assert!(mount
+        .device()
+        .switch("MOUNT_ON_COORDINATES_SET", "SYNC")
+        .await
+        .unwrap());
+
+    mount
+        .move_axis(MountAxis::Secondary, AxisDirection::Forward, SlewRate::Find)
+        .await
+        .unwrap();
+    // Motion isn't awaited, so wait for the server to report it
+    mount
+        .wait_for_property_state(
+            "MOUNT_MOTION_DEC",
+            PropertyState::Ok,
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+    mount.stop_axis(MountAxis::Secondary).await.unwrap();
+    assert!(!mount
+        .device()
+        .switch("MOUNT_MOTION_DEC", "NORTH")
+        .await
+        .unwrap());
+    assert!(mount
+        .device()
+        .switch("MOUNT_SLEW_RATE", "FIND")
+        .await
+        .unwrap());
+
+    mount.set_location(59.3, 18.1).await.unwrap();
+    assert_eq!(mount.location().await.unwrap(), (59.3, 18.1));
+    assert_eq!(mount.sidereal_time().await.unwrap(), 12.5);
+
+    mount.park().await.unwrap();
+    assert!(mount.is_parked().await.unwrap());
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_mount_slew_ignores_updates_before_busy() {
+    // The server republishes the coordinates in Ok before starting the slew
+    let server = MockServerBuilder::new()
+        .with_mount_simulator()
+        .with_busy_delay(Duration::from_millis(100))
+        .with_faults(Faults {
+            stale_updates: true,
+            ..Faults::default()
+        })
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "Mount Simulator").await;
+    let mut mount = RemoteMount::new(&client, "Mount Simulator").await.unwrap();
+
+    mount.slew_to(Coordinates::new(5.5, -5.4)).await.unwrap();
+    assert!(!mount.is_slewing().await.unwrap());
+    assert_eq!(
+        mount
+            .device()
+            .state("MOUNT_EQUATORIAL_COORDINATES")
+            .await
+            .unwrap(),
+        PropertyState::Ok
+    );
+    assert_eq!(
+        mount.coordinates().await.unwrap(),
+        Coordinates::new(5.5, -5.4)
+    );
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_mount_reports_rejected_slew() {
+    // The mount refuses to slew, as when parked, without being busy first
+    let server = MockServerBuilder::new()
+        .with_mount_simulator()
+        .with_busy_delay(Duration::from_millis(20))
+        .with_faults(Faults {
+            alert_properties: HashSet::from(["MOUNT_EQUATORIAL_COORDINATES".to_string()]),
+            ..Faults::default()
+        })
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "Mount Simulator").await;
+    let mut mount = RemoteMount::new(&client, "Mount Simulator").await.unwrap();
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        mount.slew_to(Coordinates::new(5.5, -5.4)),
+    )
+    .await
+    .expect("Rejected slew was not reported");
+    assert!(matches!(result, Err(IndigoError::InvalidState(_))));
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_mount_parks_when_parked() {
+    // Parking a parked mount is answered with Ok directly
+    let server = MockServerBuilder::new()
+        .with_mount_simulator()
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "Mount Simulator").await;
+    let mut mount = RemoteMount::new(&client, "Mount Simulator").await.unwrap();
+    assert!(mount.is_parked().await.unwrap());
+
+    tokio::time::timeout(Duration::from_secs(5), mount.park())
+        .await
+        .expect("Park was not completed")
+        .unwrap();
+    assert!(mount.is_parked().await.unwrap());
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_mount_custom_tracking_rate_not_supported() {
+    let server = MockServerBuilder::new()
+        .with_mount_simulator()
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "Mount Simulator").await;
+    let mut mount = RemoteMount::new(&client, "Mount Simulator").await.unwrap();
+
+    assert!(matches!(
+        mount.set_tracking(TrackingMode::Custom(2)).await,
+        Err(IndigoError::NotSupported(_))
+    ));
+
+    mount
+        .device_mut()
+        .change_switches("MOUNT_TRACK_RATE", &[("CUSTOM", true)])
+        .await
+        .unwrap();
+    mount
+        .device_mut()
+        .change_switches("MOUNT_TRACKING", &[("ON", true)])
+        .await
+        .unwrap();
+    assert!(matches!(
+        mount.tracking().await,
+        Err(IndigoError::NotSupported(_))
+    ));
+
+    server.shutdown().await.unwrap();
+}
+
+/// Delay before the mock server completes a change, so the busy state is observable.
+const BUSY_DELAY: Duration = Duration::from_millis(200);
+
+#[tokio::test]
+async fn test_remote_focuser_moves() {
+    let server = MockServerBuilder::new()
+        .with_focuser_simulator()
+        .with_busy_delay(BUSY_DELAY)
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "Focuser Simulator").await;
+    let mut focuser = RemoteFocuser::new(&client, "Focuser Simulator")
+        .await
+        .unwrap();
+    let observer = RemoteFocuser::new(&client, "Focuser Simulator")
+        .await
+        .unwrap();
+
+    assert_eq!(focuser.device_type(), DeviceInterface::Focuser);
+    let info = focuser.focuser_info().await.unwrap();
+    assert_eq!(info.max_position, 65535);
+    assert!(info.has_absolute);
+    assert!(info.has_temperature_compensation);
+    assert_eq!(focuser.position().await.unwrap(), 5000);
+    assert_eq!(focuser.temperature().await.unwrap(), 12.5);
+
+    // The move completes only once the server reports Ok
+    let moving = tokio::spawn(async move {
+        focuser.move_to(7000).await.unwrap();
+        focuser
+    });
+    observer
+        .wait_for_property_state(
+            "FOCUSER_POSITION",
+            PropertyState::Busy,
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+    assert!(observer.is_moving().await.unwrap());
+    let mut focuser = moving.await.unwrap();
+    assert!(!focuser.is_moving().await.unwrap());
+    assert_eq!(focuser.position().await.unwrap(), 7000);
+
+    focuser.move_relative(-250).await.unwrap();
+    assert!(focuser
+        .device()
+        .switch("FOCUSER_DIRECTION", "MOVE_INWARD")
+        .await
+        .unwrap());
+    assert_eq!(
+        focuser
+            .device()
+            .number("FOCUSER_STEPS", "STEPS")
+            .await
+            .unwrap(),
+        250.0
+    );
+
+    focuser.set_temperature_compensation(true).await.unwrap();
+    assert!(focuser.temperature_compensation().await.unwrap());
+
+    focuser.set_backlash(40).await.unwrap();
+    assert_eq!(focuser.backlash().await.unwrap(), 40);
+
+    // Speed is mapped onto the 1..5 range advertised by the device
+    focuser.set_speed(0.5).await.unwrap();
+    assert_eq!(
+        focuser
+            .device()
+            .number("FOCUSER_SPEED", "SPEED")
+            .await
+            .unwrap(),
+        3.0
+    );
+    assert_eq!(focuser.speed().await.unwrap(), 0.5);
+    assert!(matches!(
+        focuser.set_speed(1.5).await,
+        Err(IndigoError::InvalidParameter(_))
+    ));
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_filter_wheel_selects_filters() {
+    let server = MockServerBuilder::new()
+        .with_wheel_simulator()
+        .with_busy_delay(BUSY_DELAY)
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "Wheel Simulator").await;
+    let mut wheel = RemoteFilterWheel::new(&client, "Wheel Simulator")
+        .await
+        .unwrap();
+    let observer = RemoteFilterWheel::new(&client, "Wheel Simulator")
+        .await
+        .unwrap();
+
+    assert_eq!(wheel.device_type(), DeviceInterface::FilterWheel);
+    assert_eq!(wheel.slot_count().await.unwrap(), 5);
+    let filters = wheel.filters().await.unwrap();
+    assert_eq!(filters.len(), 5);
+    assert_eq!((filters[1].slot, filters[1].name.as_str()), (2, "Red"));
+    assert_eq!(wheel.current_filter_name().await.unwrap(), "Luminance");
+
+    let moving = tokio::spawn(async move {
+        wheel.select_filter(3).await.unwrap();
+        wheel
+    });
+    observer
+        .wait_for_property_state("WHEEL_SLOT", PropertyState::Busy, Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert!(observer.is_moving().await.unwrap());
+    let mut wheel = moving.await.unwrap();
+    assert!(!wheel.is_moving().await.unwrap());
+    assert_eq!(wheel.current_slot().await.unwrap(), 3);
+    assert_eq!(wheel.current_filter_name().await.unwrap(), "Green");
+
+    wheel.set_filter_name(5, "OIII").await.unwrap();
+    wheel.set_filter_offset(5, -120).await.unwrap();
+    let filters = wheel.filters().await.unwrap();
+    assert_eq!(filters[4].name, "OIII");
+    assert_eq!(filters[4].offset, -120);
+
+    assert!(matches!(
+        wheel.select_filter(6).await,
+        Err(IndigoError::InvalidParameter(_))
+    ));
+    assert!(matches!(
+        wheel.select_filter(0).await,
+        Err(IndigoError::InvalidParameter(_))
+    ));
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_guider_pulses() {
+    let server = MockServerBuilder::new()
+        .with_guider_simulator()
+        .with_busy_delay(BUSY_DELAY)
+        .build()
+        .await
+        .expect("Failed to start mock server");
+    let client = connect(&server, "Guider Simulator").await;
+    let mut guider = RemoteGuider::new(&client, "Guider Simulator")
+        .await
+        .unwrap();
+    let observer = RemoteGuider::new(&client, "Guider Simulator")
+        .await
+        .unwrap();
+
+    assert_eq!(guider.device_type(), DeviceInterface::Guider);
+    assert!(!guider.is_guiding().await.unwrap());
+
+    let guiding = tokio::spawn(async move {
+        guider
+            .guide(GuidePulse::new(GuideDirection::North, 500))
+            .await
+            .unwrap();
+        guider
+    });
+    observer
+        .wait_for_property_state(
+            "GUIDER_GUIDE_DEC",
+            PropertyState::Busy,
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+    assert!(observer.is_guiding().await.unwrap());
+    let mut guider = guiding.await.unwrap();
+    assert!(!guider.is_guiding().await.unwrap());
+    assert_eq!(
+        guider
+            .device()
+            .number("GUIDER_GUIDE_DEC", "NORTH")
+            .await
+            .unwrap(),
+        500.0
+    );
+
+    guider
+        .guide_dual(
+            Some(GuidePulse::new(GuideDirection::West, 300)),
+            Some(GuidePulse::new(GuideDirection::South, 200)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        guider
+            .device()
+            .number("GUIDER_GUIDE_RA", "WEST")
+            .await
+            .unwrap(),
+        300.0
+    );
+    assert_eq!(
+        guider
+            .device()
+            .number("GUIDER_GUIDE_DEC", "SOUTH")
+            .await
+            .unwrap(),
+        200.0
+    );
+    assert_eq!(
+        guider
+            .device()
+            .number("GUIDER_GUIDE_DEC", "NORTH")
+            .await
+            .unwrap(),
+        0.0
+    );
+
+    assert_eq!(guider.guide_rate().await.unwrap(), (0.5, 0.5));
+    guider.set_guide_rate(0.25, 0.75).await.unwrap();
+    assert_eq!(guider.guide_rate().await.unwrap(), (0.25, 0.75));
+
+    server.shutdown().await.unwrap();
+}